@@ -172,6 +172,10 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
             Commands::Debug(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Recover(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Prune(command) => runner.run_until_ctrl_c(command.execute()),
+            #[cfg(not(feature = "optimism"))]
+            Commands::ReadReplica(command) => {
+                runner.run_command_until_exit(|ctx| command.execute(ctx))
+            }
         }
     }
 
@@ -235,6 +239,11 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// Prune according to the configuration without any limits
     #[command(name = "prune")]
     Prune(prune::PruneCommand),
+    /// Serve the eth RPC namespace from the datadir of another node, without networking or
+    /// consensus
+    #[cfg(not(feature = "optimism"))]
+    #[command(name = "read-replica")]
+    ReadReplica(crate::commands::read_replica::Command),
 }
 
 #[cfg(test)]
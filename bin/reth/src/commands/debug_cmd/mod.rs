@@ -7,7 +7,9 @@ mod build_block;
 mod execution;
 mod in_memory_merkle;
 mod merkle;
+mod reexecute_witness;
 mod replay_engine;
+mod validate_witness;
 
 /// `reth debug` command
 #[derive(Debug, Parser)]
@@ -29,6 +31,10 @@ pub enum Subcommands {
     BuildBlock(build_block::Command),
     /// Debug engine API by replaying stored messages.
     ReplayEngine(replay_engine::Command),
+    /// Debug an invalid block by re-executing its execution witness.
+    ReexecuteWitness(reexecute_witness::Command),
+    /// Debug a block by validating it statelessly from its execution witness.
+    ValidateWitness(validate_witness::Command),
 }
 
 impl Command {
@@ -40,6 +46,8 @@ impl Command {
             Subcommands::InMemoryMerkle(command) => command.execute(ctx).await,
             Subcommands::BuildBlock(command) => command.execute(ctx).await,
             Subcommands::ReplayEngine(command) => command.execute(ctx).await,
+            Subcommands::ReexecuteWitness(command) => command.execute(ctx).await,
+            Subcommands::ValidateWitness(command) => command.execute(ctx).await,
         }
     }
 }
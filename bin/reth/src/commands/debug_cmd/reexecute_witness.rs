@@ -0,0 +1,86 @@
+//! Command for re-executing the witness of an invalid block.
+
+use std::{path::PathBuf, sync::Arc};
+
+use clap::Parser;
+use reth_chainspec::ChainSpec;
+use reth_cli_runner::CliContext;
+use reth_engine_tree::tree::InvalidBlockWitness;
+use reth_node_core::args::utils::{chain_help, chain_value_parser, SUPPORTED_CHAINS};
+use reth_primitives::{logs_bloom, proofs::calculate_receipt_root_no_memo};
+use tracing::*;
+
+use crate::macros::block_executor;
+
+/// `reth debug reexecute-witness` command
+/// This script re-executes an invalid block from its execution witness without a database and
+/// compares the outcome, including the state root, with the block header. It fails if the
+/// execution fails or the outcome doesn't match the header.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The chain the block belongs to.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = chain_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// The path of the witness file written with `--debug.invalid-block-witness-dir`.
+    #[arg(long = "witness", value_name = "PATH")]
+    witness: PathBuf,
+}
+
+impl Command {
+    /// Execute `debug reexecute-witness` command
+    pub async fn execute(self, _ctx: CliContext) -> eyre::Result<()> {
+        let witness = InvalidBlockWitness::read_from_file(&self.witness)?;
+        let block = &witness.block;
+        info!(target: "reth::cli", number = block.number, hash = ?block.hash(), error = %witness.error, "Re-executing invalid block witness");
+
+        let executor = block_executor!(self.chain);
+        let (output, state_root) = witness
+            .reexecute(&executor)
+            .map_err(|error| eyre::eyre!("Block execution failed: {error}"))?;
+        let receipts_root =
+            calculate_receipt_root_no_memo(&output.receipts.iter().collect::<Vec<_>>());
+        let bloom = logs_bloom(output.receipts.iter().flat_map(|receipt| &receipt.logs));
+
+        let mut mismatches = Vec::new();
+        if output.gas_used == block.gas_used {
+            info!(target: "reth::cli", gas_used = output.gas_used, "Gas used matches");
+        } else {
+            warn!(target: "reth::cli", expected = block.gas_used, got = output.gas_used, "Gas used mismatch");
+            mismatches.push("gas used");
+        }
+        if receipts_root == block.receipts_root {
+            info!(target: "reth::cli", ?receipts_root, "Receipts root matches");
+        } else {
+            warn!(target: "reth::cli", expected = ?block.receipts_root, got = ?receipts_root, "Receipts root mismatch");
+            mismatches.push("receipts root");
+        }
+        if bloom == block.logs_bloom {
+            info!(target: "reth::cli", "Logs bloom matches");
+        } else {
+            warn!(target: "reth::cli", expected = ?block.logs_bloom, got = ?bloom, "Logs bloom mismatch");
+            mismatches.push("logs bloom");
+        }
+        if state_root == block.state_root {
+            info!(target: "reth::cli", ?state_root, "State root matches");
+        } else {
+            warn!(target: "reth::cli", expected = ?block.state_root, got = ?state_root, "State root mismatch");
+            mismatches.push("state root");
+        }
+        info!(target: "reth::cli", accounts = output.state.state.len(), "Block executed");
+
+        if !mismatches.is_empty() {
+            eyre::bail!("Re-executed block doesn't match its header: {}", mismatches.join(", "))
+        }
+
+        Ok(())
+    }
+}
@@ -1,39 +1,41 @@
 use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use clap::Parser;
-use eyre::Context;
+use futures::StreamExt;
 use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
-use reth_beacon_consensus::{hooks::EngineHooks, BeaconConsensusEngine, EthBeaconConsensus};
-use reth_blockchain_tree::{
-    BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree, TreeExternals,
-};
+use reth_beacon_consensus::{BeaconEngineMessage, EthBeaconConsensus};
 use reth_cli_commands::common::{AccessRights, Environment, EnvironmentArgs};
 use reth_cli_runner::CliContext;
 use reth_cli_util::get_secret_key;
 use reth_config::Config;
 use reth_consensus::Consensus;
 use reth_db::DatabaseEnv;
-use reth_engine_util::engine_store::{EngineMessageStore, StoredEngineApiMessage};
-use reth_fs_util as fs;
+use reth_engine_service::service::{ChainEvent, EngineService};
+use reth_engine_tree::tree::TreeConfig;
+use reth_engine_util::engine_store::{
+    EngineMessageStore, StoredEngineApiMessage, StoredEngineApiOutcome,
+};
 use reth_network::{BlockDownloaderProvider, NetworkHandle};
 use reth_network_api::NetworkInfo;
 use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
 use reth_provider::{
-    providers::BlockchainProvider, CanonStateSubscriptions, ChainSpecProvider, ProviderFactory,
+    providers::BlockchainProvider2, CanonStateSubscriptions, ChainSpecProvider, ProviderFactory,
 };
-use reth_prune::PruneModes;
+use reth_prune::{PruneModes, PrunerBuilder};
 use reth_stages::Pipeline;
 use reth_static_file::StaticFileProducer;
 use reth_tasks::TaskExecutor;
 use reth_transaction_pool::noop::NoopTransactionPool;
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc::unbounded_channel, oneshot};
+use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::*;
 
 use crate::{args::NetworkArgs, macros::block_executor};
 
 /// `reth debug replay-engine` command
-/// This script will read stored engine API messages and replay them by the timestamp.
-/// It does not require
+/// This script will read stored engine API messages and replay them in order against the engine
+/// tree, comparing the outcome of each message with the recorded one. It fails if any outcome
+/// differs from the recorded one.
 #[derive(Debug, Parser)]
 pub struct Command {
     #[command(flatten)]
@@ -49,6 +51,15 @@ pub struct Command {
     /// The number of milliseconds between Engine API messages.
     #[arg(long = "interval", default_value_t = 1_000)]
     interval: u64,
+
+    /// Stop replaying after the message with this index.
+    #[arg(long = "stop-at", value_name = "INDEX")]
+    stop_at: Option<u64>,
+
+    /// Stop replaying at the first message whose outcome differs from the recorded one and
+    /// report it.
+    #[arg(long = "stop-at-divergence")]
+    stop_at_divergence: bool,
 }
 
 impl Command {
@@ -82,18 +93,8 @@ impl Command {
 
         let executor = block_executor!(provider_factory.chain_spec());
 
-        // Configure blockchain tree
-        let tree_externals =
-            TreeExternals::new(provider_factory.clone(), Arc::clone(&consensus), executor);
-        let tree = BlockchainTree::new(
-            tree_externals,
-            BlockchainTreeConfig::default(),
-            PruneModes::none(),
-        )?;
-        let blockchain_tree = Arc::new(ShareableBlockchainTree::new(tree));
-
         // Set up the blockchain provider
-        let blockchain_db = BlockchainProvider::new(provider_factory.clone(), blockchain_tree)?;
+        let blockchain_db = BlockchainProvider2::new(provider_factory.clone())?;
 
         // Set up network
         let network_secret_path =
@@ -141,64 +142,112 @@ impl Command {
 
         ctx.task_executor.spawn_critical("payload builder service", payload_service);
 
-        // Configure the consensus engine
+        // Configure the engine
         let network_client = network.fetch_client().await?;
-        let (beacon_consensus_engine, beacon_engine_handle) = BeaconConsensusEngine::new(
+        let (to_engine, from_replay) = unbounded_channel();
+        let mut engine_service = EngineService::new(
+            consensus,
+            executor,
+            provider_factory.chain_spec(),
             network_client,
+            Box::pin(UnboundedReceiverStream::from(from_replay)),
             Pipeline::builder().build(
                 provider_factory.clone(),
                 StaticFileProducer::new(provider_factory.clone(), PruneModes::none()),
             ),
-            blockchain_db.clone(),
             Box::new(ctx.task_executor.clone()),
-            Box::new(network),
-            None,
+            provider_factory.clone(),
+            blockchain_db,
+            PrunerBuilder::default().build_with_provider_factory(provider_factory),
             payload_builder,
-            None,
-            u64::MAX,
-            EngineHooks::new(),
-        )?;
+            TreeConfig::default(),
+            Default::default(),
+        );
         info!(target: "reth::cli", "Consensus engine initialized");
 
-        // Run consensus engine to completion
-        let (tx, rx) = oneshot::channel();
+        // Run consensus engine in the background for the duration of the replay
+        let (tx, mut rx) = oneshot::channel();
         info!(target: "reth::cli", "Starting consensus engine");
-        ctx.task_executor.spawn_critical_blocking("consensus engine", async move {
-            let res = beacon_consensus_engine.await;
+        ctx.task_executor.spawn_critical("consensus engine", async move {
+            let mut res = Ok(());
+            while let Some(event) = engine_service.next().await {
+                debug!(target: "reth::cli", ?event, "Received consensus engine event");
+                if let ChainEvent::FatalError = event {
+                    res = Err(eyre::eyre!("Fatal error in consensus engine"));
+                    break
+                }
+            }
             let _ = tx.send(res);
         });
 
         let engine_api_store = EngineMessageStore::new(self.engine_api_store.clone());
-        for filepath in engine_api_store.engine_messages_iter()? {
-            let contents =
-                fs::read(&filepath).wrap_err(format!("failed to read: {}", filepath.display()))?;
-            let message = serde_json::from_slice(&contents)
-                .wrap_err(format!("failed to parse: {}", filepath.display()))?;
-            debug!(target: "reth::cli", filepath = %filepath.display(), ?message, "Forwarding Engine API message");
-            match message {
+        let mut replayed = 0usize;
+        let mut divergent = 0usize;
+        for entry in engine_api_store.engine_messages()? {
+            if self.stop_at.is_some_and(|stop_at| entry.index > stop_at) {
+                info!(target: "reth::cli", index = entry.index, "Reached the message to stop at");
+                break
+            }
+
+            debug!(target: "reth::cli", index = entry.index, message = ?entry.message, "Forwarding Engine API message");
+            let outcome = match entry.message {
                 StoredEngineApiMessage::ForkchoiceUpdated { state, payload_attrs } => {
-                    let response =
-                        beacon_engine_handle.fork_choice_updated(state, payload_attrs).await?;
-                    debug!(target: "reth::cli", ?response, "Received for forkchoice updated");
+                    let (tx, rx) = oneshot::channel();
+                    to_engine
+                        .send(BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx })
+                        .map_err(|_| eyre::eyre!("consensus engine exited"))?;
+                    StoredEngineApiOutcome::from_forkchoice_updated_response(&rx.await?)
                 }
                 StoredEngineApiMessage::NewPayload { payload, cancun_fields } => {
-                    let response = beacon_engine_handle.new_payload(payload, cancun_fields).await?;
-                    debug!(target: "reth::cli", ?response, "Received for new payload");
+                    let (tx, rx) = oneshot::channel();
+                    to_engine
+                        .send(BeaconEngineMessage::NewPayload {
+                            payload,
+                            cancun_fields,
+                            tx,
+                            span: Span::current(),
+                        })
+                        .map_err(|_| eyre::eyre!("consensus engine exited"))?;
+                    StoredEngineApiOutcome::from_new_payload_response(&rx.await?)
                 }
             };
+            replayed += 1;
+
+            match entry.outcome {
+                Some(recorded) if !recorded.matches(&outcome) => {
+                    divergent += 1;
+                    warn!(target: "reth::cli", index = entry.index, ?recorded, actual = ?outcome, "Engine API message outcome differs from the recorded one");
+                    if self.stop_at_divergence {
+                        info!(target: "reth::cli", index = entry.index, "Found first divergent Engine API message");
+                        break
+                    }
+                }
+                Some(_) => {
+                    debug!(target: "reth::cli", index = entry.index, ?outcome, "Engine API message outcome matches the recorded one")
+                }
+                None => {
+                    debug!(target: "reth::cli", index = entry.index, ?outcome, "No recorded outcome for Engine API message")
+                }
+            }
 
             // Pause before next message
             tokio::time::sleep(Duration::from_millis(self.interval)).await;
         }
 
-        info!(target: "reth::cli", "Finished replaying engine API messages");
+        info!(target: "reth::cli", replayed, divergent, "Finished replaying engine API messages");
 
-        match rx.await? {
-            Ok(()) => info!("Beacon consensus engine exited successfully"),
-            Err(error) => {
-                error!(target: "reth::cli", %error, "Beacon consensus engine exited with an error")
-            }
-        };
+        // The engine never finishes on its own, so stop sending messages to it and only check
+        // whether it failed during the replay.
+        drop(to_engine);
+        if let Ok(Err(error)) = rx.try_recv() {
+            return Err(error)
+        }
+
+        if divergent > 0 {
+            eyre::bail!(
+                "{divergent} of {replayed} replayed engine API messages differ from the recorded outcomes"
+            )
+        }
 
         Ok(())
     }
@@ -0,0 +1,70 @@
+//! Command for validating a block statelessly from its execution witness.
+
+use std::{path::PathBuf, sync::Arc};
+
+use clap::Parser;
+use reth_beacon_consensus::EthBeaconConsensus;
+use reth_chainspec::ChainSpec;
+use reth_cli_runner::CliContext;
+use reth_node_core::args::utils::{chain_help, chain_value_parser, SUPPORTED_CHAINS};
+use reth_primitives::Block;
+use reth_rpc_types::{Transaction, WithOtherFields};
+use reth_stateless::{validate_block, ExecutionWitness};
+use tracing::*;
+
+use crate::macros::block_executor;
+
+/// `reth debug validate-witness` command
+/// This script validates a block without a database, using only the execution witness of the
+/// block, and verifies the state root after execution.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The chain the block belongs to.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = chain_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// The path of the block to validate, as JSON in the format of `eth_getBlockByHash` with full
+    /// transactions.
+    #[arg(long = "block", value_name = "PATH")]
+    block: PathBuf,
+
+    /// The path of the execution witness of the block as JSON, with the trie nodes, the bytecodes
+    /// and the ancestor headers that are required to validate the block, as returned by
+    /// `debug_statelessWitness`.
+    #[arg(long = "witness", value_name = "PATH")]
+    witness: PathBuf,
+}
+
+impl Command {
+    /// Execute `debug validate-witness` command
+    pub async fn execute(self, _ctx: CliContext) -> eyre::Result<()> {
+        let block: reth_rpc_types::Block<WithOtherFields<Transaction>> =
+            reth_fs_util::read_json_file(&self.block)?;
+        let block = Block::try_from(block)?.seal_slow();
+        let witness: ExecutionWitness = reth_fs_util::read_json_file(&self.witness)?;
+
+        let consensus = EthBeaconConsensus::new(self.chain.clone());
+        let executor = block_executor!(self.chain);
+
+        let number = block.number;
+        let hash = block.hash();
+        info!(target: "reth::cli", number, ?hash, "Validating block statelessly");
+        match validate_block(&executor, &consensus, block, &witness) {
+            Ok(output) => {
+                info!(target: "reth::cli", number, ?hash, gas_used = output.gas_used, "Block is valid");
+                Ok(())
+            }
+            Err(error) => {
+                eyre::bail!("block {number} ({hash}) is invalid: {error}")
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
 //! This contains all of the `reth` commands
 
 pub mod debug_cmd;
+#[cfg(not(feature = "optimism"))]
+pub mod read_replica;
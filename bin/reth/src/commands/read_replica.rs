@@ -0,0 +1,96 @@
+//! `reth read-replica` command. Serves the `eth` RPC namespace from the datadir of another node.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use clap::Parser;
+use reth_blockchain_tree::noop::NoopBlockchainTree;
+use reth_cli_commands::common::{AccessRights, Environment, EnvironmentArgs};
+use reth_cli_runner::CliContext;
+use reth_cli_util::parse_duration_from_secs_or_ms;
+use reth_node_ethereum::EthEvmConfig;
+use reth_provider::{providers::BlockchainProvider, BlockNumReader};
+use reth_rpc::EthApi;
+use reth_rpc_builder::{
+    RethRpcModule, RpcModuleBuilder, RpcServerConfig, TransportRpcModuleConfig,
+};
+use reth_rpc_server_types::constants::DEFAULT_HTTP_RPC_PORT;
+use tracing::*;
+
+/// `reth read-replica` command
+///
+/// Opens the datadir of a running node with read-only access and serves the `eth` RPC namespace
+/// from it, without networking or consensus. Blocks written by the node are picked up by polling
+/// its database and static files.
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    /// Http server address to listen on
+    #[arg(long = "http.addr", default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    http_addr: IpAddr,
+
+    /// Http server port to listen on
+    #[arg(long = "http.port", default_value_t = DEFAULT_HTTP_RPC_PORT)]
+    http_port: u16,
+
+    /// Comma separated list of domains from which to accept cross origin requests (CORS)
+    #[arg(long = "http.corsdomain")]
+    http_corsdomain: Option<String>,
+
+    /// How often to check the datadir for new blocks.
+    ///
+    /// Interval is specified in seconds or in milliseconds if the value ends with `ms`:
+    ///   * `50ms` -> 50 milliseconds
+    ///   * `1` -> 1 second
+    #[arg(long = "replica.poll-interval", value_parser = parse_duration_from_secs_or_ms, default_value = "1", value_name = "DURATION")]
+    poll_interval: Duration,
+}
+
+impl Command {
+    /// Execute `read-replica` command
+    pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
+        let Environment { provider_factory, .. } = self.env.init(AccessRights::RO)?;
+
+        // There is no blockchain tree, all blocks are read from the storage of the writing node.
+        let provider =
+            BlockchainProvider::new(provider_factory, Arc::new(NoopBlockchainTree::default()))?;
+        info!(target: "reth::cli", head = provider.best_block_number()?, "Opened datadir in read-only mode");
+
+        let modules = RpcModuleBuilder::default()
+            .with_provider(provider.clone())
+            .with_noop_pool()
+            .with_noop_network()
+            .with_executor(ctx.task_executor.clone())
+            .with_evm_config(EthEvmConfig::default())
+            .with_events(provider.clone())
+            .build(
+                TransportRpcModuleConfig::default().with_http([RethRpcModule::Eth]),
+                Box::new(EthApi::with_spawner),
+            );
+
+        let server = RpcServerConfig::http(Default::default())
+            .with_http_address(SocketAddr::new(self.http_addr, self.http_port))
+            .with_cors(self.http_corsdomain.clone())
+            .start(&modules)
+            .await?;
+        info!(target: "reth::cli", url = ?server.http_url(), "RPC HTTP server started");
+
+        let mut interval = tokio::time::interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            match provider.refresh_from_storage() {
+                Ok(true) => {
+                    let head = provider.chain_info()?;
+                    info!(target: "reth::cli", number = head.best_number, hash = ?head.best_hash, "Canonical head updated");
+                }
+                Ok(false) => {}
+                Err(err) => warn!(target: "reth::cli", %err, "Failed to refresh from storage"),
+            }
+        }
+    }
+}
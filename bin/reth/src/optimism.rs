@@ -25,7 +25,7 @@ fn main() {
     }
 
     if let Err(err) = Cli::<RollupArgs>::parse().run(|builder, rollup_args| async move {
-        let enable_engine2 = rollup_args.experimental;
+        let enable_engine2 = builder.config().engine.experimental;
         let sequencer_http_arg = rollup_args.sequencer_http.clone();
         match enable_engine2 {
             true => {
@@ -21,7 +21,8 @@ use reth_primitives::{
 use reth_provider::{
     BlockExecutionWriter, BlockNumReader, BlockWriter, CanonStateNotification,
     CanonStateNotificationSender, CanonStateNotifications, ChainSpecProvider, ChainSplit,
-    ChainSplitTarget, DisplayBlocksChain, HeaderProvider, ProviderError, StaticFileProviderFactory,
+    ChainSplitTarget, DisplayBlocksChain, HeaderProvider, ProviderError, ReorgInfo,
+    StaticFileProviderFactory,
 };
 use reth_prune_types::PruneModes;
 use reth_stages_api::{MetricEvent, MetricEventsSender};
@@ -1171,6 +1172,7 @@ where
                 durations_recorder.record_relative(MakeCanonicalAction::InsertOldCanonicalChain);
 
                 CanonStateNotification::Reorg {
+                    info: ReorgInfo::new(old_canon_chain.len() as u64),
                     old: Arc::new(old_canon_chain),
                     new: Arc::new(new_canon_chain),
                 }
@@ -2161,7 +2163,7 @@ mod tests {
         assert!(tree.make_canonical(block2a_hash).is_ok());
         // check notification.
         assert_matches!(canon_notif.try_recv(),
-            Ok(CanonStateNotification::Reorg{ old, new})
+            Ok(CanonStateNotification::Reorg{ old, new, .. })
             if *old.blocks() == BTreeMap::from([(block2.number,block2.clone())])
                 && *new.blocks() == BTreeMap::from([(block2a.number,block2a.clone())]));
 
@@ -2213,7 +2215,7 @@ mod tests {
 
         // check notification.
         assert_matches!(canon_notif.try_recv(),
-            Ok(CanonStateNotification::Reorg{ old, new})
+            Ok(CanonStateNotification::Reorg{ old, new, .. })
             if *old.blocks() == BTreeMap::from([(block1.number,block1.clone()),(block2a.number,block2a.clone())])
                 && *new.blocks() == BTreeMap::from([(block1a.number,block1a.clone())]));
 
@@ -2250,7 +2252,7 @@ mod tests {
 
         // check notification.
         assert_matches!(canon_notif.try_recv(),
-            Ok(CanonStateNotification::Reorg{ old, new})
+            Ok(CanonStateNotification::Reorg{ old, new, .. })
             if *old.blocks() == BTreeMap::from([(block1a.number,block1a.clone())])
                 && *new.blocks() == BTreeMap::from([(block1.number,block1.clone()),(block2.number,block2.clone())]));
 
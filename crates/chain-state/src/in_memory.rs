@@ -2,9 +2,9 @@
 
 use crate::{
     CanonStateNotification, CanonStateNotificationSender, CanonStateNotifications,
-    ChainInfoTracker, MemoryOverlayStateProvider,
+    ChainInfoTracker, MemoryOverlayStateProvider, ReorgInfo,
 };
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use reth_chainspec::ChainInfo;
 use reth_execution_types::{Chain, ExecutionOutcome};
 use reth_metrics::{metrics::Gauge, Metrics};
@@ -17,10 +17,13 @@ use reth_trie::{updates::TrieUpdates, HashedPostState};
 use std::{
     collections::{BTreeMap, HashMap},
     sync::Arc,
-    time::Instant,
+    time::{Duration, Instant},
 };
 use tokio::sync::{broadcast, watch};
 
+/// How long a permission granted with [`CanonicalInMemoryState::allow_reorg`] remains valid.
+const ALLOWED_REORG_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
 /// Size of the broadcast channel used to notify canonical state events.
 const CANON_STATE_NOTIFICATION_CHANNEL_SIZE: usize = 256;
 
@@ -132,6 +135,8 @@ pub(crate) struct CanonicalInMemoryStateInner {
     pub(crate) in_memory_state: InMemoryState,
     /// A broadcast stream that emits events when the canonical chain is updated.
     pub(crate) canon_state_notification_sender: CanonStateNotificationSender,
+    /// Heads of reorgs below the safe block that were explicitly allowed, and when.
+    pub(crate) allowed_reorgs: Mutex<HashMap<B256, Instant>>,
 }
 
 impl CanonicalInMemoryStateInner {
@@ -180,6 +185,7 @@ impl CanonicalInMemoryState {
                 chain_info_tracker,
                 in_memory_state,
                 canon_state_notification_sender,
+                allowed_reorgs: Mutex::default(),
             }),
         }
     }
@@ -200,6 +206,7 @@ impl CanonicalInMemoryState {
             chain_info_tracker,
             in_memory_state,
             canon_state_notification_sender,
+            allowed_reorgs: Mutex::default(),
         };
 
         Self { inner: Arc::new(inner) }
@@ -379,6 +386,25 @@ impl CanonicalInMemoryState {
         self.inner.chain_info_tracker.get_finalized_num_hash()
     }
 
+    /// Allows a reorg to the given head even if it reverts the safe block.
+    ///
+    /// The permission expires if no reorg to the head happens within 10 minutes.
+    pub fn allow_reorg(&self, head: B256) {
+        let mut allowed_reorgs = self.inner.allowed_reorgs.lock();
+        let now = Instant::now();
+        allowed_reorgs.retain(|_, allowed_at| now - *allowed_at < ALLOWED_REORG_TIMEOUT);
+        allowed_reorgs.insert(head, now);
+    }
+
+    /// Consumes the permission to reorg to the given head, returning whether it was allowed and
+    /// did not expire yet.
+    pub fn take_allowed_reorg(&self, head: B256) -> bool {
+        let mut allowed_reorgs = self.inner.allowed_reorgs.lock();
+        let now = Instant::now();
+        allowed_reorgs.retain(|_, allowed_at| now - *allowed_at < ALLOWED_REORG_TIMEOUT);
+        allowed_reorgs.remove(&head).is_some()
+    }
+
     /// Hook for new fork choice update.
     pub fn on_forkchoice_update_received(&self) {
         self.inner.chain_info_tracker.on_forkchoice_update_received();
@@ -785,7 +811,8 @@ impl NewCanonicalChain {
                     );
                     chain
                 }));
-                CanonStateNotification::Reorg { new, old }
+                let info = ReorgInfo::new(old.len() as u64);
+                CanonStateNotification::Reorg { new, old, info }
             }
         }
     }
@@ -1390,8 +1417,25 @@ mod tests {
                     vec![block1a.sealed_block_with_senders(), block2a.sealed_block_with_senders()],
                     sample_execution_outcome,
                     None
-                ))
+                )),
+                info: ReorgInfo::new(2),
             }
         );
     }
+
+    #[test]
+    fn test_allowed_reorg_is_consumed_and_expires() {
+        let state = CanonicalInMemoryState::empty();
+        let head = B256::random();
+        assert!(!state.take_allowed_reorg(head));
+
+        state.allow_reorg(head);
+        assert!(state.take_allowed_reorg(head));
+        assert!(!state.take_allowed_reorg(head));
+
+        // expired permissions are dropped
+        state.inner.allowed_reorgs.lock().insert(head, Instant::now() - ALLOWED_REORG_TIMEOUT);
+        assert!(!state.take_allowed_reorg(head));
+        assert!(state.inner.allowed_reorgs.lock().is_empty());
+    }
 }
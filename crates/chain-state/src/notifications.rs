@@ -88,9 +88,30 @@ pub enum CanonStateNotification {
         ///
         /// In the case of a revert, not a reorg, this chain segment is empty.
         new: Arc<Chain>,
+        /// How the reorg was handled.
+        info: ReorgInfo,
     },
 }
 
+/// Details of a reorg of the canonical chain.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReorgInfo {
+    /// The number of canonical blocks the reorg reverted.
+    pub depth: u64,
+    /// Whether the reorg is deeper than the configured reorg alert depth.
+    pub alerted: bool,
+    /// Whether the reorg reverted the safe block, and was applied because it was explicitly
+    /// allowed.
+    pub allowed_below_safe_block: bool,
+}
+
+impl ReorgInfo {
+    /// Creates the details of a reorg of the given depth that did not trigger any guardrails.
+    pub const fn new(depth: u64) -> Self {
+        Self { depth, alerted: false, allowed_below_safe_block: false }
+    }
+}
+
 impl CanonStateNotification {
     /// Get the chain segment that was reverted, if any.
     pub fn reverted(&self) -> Option<Arc<Chain>> {
@@ -100,6 +121,24 @@ impl CanonStateNotification {
         }
     }
 
+    /// Get the details of the reorg, if any.
+    ///
+    /// This is `None` for [`Self::Commit`].
+    pub const fn reorg_info(&self) -> Option<ReorgInfo> {
+        match self {
+            Self::Commit { .. } => None,
+            Self::Reorg { info, .. } => Some(*info),
+        }
+    }
+
+    /// Sets the details of the reorg, if this is a [`Self::Reorg`].
+    pub fn with_reorg_info(mut self, reorg_info: ReorgInfo) -> Self {
+        if let Self::Reorg { info, .. } = &mut self {
+            *info = reorg_info;
+        }
+        self
+    }
+
     /// Get the newly imported chain segment, if any.
     pub fn committed(&self) -> Arc<Chain> {
         match self {
@@ -1,6 +1,6 @@
 use crate::{
     in_memory::ExecutedBlock, CanonStateNotification, CanonStateNotifications,
-    CanonStateSubscriptions,
+    CanonStateSubscriptions, ReorgInfo,
 };
 use alloy_signer::SignerSync;
 use alloy_signer_local::PrivateKeySigner;
@@ -307,7 +307,8 @@ impl TestCanonStateSubscriptions {
     /// Adds reorg to the queue that can be consumed with
     /// [`TestCanonStateSubscriptions::subscribe_to_canonical_state`]
     pub fn add_next_reorg(&self, old: Arc<Chain>, new: Arc<Chain>) {
-        let event = CanonStateNotification::Reorg { old, new };
+        let info = ReorgInfo::new(old.len() as u64);
+        let event = CanonStateNotification::Reorg { old, new, info };
         self.canon_notif_tx.lock().as_mut().unwrap().retain(|tx| tx.send(event.clone()).is_ok())
     }
 }
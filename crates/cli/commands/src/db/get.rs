@@ -1,7 +1,9 @@
 use clap::Parser;
 use reth_db::{
-    static_file::{ColumnSelectorOne, ColumnSelectorTwo, HeaderMask, ReceiptMask, TransactionMask},
-    tables, RawKey, RawTable, Receipts, TableViewer, Transactions,
+    static_file::{
+        ColumnSelectorOne, ColumnSelectorTwo, HeaderMask, ReceiptMask, SenderMask, TransactionMask,
+    },
+    tables, RawKey, RawTable, Receipts, TableViewer, TransactionSenders, Transactions,
 };
 use reth_db_api::{
     database::Database,
@@ -72,6 +74,10 @@ impl Command {
                         table_key::<tables::Receipts>(&key)?,
                         <ReceiptMask<<Receipts as Table>::Value>>::MASK,
                     ),
+                    StaticFileSegment::Senders => (
+                        table_key::<tables::TransactionSenders>(&key)?,
+                        <SenderMask<<TransactionSenders as Table>::Value>>::MASK,
+                    ),
                 };
 
                 let content = tool.provider_factory.static_file_provider().find_static_file(
@@ -113,6 +119,13 @@ impl Command {
                                     )?;
                                     println!("{}", serde_json::to_string_pretty(&receipt)?);
                                 }
+                                StaticFileSegment::Senders => {
+                                    let sender =
+                                        <<TransactionSenders as Table>::Value>::decompress(
+                                            content[0].as_slice(),
+                                        )?;
+                                    println!("{}", serde_json::to_string_pretty(&sender)?);
+                                }
                             }
                         }
                     }
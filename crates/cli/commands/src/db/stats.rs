@@ -4,14 +4,24 @@ use comfy_table::{Cell, Row, Table as ComfyTable};
 use eyre::WrapErr;
 use human_bytes::human_bytes;
 use itertools::Itertools;
-use reth_db::{mdbx, static_file::iter_static_files, DatabaseEnv, TableViewer, Tables};
-use reth_db_api::database::Database;
+use reth_db::{
+    mdbx, static_file::iter_static_files, tables, DatabaseEnv, RawDupSort, RawTable, TableViewer,
+    Tables,
+};
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    table::{DupSort, Table},
+    transaction::DbTx,
+};
 use reth_db_common::DbTool;
 use reth_fs_util as fs;
 use reth_node_core::dirs::{ChainPath, DataDirPath};
+use reth_primitives::Address;
 use reth_provider::providers::StaticFileProvider;
 use reth_static_file_types::{find_fixed_range, SegmentRangeInclusive};
-use std::{sync::Arc, time::Duration};
+use serde::Serialize;
+use std::{collections::HashMap, sync::Arc, thread, time::Duration};
 
 #[derive(Parser, Debug)]
 /// The arguments for the `reth db stats` command
@@ -32,6 +42,22 @@ pub struct Command {
     /// For individual table checksums, use the `reth db checksum` command.
     #[arg(long, default_value_t = false)]
     checksum: bool,
+
+    /// Show the addresses with the most entries in the `PlainStorageState`, `StorageChangeSets`
+    /// and `StoragesHistory` tables, instead of the table sizes.
+    ///
+    /// WARNING: this option will take a long time to run, as it needs to traverse the storage
+    /// tables.
+    #[arg(long, default_value_t = false)]
+    by_address: bool,
+
+    /// Number of addresses to show per table when using `--by-address`.
+    #[arg(long, default_value_t = 20, requires = "by_address")]
+    top: usize,
+
+    /// Print the `--by-address` report as JSON.
+    #[arg(long, default_value_t = false, requires = "by_address")]
+    json: bool,
 }
 
 impl Command {
@@ -41,6 +67,16 @@ impl Command {
         data_dir: ChainPath<DataDirPath>,
         tool: &DbTool<Arc<DatabaseEnv>>,
     ) -> eyre::Result<()> {
+        if self.by_address {
+            let report = self.address_stats_report(tool)?;
+            if self.json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("{}", Self::address_stats_table(&report));
+            }
+            return Ok(())
+        }
+
         if self.checksum {
             let checksum_report = self.checksum_report(tool)?;
             println!("{checksum_report}");
@@ -306,6 +342,92 @@ impl Command {
         Ok(table)
     }
 
+    /// Walks the storage tables, each in its own read transaction and thread, and collects the top
+    /// addresses of each table.
+    fn address_stats_report(
+        &self,
+        tool: &DbTool<Arc<DatabaseEnv>>,
+    ) -> eyre::Result<Vec<TableAddressStats>> {
+        let db = tool.provider_factory.db_ref();
+
+        thread::scope(|scope| {
+            let handles = [
+                scope.spawn(|| {
+                    dup_table_address_stats::<tables::PlainStorageState>(db, self.top, |address| {
+                        address
+                    })
+                }),
+                scope.spawn(|| {
+                    dup_table_address_stats::<tables::StorageChangeSets>(db, self.top, |key| {
+                        key.address()
+                    })
+                }),
+                scope.spawn(|| {
+                    table_address_stats::<tables::StoragesHistory>(db, self.top, |key| key.address)
+                }),
+            ];
+
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle.join().map_err(|_| eyre::eyre!("address stats thread panicked"))?
+                })
+                .collect()
+        })
+    }
+
+    fn address_stats_table(report: &[TableAddressStats]) -> ComfyTable {
+        let mut table = ComfyTable::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        table.set_header([
+            "Table Name",
+            "Address",
+            "# Keys",
+            "# Entries",
+            "% Entries",
+            "Estimated Size",
+        ]);
+
+        for (i, table_stats) in report.iter().enumerate() {
+            if i > 0 {
+                let max_widths = table.column_max_content_widths();
+                let mut separator = Row::new();
+                for width in max_widths {
+                    separator.add_cell(Cell::new("-".repeat(width as usize)));
+                }
+                table.add_row(separator);
+            }
+
+            for address_stats in &table_stats.top {
+                let share = if table_stats.total_entries == 0 {
+                    0.0
+                } else {
+                    address_stats.entries as f64 * 100.0 / table_stats.total_entries as f64
+                };
+
+                let mut row = Row::new();
+                row.add_cell(Cell::new(table_stats.table))
+                    .add_cell(Cell::new(address_stats.address))
+                    .add_cell(Cell::new(address_stats.keys))
+                    .add_cell(Cell::new(address_stats.entries))
+                    .add_cell(Cell::new(format!("{share:.2}%")))
+                    .add_cell(Cell::new(human_bytes(address_stats.bytes as f64)));
+                table.add_row(row);
+            }
+
+            let mut row = Row::new();
+            row.add_cell(Cell::new(table_stats.table))
+                .add_cell(Cell::new(format!("Total ({} addresses)", table_stats.total_addresses)))
+                .add_cell(Cell::new(table_stats.total_keys))
+                .add_cell(Cell::new(table_stats.total_entries))
+                .add_cell(Cell::new(""))
+                .add_cell(Cell::new(human_bytes(table_stats.total_bytes as f64)));
+            table.add_row(row);
+        }
+
+        table
+    }
+
     fn checksum_report(&self, tool: &DbTool<Arc<DatabaseEnv>>) -> eyre::Result<ComfyTable> {
         let mut table = ComfyTable::new();
         table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
@@ -346,3 +468,161 @@ impl Command {
         Ok(table)
     }
 }
+
+/// Number of keys, entries and estimated size of a single address in a table.
+#[derive(Debug, Serialize)]
+struct AddressStats {
+    address: Address,
+    /// Number of distinct keys, which is lower than the number of entries for `DupSort` tables.
+    keys: u64,
+    entries: u64,
+    /// Sum of the encoded key and value sizes of all entries.
+    bytes: u64,
+}
+
+/// Top addresses of a table, as reported by `reth db stats --by-address`.
+#[derive(Debug, Serialize)]
+struct TableAddressStats {
+    table: &'static str,
+    total_addresses: usize,
+    total_keys: u64,
+    total_entries: u64,
+    total_bytes: u64,
+    top: Vec<AddressStats>,
+}
+
+/// Accumulates the [`AddressStats`] of a table.
+#[derive(Debug, Default)]
+struct AddressStatsCollector {
+    addresses: HashMap<Address, AddressStats>,
+    total_keys: u64,
+    total_entries: u64,
+    total_bytes: u64,
+}
+
+impl AddressStatsCollector {
+    /// Records an entry of `address`, which starts a new key if `new_key` is true.
+    fn record(&mut self, address: Address, bytes: u64, new_key: bool) {
+        let stats = self.addresses.entry(address).or_insert(AddressStats {
+            address,
+            keys: 0,
+            entries: 0,
+            bytes: 0,
+        });
+        stats.keys += new_key as u64;
+        stats.entries += 1;
+        stats.bytes += bytes;
+
+        self.total_keys += new_key as u64;
+        self.total_entries += 1;
+        self.total_bytes += bytes;
+    }
+
+    /// Returns the `top` addresses by number of entries.
+    fn finish(self, table: &'static str, top: usize) -> TableAddressStats {
+        let total_addresses = self.addresses.len();
+        let top = self
+            .addresses
+            .into_values()
+            .sorted_unstable_by(|a, b| b.entries.cmp(&a.entries).then(b.bytes.cmp(&a.bytes)))
+            .take(top)
+            .collect();
+
+        TableAddressStats {
+            table,
+            total_addresses,
+            total_keys: self.total_keys,
+            total_entries: self.total_entries,
+            total_bytes: self.total_bytes,
+            top,
+        }
+    }
+}
+
+/// Walks the table `T` in a new read transaction and returns the `top` addresses by number of
+/// entries, using `address` to extract the address from a key.
+fn table_address_stats<T: Table>(
+    db: &DatabaseEnv,
+    top: usize,
+    address: impl Fn(T::Key) -> Address,
+) -> eyre::Result<TableAddressStats> {
+    let mut tx = db.tx()?;
+    tx.disable_long_read_transaction_safety();
+
+    let mut collector = AddressStatsCollector::default();
+    let mut cursor = tx.cursor_read::<RawTable<T>>()?;
+    for entry in cursor.walk(None)? {
+        let (key, value) = entry?;
+        let bytes = (key.raw_key().len() + value.raw_value().len()) as u64;
+        collector.record(address(key.key()?), bytes, true);
+    }
+
+    Ok(collector.finish(T::NAME, top))
+}
+
+/// Like [`table_address_stats`], but walks the `DupSort` table `T` with a dup cursor, so that the
+/// keys are counted once and not once per duplicate entry.
+fn dup_table_address_stats<T: DupSort>(
+    db: &DatabaseEnv,
+    top: usize,
+    address: impl Fn(T::Key) -> Address,
+) -> eyre::Result<TableAddressStats> {
+    let mut tx = db.tx()?;
+    tx.disable_long_read_transaction_safety();
+
+    let mut collector = AddressStatsCollector::default();
+    let mut cursor = tx.cursor_dup_read::<RawDupSort<T>>()?;
+    let mut next = cursor.first()?;
+    while let Some((key, value)) = next {
+        let key_address = address(key.key()?);
+        let mut bytes = (key.raw_key().len() + value.raw_value().len()) as u64;
+        collector.record(key_address, bytes, true);
+
+        // the remaining entries of the same key
+        while let Some((key, value)) = cursor.next_dup()? {
+            bytes = (key.raw_key().len() + value.raw_value().len()) as u64;
+            collector.record(key_address, bytes, false);
+        }
+
+        next = cursor.next_no_dup()?;
+    }
+
+    Ok(collector.finish(T::NAME, top))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::test_utils::create_test_rw_db;
+    use reth_db_api::transaction::DbTxMut;
+    use reth_primitives::{StorageEntry, B256, U256};
+
+    #[test]
+    fn dup_table_counts_distinct_keys() {
+        let db = create_test_rw_db();
+        let (first, second) = (Address::with_last_byte(1), Address::with_last_byte(2));
+
+        let tx = db.tx_mut().unwrap();
+        for slot in 0..3 {
+            let entry = StorageEntry { key: B256::with_last_byte(slot), value: U256::from(1) };
+            tx.put::<tables::PlainStorageState>(first, entry).unwrap();
+        }
+        tx.put::<tables::PlainStorageState>(
+            second,
+            StorageEntry { key: B256::ZERO, value: U256::from(1) },
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let stats =
+            dup_table_address_stats::<tables::PlainStorageState>(db.db(), 10, |address| address)
+                .unwrap();
+        assert_eq!(stats.total_addresses, 2);
+        assert_eq!(stats.total_keys, 2);
+        assert_eq!(stats.total_entries, 4);
+        assert_eq!(
+            stats.top.iter().map(|stats| (stats.address, stats.keys, stats.entries)).collect_vec(),
+            vec![(first, 1, 3), (second, 1, 1)]
+        );
+    }
+}
@@ -0,0 +1,443 @@
+//! Crawl subcommand of P2P Debugging tool.
+
+use clap::Parser;
+use futures::{stream::FuturesUnordered, Future, Stream, StreamExt};
+use reth_cli_util::parse_duration_from_secs;
+use reth_ecies::stream::ECIESStream;
+use reth_eth_wire::{
+    errors::{EthHandshakeError, EthStreamError},
+    BlockRangeUpdate, CanDisconnect, DisconnectReason, HelloMessageWithProtocols, Status,
+    StatusMessage, UnauthedEthStream, UnauthedP2PStream,
+};
+use reth_network::{DiscoveredEvent, DiscoveryEvent, NetworkConfig, NetworkHandle};
+use reth_network_peers::PeerId;
+use reth_primitives::{hex, BlockNumber, ForkFilter, B256, U256};
+use secp256k1::SecretKey;
+use serde::Serialize;
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::Display,
+    fs::OpenOptions,
+    io::{self, Write},
+    net::SocketAddr,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::net::TcpStream;
+use tracing::{debug, info};
+
+/// Interval of the crawl progress reports.
+const REPORT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Timeout of the TCP connection to a discovered node, the handshakes have their own timeouts.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Crawls the network and records the outcome of the handshakes with every discovered node.
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// File to append the crawled nodes to, one JSON object per line.
+    #[arg(long, value_name = "FILE")]
+    output: PathBuf,
+
+    /// Stop crawling after this many seconds, crawls until interrupted if not set.
+    #[arg(long, value_name = "SECONDS", value_parser = parse_duration_from_secs)]
+    duration: Option<Duration>,
+
+    /// Maximum number of discovered nodes to dial at the same time.
+    #[arg(long, value_name = "COUNT", default_value_t = 32)]
+    max_concurrent_dials: usize,
+}
+
+impl Command {
+    /// Execute `p2p crawl` command.
+    ///
+    /// The network only runs discovery, every discovered node is dialed by the crawler itself.
+    pub async fn execute(self, network: NetworkHandle, handshake: Handshake) -> eyre::Result<()> {
+        let file = OpenOptions::new().create(true).append(true).open(&self.output)?;
+        info!(target: "reth::cli", path = %self.output.display(), "Crawling network");
+
+        let nodes = network.discovery_listener().filter_map(|event| async move {
+            match event {
+                DiscoveryEvent::NewNode(DiscoveredEvent::EventQueued { peer_id, addr, .. }) => {
+                    Some((peer_id, addr.tcp()))
+                }
+                _ => None,
+            }
+        });
+        let crawl = crawl(
+            Box::pin(nodes),
+            |peer_id, remote_addr| handshake.dial(peer_id, remote_addr),
+            self.max_concurrent_dials.max(1),
+            file,
+        );
+        match self.duration {
+            Some(duration) => match tokio::time::timeout(duration, crawl).await {
+                Ok(res) => res,
+                Err(_) => {
+                    info!(target: "reth::cli", "Crawl duration elapsed");
+                    Ok(())
+                }
+            },
+            None => crawl.await,
+        }
+    }
+}
+
+/// Dials every newly discovered node once, at most `max_concurrent_dials` at a time, and appends
+/// the outcome of the handshakes with it to `out`.
+///
+/// Returns once the stream of discovered nodes ends and all dials completed.
+async fn crawl<N, D, F, W>(
+    nodes: N,
+    dial: D,
+    max_concurrent_dials: usize,
+    mut out: W,
+) -> eyre::Result<()>
+where
+    N: Stream<Item = (PeerId, SocketAddr)> + Unpin,
+    D: Fn(PeerId, SocketAddr) -> F,
+    F: Future<Output = CrawlOutcome>,
+    W: Write,
+{
+    let mut nodes = nodes.fuse();
+    let mut report = tokio::time::interval(REPORT_INTERVAL);
+
+    let mut discovered = HashSet::new();
+    let mut queued = VecDeque::new();
+    let mut dials = FuturesUnordered::new();
+    let (mut connected, mut failed) = (0usize, 0usize);
+
+    loop {
+        while dials.len() < max_concurrent_dials {
+            let Some((peer_id, remote_addr)) = queued.pop_front() else { break };
+            let outcome = dial(peer_id, remote_addr);
+            dials.push(async move { (peer_id, remote_addr, outcome.await) });
+        }
+
+        if nodes.is_done() && dials.is_empty() {
+            return Ok(())
+        }
+
+        tokio::select! {
+            Some((peer_id, remote_addr)) = nodes.next() => {
+                if discovered.insert(peer_id) {
+                    queued.push_back((peer_id, remote_addr));
+                }
+            }
+            Some((peer_id, remote_addr, outcome)) = dials.next() => {
+                match &outcome {
+                    CrawlOutcome::Connected(_) => connected += 1,
+                    CrawlOutcome::Failed(failure) => {
+                        debug!(target: "reth::cli", ?peer_id, %remote_addr, ?failure, "Crawl failed");
+                        failed += 1
+                    }
+                }
+                let node = CrawledNode {
+                    timestamp: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs(),
+                    peer_id,
+                    remote_addr,
+                    outcome,
+                };
+                // flush every record, so an interrupted crawl keeps everything recorded
+                writeln!(out, "{}", serde_json::to_string(&node)?)?;
+                out.flush()?;
+            }
+            _ = report.tick() => {
+                info!(
+                    target: "reth::cli",
+                    discovered = discovered.len(),
+                    queued = queued.len(),
+                    dialing = dials.len(),
+                    connected,
+                    failed,
+                    "Crawl status"
+                );
+            }
+        }
+    }
+}
+
+/// The local side of the handshakes performed with every crawled node.
+#[derive(Debug, Clone)]
+pub struct Handshake {
+    secret_key: SecretKey,
+    hello: HelloMessageWithProtocols,
+    status: Status,
+    block_range: BlockRangeUpdate,
+    fork_filter: ForkFilter,
+}
+
+impl Handshake {
+    /// Creates the handshake from the configuration of the crawling network.
+    ///
+    /// The crawler doesn't serve any blocks, so it announces the head of its status as the only
+    /// block in its range.
+    pub fn new<C>(config: &NetworkConfig<C>) -> Self {
+        Self {
+            secret_key: config.secret_key,
+            hello: config.hello_message.clone(),
+            status: config.status,
+            block_range: BlockRangeUpdate {
+                earliest: 0,
+                latest: 0,
+                latest_hash: config.status.blockhash,
+            },
+            fork_filter: config.fork_filter.clone(),
+        }
+    }
+
+    /// Dials the node and performs the RLPx, `Hello` and eth `Status` handshakes with it.
+    async fn dial(&self, peer_id: PeerId, remote_addr: SocketAddr) -> CrawlOutcome {
+        match self.try_dial(peer_id, remote_addr).await {
+            Ok(session) => CrawlOutcome::Connected(session),
+            Err(failure) => CrawlOutcome::Failed(failure),
+        }
+    }
+
+    async fn try_dial(
+        &self,
+        peer_id: PeerId,
+        remote_addr: SocketAddr,
+    ) -> Result<CrawledSession, CrawlFailure> {
+        let stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(remote_addr))
+            .await
+            .unwrap_or_else(|_| Err(io::ErrorKind::TimedOut.into()))
+            .map_err(|err| CrawlFailure::new(FailureStage::Connect, err, None))?;
+        let stream = ECIESStream::connect(stream, self.secret_key, peer_id)
+            .await
+            .map_err(|err| CrawlFailure::new(FailureStage::Ecies, err, None))?;
+
+        let (p2p_stream, their_hello) = UnauthedP2PStream::new(stream)
+            .handshake(self.hello.clone())
+            .await
+            .map_err(|err| CrawlFailure::new(FailureStage::Hello, err, None))?;
+        let client_version = their_hello.client_version;
+        let capabilities = their_hello.capabilities.iter().map(ToString::to_string).collect();
+
+        let eth_version = p2p_stream.shared_capabilities().eth_version().map_err(|err| {
+            CrawlFailure::new(FailureStage::Hello, err, Some(client_version.clone()))
+        })?;
+        let status = self.status.into_message(eth_version, self.block_range);
+        let (mut eth_stream, their_status) = UnauthedEthStream::new(p2p_stream)
+            .handshake(status, self.fork_filter.clone())
+            .await
+            .map_err(|err| {
+                let stage = match err {
+                    EthStreamError::EthHandshakeError(EthHandshakeError::InvalidFork(_)) => {
+                        FailureStage::ForkId
+                    }
+                    _ => FailureStage::Status,
+                };
+                CrawlFailure::new(stage, err, Some(client_version.clone()))
+            })?;
+        let _ = eth_stream.disconnect(DisconnectReason::ClientQuitting).await;
+
+        Ok(CrawledSession {
+            client_version,
+            capabilities,
+            eth_version: eth_version as u8,
+            status: CrawledStatus::new(&their_status),
+        })
+    }
+}
+
+/// The outcome of the handshakes with a crawled node.
+#[derive(Debug, Serialize)]
+struct CrawledNode {
+    /// Unix timestamp of the dial, in seconds.
+    timestamp: u64,
+    peer_id: PeerId,
+    remote_addr: SocketAddr,
+    #[serde(flatten)]
+    outcome: CrawlOutcome,
+}
+
+/// Whether the handshakes with a crawled node succeeded.
+#[derive(Debug, Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+enum CrawlOutcome {
+    /// The node completed the RLPx and eth handshakes.
+    Connected(CrawledSession),
+    /// The node could not be connected to or failed one of the handshakes.
+    Failed(CrawlFailure),
+}
+
+/// A session established with a crawled node.
+#[derive(Debug, Serialize)]
+struct CrawledSession {
+    /// Client version from the node's `Hello` message.
+    client_version: String,
+    /// Capabilities from the node's `Hello` message.
+    capabilities: Vec<String>,
+    /// The negotiated eth version.
+    eth_version: u8,
+    #[serde(flatten)]
+    status: CrawledStatus,
+}
+
+/// A failed attempt to establish a session with a crawled node.
+#[derive(Debug, Serialize)]
+struct CrawlFailure {
+    stage: FailureStage,
+    error: String,
+    /// Client version from the node's `Hello` message, if the `Hello` handshake succeeded.
+    client_version: Option<String>,
+}
+
+impl CrawlFailure {
+    fn new(stage: FailureStage, error: impl Display, client_version: Option<String>) -> Self {
+        Self { stage, error: error.to_string(), client_version }
+    }
+}
+
+/// The step of the dial that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum FailureStage {
+    /// The TCP connection could not be established.
+    Connect,
+    /// The RLPx (ECIES) handshake failed.
+    Ecies,
+    /// The `Hello` handshake failed or no eth version is shared.
+    Hello,
+    /// The eth `Status` handshake failed for a reason other than the fork id.
+    Status,
+    /// The node's fork id is incompatible with the local chain.
+    ForkId,
+}
+
+/// The status a crawled node sent in the eth handshake.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct CrawledStatus {
+    chain_id: u64,
+    genesis: B256,
+    /// Hash of the node's head block, or the latest block it can serve for `eth/69`.
+    head: B256,
+    /// Total difficulty of the node's head block, only sent before `eth/69`.
+    total_difficulty: Option<U256>,
+    /// Earliest block the node can serve, only sent since `eth/69`.
+    earliest_block: Option<BlockNumber>,
+    /// Latest block the node can serve, only sent since `eth/69`.
+    latest_block: Option<BlockNumber>,
+    fork_hash: String,
+    fork_next: u64,
+}
+
+impl CrawledStatus {
+    fn new(status: &StatusMessage) -> Self {
+        let (head, total_difficulty) = match status {
+            StatusMessage::Legacy(status) => (status.blockhash, Some(status.total_difficulty)),
+            StatusMessage::Eth69(status) => (status.blockhash, None),
+        };
+        let block_range = status.block_range();
+        let forkid = status.forkid();
+        Self {
+            chain_id: status.chain().id(),
+            genesis: status.genesis(),
+            head,
+            total_difficulty,
+            earliest_block: block_range.map(|range| range.earliest),
+            latest_block: block_range.map(|range| range.latest),
+            fork_hash: hex::encode_prefixed(forkid.hash.0),
+            fork_next: forkid.next,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_eth_wire::StatusEth69;
+
+    #[test]
+    fn crawled_status_variants() {
+        let legacy = Status::default();
+        let status = CrawledStatus::new(&StatusMessage::Legacy(legacy));
+        assert_eq!(status.total_difficulty, Some(legacy.total_difficulty));
+        assert_eq!((status.earliest_block, status.latest_block), (None, None));
+
+        let eth69 = StatusMessage::Eth69(StatusEth69 {
+            version: 69,
+            chain: legacy.chain,
+            genesis: legacy.genesis,
+            forkid: legacy.forkid,
+            earliest: 100,
+            latest: 200,
+            blockhash: B256::with_last_byte(1),
+        });
+        let status = CrawledStatus::new(&eth69);
+        assert_eq!(status.total_difficulty, None);
+        assert_eq!((status.earliest_block, status.latest_block), (Some(100), Some(200)));
+        assert_eq!(status.head, B256::with_last_byte(1));
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert!(json["total_difficulty"].is_null());
+        assert_eq!(json["earliest_block"], 100);
+        assert_eq!(json["latest_block"], 200);
+    }
+
+    #[tokio::test]
+    async fn crawl_records_every_node_once() {
+        let connected = PeerId::with_last_byte(1);
+        let forked = PeerId::with_last_byte(2);
+        let unreachable = PeerId::with_last_byte(3);
+        let remote_addr = SocketAddr::from(([127, 0, 0, 1], 30303));
+
+        // the first node is discovered twice, but must only be dialed once
+        let nodes = futures::stream::iter(
+            [connected, forked, connected, unreachable].map(|peer_id| (peer_id, remote_addr)),
+        );
+        let dial = move |peer_id: PeerId, _: SocketAddr| async move {
+            if peer_id == connected {
+                CrawlOutcome::Connected(CrawledSession {
+                    client_version: "reth/v1.0.0".to_string(),
+                    capabilities: vec!["eth/68".to_string()],
+                    eth_version: 68,
+                    status: CrawledStatus::new(&StatusMessage::Legacy(Status::default())),
+                })
+            } else if peer_id == forked {
+                CrawlOutcome::Failed(CrawlFailure::new(
+                    FailureStage::ForkId,
+                    "fork id mismatch",
+                    Some("geth/v1.14.0".to_string()),
+                ))
+            } else {
+                CrawlOutcome::Failed(CrawlFailure::new(FailureStage::Connect, "refused", None))
+            }
+        };
+
+        let mut out = Vec::new();
+        crawl(nodes, dial, 2, &mut out).await.unwrap();
+
+        let records = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 3);
+        let records = records
+            .into_iter()
+            .map(|record| (record["peer_id"].as_str().unwrap().parse::<PeerId>().unwrap(), record))
+            .collect::<std::collections::HashMap<_, _>>();
+        assert_eq!(records.len(), 3);
+
+        let record = &records[&connected];
+        assert_eq!(record["result"], "connected");
+        assert_eq!(record["client_version"], "reth/v1.0.0");
+        assert_eq!(record["eth_version"], 68);
+        assert_eq!(record["remote_addr"], "127.0.0.1:30303");
+
+        let record = &records[&forked];
+        assert_eq!(record["result"], "failed");
+        assert_eq!(record["stage"], "fork_id");
+        assert_eq!(record["client_version"], "geth/v1.14.0");
+
+        let record = &records[&unreachable];
+        assert_eq!(record["result"], "failed");
+        assert_eq!(record["stage"], "connect");
+        assert!(record["client_version"].is_null());
+    }
+}
@@ -0,0 +1,100 @@
+//! DNS tree subcommand of P2P Debugging tool.
+
+use clap::{Parser, ValueEnum};
+use reth_dns_discovery::{tree::LinkEntry, DnsTree};
+use reth_network_peers::Enr;
+use secp256k1::{SecretKey, SECP256K1};
+use std::{path::PathBuf, str::FromStr};
+use tracing::info;
+
+/// Builds and signs an EIP-1459 DNS discovery tree.
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The domain the tree is published at.
+    #[arg(long, value_name = "DOMAIN")]
+    domain: String,
+
+    /// Node record to include in the tree, in base64 `enr:` form.
+    #[arg(long = "enr", value_name = "ENR")]
+    enrs: Vec<Enr<SecretKey>>,
+
+    /// File with node records to include in the tree, one per line.
+    ///
+    /// Empty lines and lines starting with `#` are ignored.
+    #[arg(long, value_name = "FILE")]
+    enrs_file: Option<PathBuf>,
+
+    /// Link to another tree to include, in `enrtree://<public key>@<domain>` form.
+    #[arg(long = "link", value_name = "LINK")]
+    links: Vec<LinkEntry>,
+
+    /// The sequence number of the tree.
+    ///
+    /// Must be increased on every update of a published tree.
+    #[arg(long, default_value_t = 1)]
+    sequence: u64,
+
+    /// File with the hex encoded secret key to sign the tree with.
+    #[arg(long, value_name = "FILE")]
+    key: PathBuf,
+
+    /// The output format of the records.
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+
+    /// The TTL of the records in the zone file.
+    #[arg(long, default_value_t = 3600)]
+    ttl: u32,
+
+    /// File to write the records to, instead of stdout.
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+/// Output format of the tree records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// JSON object of the TXT records by name.
+    Json,
+    /// Zone file.
+    Zone,
+}
+
+impl Command {
+    /// Execute `p2p dns-tree` command.
+    pub fn execute(&self) -> eyre::Result<()> {
+        let key = reth_fs_util::read_to_string(&self.key)?;
+        let key = SecretKey::from_str(key.trim())
+            .map_err(|err| eyre::eyre!("invalid secret key in {}: {err}", self.key.display()))?;
+
+        let mut enrs = self.enrs.clone();
+        if let Some(path) = &self.enrs_file {
+            for line in reth_fs_util::read_to_string(path)?.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue
+                }
+                enrs.push(
+                    line.parse().map_err(|err| eyre::eyre!("invalid node record {line}: {err}"))?,
+                );
+            }
+        }
+
+        let mut tree = DnsTree::new(self.sequence, enrs, self.links.clone());
+        tree.sign(&key);
+
+        let output = match self.format {
+            Format::Json => serde_json::to_string_pretty(&tree.records(&self.domain))? + "\n",
+            Format::Zone => tree.to_zone_file(&self.domain, self.ttl),
+        };
+        match &self.output {
+            Some(path) => reth_fs_util::write(path, output)?,
+            None => print!("{output}"),
+        }
+
+        let link = LinkEntry { domain: self.domain.clone(), pubkey: key.public_key(SECP256K1) };
+        info!(target: "reth::cli", %link, "Signed DNS tree");
+
+        Ok(())
+    }
+}
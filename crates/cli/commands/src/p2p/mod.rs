@@ -7,7 +7,7 @@ use clap::{Parser, Subcommand};
 use reth_chainspec::ChainSpec;
 use reth_cli_util::{get_secret_key, hash_or_num_value_parser};
 use reth_config::Config;
-use reth_network::{BlockDownloaderProvider, NetworkConfigBuilder};
+use reth_network::{BlockDownloaderProvider, NetworkConfig, NetworkConfigBuilder, SessionsConfig};
 use reth_network_p2p::bodies::client::BodiesClient;
 use reth_node_core::{
     args::{
@@ -17,7 +17,11 @@ use reth_node_core::{
     utils::get_single_header,
 };
 use reth_primitives::BlockHashOrNumber;
+use reth_provider::noop::NoopBlockReader;
+use secp256k1::SecretKey;
 
+mod crawl;
+mod dns_tree;
 mod rlpx;
 
 /// `reth p2p` command
@@ -73,11 +77,20 @@ pub enum Subcommands {
     },
     // RLPx utilities
     Rlpx(rlpx::Command),
+    /// Build and sign an EIP-1459 DNS discovery tree
+    DnsTree(dns_tree::Command),
+    /// Crawl the network and record the peers that can be connected to
+    Crawl(crawl::Command),
 }
 
 impl Command {
     /// Execute `p2p` command
     pub async fn execute(self) -> eyre::Result<()> {
+        // building a tree doesn't need the network
+        if let Subcommands::DnsTree(command) = &self.command {
+            return command.execute()
+        }
+
         let data_dir = self.datadir.clone().resolve_datadir(self.chain.chain);
         let config_path = self.config.clone().unwrap_or_else(|| data_dir.config());
 
@@ -96,21 +109,10 @@ impl Command {
         let secret_key_path =
             self.network.p2p_secret_key.clone().unwrap_or(default_secret_key_path);
         let p2p_secret_key = get_secret_key(&secret_key_path)?;
-        let rlpx_socket = (self.network.addr, self.network.port).into();
-        let boot_nodes = self.chain.bootnodes().unwrap_or_default();
 
-        let net = NetworkConfigBuilder::new(p2p_secret_key)
-            .peer_config(config.peers_config_with_basic_nodes_from_file(None))
-            .external_ip_resolver(self.network.nat)
-            .chain_spec(self.chain.clone())
-            .disable_discv4_discovery_if(self.chain.chain.is_optimism())
-            .boot_nodes(boot_nodes.clone())
-            .apply(|builder| {
-                self.network.discovery.apply_to_builder(builder, rlpx_socket, boot_nodes)
-            })
-            .build_with_noop_provider()
-            .manager()
-            .await?;
+        let network_config = self.network_config(&config, p2p_secret_key);
+        let crawl_handshake = crawl::Handshake::new(&network_config);
+        let net = network_config.manager().await?;
         let network = net.handle().clone();
         tokio::task::spawn(net);
 
@@ -161,8 +163,79 @@ impl Command {
             Subcommands::Rlpx(command) => {
                 command.execute().await?;
             }
+            Subcommands::Crawl(command) => {
+                command.execute(network, crawl_handshake).await?;
+            }
+            Subcommands::DnsTree(_) => unreachable!("handled before the network is started"),
         }
 
         Ok(())
     }
+
+    /// Builds the [`NetworkConfig`] of the command's network.
+    fn network_config(
+        &self,
+        config: &Config,
+        p2p_secret_key: SecretKey,
+    ) -> NetworkConfig<NoopBlockReader> {
+        let rlpx_socket = (self.network.addr, self.network.port).into();
+        let boot_nodes = self.chain.bootnodes().unwrap_or_default();
+
+        let mut sessions_config = SessionsConfig::default();
+        if let Some(proxy) = &self.network.proxy {
+            sessions_config = sessions_config.with_proxy(proxy.clone());
+        }
+
+        let mut peers_config = config
+            .peers_config_with_basic_nodes_from_file(None)
+            .with_max_outbound_opt(self.network.max_outbound_peers);
+        let mut discovery = self.network.discovery.clone();
+        if matches!(self.command, Subcommands::Crawl(_)) {
+            // the crawler dials discovered nodes itself, the network only runs discovery
+            peers_config = peers_config.with_max_outbound(0).with_max_inbound(0);
+            if self.network.proxy.is_none() {
+                // crawls cover both discovery protocols
+                discovery.enable_discv5_discovery = true;
+            }
+        }
+
+        NetworkConfigBuilder::new(p2p_secret_key)
+            .peer_config(peers_config)
+            .external_ip_resolver(self.network.nat_resolver())
+            .sessions_config(sessions_config)
+            .chain_spec(self.chain.clone())
+            .disable_discv4_discovery_if(self.chain.chain.is_optimism())
+            .boot_nodes(boot_nodes.clone())
+            .apply(|builder| {
+                discovery
+                    .apply_to_builder(builder, rlpx_socket, boot_nodes)
+                    // discovery runs over UDP, which the proxy does not carry
+                    .disable_discovery_if(self.network.proxy.is_some())
+            })
+            .build_with_noop_provider()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_network::Socks5Proxy;
+
+    #[test]
+    fn network_config_uses_proxy() {
+        let proxy = "socks5://127.0.0.1:1080".parse::<Socks5Proxy>().unwrap();
+        let secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+
+        for subcommand in [&["header", "1"][..], &["crawl", "--output", "peers.json"][..]] {
+            let args =
+                ["reth", "--proxy", "socks5://127.0.0.1:1080"].iter().chain(subcommand).copied();
+            let command = Command::parse_from(args);
+            let config = command.network_config(&Config::default(), secret_key);
+
+            assert_eq!(config.sessions_config.proxy, Some(proxy.clone()));
+            assert!(config.discovery_v4_config.is_none());
+            assert!(config.discovery_v5_config.is_none());
+            assert!(config.dns_discovery_config.is_none());
+        }
+    }
 }
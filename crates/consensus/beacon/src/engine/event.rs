@@ -1,5 +1,5 @@
 use crate::engine::forkchoice::ForkchoiceStatus;
-use reth_primitives::{SealedBlock, SealedHeader, B256};
+use reth_primitives::{BlockNumHash, SealedBlock, SealedHeader, B256};
 use reth_rpc_types::engine::ForkchoiceState;
 use std::{sync::Arc, time::Duration};
 
@@ -16,6 +16,9 @@ pub enum BeaconConsensusEngineEvent {
     LiveSyncProgress(ConsensusEngineLiveSyncProgress),
     /// A block was added to the fork chain.
     ForkBlockAdded(Arc<SealedBlock>),
+    /// A reorg was requested that is deeper than the configured alert depth, or that was refused
+    /// because it would revert the safe block.
+    ReorgAlert(ReorgAlert),
 }
 
 impl BeaconConsensusEngineEvent {
@@ -29,6 +32,19 @@ impl BeaconConsensusEngineEvent {
     }
 }
 
+/// A reorg that triggered the reorg guardrails of the engine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReorgAlert {
+    /// The number of canonical blocks the reorg reverts.
+    pub depth: u64,
+    /// The canonical head before the reorg.
+    pub old_head: BlockNumHash,
+    /// The requested new head.
+    pub new_head: BlockNumHash,
+    /// Whether the reorg was refused because it would revert the safe block.
+    pub rejected: bool,
+}
+
 /// Progress of the consensus engine during live sync.
 #[derive(Clone, Debug)]
 pub enum ConsensusEngineLiveSyncProgress {
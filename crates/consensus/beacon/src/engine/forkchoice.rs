@@ -13,6 +13,9 @@ pub struct ForkchoiceStateTracker {
     last_syncing: Option<ForkchoiceState>,
     /// The latest valid forkchoice state that we received and processed as valid.
     last_valid: Option<ForkchoiceState>,
+    /// The latest forkchoice state whose reorg was refused because it would revert the safe
+    /// block.
+    last_refused_reorg: Option<ForkchoiceState>,
 }
 
 impl ForkchoiceStateTracker {
@@ -20,10 +23,13 @@ impl ForkchoiceStateTracker {
     ///
     /// If the status is `VALID`, we also update the last valid forkchoice state and set the
     /// `sync_target` to `None`, since we're now fully synced.
+    ///
+    /// A refused reorg is answered with `SYNCING`, but does not become the `sync_target`, since
+    /// its head is already known.
     pub fn set_latest(&mut self, state: ForkchoiceState, status: ForkchoiceStatus) {
         if status.is_valid() {
             self.set_valid(state);
-        } else if status.is_syncing() {
+        } else if status.is_syncing() && self.last_refused_reorg != Some(state) {
             self.last_syncing = Some(state);
         }
 
@@ -34,10 +40,22 @@ impl ForkchoiceStateTracker {
     fn set_valid(&mut self, state: ForkchoiceState) {
         // we no longer need to sync to this state.
         self.last_syncing = None;
+        self.last_refused_reorg = None;
 
         self.last_valid = Some(state);
     }
 
+    /// Records a forkchoice state whose reorg was refused because it would revert the safe block.
+    pub fn set_refused_reorg(&mut self, state: ForkchoiceState) {
+        self.last_refused_reorg = Some(state);
+    }
+
+    /// Returns the last received `ForkchoiceState` whose reorg was refused because it would
+    /// revert the safe block, if it was not followed by a valid forkchoice state.
+    pub const fn last_refused_reorg(&self) -> Option<ForkchoiceState> {
+        self.last_refused_reorg
+    }
+
     /// Returns the [`ForkchoiceStatus`] of the latest received FCU.
     ///
     /// Caution: this can be invalid.
@@ -101,6 +101,7 @@ impl<DB: Database + 'static> StaticFileHook<DB> {
                         headers: Some(finalized_block_number),
                         receipts: Some(finalized_block_number),
                         transactions: Some(finalized_block_number),
+                        senders: Some(finalized_block_number),
                     })?;
 
                 // Check if the moving data to static files has been requested.
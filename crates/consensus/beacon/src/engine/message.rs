@@ -13,6 +13,7 @@ use std::{
     task::{ready, Context, Poll},
 };
 use tokio::sync::oneshot;
+use tracing::Span;
 
 /// Represents the outcome of forkchoice update.
 ///
@@ -147,6 +148,9 @@ pub enum BeaconEngineMessage<Engine: EngineTypes> {
         cancun_fields: Option<CancunPayloadFields>,
         /// The sender for returning payload status result.
         tx: oneshot::Sender<Result<PayloadStatus, BeaconOnNewPayloadError>>,
+        /// The span of the Engine API request, so that processing the payload is traced as part
+        /// of the request.
+        span: Span,
     },
     /// Message with updated forkchoice state.
     ForkchoiceUpdated {
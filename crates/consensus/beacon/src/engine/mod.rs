@@ -56,7 +56,7 @@ mod invalid_headers;
 pub use invalid_headers::InvalidHeaderCache;
 
 mod event;
-pub use event::{BeaconConsensusEngineEvent, ConsensusEngineLiveSyncProgress};
+pub use event::{BeaconConsensusEngineEvent, ConsensusEngineLiveSyncProgress, ReorgAlert};
 
 mod handle;
 pub use handle::BeaconConsensusEngineHandle;
@@ -1859,8 +1859,8 @@ where
                         BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx } => {
                             this.on_forkchoice_updated(state, payload_attrs, tx);
                         }
-                        BeaconEngineMessage::NewPayload { payload, cancun_fields, tx } => {
-                            match this.on_new_payload(payload, cancun_fields) {
+                        BeaconEngineMessage::NewPayload { payload, cancun_fields, tx, span } => {
+                            match span.in_scope(|| this.on_new_payload(payload, cancun_fields)) {
                                 Ok(Either::Right(block)) => {
                                     this.set_blockchain_tree_action(
                                         BlockchainTreeAction::InsertNewPayload { block, tx },
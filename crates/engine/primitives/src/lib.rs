@@ -15,6 +15,9 @@ pub use reth_payload_primitives::{
 };
 use serde::{de::DeserializeOwned, ser::Serialize};
 
+mod payload_timings;
+pub use payload_timings::PayloadTimingsTracker;
+
 /// This type defines the versioned types of the engine API.
 ///
 /// This includes the execution payload types and payload attributes that are used to trigger a
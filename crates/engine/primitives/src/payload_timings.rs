@@ -0,0 +1,125 @@
+//! Tracking of the per-phase timings of the payloads processed by the engine.
+
+use alloy_primitives::{BlockNumber, B256};
+use parking_lot::Mutex;
+use reth_rpc_types::reth::PayloadTimings;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Instant,
+};
+
+/// Maximum number of payloads the timings are kept for.
+const PAYLOAD_TIMINGS_LIMIT: usize = 256;
+
+/// Tracks the [`PayloadTimings`] of the most recent payloads by block hash.
+///
+/// This is shared between the engine API, which records when a payload is received, and the
+/// engine tree, which records the time spent in the phases of processing it.
+#[derive(Debug, Clone, Default)]
+pub struct PayloadTimingsTracker {
+    inner: Arc<Mutex<PayloadTimingsInner>>,
+}
+
+impl PayloadTimingsTracker {
+    /// Records that the payload with the given block hash was received by the engine API.
+    pub fn on_received(&self, hash: B256) {
+        let mut inner = self.inner.lock();
+        inner.insert(hash).received_at = Some(Instant::now());
+    }
+
+    /// Records that the engine tree started processing the payload with the given block hash,
+    /// and returns the time the payload was received by the engine API, if known.
+    pub fn on_processing_started(&self, hash: B256, number: BlockNumber) -> Option<Instant> {
+        let mut inner = self.inner.lock();
+        let entry = inner.insert(hash);
+        entry.timings.block_number = number;
+        entry.received_at
+    }
+
+    /// Updates the timings of the payload with the given block hash, if it is tracked.
+    pub fn update(&self, hash: B256, f: impl FnOnce(&mut PayloadTimings)) {
+        if let Some(entry) = self.inner.lock().entries.get_mut(&hash) {
+            f(&mut entry.timings);
+        }
+    }
+
+    /// Returns the timings of the payload with the given block hash, if it is tracked.
+    pub fn get(&self, hash: &B256) -> Option<PayloadTimings> {
+        self.inner.lock().entries.get(hash).map(|entry| entry.timings)
+    }
+}
+
+/// The tracked payloads, with the oldest evicted first.
+#[derive(Debug, Default)]
+struct PayloadTimingsInner {
+    entries: HashMap<B256, PayloadTimingsEntry>,
+    order: VecDeque<B256>,
+}
+
+impl PayloadTimingsInner {
+    /// Returns the entry of the given block hash, inserting it if it is not tracked yet.
+    fn insert(&mut self, hash: B256) -> &mut PayloadTimingsEntry {
+        if !self.entries.contains_key(&hash) {
+            if self.order.len() >= PAYLOAD_TIMINGS_LIMIT {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.entries.remove(&oldest);
+                }
+            }
+            self.order.push_back(hash);
+        }
+        self.entries.entry(hash).or_default()
+    }
+}
+
+/// A tracked payload.
+#[derive(Debug, Default)]
+struct PayloadTimingsEntry {
+    /// When the payload was received by the engine API.
+    received_at: Option<Instant>,
+    /// The recorded timings.
+    timings: PayloadTimings,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use std::time::Duration;
+
+    #[test]
+    fn test_payload_timings_tracker() {
+        let tracker = PayloadTimingsTracker::default();
+        let hash = B256::with_last_byte(1);
+
+        // untracked payloads are not updated
+        tracker.update(hash, |timings| timings.execution = Some(Duration::from_millis(1)));
+        assert_eq!(tracker.get(&hash), None);
+
+        tracker.on_received(hash);
+        assert!(tracker.on_processing_started(hash, 1).is_some());
+        tracker.update(hash, |timings| timings.execution = Some(Duration::from_millis(1)));
+        assert_eq!(
+            tracker.get(&hash),
+            Some(PayloadTimings {
+                block_number: 1,
+                execution: Some(Duration::from_millis(1)),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_payload_timings_tracker_limit() {
+        let tracker = PayloadTimingsTracker::default();
+        let hash = |number: u64| B256::from(U256::from(number));
+        for number in 0..=PAYLOAD_TIMINGS_LIMIT as u64 {
+            tracker.on_processing_started(hash(number), number);
+        }
+
+        // the oldest payload was evicted
+        assert_eq!(tracker.get(&hash(0)), None);
+        assert!(tracker.get(&hash(PAYLOAD_TIMINGS_LIMIT as u64)).is_some());
+        assert_eq!(tracker.inner.lock().entries.len(), PAYLOAD_TIMINGS_LIMIT);
+    }
+}
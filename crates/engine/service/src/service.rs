@@ -4,13 +4,13 @@ use reth_beacon_consensus::{BeaconConsensusEngineEvent, BeaconEngineMessage};
 use reth_chainspec::ChainSpec;
 use reth_consensus::Consensus;
 use reth_db_api::database::Database;
-use reth_engine_primitives::EngineTypes;
+use reth_engine_primitives::{EngineTypes, PayloadTimingsTracker};
 use reth_engine_tree::{
     backfill::PipelineSync,
     download::BasicBlockDownloader,
     engine::{EngineApiRequest, EngineApiRequestHandler, EngineHandler},
     persistence::PersistenceHandle,
-    tree::{EngineApiTreeHandler, TreeConfig},
+    tree::{EngineApiTreeHandler, ParallelStateRootTaskSpawner, TreeConfig},
 };
 pub use reth_engine_tree::{
     chain::{ChainEvent, ChainOrchestrator},
@@ -80,6 +80,7 @@ where
         pruner: Pruner<DB, ProviderFactory<DB>>,
         payload_builder: PayloadBuilderHandle<T>,
         tree_config: TreeConfig,
+        payload_timings: PayloadTimingsTracker,
     ) -> Self {
         let downloader = BasicBlockDownloader::new(client, consensus.clone());
 
@@ -87,6 +88,8 @@ where
         let payload_validator = ExecutionPayloadValidator::new(chain_spec);
 
         let canonical_in_memory_state = blockchain_db.canonical_in_memory_state();
+        let state_root_task_spawner =
+            ParallelStateRootTaskSpawner::<DB, _>::new(blockchain_db.clone());
 
         let (to_tree_tx, from_tree) = EngineApiTreeHandler::spawn_new(
             blockchain_db,
@@ -97,6 +100,8 @@ where
             payload_builder,
             canonical_in_memory_state,
             tree_config,
+            Some(Box::new(state_root_task_spawner)),
+            payload_timings,
         );
 
         let engine_handler = EngineApiRequestHandler::new(to_tree_tx, from_tree);
@@ -196,6 +201,7 @@ mod tests {
             pruner,
             PayloadBuilderHandle::new(tx),
             TreeConfig::default(),
+            PayloadTimingsTracker::default(),
         );
     }
 }
@@ -10,12 +10,13 @@ use reth_beacon_consensus::{BeaconConsensusEngineEvent, BeaconEngineMessage};
 use reth_chain_state::ExecutedBlock;
 use reth_engine_primitives::EngineTypes;
 use reth_primitives::{SealedBlockWithSenders, B256};
+use reth_rpc_types::reth::ForkTree;
 use std::{
     collections::HashSet,
     sync::mpsc::Sender,
     task::{ready, Context, Poll},
 };
-use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::{mpsc::UnboundedReceiver, oneshot};
 
 /// A [`ChainHandler`] that advances the chain based on incoming requests (CL engine API).
 ///
@@ -226,6 +227,9 @@ pub enum EngineApiRequest<T: EngineTypes> {
     Beacon(BeaconEngineMessage<T>),
     /// Request to insert an already executed block, e.g. via payload building.
     InsertExecutedBlock(ExecutedBlock),
+    /// Request for a snapshot of the executed blocks kept in memory, including non-canonical
+    /// forks.
+    ForkTree(oneshot::Sender<ForkTree>),
 }
 
 impl<T: EngineTypes> From<BeaconEngineMessage<T>> for EngineApiRequest<T> {
@@ -8,12 +8,16 @@ use reth_primitives::B256;
 use reth_provider::{writer::UnifiedStorageWriter, ProviderFactory, StaticFileProviderFactory};
 use reth_prune::{Pruner, PrunerError, PrunerOutput};
 use std::{
-    sync::mpsc::{Receiver, SendError, Sender},
-    time::Instant,
+    collections::VecDeque,
+    sync::{
+        mpsc::{Receiver, SendError, Sender},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
 use thiserror::Error;
 use tokio::sync::oneshot;
-use tracing::{debug, error};
+use tracing::{debug, error, instrument};
 
 /// Writes parts of reth's in memory tree state to the database and static files.
 ///
@@ -32,6 +36,8 @@ pub struct PersistenceService<DB> {
     pruner: Pruner<DB, ProviderFactory<DB>>,
     /// metrics
     metrics: PersistenceMetrics,
+    /// Tracks when blocks are saved
+    saves: SaveBlocksTracker,
 }
 
 impl<DB: Database> PersistenceService<DB> {
@@ -40,8 +46,9 @@ impl<DB: Database> PersistenceService<DB> {
         provider: ProviderFactory<DB>,
         incoming: Receiver<PersistenceAction>,
         pruner: Pruner<DB, ProviderFactory<DB>>,
+        saves: SaveBlocksTracker,
     ) -> Self {
-        Self { provider, incoming, pruner, metrics: PersistenceMetrics::default() }
+        Self { provider, incoming, pruner, metrics: PersistenceMetrics::default(), saves }
     }
 
     /// Prunes block data before the given block hash according to the configured prune
@@ -99,8 +106,10 @@ where
         Ok(())
     }
 
+    #[instrument(level = "debug", target = "engine::persistence", skip_all, fields(first = ?blocks.first().map(|block| block.block().num_hash()), last = ?blocks.last().map(|block| block.block().num_hash())))]
     fn on_save_blocks(&self, blocks: Vec<ExecutedBlock>) -> Result<Option<B256>, PersistenceError> {
         let start_time = Instant::now();
+        self.saves.on_started(start_time);
         let last_block_hash = blocks.last().map(|block| block.block().hash());
 
         if last_block_hash.is_some() {
@@ -111,10 +120,68 @@ where
             UnifiedStorageWriter::commit(provider_rw, static_file_provider)?;
         }
         self.metrics.save_blocks_duration_seconds.record(start_time.elapsed());
+        self.saves.on_finished(Instant::now());
         Ok(last_block_hash)
     }
 }
 
+/// Maximum number of completed saves the [`SaveBlocksTracker`] keeps.
+const SAVE_BLOCKS_WINDOWS_LIMIT: usize = 16;
+
+/// Tracks when the persistence service saves blocks.
+///
+/// This is shared between the persistence service and the engine tree, which uses it to measure
+/// how long saving blocks overlapped the processing of a payload, since both contend for the
+/// database and the disk.
+#[derive(Debug, Clone, Default)]
+pub struct SaveBlocksTracker {
+    inner: Arc<Mutex<SaveBlocksWindows>>,
+}
+
+impl SaveBlocksTracker {
+    /// Records that saving blocks started.
+    fn on_started(&self, at: Instant) {
+        self.lock().in_progress = Some(at);
+    }
+
+    /// Records that saving blocks finished.
+    fn on_finished(&self, at: Instant) {
+        let mut windows = self.lock();
+        if let Some(start) = windows.in_progress.take() {
+            if windows.completed.len() >= SAVE_BLOCKS_WINDOWS_LIMIT {
+                windows.completed.pop_front();
+            }
+            windows.completed.push_back((start, at));
+        }
+    }
+
+    /// Returns how long blocks were being saved between `since` and now.
+    pub fn overlap_since(&self, since: Instant) -> Duration {
+        let now = Instant::now();
+        let windows = self.lock();
+        windows
+            .completed
+            .iter()
+            .copied()
+            .chain(windows.in_progress.map(|start| (start, now)))
+            .map(|(start, end)| end.saturating_duration_since(start.max(since)))
+            .sum()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SaveBlocksWindows> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// The recent saves of blocks.
+#[derive(Debug, Default)]
+struct SaveBlocksWindows {
+    /// Start of the save in progress.
+    in_progress: Option<Instant>,
+    /// Start and end of the most recently completed saves, oldest first.
+    completed: VecDeque<(Instant, Instant)>,
+}
+
 /// One of the errors that can happen when using the persistence service.
 #[derive(Debug, Error)]
 pub enum PersistenceError {
@@ -153,12 +220,20 @@ pub enum PersistenceAction {
 pub struct PersistenceHandle {
     /// The channel used to communicate with the persistence service
     sender: Sender<PersistenceAction>,
+    /// Tracks when the persistence service saves blocks
+    saves: SaveBlocksTracker,
 }
 
 impl PersistenceHandle {
-    /// Create a new [`PersistenceHandle`] from a [`Sender<PersistenceAction>`].
-    pub const fn new(sender: Sender<PersistenceAction>) -> Self {
-        Self { sender }
+    /// Create a new [`PersistenceHandle`] from a [`Sender<PersistenceAction>`] and the
+    /// [`SaveBlocksTracker`] of the persistence service it sends to.
+    pub const fn new(sender: Sender<PersistenceAction>, saves: SaveBlocksTracker) -> Self {
+        Self { sender, saves }
+    }
+
+    /// Returns the [`SaveBlocksTracker`] shared with the persistence service.
+    pub const fn saves(&self) -> &SaveBlocksTracker {
+        &self.saves
     }
 
     /// Create a new [`PersistenceHandle`], and spawn the persistence service.
@@ -170,10 +245,11 @@ impl PersistenceHandle {
         let (db_service_tx, db_service_rx) = std::sync::mpsc::channel();
 
         // construct persistence handle
-        let persistence_handle = Self::new(db_service_tx);
+        let saves = SaveBlocksTracker::default();
+        let persistence_handle = Self::new(db_service_tx, saves.clone());
 
         // spawn the persistence service
-        let db_service = PersistenceService::new(provider_factory, db_service_rx, pruner);
+        let db_service = PersistenceService::new(provider_factory, db_service_rx, pruner, saves);
         std::thread::Builder::new()
             .name("Persistence Service".to_string())
             .spawn(|| {
@@ -263,6 +339,23 @@ mod tests {
         PersistenceHandle::spawn_service(provider, pruner)
     }
 
+    #[test]
+    fn test_save_blocks_tracker_overlap() {
+        let tracker = SaveBlocksTracker::default();
+        let start = Instant::now();
+        tracker.on_started(start);
+        tracker.on_finished(start + Duration::from_millis(10));
+        tracker.on_started(start + Duration::from_millis(20));
+        tracker.on_finished(start + Duration::from_millis(25));
+
+        assert_eq!(tracker.overlap_since(start), Duration::from_millis(15));
+        assert_eq!(
+            tracker.overlap_since(start + Duration::from_millis(5)),
+            Duration::from_millis(10)
+        );
+        assert_eq!(tracker.overlap_since(start + Duration::from_millis(30)), Duration::ZERO);
+    }
+
     #[tokio::test]
     async fn test_save_blocks_empty() {
         reth_tracing::init_test_tracing();
@@ -1,11 +1,14 @@
 //! Engine tree configuration.
 
+use std::path::{Path, PathBuf};
+
 const DEFAULT_PERSISTENCE_THRESHOLD: u64 = 6;
 const DEFAULT_MEMORY_BLOCK_BUFFER_TARGET: u64 = 6;
 const DEFAULT_BLOCK_BUFFER_LIMIT: u32 = 256;
 const DEFAULT_MAX_INVALID_HEADER_CACHE_LENGTH: u32 = 256;
 
 const DEFAULT_MAX_EXECUTE_BLOCK_BATCH_SIZE: usize = 4;
+const DEFAULT_MAX_FORK_BRANCHES: usize = 8;
 
 /// The configuration of the engine tree.
 #[derive(Debug)]
@@ -23,6 +26,14 @@ pub struct TreeConfig {
     max_invalid_header_cache_length: u32,
     /// Maximum number of blocks to execute sequentially in a batch.
     max_execute_block_batch_size: usize,
+    /// Maximum number of non-canonical branches of executed blocks to keep in memory.
+    max_fork_branches: usize,
+    /// Directory to write execution witnesses of invalid blocks to, if any.
+    invalid_block_witness_dir: Option<PathBuf>,
+    /// Reorg depth above which an alert is raised, if any.
+    reorg_alert_depth: Option<u64>,
+    /// Whether reorgs below the safe block are refused unless explicitly allowed.
+    protect_safe_block: bool,
 }
 
 impl Default for TreeConfig {
@@ -33,6 +44,10 @@ impl Default for TreeConfig {
             block_buffer_limit: DEFAULT_BLOCK_BUFFER_LIMIT,
             max_invalid_header_cache_length: DEFAULT_MAX_INVALID_HEADER_CACHE_LENGTH,
             max_execute_block_batch_size: DEFAULT_MAX_EXECUTE_BLOCK_BATCH_SIZE,
+            max_fork_branches: DEFAULT_MAX_FORK_BRANCHES,
+            invalid_block_witness_dir: None,
+            reorg_alert_depth: None,
+            protect_safe_block: false,
         }
     }
 }
@@ -45,6 +60,7 @@ impl TreeConfig {
         block_buffer_limit: u32,
         max_invalid_header_cache_length: u32,
         max_execute_block_batch_size: usize,
+        max_fork_branches: usize,
     ) -> Self {
         Self {
             persistence_threshold,
@@ -52,6 +68,10 @@ impl TreeConfig {
             block_buffer_limit,
             max_invalid_header_cache_length,
             max_execute_block_batch_size,
+            max_fork_branches,
+            invalid_block_witness_dir: None,
+            reorg_alert_depth: None,
+            protect_safe_block: false,
         }
     }
 
@@ -80,6 +100,26 @@ impl TreeConfig {
         self.max_execute_block_batch_size
     }
 
+    /// Return the maximum number of non-canonical branches kept in memory.
+    pub const fn max_fork_branches(&self) -> usize {
+        self.max_fork_branches
+    }
+
+    /// Return the directory to write execution witnesses of invalid blocks to, if any.
+    pub fn invalid_block_witness_dir(&self) -> Option<&Path> {
+        self.invalid_block_witness_dir.as_deref()
+    }
+
+    /// Return the reorg depth above which an alert is raised, if any.
+    pub const fn reorg_alert_depth(&self) -> Option<u64> {
+        self.reorg_alert_depth
+    }
+
+    /// Return whether reorgs below the safe block are refused unless explicitly allowed.
+    pub const fn protect_safe_block(&self) -> bool {
+        self.protect_safe_block
+    }
+
     /// Setter for persistence threshold.
     pub const fn with_persistence_threshold(mut self, persistence_threshold: u64) -> Self {
         self.persistence_threshold = persistence_threshold;
@@ -118,4 +158,28 @@ impl TreeConfig {
         self.max_execute_block_batch_size = max_execute_block_batch_size;
         self
     }
+
+    /// Setter for maximum number of non-canonical branches kept in memory.
+    pub const fn with_max_fork_branches(mut self, max_fork_branches: usize) -> Self {
+        self.max_fork_branches = max_fork_branches;
+        self
+    }
+
+    /// Setter for the directory to write execution witnesses of invalid blocks to.
+    pub fn with_invalid_block_witness_dir(mut self, invalid_block_witness_dir: PathBuf) -> Self {
+        self.invalid_block_witness_dir = Some(invalid_block_witness_dir);
+        self
+    }
+
+    /// Setter for the reorg depth above which an alert is raised.
+    pub const fn with_reorg_alert_depth(mut self, reorg_alert_depth: u64) -> Self {
+        self.reorg_alert_depth = Some(reorg_alert_depth);
+        self
+    }
+
+    /// Setter for whether reorgs below the safe block are refused unless explicitly allowed.
+    pub const fn with_protect_safe_block(mut self, protect_safe_block: bool) -> Self {
+        self.protect_safe_block = protect_safe_block;
+        self
+    }
 }
@@ -0,0 +1,413 @@
+//! Execution witnesses of invalid blocks.
+//!
+//! If configured, the engine tree re-executes blocks that fail validation on top of their parent
+//! state while recording all state that is accessed, and writes the block, the validation error
+//! and the [`ExecutionWitness`] of the block into a self-contained [`InvalidBlockWitness`] file.
+//! The witness can be re-executed without a database with [`InvalidBlockWitness::reexecute`], for
+//! example to compare the outcome against other clients in a consensus bug report.
+
+use reth_chain_state::{ExecutedBlock, MemoryOverlayStateProvider};
+use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider};
+use reth_fs_util as fs;
+use reth_primitives::{
+    Address, BlockWithSenders, Header, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader,
+    B256,
+};
+use reth_provider::{
+    BlockReader, ProviderResult, StateProvider, StateProviderBox, StateProviderFactory,
+};
+use reth_stateless::{ExecutionWitness, StatelessValidationError};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, SyncSender, TrySendError},
+};
+use tracing::{debug, info, warn};
+
+/// Maximum number of invalid blocks waiting for their witness to be generated.
+///
+/// Invalid blocks received while the queue is full are skipped.
+const MAX_QUEUED_INVALID_BLOCKS: usize = 4;
+
+/// Number of queued invalid blocks that are remembered to skip duplicates.
+const MAX_SEEN_INVALID_BLOCKS: usize = 256;
+
+/// A self-contained execution witness of a block that failed validation.
+///
+/// This contains the block together with the [`ExecutionWitness`] of executing it on top of its
+/// parent state: the trie nodes of all state that was accessed and that is required to compute the
+/// state root after execution, the accessed bytecodes and the ancestor headers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidBlockWitness {
+    /// The invalid block.
+    pub block: SealedBlock,
+    /// The recovered senders of the block transactions.
+    pub senders: Vec<Address>,
+    /// The validation error of the block.
+    pub error: String,
+    /// The execution witness of the block.
+    pub witness: ExecutionWitness,
+}
+
+impl InvalidBlockWitness {
+    /// Generates the witness by executing the block on top of the given parent state.
+    ///
+    /// The block is expected to fail execution or validation, so the execution result is ignored
+    /// and only the state accessed until then is recorded.
+    pub fn generate<E, F>(
+        executor_provider: &E,
+        state_provider: &dyn StateProvider,
+        parent: &SealedHeader,
+        header_by_hash: F,
+        block: SealedBlockWithSenders,
+        error: String,
+    ) -> ProviderResult<Self>
+    where
+        E: BlockExecutorProvider,
+        F: FnMut(B256) -> ProviderResult<Option<Header>>,
+    {
+        let executable = block.clone().unseal();
+        let (witness, _) = ExecutionWitness::generate(
+            executor_provider,
+            state_provider,
+            parent,
+            header_by_hash,
+            &executable,
+        )?;
+
+        Ok(Self { block: block.block, senders: block.senders, error, witness })
+    }
+
+    /// Re-executes the block on top of the parent state of the witness, without a database.
+    ///
+    /// Returns the execution output together with the state root after execution.
+    pub fn reexecute<E>(
+        &self,
+        executor_provider: &E,
+    ) -> Result<(BlockExecutionOutput<Receipt>, B256), StatelessValidationError>
+    where
+        E: BlockExecutorProvider,
+    {
+        let block = BlockWithSenders::new(self.block.clone().unseal(), self.senders.clone())
+            .ok_or(StatelessValidationError::SenderRecovery)?;
+        reth_stateless::execute_block(executor_provider, &block, &self.witness)
+    }
+
+    /// Returns the file name of the witness, made of the block number and hash.
+    pub fn file_name(&self) -> String {
+        format!("{}-{}.json", self.block.number, self.block.hash())
+    }
+
+    /// Writes the witness into the given directory and returns the path of the written file.
+    pub fn write_to_dir(&self, dir: &Path) -> Result<PathBuf, fs::FsPathError> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(self.file_name());
+        fs::write_json_file(&path, self)?;
+        Ok(path)
+    }
+
+    /// Reads a witness from the given file.
+    pub fn read_from_file(path: &Path) -> Result<Self, fs::FsPathError> {
+        fs::read_json_file(path)
+    }
+}
+
+/// Generates [`InvalidBlockWitness`]es on a single background thread, so that re-executing blocks
+/// doesn't hold up the engine.
+///
+/// The parent state of a block is only opened once the worker picks it up, so queued blocks don't
+/// hold database transactions. This is best effort, failures are only logged.
+#[derive(Debug)]
+pub(crate) struct InvalidBlockWitnessWriter {
+    to_worker: SyncSender<InvalidBlockWitnessJob>,
+    /// Hashes of the queued invalid blocks, oldest first.
+    seen: VecDeque<B256>,
+}
+
+impl InvalidBlockWitnessWriter {
+    /// Spawns the worker that writes the witnesses into the given directory.
+    pub(crate) fn spawn<P, E>(provider: P, executor_provider: E, dir: PathBuf) -> Self
+    where
+        P: BlockReader + StateProviderFactory + 'static,
+        E: BlockExecutorProvider,
+    {
+        let (to_worker, jobs) = mpsc::sync_channel(MAX_QUEUED_INVALID_BLOCKS);
+        std::thread::Builder::new()
+            .name("Invalid Block Witness".to_string())
+            .spawn(move || {
+                while let Ok(job) = jobs.recv() {
+                    job.run(&provider, &executor_provider, &dir);
+                }
+            })
+            .unwrap();
+        Self::new(to_worker)
+    }
+
+    const fn new(to_worker: SyncSender<InvalidBlockWitnessJob>) -> Self {
+        Self { to_worker, seen: VecDeque::new() }
+    }
+
+    /// Queues the invalid block for its witness to be generated.
+    ///
+    /// Blocks that were queued before, or that are received while the queue is full, are skipped.
+    pub(crate) fn queue(&mut self, job: InvalidBlockWitnessJob) {
+        let num_hash = job.block.num_hash();
+        if self.seen.contains(&num_hash.hash) {
+            debug!(target: "engine", block = ?num_hash, "Skipping invalid block witness, already queued");
+            return
+        }
+
+        match self.to_worker.try_send(job) {
+            Ok(()) => {
+                if self.seen.len() >= MAX_SEEN_INVALID_BLOCKS {
+                    self.seen.pop_front();
+                }
+                self.seen.push_back(num_hash.hash);
+            }
+            Err(TrySendError::Full(_)) => {
+                debug!(target: "engine", block = ?num_hash, "Skipping invalid block witness, too many queued");
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                warn!(target: "engine", block = ?num_hash, "Skipping invalid block witness, worker is gone");
+            }
+        }
+    }
+}
+
+/// An invalid block whose [`InvalidBlockWitness`] is to be generated.
+#[derive(Debug)]
+pub(crate) struct InvalidBlockWitnessJob {
+    /// The invalid block.
+    pub(crate) block: SealedBlock,
+    /// The validation error of the block.
+    pub(crate) error: String,
+    /// The hash of the persisted block the parent state is based on.
+    pub(crate) historical: B256,
+    /// The in-memory ancestors of the block on top of the persisted block, newest first.
+    pub(crate) in_memory: Vec<ExecutedBlock>,
+}
+
+impl InvalidBlockWitnessJob {
+    /// Generates the witness and writes it into the given directory.
+    fn run<P, E>(self, provider: &P, executor_provider: &E, dir: &Path)
+    where
+        P: BlockReader + StateProviderFactory,
+        E: BlockExecutorProvider,
+    {
+        let num_hash = self.block.num_hash();
+        let witness = match self.generate(provider, executor_provider) {
+            Ok(Some(witness)) => witness,
+            Ok(None) => return,
+            Err(err) => {
+                warn!(target: "engine", block = ?num_hash, %err, "Failed to generate invalid block witness");
+                return
+            }
+        };
+        match witness.write_to_dir(dir) {
+            Ok(path) => {
+                info!(target: "engine", block = ?num_hash, path = %path.display(), "Wrote invalid block witness")
+            }
+            Err(err) => {
+                warn!(target: "engine", block = ?num_hash, %err, "Failed to write invalid block witness")
+            }
+        }
+    }
+
+    /// Re-executes the block on top of its parent state.
+    ///
+    /// Returns `None` if the senders can't be recovered or the parent is not available.
+    fn generate<P, E>(
+        self,
+        provider: &P,
+        executor_provider: &E,
+    ) -> ProviderResult<Option<InvalidBlockWitness>>
+    where
+        P: BlockReader + StateProviderFactory,
+        E: BlockExecutorProvider,
+    {
+        let Self { block, error, historical, in_memory } = self;
+        let num_hash = block.num_hash();
+        let Some(senders) = block.senders() else {
+            debug!(target: "engine", block = ?num_hash, "Skipping invalid block witness, senders can't be recovered");
+            return Ok(None)
+        };
+
+        // headers of the in-memory ancestors, the others are looked up in the database
+        let in_memory_headers = in_memory
+            .iter()
+            .map(|executed| (executed.block.hash(), executed.block.header.clone()))
+            .collect::<HashMap<_, _>>();
+        let parent = match in_memory_headers.get(&block.parent_hash) {
+            Some(parent) => parent.clone(),
+            None => match provider.header(&block.parent_hash)? {
+                Some(parent) => parent.seal(block.parent_hash),
+                None => {
+                    debug!(target: "engine", block = ?num_hash, "Skipping invalid block witness, parent state unavailable");
+                    return Ok(None)
+                }
+            },
+        };
+
+        let historical = provider.state_by_block_hash(historical)?;
+        let state_provider: StateProviderBox = if in_memory.is_empty() {
+            historical
+        } else {
+            Box::new(MemoryOverlayStateProvider::new(historical, in_memory))
+        };
+        let header_by_hash = |hash: B256| match in_memory_headers.get(&hash) {
+            Some(header) => Ok(Some(header.header().clone())),
+            None => provider.header(&hash),
+        };
+
+        InvalidBlockWitness::generate(
+            executor_provider,
+            &*state_provider,
+            &parent,
+            header_by_hash,
+            SealedBlockWithSenders { block, senders },
+            error,
+        )
+        .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::{ChainSpec, ChainSpecBuilder, MAINNET};
+    use reth_db_common::init::init_genesis;
+    use reth_evm::execute::Executor;
+    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_primitives::{
+        constants::ETH_TO_WEI, proofs, public_key_to_address, Block, Genesis, GenesisAccount,
+        Transaction, TxEip1559, TxKind, Withdrawals, EMPTY_OMMER_ROOT_HASH, U256,
+    };
+    use reth_provider::{
+        test_utils::create_test_provider_factory_with_chain_spec, HeaderProvider,
+        StateProviderFactory,
+    };
+    use reth_revm::database::StateProviderDatabase;
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use reth_trie::HashedPostState;
+    use secp256k1::Keypair;
+    use std::{collections::BTreeMap, sync::Arc};
+
+    #[test]
+    fn generate_write_read_reexecute() {
+        let key_pair = Keypair::new_global(&mut generators::rng());
+        let sender = public_key_to_address(key_pair.public_key());
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(Genesis {
+                    gas_limit: 30_000_000,
+                    alloc: BTreeMap::from([(
+                        sender,
+                        GenesisAccount { balance: U256::from(ETH_TO_WEI), ..Default::default() },
+                    )]),
+                    ..MAINNET.genesis.clone()
+                })
+                .shanghai_activated()
+                .build(),
+        );
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(factory.clone()).unwrap();
+        let executor_provider = EthExecutorProvider::ethereum(chain_spec.clone());
+
+        // a transfer with a state root that doesn't match the execution outcome
+        let parent = chain_spec.sealed_genesis_header();
+        let timestamp = parent.timestamp + 12;
+        let transaction = sign_tx_with_key_pair(
+            key_pair,
+            Transaction::Eip1559(TxEip1559 {
+                chain_id: chain_spec.chain.id(),
+                nonce: 0,
+                gas_limit: 21_000,
+                max_fee_per_gas: 2_000_000_000,
+                to: TxKind::Call(Address::with_last_byte(1)),
+                value: U256::from(1),
+                ..Default::default()
+            }),
+        );
+        let withdrawals = Withdrawals::default();
+        let block = Block {
+            header: Header {
+                parent_hash: parent.hash(),
+                ommers_hash: EMPTY_OMMER_ROOT_HASH,
+                state_root: B256::with_last_byte(1),
+                transactions_root: proofs::calculate_transaction_root(&[transaction.clone()]),
+                withdrawals_root: Some(proofs::calculate_withdrawals_root(&withdrawals)),
+                number: parent.number + 1,
+                gas_limit: parent.gas_limit,
+                gas_used: 21_000,
+                timestamp,
+                base_fee_per_gas: parent
+                    .next_block_base_fee(chain_spec.base_fee_params_at_timestamp(timestamp)),
+                ..Default::default()
+            },
+            body: vec![transaction],
+            ommers: Vec::new(),
+            withdrawals: Some(withdrawals),
+            requests: None,
+        };
+        let block = SealedBlockWithSenders { block: block.seal_slow(), senders: vec![sender] };
+
+        let state_provider = factory.latest().unwrap();
+        let witness = InvalidBlockWitness::generate(
+            &executor_provider,
+            &*state_provider,
+            &parent,
+            |hash| factory.header(&hash),
+            block.clone(),
+            "state root mismatch".to_string(),
+        )
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = witness.write_to_dir(dir.path()).unwrap();
+        assert_eq!(path, dir.path().join(witness.file_name()));
+        let read = InvalidBlockWitness::read_from_file(&path).unwrap();
+        assert_eq!(read, witness);
+
+        let (output, state_root) = read.reexecute(&executor_provider).unwrap();
+        let expected = executor_provider
+            .executor(StateProviderDatabase::new(&*state_provider))
+            .execute((&block.clone().unseal(), U256::MAX).into())
+            .unwrap();
+        assert_eq!(output.receipts, expected.receipts);
+        assert_eq!(
+            state_root,
+            state_provider
+                .state_root(HashedPostState::from_bundle_state(&expected.state.state))
+                .unwrap()
+        );
+        assert_ne!(state_root, block.state_root);
+    }
+
+    #[test]
+    fn writer_skips_duplicates_and_full_queue() {
+        let (to_worker, jobs) = mpsc::sync_channel(MAX_QUEUED_INVALID_BLOCKS);
+        let mut writer = InvalidBlockWitnessWriter::new(to_worker);
+        let job = |number| InvalidBlockWitnessJob {
+            block: Block { header: Header { number, ..Default::default() }, ..Default::default() }
+                .seal_slow(),
+            error: String::new(),
+            historical: B256::ZERO,
+            in_memory: Vec::new(),
+        };
+
+        writer.queue(job(0));
+        writer.queue(job(0));
+        assert_eq!(jobs.try_iter().map(|job| job.block.number).collect::<Vec<_>>(), vec![0]);
+
+        // blocks received while the queue is full are skipped, and can be queued again later
+        for number in 1..=MAX_QUEUED_INVALID_BLOCKS as u64 + 1 {
+            writer.queue(job(number));
+        }
+        assert_eq!(jobs.try_iter().count(), MAX_QUEUED_INVALID_BLOCKS);
+        writer.queue(job(MAX_QUEUED_INVALID_BLOCKS as u64 + 1));
+        assert_eq!(jobs.try_iter().count(), 1);
+    }
+}
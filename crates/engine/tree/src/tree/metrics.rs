@@ -15,7 +15,50 @@ pub(crate) struct EngineApiMetrics {
     pub(crate) forkchoice_updated_messages: Counter,
     /// The total count of new payload messages received.
     pub(crate) new_payload_messages: Counter,
+    /// The total count of non-canonical branches removed because they exceeded the limit.
+    pub(crate) pruned_fork_branches: Counter,
+    /// The total count of reorgs requested.
+    pub(crate) reorgs: Counter,
+    /// Histogram of the depth of requested reorgs.
+    pub(crate) reorg_depth: Histogram,
+    /// The total count of reorgs deeper than the configured alert depth.
+    pub(crate) deep_reorgs: Counter,
+    /// The total count of reorgs refused because they would revert the safe block.
+    pub(crate) rejected_reorgs: Counter,
     /// Histogram of persistence operation durations (in seconds)
     pub(crate) persistence_duration: Histogram,
-    // TODO add latency metrics
+    /// Histogram of state root durations of every inserted block, regardless of whether the root
+    /// was computed in parallel or sequentially (in seconds)
+    pub(crate) state_root_duration: Histogram,
+    /// Histogram of sequential state root computation durations (in seconds)
+    pub(crate) state_root_sequential_duration: Histogram,
+    /// Histogram of durations between the end of execution and the parallel state root task
+    /// returning, including attempts that fell back to the sequential state root (in seconds)
+    pub(crate) state_root_parallel_duration: Histogram,
+    /// The total count of times the parallel state root was unavailable and the state root was
+    /// computed sequentially.
+    pub(crate) state_root_parallel_fallbacks: Counter,
+    /// Histogram of durations new payloads spent queued before being processed (in seconds)
+    pub(crate) new_payload_queue_duration: Histogram,
+    /// Histogram of durations blocks were saved to disk between new payloads being received and
+    /// processed (in seconds)
+    pub(crate) new_payload_persistence_overlap_duration: Histogram,
+    /// Histogram of new payload processing durations (in seconds)
+    pub(crate) new_payload_duration: Histogram,
+    /// Histogram of block validation durations before execution (in seconds)
+    pub(crate) block_validation_duration: Histogram,
+    /// Histogram of block execution durations (in seconds)
+    pub(crate) block_execution_duration: Histogram,
+}
+
+/// Metrics for the background state root task.
+#[derive(Clone, Metrics)]
+#[metrics(scope = "consensus.engine.state_root_task")]
+pub(crate) struct StateRootTaskMetrics {
+    /// Histogram of the number of accounts prefetched per block.
+    pub(crate) prefetched_targets: Histogram,
+    /// Histogram of proof prefetch batch durations (in seconds)
+    pub(crate) prefetch_duration: Histogram,
+    /// Histogram of parallel state root computation durations (in seconds)
+    pub(crate) state_root_duration: Histogram,
 }
@@ -6,17 +6,17 @@ use crate::{
 };
 use reth_beacon_consensus::{
     BeaconConsensusEngineEvent, BeaconEngineMessage, ForkchoiceStateTracker, InvalidHeaderCache,
-    OnForkChoiceUpdated, MIN_BLOCKS_FOR_PIPELINE_RUN,
+    OnForkChoiceUpdated, ReorgAlert, MIN_BLOCKS_FOR_PIPELINE_RUN,
 };
 use reth_blockchain_tree::{
     error::{InsertBlockErrorKindTwo, InsertBlockErrorTwo, InsertBlockFatalError},
     BlockBuffer, BlockStatus2, InsertPayloadOk2,
 };
 use reth_chain_state::{
-    CanonicalInMemoryState, ExecutedBlock, MemoryOverlayStateProvider, NewCanonicalChain,
+    CanonicalInMemoryState, ExecutedBlock, MemoryOverlayStateProvider, NewCanonicalChain, ReorgInfo,
 };
 use reth_consensus::{Consensus, PostExecutionInput};
-use reth_engine_primitives::EngineTypes;
+use reth_engine_primitives::{EngineTypes, PayloadTimingsTracker};
 use reth_errors::{ConsensusError, ProviderResult};
 use reth_evm::execute::{BlockExecutorProvider, Executor};
 use reth_payload_builder::PayloadBuilderHandle;
@@ -36,6 +36,7 @@ use reth_rpc_types::{
         CancunPayloadFields, ForkchoiceState, PayloadStatus, PayloadStatusEnum,
         PayloadValidationError,
     },
+    reth::{ForkTree, ForkTreeBlock},
     ExecutionPayload,
 };
 use reth_stages_api::ControlFlow;
@@ -47,7 +48,7 @@ use std::{
         mpsc::{Receiver, RecvError, RecvTimeoutError, Sender},
         Arc,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 use tokio::sync::{
     mpsc::{UnboundedReceiver, UnboundedSender},
@@ -57,9 +58,17 @@ use tokio::sync::{
 use tracing::*;
 
 mod config;
+mod invalid_block_witness;
 mod metrics;
+mod root;
 use crate::{engine::EngineApiRequest, tree::metrics::EngineApiMetrics};
 pub use config::TreeConfig;
+pub use invalid_block_witness::InvalidBlockWitness;
+use invalid_block_witness::{InvalidBlockWitnessJob, InvalidBlockWitnessWriter};
+pub use root::{
+    ParallelStateRootTaskSpawner, StateRootHandle, StateRootMessage, StateRootTask,
+    StateRootTaskError, StateRootTaskResult, StateRootTaskSpawner,
+};
 
 /// Keeps track of the state of the tree.
 ///
@@ -206,6 +215,136 @@ impl TreeState {
         }
     }
 
+    /// Removes the block with the given hash, without removing its descendants.
+    fn remove_block(&mut self, hash: B256) -> Option<ExecutedBlock> {
+        let executed = self.blocks_by_hash.remove(&hash)?;
+        let block_number = executed.block.number;
+        let parent_hash = executed.block.parent_hash;
+
+        if let Some(blocks) = self.blocks_by_number.get_mut(&block_number) {
+            blocks.retain(|block| block.block.hash() != hash);
+            if blocks.is_empty() {
+                self.blocks_by_number.remove(&block_number);
+            }
+        }
+
+        if let Some(parent_children) = self.parent_to_child.get_mut(&parent_hash) {
+            parent_children.remove(&hash);
+            if parent_children.is_empty() {
+                self.parent_to_child.remove(&parent_hash);
+            }
+        }
+        self.parent_to_child.remove(&hash);
+
+        Some(executed)
+    }
+
+    /// Removes all blocks that do not descend from the given anchor block.
+    ///
+    /// This is used to drop forks that branched off below the last persisted block, and can
+    /// therefore never become canonical.
+    fn remove_disconnected(&mut self, anchor: B256) {
+        let mut connected = HashSet::from([anchor]);
+        let mut disconnected = Vec::new();
+
+        // blocks are visited in ascending order, so parents are always visited before children
+        for blocks in self.blocks_by_number.values() {
+            for block in blocks {
+                if connected.contains(&block.block.parent_hash) {
+                    connected.insert(block.block.hash());
+                } else {
+                    disconnected.push(block.block.hash());
+                }
+            }
+        }
+
+        for hash in disconnected {
+            self.remove_block(hash);
+        }
+    }
+
+    /// Returns the hashes of all in-memory blocks that are part of the canonical chain.
+    fn canonical_hashes(&self) -> HashSet<B256> {
+        let mut hashes = HashSet::new();
+        let mut current_hash = self.canonical_block_hash();
+        while let Some(block) = self.blocks_by_hash.get(&current_hash) {
+            hashes.insert(current_hash);
+            current_hash = block.block.parent_hash;
+        }
+        hashes
+    }
+
+    /// Returns the tips of all non-canonical branches, from lowest to highest block number.
+    fn fork_branch_tips(&self) -> Vec<BlockNumHash> {
+        let canonical = self.canonical_hashes();
+        self.blocks_by_number
+            .values()
+            .flatten()
+            .filter(|block| {
+                let hash = block.block.hash();
+                !canonical.contains(&hash) &&
+                    self.parent_to_child.get(&hash).map_or(true, |children| children.is_empty())
+            })
+            .map(|block| block.block.num_hash())
+            .collect()
+    }
+
+    /// Removes the non-canonical branch that ends at the given tip, down to the block where it
+    /// joins another branch or the canonical chain.
+    fn remove_fork_branch(&mut self, tip: B256, canonical: &HashSet<B256>) {
+        let mut current_hash = tip;
+        while !canonical.contains(&current_hash) &&
+            self.parent_to_child.get(&current_hash).map_or(true, |children| children.is_empty())
+        {
+            let Some(block) = self.remove_block(current_hash) else { break };
+            current_hash = block.block.parent_hash;
+        }
+    }
+
+    /// Removes the lowest non-canonical branches until at most `max_branches` remain.
+    ///
+    /// The branch ending at `keep` is never removed.
+    ///
+    /// Returns the number of removed branches.
+    fn prune_fork_branches(&mut self, max_branches: usize, keep: B256) -> usize {
+        let tips = self.fork_branch_tips();
+        let excess = tips.len().saturating_sub(max_branches);
+        if excess == 0 {
+            return 0
+        }
+
+        let canonical = self.canonical_hashes();
+        let to_remove =
+            tips.into_iter().filter(|tip| tip.hash != keep).take(excess).collect::<Vec<_>>();
+        for tip in &to_remove {
+            self.remove_fork_branch(tip.hash, &canonical);
+        }
+        to_remove.len()
+    }
+
+    /// Returns the shape of the tree of executed blocks.
+    fn fork_tree(&self) -> ForkTree {
+        let canonical = self.canonical_hashes();
+        ForkTree {
+            canonical_head_number: self.canonical_block_number(),
+            canonical_head_hash: self.canonical_block_hash(),
+            blocks: self
+                .blocks_by_number
+                .values()
+                .flatten()
+                .map(|block| {
+                    let hash = block.block.hash();
+                    ForkTreeBlock {
+                        number: block.block.number,
+                        hash,
+                        parent_hash: block.block.parent_hash,
+                        canonical: canonical.contains(&hash),
+                    }
+                })
+                .collect(),
+        }
+    }
+
     /// Updates the canonical head to the given block.
     fn set_canonical_head(&mut self, new_head: BlockNumHash) {
         self.current_canonical_head = new_head;
@@ -413,6 +552,14 @@ pub struct EngineApiTreeHandler<P, E, T: EngineTypes> {
     payload_builder: PayloadBuilderHandle<T>,
     /// Configuration settings.
     config: TreeConfig,
+    /// Spawns background tasks that compute the state root while a block is being executed.
+    ///
+    /// If not set, the state root is computed sequentially after execution.
+    state_root_task_spawner: Option<Box<dyn StateRootTaskSpawner>>,
+    /// Per-phase timings of the most recent payloads, shared with the engine API.
+    payload_timings: PayloadTimingsTracker,
+    /// Generates the witnesses of invalid blocks, if configured.
+    invalid_block_witnesses: Option<InvalidBlockWitnessWriter>,
     /// Metrics for the engine api.
     metrics: EngineApiMetrics,
 }
@@ -439,6 +586,13 @@ where
         config: TreeConfig,
     ) -> Self {
         let (incoming_tx, incoming) = std::sync::mpsc::channel();
+        let invalid_block_witnesses = config.invalid_block_witness_dir().map(|dir| {
+            InvalidBlockWitnessWriter::spawn(
+                provider.clone(),
+                executor_provider.clone(),
+                dir.to_path_buf(),
+            )
+        });
         Self {
             provider,
             executor_provider,
@@ -453,11 +607,30 @@ where
             canonical_in_memory_state,
             payload_builder,
             config,
+            state_root_task_spawner: None,
+            payload_timings: Default::default(),
+            invalid_block_witnesses,
             metrics: Default::default(),
             incoming_tx,
         }
     }
 
+    /// Sets the [`StateRootTaskSpawner`] used to compute state roots of new blocks in parallel
+    /// with their execution.
+    pub fn with_state_root_task_spawner(
+        mut self,
+        state_root_task_spawner: Option<Box<dyn StateRootTaskSpawner>>,
+    ) -> Self {
+        self.state_root_task_spawner = state_root_task_spawner;
+        self
+    }
+
+    /// Sets the [`PayloadTimingsTracker`] that the timings of processed payloads are recorded to.
+    pub fn with_payload_timings(mut self, payload_timings: PayloadTimingsTracker) -> Self {
+        self.payload_timings = payload_timings;
+        self
+    }
+
     /// Creates a new [`EngineApiTreeHandler`] instance and spawns it in its
     /// own thread.
     ///
@@ -473,6 +646,8 @@ where
         payload_builder: PayloadBuilderHandle<T>,
         canonical_in_memory_state: CanonicalInMemoryState,
         config: TreeConfig,
+        state_root_task_spawner: Option<Box<dyn StateRootTaskSpawner>>,
+        payload_timings: PayloadTimingsTracker,
     ) -> (Sender<FromEngine<EngineApiRequest<T>>>, UnboundedReceiver<EngineApiEvent>) {
         let best_block_number = provider.best_block_number().unwrap_or(0);
         let header = provider.sealed_header(best_block_number).ok().flatten().unwrap_or_default();
@@ -481,6 +656,7 @@ where
             last_persisted_block_hash: header.hash(),
             last_persisted_block_number: best_block_number,
             rx: None,
+            blocks: Vec::new(),
         };
 
         let (tx, outgoing) = tokio::sync::mpsc::unbounded_channel();
@@ -502,7 +678,9 @@ where
             persistence_state,
             payload_builder,
             config,
-        );
+        )
+        .with_state_root_task_spawner(state_root_task_spawner)
+        .with_payload_timings(payload_timings);
         let incoming = task.incoming_tx.clone();
         std::thread::Builder::new().name("Tree Task".to_string()).spawn(|| task.run()).unwrap();
         (incoming, outgoing)
@@ -576,6 +754,38 @@ where
         Ok(None)
     }
 
+    /// Records the time the payload with the given block hash spent queued before the tree started
+    /// processing it, and returns the time it was received by the engine API, if known.
+    fn on_payload_processing_started(
+        &self,
+        block_hash: B256,
+        block_number: u64,
+    ) -> Option<Instant> {
+        let received_at = self.payload_timings.on_processing_started(block_hash, block_number)?;
+
+        let queue = received_at.elapsed();
+        self.metrics.new_payload_queue_duration.record(queue);
+        self.payload_timings.update(block_hash, |timings| timings.queue = Some(queue));
+        Some(received_at)
+    }
+
+    /// Records the time the persistence service spent saving blocks since the payload with the
+    /// given block hash was received, until the tree finished processing it.
+    fn on_payload_processing_finished(&self, block_hash: B256, received_at: Instant) {
+        let persistence_overlap = self.persistence.saves().overlap_since(received_at);
+        self.metrics.new_payload_persistence_overlap_duration.record(persistence_overlap);
+        self.payload_timings
+            .update(block_hash, |timings| timings.persistence_overlap = Some(persistence_overlap));
+    }
+
+    /// Records time spent validating the block with the given hash before execution.
+    fn on_block_validated(&self, block_hash: B256, elapsed: Duration) {
+        self.metrics.block_validation_duration.record(elapsed);
+        self.payload_timings.update(block_hash, |timings| {
+            timings.validation = Some(timings.validation.unwrap_or_default() + elapsed)
+        });
+    }
+
     /// When the Consensus layer receives a new block via the consensus gossip protocol,
     /// the transactions in the block are sent to the execution layer in the form of a
     /// [`ExecutionPayload`]. The Execution layer executes the transactions and validates the
@@ -588,7 +798,7 @@ where
     ///
     /// This returns a [`PayloadStatus`] that represents the outcome of a processed new payload and
     /// returns an error if an internal error occurred.
-    #[instrument(level = "trace", skip_all, fields(block_hash = %payload.block_hash(), block_num = %payload.block_number(),), target = "engine")]
+    #[instrument(level = "debug", skip_all, fields(block_hash = %payload.block_hash(), block_num = %payload.block_number(),), target = "engine")]
     fn on_new_payload(
         &mut self,
         payload: ExecutionPayload,
@@ -623,10 +833,12 @@ where
         //
         // This validation **MUST** be instantly run in all cases even during active sync process.
         let parent_hash = payload.parent_hash();
-        let block = match self
-            .payload_validator
-            .ensure_well_formed_payload(payload, cancun_fields.into())
-        {
+        let payload_hash = payload.block_hash();
+        let validation_start = Instant::now();
+        let block =
+            self.payload_validator.ensure_well_formed_payload(payload, cancun_fields.into());
+        self.on_block_validated(payload_hash, validation_start.elapsed());
+        let block = match block {
             Ok(block) => block,
             Err(error) => {
                 error!(target: "engine::tree", %error, "Invalid payload");
@@ -774,8 +986,16 @@ where
 
         // 2. ensure we can apply a new chain update for the head block
         if let Some(chain_update) = self.state.tree_state.on_new_head(state.head_block_hash) {
+            // the CL retries a refused update every slot, which must not count as another reorg
+            let is_retry = self.state.forkchoice_state_tracker.last_refused_reorg() == Some(state);
+            let Some(reorg_info) = self.check_reorg(&chain_update, is_retry) else {
+                // the reorg is refused until it is explicitly allowed, the CL retries the update
+                self.state.forkchoice_state_tracker.set_refused_reorg(state);
+                return Ok(TreeOutcome::new(OnForkChoiceUpdated::syncing()))
+            };
+
             let tip = chain_update.tip().header.clone();
-            self.on_canonical_chain_update(chain_update);
+            self.on_canonical_chain_update(chain_update, reorg_info);
 
             // update the safe and finalized blocks and ensure their values are valid
             if let Err(outcome) = self.ensure_consistent_forkchoice_state(state) {
@@ -871,9 +1091,10 @@ where
             if blocks_to_persist.is_empty() {
                 debug!(target: "engine", "Returned empty set of blocks to persist");
             } else {
+                let blocks = blocks_to_persist.iter().map(|block| block.block().hash()).collect();
                 let (tx, rx) = oneshot::channel();
                 let _ = self.persistence.save_blocks(blocks_to_persist, tx);
-                self.persistence_state.start(rx);
+                self.persistence_state.start(rx, blocks);
             }
         }
 
@@ -886,7 +1107,12 @@ where
             // Check if persistence has complete
             match rx.try_recv() {
                 Ok(last_persisted_block_hash) => {
-                    self.metrics.persistence_duration.record(start_time.elapsed());
+                    let elapsed = start_time.elapsed();
+                    self.metrics.persistence_duration.record(elapsed);
+                    for hash in std::mem::take(&mut self.persistence_state.blocks) {
+                        self.payload_timings
+                            .update(hash, |timings| timings.persistence = Some(elapsed));
+                    }
                     let Some(last_persisted_block_hash) = last_persisted_block_hash else {
                         // if this happened, then we persisted no blocks because we sent an
                         // empty vec of blocks
@@ -929,6 +1155,9 @@ where
                     EngineApiRequest::InsertExecutedBlock(block) => {
                         self.state.tree_state.insert_executed(block);
                     }
+                    EngineApiRequest::ForkTree(tx) => {
+                        let _ = tx.send(self.state.tree_state.fork_tree());
+                    }
                     EngineApiRequest::Beacon(request) => {
                         match request {
                             BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx } => {
@@ -956,8 +1185,28 @@ where
                                     error!("Failed to send event: {err:?}");
                                 }
                             }
-                            BeaconEngineMessage::NewPayload { payload, cancun_fields, tx } => {
-                                let output = self.on_new_payload(payload, cancun_fields);
+                            BeaconEngineMessage::NewPayload {
+                                payload,
+                                cancun_fields,
+                                tx,
+                                span,
+                            } => {
+                                let block_hash = payload.block_hash();
+                                let received_at = self.on_payload_processing_started(
+                                    block_hash,
+                                    payload.block_number(),
+                                );
+                                let start = Instant::now();
+                                let output =
+                                    span.in_scope(|| self.on_new_payload(payload, cancun_fields));
+                                let elapsed = start.elapsed();
+                                self.metrics.new_payload_duration.record(elapsed);
+                                self.payload_timings.update(block_hash, |timings| {
+                                    timings.processing = Some(elapsed)
+                                });
+                                if let Some(received_at) = received_at {
+                                    self.on_payload_processing_finished(block_hash, received_at);
+                                }
                                 if let Err(err) = tx.send(output.map(|o| o.outcome).map_err(|e| {
                                     reth_beacon_consensus::BeaconOnNewPayloadError::Internal(
                                         Box::new(e),
@@ -1016,7 +1265,6 @@ where
         // state house keeping after backfill sync
         // remove all executed blocks below the backfill height
         self.state.tree_state.remove_before(backfill_height);
-        self.metrics.executed_blocks.set(self.state.tree_state.block_count() as f64);
 
         // remove all buffered blocks below the backfill height
         self.state.buffer.remove_old_blocks(backfill_height);
@@ -1028,11 +1276,13 @@ where
             // update the tracked chain height, after backfill sync both the canonical height and
             // persisted height are the same
             self.state.tree_state.set_canonical_head(new_head.num_hash());
+            self.state.tree_state.remove_disconnected(new_head.hash());
             self.persistence_state.finish(new_head.hash(), new_head.number);
 
             // update the tracked canonical head
             self.canonical_in_memory_state.set_canonical_head(new_head);
         }
+        self.metrics.executed_blocks.set(self.state.tree_state.block_count() as f64);
 
         // check if we need to run backfill again by comparing the most recent finalized height to
         // the backfill height
@@ -1079,8 +1329,74 @@ where
     /// This will update the tracked canonical in memory state and do the necessary housekeeping.
     fn make_canonical(&mut self, target: B256) {
         if let Some(chain_update) = self.state.tree_state.on_new_head(target) {
-            self.on_canonical_chain_update(chain_update);
+            if let Some(reorg_info) = self.check_reorg(&chain_update, false) {
+                self.on_canonical_chain_update(chain_update, reorg_info);
+            }
+        }
+    }
+
+    /// Applies the configured reorg guardrails to the given chain update.
+    ///
+    /// Reorgs deeper than the configured alert depth emit a [`ReorgAlert`]. If the safe block is
+    /// protected, reorgs that would revert it are refused unless they were explicitly allowed
+    /// with [`CanonicalInMemoryState::allow_reorg`].
+    ///
+    /// If `is_retry` is true, the chain update repeats the last refused reorg, which is neither
+    /// counted nor alerted again.
+    ///
+    /// Returns the details of the reorg to annotate the canonical state notification with, or
+    /// `None` if the chain update must not be applied.
+    fn check_reorg(
+        &mut self,
+        chain_update: &NewCanonicalChain,
+        is_retry: bool,
+    ) -> Option<ReorgInfo> {
+        let depth = chain_update.reorged_block_count() as u64;
+        if depth == 0 {
+            return Some(ReorgInfo::new(depth))
+        }
+
+        let old_head = self.state.tree_state.current_canonical_head;
+        let new_head = chain_update.tip().num_hash();
+        let reverts_safe_block = self
+            .canonical_in_memory_state
+            .get_safe_num_hash()
+            .is_some_and(|safe| safe.number + depth > old_head.number);
+        let protected = self.config.protect_safe_block() && reverts_safe_block;
+        // the permission is consumed by any reorg to the head, so it can't be used later on
+        let permitted = self.canonical_in_memory_state.take_allowed_reorg(new_head.hash);
+        let allowed = protected && permitted;
+        let rejected = protected && !allowed;
+        let is_deep =
+            self.config.reorg_alert_depth().is_some_and(|alert_depth| depth > alert_depth);
+
+        if is_retry {
+            trace!(target: "engine", depth, ?new_head, rejected, "Retried reorg");
+        } else {
+            self.metrics.reorgs.increment(1);
+            self.metrics.reorg_depth.record(depth as f64);
+            if is_deep {
+                self.metrics.deep_reorgs.increment(1);
+            }
+            if rejected {
+                self.metrics.rejected_reorgs.increment(1);
+            }
         }
+        // the alert is logged by the node events
+        if !is_retry && (is_deep || rejected) {
+            self.emit_event(BeaconConsensusEngineEvent::ReorgAlert(ReorgAlert {
+                depth,
+                old_head,
+                new_head,
+                rejected,
+            }));
+        }
+
+        (!rejected).then_some(ReorgInfo {
+            depth,
+            alerted: is_deep,
+            allowed_below_safe_block: allowed,
+        })
     }
 
     /// Convenience function to handle an optional tree event.
@@ -1190,6 +1506,8 @@ where
     /// Assumes that `finish` has been called on the `persistence_state` at least once
     fn on_new_persisted_block(&mut self) {
         self.state.tree_state.remove_before(self.persistence_state.last_persisted_block_number);
+        // forks that branched off below the persisted block can no longer become canonical
+        self.state.tree_state.remove_disconnected(self.persistence_state.last_persisted_block_hash);
         self.canonical_in_memory_state
             .remove_persisted_blocks(self.persistence_state.last_persisted_block_number);
     }
@@ -1561,7 +1879,11 @@ where
     /// Invoked when we the canonical chain has been updated.
     ///
     /// This is invoked on a valid forkchoice update, or if we can make the target block canonical.
-    fn on_canonical_chain_update(&mut self, chain_update: NewCanonicalChain) {
+    fn on_canonical_chain_update(
+        &mut self,
+        chain_update: NewCanonicalChain,
+        reorg_info: ReorgInfo,
+    ) {
         trace!(target: "engine", new_blocks = %chain_update.new_block_count(), reorged_blocks =  %chain_update.reorged_block_count() ,"applying new chain update");
         let start = Instant::now();
 
@@ -1569,7 +1891,7 @@ where
         self.state.tree_state.set_canonical_head(chain_update.tip().num_hash());
 
         let tip = chain_update.tip().header.clone();
-        let notification = chain_update.to_chain_notification();
+        let notification = chain_update.to_chain_notification().with_reorg_info(reorg_info);
 
         // update the tracked in-memory state with the new chain
         self.canonical_in_memory_state.update_chain(chain_update);
@@ -1717,9 +2039,12 @@ where
         }
 
         let start = Instant::now();
+        let block_hash = block.hash();
 
         // validate block consensus rules
+        let validation_start = Instant::now();
         self.validate_block(&block)?;
+        let mut validation_time = validation_start.elapsed();
 
         let Some(state_provider) = self.state_provider(block.parent_hash)? else {
             // we don't have the state required to execute this block, buffering it and find the
@@ -1745,32 +2070,80 @@ where
                 block.parent_hash.into(),
             ))
         })?;
+        let validation_start = Instant::now();
         if let Err(e) = self.consensus.validate_header_against_parent(&block, &parent_block) {
             warn!(?block, "Failed to validate header {} against parent: {e}", block.header.hash());
             return Err(e.into())
         }
-
-        let executor = self.executor_provider.executor(StateProviderDatabase::new(&state_provider));
+        validation_time += validation_start.elapsed();
+        self.on_block_validated(block_hash, validation_time);
 
         let block_number = block.number;
-        let block_hash = block.hash();
         let sealed_block = Arc::new(block.block.clone());
         let block = block.unseal();
 
+        // start computing the state root in the background while the block is being executed
+        let state_root_task = self.spawn_state_root_task(block.parent_hash);
+
+        let exec_span = debug_span!(target: "engine", "execute_block").entered();
         let exec_time = Instant::now();
-        let output = executor.execute((&block, U256::MAX).into())?;
-        debug!(target: "engine", elapsed=?exec_time.elapsed(), ?block_number, "Executed block");
+        let state_provider_db = StateProviderDatabase::new(&state_provider);
+        let executor = self.executor_provider.executor(state_provider_db);
+        let output = if let Some(task) = &state_root_task {
+            executor.execute_with_state_hook((&block, U256::MAX).into(), task.state_hook())?
+        } else {
+            executor.execute((&block, U256::MAX).into())?
+        };
+        let exec_elapsed = exec_time.elapsed();
+        exec_span.exit();
+        debug!(target: "engine", elapsed=?exec_elapsed, ?block_number, "Executed block");
+        self.metrics.block_execution_duration.record(exec_elapsed);
+        self.payload_timings.update(block_hash, |timings| timings.execution = Some(exec_elapsed));
 
         self.consensus.validate_block_post_execution(
             &block,
             PostExecutionInput::new(&output.receipts, &output.requests),
         )?;
 
-        let hashed_state = HashedPostState::from_bundle_state(&output.state.state);
+        let hashed_state = Arc::new(HashedPostState::from_bundle_state(&output.state.state));
 
+        let root_span = debug_span!(target: "engine", "state_root").entered();
         let root_time = Instant::now();
-        let (state_root, trie_output) =
-            state_provider.state_root_with_updates(hashed_state.clone())?;
+        let parallel_state_root = state_root_task.and_then(|task| {
+            let result = task.state_root(hashed_state.clone());
+            // record the duration of every parallel attempt, including the ones that fall back
+            self.metrics.state_root_parallel_duration.record(root_time.elapsed());
+            match result {
+                Ok((state_root, trie_output)) if state_root == block.state_root => {
+                    Some((state_root, trie_output))
+                }
+                Ok((state_root, _)) => {
+                    // verify the mismatch sequentially before rejecting the block
+                    warn!(target: "engine", ?block_number, got=%state_root, expected=%block.state_root, "Parallel state root mismatch, falling back to sequential state root");
+                    self.metrics.state_root_parallel_fallbacks.increment(1);
+                    None
+                }
+                Err(err) => {
+                    debug!(target: "engine", %err, ?block_number, "Failed to compute parallel state root, falling back to sequential state root");
+                    self.metrics.state_root_parallel_fallbacks.increment(1);
+                    None
+                }
+            }
+        });
+        let (state_root, trie_output) = match parallel_state_root {
+            Some(result) => result,
+            None => {
+                let sequential_time = Instant::now();
+                let result =
+                    state_provider.state_root_with_updates(hashed_state.as_ref().clone())?;
+                self.metrics.state_root_sequential_duration.record(sequential_time.elapsed());
+                result
+            }
+        };
+        let root_elapsed = root_time.elapsed();
+        self.metrics.state_root_duration.record(root_elapsed);
+        root_span.exit();
+        self.payload_timings.update(block_hash, |timings| timings.state_root = Some(root_elapsed));
         if state_root != block.state_root {
             return Err(ConsensusError::BodyStateRootDiff(
                 GotExpected { got: state_root, expected: block.state_root }.into(),
@@ -1778,13 +2151,13 @@ where
             .into())
         }
 
-        debug!(target: "engine", elapsed=?root_time.elapsed(), ?block_number, "Calculated state root");
+        debug!(target: "engine", elapsed=?root_elapsed, ?block_number, "Calculated state root");
 
         let executed = ExecutedBlock {
             block: sealed_block.clone(),
             senders: Arc::new(block.senders),
             execution_output: Arc::new(ExecutionOutcome::from((output, block_number))),
-            hashed_state: Arc::new(hashed_state),
+            hashed_state,
             trie: Arc::new(trie_output),
         };
 
@@ -1795,6 +2168,14 @@ where
         }
 
         self.state.tree_state.insert_executed(executed);
+
+        // bound the number of sibling branches that are kept in memory
+        let pruned =
+            self.state.tree_state.prune_fork_branches(self.config.max_fork_branches(), block_hash);
+        if pruned > 0 {
+            debug!(target: "engine", pruned, "Removed fork branches exceeding the limit");
+            self.metrics.pruned_fork_branches.increment(pruned as u64);
+        }
         self.metrics.executed_blocks.set(self.state.tree_state.block_count() as f64);
 
         // emit insert event
@@ -1808,6 +2189,50 @@ where
         Ok(InsertPayloadOk2::Inserted(BlockStatus2::Valid))
     }
 
+    /// Spawns a background state root task for a block on top of the given parent.
+    ///
+    /// Returns `None` if no [`StateRootTaskSpawner`] is configured or if the parent state does not
+    /// lead back to the latest persisted block, in which case the state root is computed
+    /// sequentially.
+    fn spawn_state_root_task(&self, parent_hash: B256) -> Option<StateRootHandle> {
+        let spawner = self.state_root_task_spawner.as_ref()?;
+        let task = match spawner.spawn_state_root_task() {
+            Ok(task) => task,
+            Err(err) => {
+                debug!(target: "engine", %err, "Failed to spawn state root task");
+                self.metrics.state_root_parallel_fallbacks.increment(1);
+                return None
+            }
+        };
+        let tip = task.tip()?;
+
+        // collect the in-memory blocks on top of the persisted tip, which are not yet reflected in
+        // the database
+        let (historical, blocks) = self
+            .state
+            .tree_state
+            .blocks_by_hash(parent_hash)
+            .unwrap_or_else(|| (parent_hash, Vec::new()));
+        let mut connected = historical == tip;
+        let mut ancestors = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            if block.block.hash() == tip {
+                connected = true;
+                break
+            }
+            ancestors.push(block.hashed_state);
+        }
+        if !connected {
+            trace!(target: "engine", %parent_hash, %tip, "Parent state does not lead back to the persisted tip");
+            self.metrics.state_root_parallel_fallbacks.increment(1);
+            return None
+        }
+
+        ancestors.reverse();
+        task.send_ancestors(ancestors);
+        Some(task)
+    }
+
     /// Handles an error that occurred while inserting a block.
     ///
     /// If this is a validation error this will mark the block as invalid.
@@ -1834,6 +2259,8 @@ where
             self.latest_valid_hash_for_invalid_payload(block.parent_hash)?
         };
 
+        self.queue_invalid_block_witness(block.clone(), validation_err.to_string());
+
         // keep track of the invalid header
         self.state.invalid_headers.insert(block.header);
         Ok(PayloadStatus::new(
@@ -1842,6 +2269,20 @@ where
         ))
     }
 
+    /// Queues the invalid block for its [`InvalidBlockWitness`] to be generated, if configured.
+    fn queue_invalid_block_witness(&mut self, block: SealedBlock, error: String) {
+        let Some(witnesses) = &mut self.invalid_block_witnesses else { return };
+
+        // the parent state is opened by the witness worker, either from the database or on top of
+        // the in-memory ancestors
+        let (historical, in_memory) = self
+            .state
+            .tree_state
+            .blocks_by_hash(block.parent_hash)
+            .unwrap_or_else(|| (block.parent_hash, Vec::new()));
+        witnesses.queue(InvalidBlockWitnessJob { block, error, historical, in_memory });
+    }
+
     /// Attempts to find the header for the given block hash if it is canonical.
     pub fn find_canonical_header(&self, hash: B256) -> Result<Option<SealedHeader>, ProviderError> {
         let mut canonical = self.canonical_in_memory_state.header_by_hash(hash);
@@ -2025,6 +2466,8 @@ pub struct PersistenceState {
     ///
     /// This tracks the chain height that is persisted on disk
     last_persisted_block_number: u64,
+    /// Hashes of the blocks that are being persisted by the persistence task in progress.
+    blocks: Vec<B256>,
 }
 
 impl PersistenceState {
@@ -2034,9 +2477,10 @@ impl PersistenceState {
         self.rx.is_some()
     }
 
-    /// Sets state for a started persistence task.
-    fn start(&mut self, rx: oneshot::Receiver<Option<B256>>) {
+    /// Sets state for a started persistence task that persists the given blocks.
+    fn start(&mut self, rx: oneshot::Receiver<Option<B256>>, blocks: Vec<B256>) {
         self.rx = Some((rx, Instant::now()));
+        self.blocks = blocks;
     }
 
     /// Sets state for a finished persistence task.
@@ -2064,7 +2508,10 @@ mod tests {
     use reth_trie::updates::TrieUpdates;
     use std::{
         str::FromStr,
-        sync::mpsc::{channel, Sender},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            mpsc::{channel, Sender},
+        },
     };
     use tokio::sync::mpsc::unbounded_channel;
 
@@ -2082,7 +2529,7 @@ mod tests {
     impl TestHarness {
         fn new(chain_spec: Arc<ChainSpec>) -> Self {
             let (action_tx, action_rx) = channel();
-            let persistence_handle = PersistenceHandle::new(action_tx);
+            let persistence_handle = PersistenceHandle::new(action_tx, Default::default());
 
             let consensus = Arc::new(EthBeaconConsensus::new(chain_spec.clone()));
 
@@ -2383,6 +2830,52 @@ mod tests {
         }
     }
 
+    /// The outcome of a [`MockStateRootTaskSpawner`] task.
+    #[derive(Debug, Clone, Copy)]
+    enum MockStateRoot {
+        /// Replies with the given state root.
+        Root(B256),
+        /// Replies with an error.
+        Error,
+        /// Exits without replying.
+        Disconnect,
+    }
+
+    /// A [`StateRootTaskSpawner`] that spawns tasks on top of a fixed tip and replies with a fixed
+    /// outcome.
+    #[derive(Debug)]
+    struct MockStateRootTaskSpawner {
+        tip: B256,
+        outcome: MockStateRoot,
+        finished_state_updates: Arc<AtomicUsize>,
+    }
+
+    impl StateRootTaskSpawner for MockStateRootTaskSpawner {
+        fn spawn_state_root_task(&self) -> Result<StateRootHandle, StateRootTaskError> {
+            let (to_task, incoming) = channel();
+            let (result_tx, result_rx) = channel();
+            let outcome = self.outcome;
+            let finished_state_updates = self.finished_state_updates.clone();
+            std::thread::spawn(move || {
+                while let Ok(message) = incoming.recv() {
+                    if let StateRootMessage::FinishedStateUpdates(_) = message {
+                        finished_state_updates.fetch_add(1, Ordering::Relaxed);
+                        let result = match outcome {
+                            MockStateRoot::Root(root) => Ok((root, TrieUpdates::default())),
+                            MockStateRoot::Error => Err(StateRootTaskError::Provider(
+                                ProviderError::UnsupportedProvider,
+                            )),
+                            MockStateRoot::Disconnect => return,
+                        };
+                        let _ = result_tx.send(result);
+                        return
+                    }
+                }
+            });
+            Ok(StateRootHandle::new(Some(self.tip), to_task, result_rx))
+        }
+    }
+
     #[test]
     fn test_tree_persist_block_batch() {
         let tree_config = TreeConfig::default();
@@ -2567,6 +3060,7 @@ mod tests {
                     payload: payload.clone().into(),
                     cancun_fields: None,
                     tx,
+                    span: Span::none(),
                 }
                 .into(),
             ))
@@ -2787,6 +3281,81 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_tree_state_prune_fork_branches() {
+        let mut tree_state = TreeState::new(BlockNumHash::default());
+        let mut test_block_builder = TestBlockBuilder::default();
+
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+        for block in &blocks {
+            tree_state.insert_executed(block.clone());
+        }
+        tree_state.set_canonical_head(blocks[2].block.num_hash());
+
+        // two sibling branches off block 1, and one sibling of the canonical head
+        let fork_a_2 = test_block_builder.get_executed_block_with_number(2, blocks[0].block.hash());
+        let fork_a_3 = test_block_builder.get_executed_block_with_number(3, fork_a_2.block.hash());
+        let fork_b_2 = test_block_builder.get_executed_block_with_number(2, blocks[0].block.hash());
+        let fork_c_3 = test_block_builder.get_executed_block_with_number(3, blocks[1].block.hash());
+        for block in [&fork_a_2, &fork_a_3, &fork_b_2, &fork_c_3] {
+            tree_state.insert_executed(block.clone());
+        }
+
+        let tips = tree_state.fork_branch_tips();
+        assert_eq!(tips.len(), 3);
+        assert_eq!(tips[0].hash, fork_b_2.block.hash());
+
+        // the lowest branch is removed first, the kept branch is never removed
+        assert_eq!(tree_state.prune_fork_branches(1, fork_b_2.block.hash()), 2);
+        assert!(tree_state.blocks_by_hash.contains_key(&fork_b_2.block.hash()));
+        assert!(!tree_state.blocks_by_hash.contains_key(&fork_a_2.block.hash()));
+        assert!(!tree_state.blocks_by_hash.contains_key(&fork_a_3.block.hash()));
+        assert!(!tree_state.blocks_by_hash.contains_key(&fork_c_3.block.hash()));
+
+        // the canonical chain is untouched
+        for block in &blocks {
+            assert!(tree_state.blocks_by_hash.contains_key(&block.block.hash()));
+        }
+        assert_eq!(
+            tree_state.parent_to_child.get(&blocks[0].block.hash()),
+            Some(&HashSet::from([blocks[1].block.hash(), fork_b_2.block.hash()]))
+        );
+
+        // switching to the remaining fork is a pointer swap
+        let fork_tree = tree_state.fork_tree();
+        assert_eq!(fork_tree.blocks.len(), 4);
+        assert_eq!(fork_tree.blocks.iter().filter(|block| !block.canonical).count(), 1);
+        assert!(matches!(
+            tree_state.on_new_head(fork_b_2.block.hash()),
+            Some(NewCanonicalChain::Reorg { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tree_state_remove_disconnected() {
+        let mut tree_state = TreeState::new(BlockNumHash::default());
+        let mut test_block_builder = TestBlockBuilder::default();
+
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..5).collect();
+        for block in &blocks {
+            tree_state.insert_executed(block.clone());
+        }
+
+        // fork off block 1 which becomes disconnected once block 2 is persisted
+        let fork_2 = test_block_builder.get_executed_block_with_number(2, blocks[0].block.hash());
+        let fork_3 = test_block_builder.get_executed_block_with_number(3, fork_2.block.hash());
+        tree_state.insert_executed(fork_2);
+        tree_state.insert_executed(fork_3.clone());
+
+        tree_state.remove_before(2);
+        tree_state.remove_disconnected(blocks[1].block.hash());
+
+        assert!(!tree_state.blocks_by_hash.contains_key(&fork_3.block.hash()));
+        assert_eq!(tree_state.blocks_by_number.get(&3).map(Vec::len), Some(1));
+        assert!(tree_state.blocks_by_hash.contains_key(&blocks[2].block.hash()));
+        assert!(tree_state.blocks_by_hash.contains_key(&blocks[3].block.hash()));
+    }
+
     #[tokio::test]
     async fn test_get_canonical_blocks_to_persist() {
         let chain_spec = MAINNET.clone();
@@ -2916,6 +3485,138 @@ mod tests {
         test_harness.check_canon_head(fork_chain_last_hash);
     }
 
+    #[tokio::test]
+    async fn test_engine_tree_fork_tree_request() {
+        let chain_spec = MAINNET.clone();
+        let mut test_harness = TestHarness::new(chain_spec.clone());
+
+        let main_chain: Vec<_> = test_harness.block_builder.get_executed_blocks(0..5).collect();
+        test_harness = test_harness.with_blocks(main_chain.clone());
+
+        let fork_chain = test_harness.block_builder.create_fork(main_chain[2].block(), 2);
+        for block in &fork_chain {
+            test_harness.insert_block(block.clone()).unwrap();
+        }
+
+        // the snapshot is built when it is requested
+        let (tx, rx) = oneshot::channel();
+        test_harness
+            .tree
+            .on_engine_message(FromEngine::Request(EngineApiRequest::ForkTree(tx)))
+            .unwrap();
+        let fork_tree = rx.await.unwrap();
+
+        let canonical_head = main_chain.last().unwrap().block();
+        assert_eq!(fork_tree.canonical_head_hash, canonical_head.hash());
+        assert_eq!(fork_tree.canonical_head_number, canonical_head.number);
+        assert_eq!(fork_tree.blocks.len(), main_chain.len() + fork_chain.len());
+        let forked = fork_tree.blocks.iter().filter(|block| !block.canonical).collect::<Vec<_>>();
+        assert_eq!(forked.len(), fork_chain.len());
+        assert_eq!(forked[0].parent_hash, main_chain[2].block().hash());
+    }
+
+    #[tokio::test]
+    async fn test_engine_tree_fcu_reorg_below_safe_block() {
+        let chain_spec = MAINNET.clone();
+        let mut test_harness = TestHarness::new(chain_spec.clone());
+        test_harness.tree.config = TreeConfig::default().with_protect_safe_block(true);
+
+        let main_chain: Vec<_> = test_harness.block_builder.get_executed_blocks(0..5).collect();
+        test_harness = test_harness.with_blocks(main_chain.clone());
+        test_harness.tree.canonical_in_memory_state.set_safe(main_chain[3].block().header.clone());
+
+        let fork_chain = test_harness.block_builder.create_fork(main_chain[2].block(), 3);
+        let fork_chain_last_hash = fork_chain.last().unwrap().hash();
+
+        for block in &fork_chain {
+            test_harness.insert_block(block.clone()).unwrap();
+        }
+        test_harness.check_fork_chain_insertion(fork_chain.clone()).await;
+
+        // the reorg reverts the safe block and is refused
+        test_harness.send_fcu(fork_chain_last_hash, ForkchoiceStatus::Syncing).await;
+        let event = test_harness.from_tree_rx.recv().await.unwrap();
+        match event {
+            EngineApiEvent::BeaconConsensus(BeaconConsensusEngineEvent::ReorgAlert(alert)) => {
+                assert_eq!(alert.depth, 2);
+                assert_eq!(alert.new_head.hash, fork_chain_last_hash);
+                assert!(alert.rejected);
+            }
+            _ => panic!("Unexpected event: {:#?}", event),
+        }
+        test_harness.check_fcu(fork_chain_last_hash, ForkchoiceStatus::Syncing).await;
+        test_harness.check_canon_head(main_chain.last().unwrap().block().hash());
+        let tracker = &test_harness.tree.state.forkchoice_state_tracker;
+        assert_eq!(
+            tracker.last_refused_reorg().map(|state| state.head_block_hash),
+            Some(fork_chain_last_hash)
+        );
+        assert_eq!(tracker.sync_target_state(), None);
+
+        // a retry of the refused update is refused again without another alert
+        test_harness.send_fcu(fork_chain_last_hash, ForkchoiceStatus::Syncing).await;
+        test_harness.check_fcu(fork_chain_last_hash, ForkchoiceStatus::Syncing).await;
+        test_harness.check_canon_head(main_chain.last().unwrap().block().hash());
+
+        // the reorg is applied once it is allowed
+        let mut canon_notifications =
+            test_harness.tree.canonical_in_memory_state.subscribe_canon_state();
+        test_harness.tree.canonical_in_memory_state.allow_reorg(fork_chain_last_hash);
+        test_harness.send_fcu(fork_chain_last_hash, ForkchoiceStatus::Valid).await;
+        test_harness.check_canon_commit(fork_chain_last_hash).await;
+        test_harness.check_fcu(fork_chain_last_hash, ForkchoiceStatus::Valid).await;
+        test_harness.check_canon_head(fork_chain_last_hash);
+        assert_eq!(test_harness.tree.state.forkchoice_state_tracker.last_refused_reorg(), None);
+
+        let notification = canon_notifications.recv().await.unwrap();
+        assert_eq!(
+            notification.reorg_info(),
+            Some(ReorgInfo { depth: 2, alerted: false, allowed_below_safe_block: true })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_engine_tree_fcu_deep_reorg_alert() {
+        let chain_spec = MAINNET.clone();
+        let mut test_harness = TestHarness::new(chain_spec.clone());
+        test_harness.tree.config = TreeConfig::default().with_reorg_alert_depth(1);
+
+        let main_chain: Vec<_> = test_harness.block_builder.get_executed_blocks(0..5).collect();
+        test_harness = test_harness.with_blocks(main_chain.clone());
+
+        let fork_chain = test_harness.block_builder.create_fork(main_chain[2].block(), 3);
+        let fork_chain_last_hash = fork_chain.last().unwrap().hash();
+
+        for block in &fork_chain {
+            test_harness.insert_block(block.clone()).unwrap();
+        }
+        test_harness.check_fork_chain_insertion(fork_chain.clone()).await;
+
+        // the reorg is deeper than the alert depth, so it is alerted but applied
+        let mut canon_notifications =
+            test_harness.tree.canonical_in_memory_state.subscribe_canon_state();
+        test_harness.send_fcu(fork_chain_last_hash, ForkchoiceStatus::Valid).await;
+        let event = test_harness.from_tree_rx.recv().await.unwrap();
+        match event {
+            EngineApiEvent::BeaconConsensus(BeaconConsensusEngineEvent::ReorgAlert(alert)) => {
+                assert_eq!(alert.depth, 2);
+                assert_eq!(alert.old_head.hash, main_chain.last().unwrap().block().hash());
+                assert_eq!(alert.new_head.hash, fork_chain_last_hash);
+                assert!(!alert.rejected);
+            }
+            _ => panic!("Unexpected event: {:#?}", event),
+        }
+        test_harness.check_canon_commit(fork_chain_last_hash).await;
+        test_harness.check_fcu(fork_chain_last_hash, ForkchoiceStatus::Valid).await;
+        test_harness.check_canon_head(fork_chain_last_hash);
+
+        let notification = canon_notifications.recv().await.unwrap();
+        assert_eq!(
+            notification.reorg_info(),
+            Some(ReorgInfo { depth: 2, alerted: true, allowed_below_safe_block: false })
+        );
+    }
+
     #[tokio::test]
     async fn test_engine_tree_live_sync_transition_required_blocks_requested() {
         reth_tracing::init_test_tracing();
@@ -3331,4 +4032,43 @@ mod tests {
         let fork_tip_hash = side_chain.last().unwrap().hash();
         test_harness.send_fcu(fork_tip_hash, ForkchoiceStatus::Invalid).await;
     }
+
+    #[test]
+    fn test_insert_block_state_root_task() {
+        let chain_spec = MAINNET.clone();
+
+        let mut test_harness = TestHarness::new(chain_spec.clone());
+        let main_chain: Vec<_> = test_harness.block_builder.get_executed_blocks(0..3).collect();
+        test_harness = test_harness.with_blocks(main_chain.clone());
+        let block = test_harness
+            .block_builder
+            .create_fork(main_chain.last().unwrap().block(), 1)
+            .pop()
+            .unwrap();
+
+        for (outcome, uses_task) in [
+            (MockStateRoot::Root(block.state_root), true),
+            (MockStateRoot::Root(B256::random()), false),
+            (MockStateRoot::Error, false),
+            (MockStateRoot::Disconnect, false),
+        ] {
+            let mut test_harness =
+                TestHarness::new(chain_spec.clone()).with_blocks(main_chain.clone());
+            let finished_state_updates = Arc::new(AtomicUsize::new(0));
+            test_harness.tree.state_root_task_spawner = Some(Box::new(MockStateRootTaskSpawner {
+                tip: block.parent_hash,
+                outcome,
+                finished_state_updates: finished_state_updates.clone(),
+            }));
+
+            let result = test_harness.insert_block(block.clone()).unwrap();
+            assert_eq!(result, InsertPayloadOk2::Inserted(BlockStatus2::Valid), "{outcome:?}");
+            assert_eq!(finished_state_updates.load(Ordering::Relaxed), 1, "{outcome:?}");
+            assert!(test_harness.tree.state.tree_state.block_by_hash(block.hash()).is_some());
+
+            // the sequential state root is only computed if the task result is unusable
+            let sequential_roots = test_harness.tree.provider.state_roots.lock().len();
+            assert_eq!(sequential_roots, usize::from(uses_task), "{outcome:?}");
+        }
+    }
 }
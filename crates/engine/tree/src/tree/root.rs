@@ -0,0 +1,360 @@
+//! Background state root computation for payloads that are being executed.
+
+use crate::tree::metrics::StateRootTaskMetrics;
+use reth_db_api::database::Database as RethDatabase;
+use reth_evm::execute::OnStateHook;
+use reth_primitives::{keccak256, B256};
+use reth_provider::{providers::ConsistentDbView, DatabaseProviderFactory, ProviderError};
+use reth_revm::primitives::EvmState;
+use reth_trie::{proof::Proof, updates::TrieUpdates, HashedPostState};
+use reth_trie_db::DatabaseProof;
+use reth_trie_parallel::parallel_root::{ParallelStateRoot, ParallelStateRootError};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    fmt,
+    sync::{
+        mpsc::{channel, Receiver, Sender, TryRecvError},
+        Arc,
+    },
+    time::Instant,
+};
+use tracing::*;
+
+/// Spawns background tasks that compute the state root of a block while it is being executed.
+pub trait StateRootTaskSpawner: Send + Sync + fmt::Debug + 'static {
+    /// Spawns a new [`StateRootTask`] on top of the latest persisted state.
+    fn spawn_state_root_task(&self) -> Result<StateRootHandle, StateRootTaskError>;
+}
+
+/// A [`StateRootTaskSpawner`] that computes state roots with [`ParallelStateRoot`] over a
+/// [`ConsistentDbView`] of the database.
+///
+/// All tasks are run one after another on a single background worker thread.
+pub struct ParallelStateRootTaskSpawner<DB, Provider> {
+    /// The database provider factory.
+    provider: Provider,
+    /// Sender half of the worker channel.
+    to_worker: Sender<StateRootTask<DB, Provider>>,
+    /// State root task metrics.
+    metrics: StateRootTaskMetrics,
+}
+
+impl<DB, Provider> ParallelStateRootTaskSpawner<DB, Provider>
+where
+    DB: RethDatabase + 'static,
+    Provider: DatabaseProviderFactory<DB> + Send + Sync + 'static,
+{
+    /// Creates a new [`ParallelStateRootTaskSpawner`] and spawns its worker thread.
+    ///
+    /// The worker exits once the spawner is dropped.
+    pub fn new(provider: Provider) -> Self {
+        let (to_worker, tasks) = channel::<StateRootTask<DB, Provider>>();
+        std::thread::Builder::new()
+            .name("State Root Task".to_string())
+            .spawn(move || {
+                while let Ok(task) = tasks.recv() {
+                    task.run();
+                }
+            })
+            .unwrap();
+
+        Self { provider, to_worker, metrics: StateRootTaskMetrics::default() }
+    }
+}
+
+impl<DB, Provider> fmt::Debug for ParallelStateRootTaskSpawner<DB, Provider> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParallelStateRootTaskSpawner").finish_non_exhaustive()
+    }
+}
+
+impl<DB, Provider> StateRootTaskSpawner for ParallelStateRootTaskSpawner<DB, Provider>
+where
+    DB: RethDatabase + 'static,
+    Provider: DatabaseProviderFactory<DB> + Clone + Send + Sync + 'static,
+{
+    fn spawn_state_root_task(&self) -> Result<StateRootHandle, StateRootTaskError> {
+        let view = ConsistentDbView::new_with_latest_tip(self.provider.clone())?;
+        let tip = view.tip();
+
+        let (to_task, from_handle) = channel();
+        let (result_tx, result_rx) = channel();
+        let task =
+            StateRootTask { view, incoming: from_handle, result_tx, metrics: self.metrics.clone() };
+        self.to_worker.send(task).map_err(|_| StateRootTaskError::TaskClosed)?;
+
+        Ok(StateRootHandle::new(tip, to_task, result_rx))
+    }
+}
+
+/// Messages sent from the engine to the [`StateRootTask`].
+#[derive(Debug)]
+pub enum StateRootMessage {
+    /// State changes of a transaction that was executed.
+    StateUpdate(EvmState),
+    /// Hashed post states of the in-memory ancestors of the block, from oldest to newest.
+    Ancestors(Vec<Arc<HashedPostState>>),
+    /// The block has been executed, this is the resulting hashed post state.
+    FinishedStateUpdates(Arc<HashedPostState>),
+}
+
+/// Handle to a spawned [`StateRootTask`].
+///
+/// Dropping the handle terminates the task.
+#[derive(Debug)]
+pub struct StateRootHandle {
+    /// The latest persisted block the task computes the state root on top of.
+    tip: Option<B256>,
+    /// Sender half of the task channel.
+    to_task: Sender<StateRootMessage>,
+    /// Receiver of the computed state root.
+    result_rx: Receiver<StateRootTaskResult>,
+}
+
+impl StateRootHandle {
+    /// Creates a new handle from the channels of a spawned task.
+    pub const fn new(
+        tip: Option<B256>,
+        to_task: Sender<StateRootMessage>,
+        result_rx: Receiver<StateRootTaskResult>,
+    ) -> Self {
+        Self { tip, to_task, result_rx }
+    }
+
+    /// Returns the hash of the latest persisted block the task computes the state root on top of.
+    pub const fn tip(&self) -> Option<B256> {
+        self.tip
+    }
+
+    /// Sends the hashed post states of the in-memory blocks between the persisted tip and the
+    /// block that is being executed, from oldest to newest.
+    pub fn send_ancestors(&self, ancestors: Vec<Arc<HashedPostState>>) {
+        let _ = self.to_task.send(StateRootMessage::Ancestors(ancestors));
+    }
+
+    /// Returns an [`OnStateHook`] that streams the state changes of every executed transaction to
+    /// the task, so that the proofs of the changed state get prefetched.
+    pub fn state_hook(&self) -> impl OnStateHook {
+        let to_task = self.to_task.clone();
+        move |state: &EvmState| {
+            let _ = to_task.send(StateRootMessage::StateUpdate(state.clone()));
+        }
+    }
+
+    /// Sends the hashed post state of the executed block to the task and waits for the state root.
+    pub fn state_root(self, hashed_state: Arc<HashedPostState>) -> StateRootTaskResult {
+        self.to_task
+            .send(StateRootMessage::FinishedStateUpdates(hashed_state))
+            .map_err(|_| StateRootTaskError::TaskClosed)?;
+        self.result_rx.recv().map_err(|_| StateRootTaskError::TaskClosed)?
+    }
+}
+
+/// The result of a [`StateRootTask`].
+pub type StateRootTaskResult = Result<(B256, TrieUpdates), StateRootTaskError>;
+
+/// Background task that prefetches proofs for the state changed during execution and computes the
+/// state root with [`ParallelStateRoot`] once execution is finished.
+///
+/// Prefetching warms the trie nodes and hashed state of the changed accounts and storage slots, so
+/// that the final state root computation mostly hits cached database pages.
+#[derive(Debug)]
+pub struct StateRootTask<DB, Provider> {
+    /// Consistent view of the database.
+    view: ConsistentDbView<DB, Provider>,
+    /// Incoming messages from the [`StateRootHandle`].
+    incoming: Receiver<StateRootMessage>,
+    /// Sender for the computed state root.
+    result_tx: Sender<StateRootTaskResult>,
+    /// State root task metrics.
+    metrics: StateRootTaskMetrics,
+}
+
+impl<DB, Provider> StateRootTask<DB, Provider>
+where
+    DB: RethDatabase,
+    Provider: DatabaseProviderFactory<DB> + Send + Sync,
+{
+    /// Runs the task until the state root is computed or the handle is dropped.
+    pub fn run(self) {
+        let mut hashed_state = HashedPostState::default();
+        let mut seen = HashMap::<B256, HashSet<B256>>::default();
+        let mut targets = HashMap::<B256, Vec<B256>>::default();
+        let mut prefetch_enabled = true;
+        let mut prefetched_targets = 0;
+
+        loop {
+            // Block on the channel only if there is nothing left to prefetch, otherwise prefetch
+            // the accumulated targets once all pending messages are drained.
+            let message = if targets.is_empty() {
+                match self.incoming.recv() {
+                    Ok(message) => message,
+                    Err(_) => return,
+                }
+            } else {
+                match self.incoming.try_recv() {
+                    Ok(message) => message,
+                    Err(TryRecvError::Empty) => {
+                        let targets = std::mem::take(&mut targets);
+                        prefetched_targets += targets.len();
+                        if prefetch_enabled {
+                            if let Err(err) = self.prefetch(targets) {
+                                debug!(target: "engine::root", %err, "Failed to prefetch proofs");
+                                prefetch_enabled = false;
+                            }
+                        }
+                        continue
+                    }
+                    Err(TryRecvError::Disconnected) => return,
+                }
+            };
+
+            match message {
+                StateRootMessage::StateUpdate(state) => {
+                    for (address, account) in state {
+                        if !account.is_touched() {
+                            continue
+                        }
+
+                        let hashed_address = keccak256(address);
+                        let seen_slots = match seen.entry(hashed_address) {
+                            Entry::Occupied(entry) => entry.into_mut(),
+                            Entry::Vacant(entry) => {
+                                targets.entry(hashed_address).or_default();
+                                entry.insert(HashSet::default())
+                            }
+                        };
+                        for (slot, _) in account.changed_storage_slots() {
+                            let hashed_slot = keccak256(B256::new(slot.to_be_bytes()));
+                            if seen_slots.insert(hashed_slot) {
+                                targets.entry(hashed_address).or_default().push(hashed_slot);
+                            }
+                        }
+                    }
+                }
+                StateRootMessage::Ancestors(ancestors) => {
+                    for ancestor in ancestors {
+                        hashed_state.extend_ref(&ancestor);
+                    }
+                }
+                StateRootMessage::FinishedStateUpdates(state) => {
+                    hashed_state.extend_ref(&state);
+                    self.metrics.prefetched_targets.record(prefetched_targets as f64);
+
+                    let start = Instant::now();
+                    let result = ParallelStateRoot::new(self.view, hashed_state)
+                        .incremental_root_with_updates()
+                        .map_err(Into::into);
+                    self.metrics.state_root_duration.record(start.elapsed());
+
+                    let _ = self.result_tx.send(result);
+                    return
+                }
+            }
+        }
+    }
+
+    /// Generates a multiproof for the given targets, loading the touched trie nodes and hashed
+    /// state from the database.
+    fn prefetch(&self, targets: HashMap<B256, Vec<B256>>) -> Result<(), StateRootTaskError> {
+        let start = Instant::now();
+        let provider_ro = self.view.provider_ro()?;
+        Proof::from_tx(provider_ro.tx_ref())
+            .with_targets(targets)
+            .multiproof()
+            .map_err(ProviderError::from)?;
+        self.metrics.prefetch_duration.record(start.elapsed());
+        Ok(())
+    }
+}
+
+/// Error during background state root computation.
+#[derive(Debug, thiserror::Error)]
+pub enum StateRootTaskError {
+    /// The task terminated before sending the state root.
+    #[error("state root task terminated unexpectedly")]
+    TaskClosed,
+    /// Error while computing the parallel state root.
+    #[error(transparent)]
+    ParallelStateRoot(#[from] ParallelStateRootError),
+    /// Provider error.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Account, Address, StorageEntry, U256};
+    use reth_provider::{test_utils::create_test_provider_factory, HashingWriter};
+    use reth_revm::primitives::{Account as EvmAccount, AccountStatus, EvmStorageSlot};
+    use reth_trie::{HashedStorage, StateRoot};
+    use reth_trie_db::DatabaseStateRoot;
+
+    #[test]
+    fn state_root_task_matches_sequential_root() {
+        let factory = create_test_provider_factory();
+
+        let addresses = (0..10u64).map(|i| Address::with_last_byte(i as u8)).collect::<Vec<_>>();
+        {
+            let provider_rw = factory.provider_rw().unwrap();
+            provider_rw
+                .insert_account_for_hashing(addresses.iter().enumerate().map(|(i, address)| {
+                    (*address, Some(Account { nonce: i as u64, ..Default::default() }))
+                }))
+                .unwrap();
+            provider_rw
+                .insert_storage_for_hashing(addresses.iter().map(|address| {
+                    (
+                        *address,
+                        [StorageEntry { key: B256::with_last_byte(1), value: U256::from(1) }],
+                    )
+                }))
+                .unwrap();
+            provider_rw.commit().unwrap();
+        }
+
+        // ancestor changes the nonce of the first account, the block changes storage of the second
+        let mut ancestor = HashedPostState::default();
+        ancestor
+            .accounts
+            .insert(keccak256(addresses[0]), Some(Account { nonce: 100, ..Default::default() }));
+        let mut block = HashedPostState::default();
+        let mut storage = HashedStorage::new(false);
+        storage.storage.insert(keccak256(B256::with_last_byte(1)), U256::from(2));
+        block.storages.insert(keccak256(addresses[1]), storage);
+
+        let spawner = ParallelStateRootTaskSpawner::new(factory.clone());
+        let handle = spawner.spawn_state_root_task().unwrap();
+        handle.send_ancestors(vec![Arc::new(ancestor.clone())]);
+        {
+            let mut hook = handle.state_hook();
+            let state: EvmState = [
+                (addresses[0], EvmAccount { status: AccountStatus::Touched, ..Default::default() }),
+                (
+                    addresses[1],
+                    EvmAccount {
+                        storage: [(
+                            U256::from(1),
+                            EvmStorageSlot::new_changed(U256::from(1), U256::from(2)),
+                        )]
+                        .into_iter()
+                        .collect(),
+                        status: AccountStatus::Touched,
+                        ..Default::default()
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect();
+            hook.on_state(&state);
+        }
+        let (root, _) = handle.state_root(Arc::new(block.clone())).unwrap();
+
+        let mut expected_state = ancestor;
+        expected_state.extend(block);
+        let provider = factory.provider().unwrap();
+        let expected = StateRoot::overlay_root(provider.tx_ref(), expected_state).unwrap();
+        assert_eq!(root, expected);
+    }
+}
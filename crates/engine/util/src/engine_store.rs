@@ -1,23 +1,61 @@
 //! Stores engine API messages to disk for later inspection and replay.
+//!
+//! Messages are appended as JSON lines to message files in the store directory. Once a file
+//! exceeds the configured maximum size, the store rotates to a new file and removes the oldest
+//! files beyond the configured maximum number of files. Next to the messages,
+//! the store records the outcome the engine returned for each of them, so that a replay can
+//! compare the recorded outcome with the actual one.
+//!
+//! Stores written by earlier versions kept every message in its own JSON file, named after the
+//! time the message was received. These files are still read, ahead of the message files, but
+//! are neither written nor removed.
 
 use futures::{Stream, StreamExt};
-use reth_beacon_consensus::BeaconEngineMessage;
+use parking_lot::Mutex;
+use reth_beacon_consensus::{
+    BeaconEngineMessage, BeaconOnNewPayloadError, ForkchoiceStatus, OnForkChoiceUpdated,
+};
 use reth_engine_primitives::EngineTypes;
+use reth_errors::RethResult;
 use reth_fs_util as fs;
 use reth_rpc_types::{
-    engine::{CancunPayloadFields, ForkchoiceState},
+    engine::{CancunPayloadFields, ForkchoiceState, PayloadStatus},
     ExecutionPayload,
 };
-use serde::{Deserialize, Serialize};
+use reth_tasks::TaskSpawner;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
-    collections::BTreeMap,
-    path::PathBuf,
+    collections::{BTreeMap, HashMap},
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
     pin::Pin,
+    sync::Arc,
     task::{ready, Context, Poll},
     time::SystemTime,
 };
+use tokio::sync::oneshot;
 use tracing::*;
 
+/// The default size in bytes after which the store rotates to a new message file.
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 128 * 1024 * 1024;
+
+/// The default number of message files the store retains.
+pub const DEFAULT_MAX_FILES: usize = 8;
+
+/// The prefix of message file names, followed by the index of the first message in the file.
+const MESSAGE_FILE_PREFIX: &str = "messages-";
+
+/// The extension of message files.
+const MESSAGE_FILE_EXTENSION: &str = ".jsonl";
+
+/// The extension of legacy message files, which contain a single message and are named
+/// `<received_at>-<method>-<block_hash>.json`.
+const LEGACY_MESSAGE_FILE_EXTENSION: &str = ".json";
+
+/// The number of bytes read at once when searching the last message file for the last message.
+const TAIL_CHUNK_SIZE: usize = 64 * 1024;
+
 /// A message from the engine API that has been stored to disk.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -38,84 +76,447 @@ pub enum StoredEngineApiMessage<Attributes> {
     },
 }
 
+/// The on-disk representation of a [`ForkchoiceStatus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StoredForkchoiceStatus {
+    /// The forkchoice state is valid.
+    Valid,
+    /// The forkchoice state is invalid.
+    Invalid,
+    /// The forkchoice state is unknown.
+    Syncing,
+}
+
+impl From<ForkchoiceStatus> for StoredForkchoiceStatus {
+    fn from(status: ForkchoiceStatus) -> Self {
+        match status {
+            ForkchoiceStatus::Valid => Self::Valid,
+            ForkchoiceStatus::Invalid => Self::Invalid,
+            ForkchoiceStatus::Syncing => Self::Syncing,
+        }
+    }
+}
+
+/// The outcome the engine returned for a stored engine API message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StoredEngineApiOutcome {
+    /// The [`PayloadStatus`] returned for an `engine_newPayload` call.
+    NewPayload(PayloadStatus),
+    /// The status of the forkchoice state of an `engine_forkchoiceUpdated` call.
+    ForkchoiceUpdated(StoredForkchoiceStatus),
+    /// The engine failed to process the message.
+    Error(String),
+}
+
+impl StoredEngineApiOutcome {
+    /// Creates the outcome from the response of the engine to a
+    /// [`BeaconEngineMessage::NewPayload`] message.
+    pub fn from_new_payload_response(
+        response: &Result<PayloadStatus, BeaconOnNewPayloadError>,
+    ) -> Self {
+        match response {
+            Ok(status) => Self::NewPayload(status.clone()),
+            Err(error) => Self::Error(error.to_string()),
+        }
+    }
+
+    /// Creates the outcome from the response of the engine to a
+    /// [`BeaconEngineMessage::ForkchoiceUpdated`] message.
+    pub fn from_forkchoice_updated_response(response: &RethResult<OnForkChoiceUpdated>) -> Self {
+        match response {
+            Ok(on_updated) => Self::ForkchoiceUpdated(on_updated.forkchoice_status().into()),
+            Err(error) => Self::Error(error.to_string()),
+        }
+    }
+
+    /// Returns true if both outcomes are equivalent.
+    ///
+    /// Unlike [`PartialEq`], this ignores the messages of validation errors and engine errors,
+    /// which may change between versions without the outcome being any different.
+    pub fn matches(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::NewPayload(this), Self::NewPayload(other)) => {
+                std::mem::discriminant(&this.status) == std::mem::discriminant(&other.status) &&
+                    this.latest_valid_hash == other.latest_valid_hash
+            }
+            (Self::ForkchoiceUpdated(this), Self::ForkchoiceUpdated(other)) => this == other,
+            (Self::Error(_), Self::Error(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A single line of a message file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum StoredEngineApiRecord<Attributes> {
+    /// A received engine API message.
+    Message {
+        /// The index of the message in the store.
+        index: u64,
+        /// When the message was received, in milliseconds since the unix epoch.
+        received_at: u64,
+        /// The received message.
+        message: StoredEngineApiMessage<Attributes>,
+    },
+    /// The outcome of a previously stored message.
+    Outcome {
+        /// The index of the message this is the outcome of.
+        index: u64,
+        /// The outcome returned by the engine.
+        outcome: StoredEngineApiOutcome,
+    },
+}
+
+/// The index of a [`StoredEngineApiRecord`], used to resume the indices of an existing store
+/// without knowing the payload attributes type.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum StoredRecordIndex {
+    Message { index: u64 },
+    Outcome {},
+}
+
+/// An engine API message read from the store, together with its recorded outcome.
+#[derive(Debug)]
+pub struct StoredEngineApiEntry<Attributes> {
+    /// The index of the message in the store.
+    pub index: u64,
+    /// When the message was received, in milliseconds since the unix epoch.
+    pub received_at: u64,
+    /// The stored message.
+    pub message: StoredEngineApiMessage<Attributes>,
+    /// The outcome the engine returned for the message, if it was recorded.
+    pub outcome: Option<StoredEngineApiOutcome>,
+}
+
+/// The message file that is currently appended to.
+#[derive(Debug)]
+struct MessageFileWriter {
+    /// The index of the next stored message.
+    next_index: u64,
+    /// The open message file and its current size, if any.
+    file: Option<(File, u64)>,
+}
+
 /// This can read and write engine API messages in a specific directory.
 #[derive(Debug)]
 pub struct EngineMessageStore {
     /// The path to the directory that stores the engine API messages.
     path: PathBuf,
+    /// The size in bytes after which the store rotates to a new message file.
+    max_file_size: u64,
+    /// The maximum number of message files retained, the oldest files are removed on rotation.
+    max_files: usize,
+    /// The writer of message files, initialized on the first write.
+    writer: Mutex<Option<MessageFileWriter>>,
 }
 
 impl EngineMessageStore {
     /// Creates a new [`EngineMessageStore`] at the given path.
     ///
-    /// The path is expected to be a directory, where individual message JSON files will be stored.
+    /// The path is expected to be a directory, where message files will be stored.
     pub const fn new(path: PathBuf) -> Self {
-        Self { path }
+        Self {
+            path,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            max_files: DEFAULT_MAX_FILES,
+            writer: Mutex::new(None),
+        }
+    }
+
+    /// Creates a new [`EngineMessageStore`] at the given path with the given rotation limits.
+    ///
+    /// Limits that are not set fall back to [`DEFAULT_MAX_FILE_SIZE`] and [`DEFAULT_MAX_FILES`].
+    pub fn with_limits(
+        path: PathBuf,
+        max_file_size: Option<u64>,
+        max_files: Option<usize>,
+    ) -> Self {
+        Self {
+            max_file_size: max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE),
+            max_files: max_files.unwrap_or(DEFAULT_MAX_FILES).max(1),
+            ..Self::new(path)
+        }
+    }
+
+    /// Sets the size in bytes after which the store rotates to a new message file.
+    pub const fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
     }
 
-    /// Stores the received [`BeaconEngineMessage`] to disk, appending the `received_at` time to the
-    /// path.
+    /// Sets the maximum number of message files retained by the store.
+    ///
+    /// This is at least one, the file that is currently written to.
+    pub fn with_max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files.max(1);
+        self
+    }
+
+    /// Returns the path to the directory that stores the engine API messages.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Stores the received [`BeaconEngineMessage`] to disk.
+    ///
+    /// Returns the index of the stored message, or `None` if the message is not stored.
     pub fn on_message<Engine>(
         &self,
         msg: &BeaconEngineMessage<Engine>,
         received_at: SystemTime,
-    ) -> eyre::Result<()>
+    ) -> eyre::Result<Option<u64>>
     where
         Engine: EngineTypes,
     {
-        fs::create_dir_all(&self.path)?; // ensure that store path had been created
-        let timestamp = received_at.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis();
-        match msg {
+        let message = match msg {
             BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx: _tx } => {
-                let filename = format!("{}-fcu-{}.json", timestamp, state.head_block_hash);
-                fs::write(
-                    self.path.join(filename),
-                    serde_json::to_vec(&StoredEngineApiMessage::ForkchoiceUpdated {
-                        state: *state,
-                        payload_attrs: payload_attrs.clone(),
-                    })?,
-                )?;
+                StoredEngineApiMessage::ForkchoiceUpdated {
+                    state: *state,
+                    payload_attrs: payload_attrs.clone(),
+                }
             }
-            BeaconEngineMessage::NewPayload { payload, cancun_fields, tx: _tx } => {
-                let filename = format!("{}-new_payload-{}.json", timestamp, payload.block_hash());
-                fs::write(
-                    self.path.join(filename),
-                    serde_json::to_vec(
-                        &StoredEngineApiMessage::<Engine::PayloadAttributes>::NewPayload {
-                            payload: payload.clone(),
-                            cancun_fields: cancun_fields.clone(),
-                        },
-                    )?,
-                )?;
+            BeaconEngineMessage::NewPayload { payload, cancun_fields, .. } => {
+                StoredEngineApiMessage::NewPayload {
+                    payload: payload.clone(),
+                    cancun_fields: cancun_fields.clone(),
+                }
             }
             // noop
-            BeaconEngineMessage::TransitionConfigurationExchanged => (),
+            BeaconEngineMessage::TransitionConfigurationExchanged => return Ok(None),
         };
+        let received_at =
+            received_at.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis() as u64;
+        self.write_message(message, received_at).map(Some)
+    }
+
+    /// Appends the message to the store and returns its index.
+    fn write_message<Attributes: Serialize>(
+        &self,
+        message: StoredEngineApiMessage<Attributes>,
+        received_at: u64,
+    ) -> eyre::Result<u64> {
+        let mut writer = self.writer.lock();
+        let writer = self.init_writer(&mut writer)?;
+        let index = writer.next_index;
+        self.write_record(writer, &StoredEngineApiRecord::Message { index, received_at, message })?;
+        writer.next_index += 1;
+        Ok(index)
+    }
+
+    /// Stores the outcome the engine returned for the message with the given index.
+    pub fn on_outcome(&self, index: u64, outcome: StoredEngineApiOutcome) -> eyre::Result<()> {
+        let mut writer = self.writer.lock();
+        let writer = self.init_writer(&mut writer)?;
+        self.write_record(writer, &StoredEngineApiRecord::<()>::Outcome { index, outcome })
+    }
+
+    /// Returns the writer, initializing it on first use.
+    ///
+    /// The index of the next message continues after the last message already in the store.
+    fn init_writer<'a>(
+        &self,
+        writer: &'a mut Option<MessageFileWriter>,
+    ) -> eyre::Result<&'a mut MessageFileWriter> {
+        if writer.is_none() {
+            fs::create_dir_all(&self.path)?; // ensure that store path had been created
+            let (legacy_files, mut message_files) = self.store_files()?;
+            // legacy messages are numbered ahead of the messages written by this store
+            let mut next_index = legacy_files.len() as u64;
+            if let Some((first_index, path)) = message_files.pop() {
+                next_index = match last_message_index(&path)? {
+                    Some(index) => index + 1,
+                    None => first_index,
+                };
+            }
+            *writer = Some(MessageFileWriter { next_index, file: None });
+        }
+        Ok(writer.as_mut().expect("initialized above"))
+    }
+
+    /// Appends the record to the current message file, rotating to a new file if the current one
+    /// exceeds the maximum file size.
+    ///
+    /// On rotation, the oldest message files beyond the maximum number of files are removed.
+    fn write_record<Attributes: Serialize>(
+        &self,
+        writer: &mut MessageFileWriter,
+        record: &StoredEngineApiRecord<Attributes>,
+    ) -> eyre::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        if writer.file.as_ref().map_or(true, |(_, size)| *size >= self.max_file_size) {
+            let path = self.path.join(format!(
+                "{MESSAGE_FILE_PREFIX}{:020}{MESSAGE_FILE_EXTENSION}",
+                writer.next_index
+            ));
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            let size = file.metadata()?.len();
+            debug!(target: "engine::store", path = %path.display(), "Opened engine API message file");
+            writer.file = Some((file, size));
+            self.remove_old_message_files()?;
+        }
+
+        let (file, size) = writer.file.as_mut().expect("opened above");
+        file.write_all(&line)?;
+        *size += line.len() as u64;
         Ok(())
     }
 
-    /// Finds and iterates through any stored engine API message files, ordered by timestamp.
-    pub fn engine_messages_iter(&self) -> eyre::Result<impl Iterator<Item = PathBuf>> {
-        let mut filenames_by_ts = BTreeMap::<u64, Vec<PathBuf>>::default();
+    /// Removes the oldest message files until at most the maximum number of files remain.
+    fn remove_old_message_files(&self) -> eyre::Result<()> {
+        let files = self.message_files()?;
+        let excess = files.len().saturating_sub(self.max_files);
+        for (_, path) in files.into_iter().take(excess) {
+            debug!(target: "engine::store", path = %path.display(), "Removing old engine API message file");
+            fs::remove_file(&path)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the paths of all message files in the store, ordered by the index of their first
+    /// message.
+    fn message_files(&self) -> eyre::Result<Vec<(u64, PathBuf)>> {
+        Ok(self.store_files()?.1)
+    }
+
+    /// Returns the paths of all legacy message files in the store, ordered by the time their
+    /// message was received, and of all message files, ordered by the index of their first
+    /// message.
+    fn store_files(&self) -> eyre::Result<(Vec<(u64, PathBuf)>, Vec<(u64, PathBuf)>)> {
+        let mut legacy_files = Vec::new();
+        let mut message_files = Vec::new();
         for entry in fs::read_dir(&self.path)? {
             let entry = entry?;
             let filename = entry.file_name();
-            if let Some(filename) = filename.to_str().filter(|n| n.ends_with(".json")) {
-                if let Some(Ok(timestamp)) = filename.split('-').next().map(|n| n.parse::<u64>()) {
-                    filenames_by_ts.entry(timestamp).or_default().push(entry.path());
-                    tracing::debug!(target: "engine::store", timestamp, filename, "Queued engine API message");
-                } else {
-                    tracing::warn!(target: "engine::store", %filename, "Could not parse timestamp from filename")
-                }
+            let Some(name) = filename.to_str() else {
+                warn!(target: "engine::store", ?filename, "Skipping unknown file");
+                continue
+            };
+            if let Some(first_index) = name
+                .strip_prefix(MESSAGE_FILE_PREFIX)
+                .and_then(|name| name.strip_suffix(MESSAGE_FILE_EXTENSION))
+                .and_then(|index| index.parse::<u64>().ok())
+            {
+                message_files.push((first_index, entry.path()));
+            } else if let Some(received_at) = name
+                .strip_suffix(LEGACY_MESSAGE_FILE_EXTENSION)
+                .and_then(|name| name.split('-').next())
+                .and_then(|timestamp| timestamp.parse::<u64>().ok())
+            {
+                legacy_files.push((received_at, entry.path()));
             } else {
-                tracing::warn!(target: "engine::store", ?filename, "Skipping non json file");
+                warn!(target: "engine::store", ?filename, "Skipping unknown file");
             }
         }
-        Ok(filenames_by_ts.into_iter().flat_map(|(_, paths)| paths))
+        legacy_files.sort_unstable();
+        message_files.sort_unstable();
+        Ok((legacy_files, message_files))
     }
+
+    /// Reads all stored engine API messages together with their recorded outcomes, ordered by
+    /// index.
+    ///
+    /// Messages of legacy message files come first and are numbered in the order they were
+    /// received, they have no recorded outcome.
+    ///
+    /// Lines that can't be parsed, for example because the node crashed while writing them, are
+    /// skipped.
+    pub fn engine_messages<Attributes>(&self) -> eyre::Result<Vec<StoredEngineApiEntry<Attributes>>>
+    where
+        Attributes: DeserializeOwned,
+    {
+        let (legacy_files, message_files) = self.store_files()?;
+
+        let mut legacy_entries = Vec::with_capacity(legacy_files.len());
+        for (received_at, path) in legacy_files {
+            match serde_json::from_slice(&fs::read(&path)?) {
+                Ok(message) => {
+                    let index = legacy_entries.len() as u64;
+                    debug!(target: "engine::store", index, received_at, "Queued legacy engine API message");
+                    legacy_entries.push(StoredEngineApiEntry {
+                        index,
+                        received_at,
+                        message,
+                        outcome: None,
+                    });
+                }
+                Err(error) => {
+                    warn!(target: "engine::store", path = %path.display(), %error, "Skipping invalid legacy engine API message file");
+                }
+            }
+        }
+
+        let mut entries = BTreeMap::new();
+        let mut outcomes = HashMap::new();
+        for (_, path) in message_files {
+            for (line_number, line) in fs::read_to_string(&path)?.lines().enumerate() {
+                match serde_json::from_str::<StoredEngineApiRecord<Attributes>>(line) {
+                    Ok(StoredEngineApiRecord::Message { index, received_at, message }) => {
+                        debug!(target: "engine::store", index, received_at, "Queued engine API message");
+                        entries.insert(
+                            index,
+                            StoredEngineApiEntry { index, received_at, message, outcome: None },
+                        );
+                    }
+                    Ok(StoredEngineApiRecord::Outcome { index, outcome }) => {
+                        outcomes.insert(index, outcome);
+                    }
+                    Err(error) => {
+                        warn!(target: "engine::store", path = %path.display(), line = line_number + 1, %error, "Skipping invalid engine API message record");
+                    }
+                }
+            }
+        }
+
+        for (index, outcome) in outcomes {
+            if let Some(entry) = entries.get_mut(&index) {
+                entry.outcome = Some(outcome);
+            }
+        }
+        legacy_entries.extend(entries.into_values());
+        Ok(legacy_entries)
+    }
+}
+
+/// Returns the index of the last message in the given message file, if it contains any.
+///
+/// The file is read backwards from its end, until the last line that is a message record.
+fn last_message_index(path: &Path) -> eyre::Result<Option<u64>> {
+    let mut file = File::open(path)?;
+    let mut end = file.seek(SeekFrom::End(0))?;
+    // the start of the line that continues past the bytes read so far
+    let mut partial_line = Vec::new();
+    while end > 0 {
+        // read at least as much as the partial line, so that long lines are read in few steps
+        let chunk_size = TAIL_CHUNK_SIZE.max(partial_line.len()) as u64;
+        let start = end.saturating_sub(chunk_size);
+        file.seek(SeekFrom::Start(start))?;
+        let mut chunk = vec![0; (end - start) as usize];
+        file.read_exact(&mut chunk)?;
+        chunk.append(&mut partial_line);
+
+        let mut lines = chunk.rsplit(|byte| *byte == b'\n').peekable();
+        while let Some(line) = lines.next() {
+            if lines.peek().is_none() && start > 0 {
+                // the first line of the chunk may start before it
+                partial_line = line.to_vec();
+                break
+            }
+            if let Ok(StoredRecordIndex::Message { index }) = serde_json::from_slice(line) {
+                return Ok(Some(index))
+            }
+        }
+        end = start;
+    }
+    Ok(None)
 }
 
-/// A wrapper stream that stores Engine API messages in
+/// A wrapper stream that stores Engine API messages and the outcomes returned by the engine in
 /// the specified directory.
 #[derive(Debug)]
 #[pin_project::pin_project]
@@ -124,13 +525,15 @@ pub struct EngineStoreStream<S> {
     #[pin]
     stream: S,
     /// Engine message store.
-    store: EngineMessageStore,
+    store: Arc<EngineMessageStore>,
+    /// The task spawner used to store the outcomes of the messages.
+    task_spawner: Box<dyn TaskSpawner>,
 }
 
 impl<S> EngineStoreStream<S> {
     /// Create new engine store stream wrapper.
-    pub const fn new(stream: S, path: PathBuf) -> Self {
-        Self { stream, store: EngineMessageStore::new(path) }
+    pub fn new(stream: S, store: EngineMessageStore, task_spawner: Box<dyn TaskSpawner>) -> Self {
+        Self { stream, store: Arc::new(store), task_spawner }
     }
 }
 
@@ -143,12 +546,280 @@ where
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let mut this = self.project();
-        let next = ready!(this.stream.poll_next_unpin(cx));
-        if let Some(msg) = &next {
-            if let Err(error) = this.store.on_message(msg, SystemTime::now()) {
-                error!(target: "engine::stream::store", ?msg, %error, "Error handling Engine API message");
+        let mut next = ready!(this.stream.poll_next_unpin(cx));
+        if let Some(msg) = &mut next {
+            match this.store.on_message(msg, SystemTime::now()) {
+                Ok(Some(index)) => {
+                    record_outcome(this.task_spawner.as_ref(), this.store.clone(), index, msg)
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    error!(target: "engine::stream::store", ?msg, %error, "Error handling Engine API message");
+                }
             }
         }
         Poll::Ready(next)
     }
 }
+
+/// Intercepts the response of the engine to the stored message with the given index, so that the
+/// outcome is stored once the response is forwarded.
+fn record_outcome<Engine: EngineTypes>(
+    task_spawner: &dyn TaskSpawner,
+    store: Arc<EngineMessageStore>,
+    index: u64,
+    msg: &mut BeaconEngineMessage<Engine>,
+) {
+    match msg {
+        BeaconEngineMessage::NewPayload { tx, .. } => intercept_response(
+            task_spawner,
+            store,
+            index,
+            tx,
+            StoredEngineApiOutcome::from_new_payload_response,
+        ),
+        BeaconEngineMessage::ForkchoiceUpdated { tx, .. } => intercept_response(
+            task_spawner,
+            store,
+            index,
+            tx,
+            StoredEngineApiOutcome::from_forkchoice_updated_response,
+        ),
+        BeaconEngineMessage::TransitionConfigurationExchanged => {}
+    }
+}
+
+/// Replaces the response sender with one that forwards the response to the original sender and
+/// then stores its outcome, so that storing the outcome does not delay the response.
+fn intercept_response<T, F>(
+    task_spawner: &dyn TaskSpawner,
+    store: Arc<EngineMessageStore>,
+    index: u64,
+    tx: &mut oneshot::Sender<T>,
+    to_outcome: F,
+) where
+    T: Send + 'static,
+    F: FnOnce(&T) -> StoredEngineApiOutcome + Send + 'static,
+{
+    let (intercepted_tx, intercepted_rx) = oneshot::channel();
+    let tx = std::mem::replace(tx, intercepted_tx);
+    task_spawner.spawn(Box::pin(async move {
+        // the engine dropped the message without responding
+        let Ok(response) = intercepted_rx.await else { return };
+        let outcome = to_outcome(&response);
+        let _ = tx.send(response);
+        if let Err(error) = store.on_outcome(index, outcome) {
+            error!(target: "engine::stream::store", index, %error, "Error storing Engine API message outcome");
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::B256;
+    use reth_rpc_types::engine::PayloadStatusEnum;
+
+    fn forkchoice_updated(head: u8) -> StoredEngineApiMessage<()> {
+        StoredEngineApiMessage::ForkchoiceUpdated {
+            state: ForkchoiceState {
+                head_block_hash: B256::with_last_byte(head),
+                safe_block_hash: B256::ZERO,
+                finalized_block_hash: B256::ZERO,
+            },
+            payload_attrs: None,
+        }
+    }
+
+    fn stored_heads(store: &EngineMessageStore) -> Vec<(u64, B256)> {
+        store
+            .engine_messages::<()>()
+            .unwrap()
+            .into_iter()
+            .map(|entry| match entry.message {
+                StoredEngineApiMessage::ForkchoiceUpdated { state, .. } => {
+                    (entry.index, state.head_block_hash)
+                }
+                StoredEngineApiMessage::NewPayload { .. } => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rotates_message_files_by_size() {
+        let dir = tempfile::tempdir().unwrap();
+        // every record exceeds the maximum file size, so every message starts a new file
+        let store = EngineMessageStore::new(dir.path().to_path_buf()).with_max_file_size(1);
+        for head in 0..3 {
+            assert_eq!(store.write_message(forkchoice_updated(head), 0).unwrap(), head as u64);
+        }
+
+        let files = store.message_files().unwrap();
+        assert_eq!(files.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(stored_heads(&store).len(), 3);
+    }
+
+    #[test]
+    fn removes_oldest_message_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EngineMessageStore::with_limits(dir.path().to_path_buf(), Some(1), Some(2));
+        for head in 0..4 {
+            store.write_message(forkchoice_updated(head), 0).unwrap();
+        }
+
+        assert_eq!(store.message_files().unwrap().len(), 2);
+        assert_eq!(
+            stored_heads(&store),
+            vec![(2, B256::with_last_byte(2)), (3, B256::with_last_byte(3))]
+        );
+    }
+
+    #[test]
+    fn resumes_index_from_existing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EngineMessageStore::new(dir.path().to_path_buf());
+        store.write_message(forkchoice_updated(0), 0).unwrap();
+        store.write_message(forkchoice_updated(1), 0).unwrap();
+        drop(store);
+
+        let store = EngineMessageStore::new(dir.path().to_path_buf());
+        assert_eq!(store.write_message(forkchoice_updated(2), 0).unwrap(), 2);
+        assert_eq!(
+            stored_heads(&store).into_iter().map(|(index, _)| index).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn resumes_index_after_long_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EngineMessageStore::new(dir.path().to_path_buf());
+        store.write_message(forkchoice_updated(0), 0).unwrap();
+        // records that span multiple chunks, followed by an outcome
+        let attributes = "a".repeat(TAIL_CHUNK_SIZE * 2);
+        for index in 1..3 {
+            let message = StoredEngineApiMessage::ForkchoiceUpdated {
+                state: ForkchoiceState::default(),
+                payload_attrs: Some(attributes.clone()),
+            };
+            assert_eq!(store.write_message(message, 0).unwrap(), index);
+        }
+        store.on_outcome(0, StoredEngineApiOutcome::Error("a".to_string())).unwrap();
+        drop(store);
+
+        let store = EngineMessageStore::new(dir.path().to_path_buf());
+        assert_eq!(store.write_message(forkchoice_updated(3), 0).unwrap(), 3);
+    }
+
+    #[test]
+    fn reads_legacy_message_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for (received_at, head) in [(20, 1), (10, 0)] {
+            fs::write(
+                dir.path().join(format!("{received_at}-fcu-{}.json", B256::with_last_byte(head))),
+                serde_json::to_vec(&forkchoice_updated(head)).unwrap(),
+            )
+            .unwrap();
+        }
+
+        let store = EngineMessageStore::new(dir.path().to_path_buf());
+        assert_eq!(
+            store
+                .engine_messages::<()>()
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.received_at)
+                .collect::<Vec<_>>(),
+            vec![10, 20]
+        );
+        // new messages are numbered after the legacy ones
+        assert_eq!(store.write_message(forkchoice_updated(2), 30).unwrap(), 2);
+        assert_eq!(
+            stored_heads(&store),
+            vec![
+                (0, B256::with_last_byte(0)),
+                (1, B256::with_last_byte(1)),
+                (2, B256::with_last_byte(2))
+            ]
+        );
+        // legacy files are kept
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn skips_torn_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EngineMessageStore::new(dir.path().to_path_buf());
+        store.write_message(forkchoice_updated(0), 0).unwrap();
+        drop(store);
+
+        // simulate a crash while writing the second message
+        let (_, path) = EngineMessageStore::new(dir.path().to_path_buf())
+            .message_files()
+            .unwrap()
+            .pop()
+            .unwrap();
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(br#"{"type":"message","index":1,"rece"#).unwrap();
+
+        let store = EngineMessageStore::new(dir.path().to_path_buf());
+        assert_eq!(stored_heads(&store), vec![(0, B256::with_last_byte(0))]);
+        // the torn message is not counted when resuming the index
+        assert_eq!(store.write_message(forkchoice_updated(1), 0).unwrap(), 1);
+        assert_eq!(stored_heads(&store).len(), 2);
+    }
+
+    #[test]
+    fn pairs_messages_with_outcomes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EngineMessageStore::new(dir.path().to_path_buf());
+        store.write_message(forkchoice_updated(0), 0).unwrap();
+        store.write_message(forkchoice_updated(1), 0).unwrap();
+        store.write_message(forkchoice_updated(2), 0).unwrap();
+        // outcomes are recorded out of order and may be missing
+        let valid = StoredEngineApiOutcome::ForkchoiceUpdated(StoredForkchoiceStatus::Valid);
+        let syncing = StoredEngineApiOutcome::ForkchoiceUpdated(StoredForkchoiceStatus::Syncing);
+        store.on_outcome(2, syncing.clone()).unwrap();
+        store.on_outcome(0, valid.clone()).unwrap();
+        // outcome of a message that is not in the store
+        store.on_outcome(5, valid.clone()).unwrap();
+
+        let outcomes = store
+            .engine_messages::<()>()
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.index, entry.outcome))
+            .collect::<Vec<_>>();
+        assert_eq!(outcomes, vec![(0, Some(valid)), (1, None), (2, Some(syncing))]);
+    }
+
+    #[test]
+    fn outcome_matches() {
+        let hash = B256::with_last_byte(1);
+        let valid = StoredEngineApiOutcome::NewPayload(PayloadStatus::new(
+            PayloadStatusEnum::Valid,
+            Some(hash),
+        ));
+        let invalid = |validation_error: &str| {
+            StoredEngineApiOutcome::NewPayload(PayloadStatus::new(
+                PayloadStatusEnum::Invalid { validation_error: validation_error.to_string() },
+                Some(hash),
+            ))
+        };
+
+        assert!(valid.matches(&valid));
+        // validation and engine error messages are ignored
+        assert!(invalid("a").matches(&invalid("b")));
+        assert!(StoredEngineApiOutcome::Error("a".to_string())
+            .matches(&StoredEngineApiOutcome::Error("b".to_string())));
+
+        assert!(!valid.matches(&invalid("a")));
+        assert!(!valid.matches(&StoredEngineApiOutcome::NewPayload(PayloadStatus::new(
+            PayloadStatusEnum::Valid,
+            Some(B256::with_last_byte(2)),
+        ))));
+        assert!(!valid.matches(&StoredEngineApiOutcome::Error("a".to_string())));
+        assert!(!StoredEngineApiOutcome::ForkchoiceUpdated(StoredForkchoiceStatus::Valid)
+            .matches(&StoredEngineApiOutcome::ForkchoiceUpdated(StoredForkchoiceStatus::Invalid)));
+    }
+}
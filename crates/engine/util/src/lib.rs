@@ -4,11 +4,11 @@ use futures::Stream;
 use reth_beacon_consensus::BeaconEngineMessage;
 use reth_engine_primitives::EngineTypes;
 use reth_payload_validator::ExecutionPayloadValidator;
-use std::path::PathBuf;
+use reth_tasks::TaskSpawner;
 use tokio_util::either::Either;
 
 pub mod engine_store;
-use engine_store::EngineStoreStream;
+use engine_store::{EngineMessageStore, EngineStoreStream};
 
 pub mod skip_fcu;
 use skip_fcu::EngineSkipFcu;
@@ -70,25 +70,32 @@ pub trait EngineMessageStreamExt<Engine: EngineTypes>:
         }
     }
 
-    /// Stores engine messages at the specified location.
-    fn store_messages(self, path: PathBuf) -> EngineStoreStream<Self>
+    /// Stores engine messages and their outcomes in the given store.
+    ///
+    /// The outcomes are stored by tasks spawned with the given task spawner.
+    fn store_messages(
+        self,
+        store: EngineMessageStore,
+        task_spawner: Box<dyn TaskSpawner>,
+    ) -> EngineStoreStream<Self>
     where
         Self: Sized,
     {
-        EngineStoreStream::new(self, path)
+        EngineStoreStream::new(self, store, task_spawner)
     }
 
-    /// If the path is [Some], returns the stream that stores engine messages at the specified
-    /// location. Otherwise, returns `Self`.
+    /// If the store is [Some], returns the stream that stores engine messages and their outcomes
+    /// in it. Otherwise, returns `Self`.
     fn maybe_store_messages(
         self,
-        maybe_path: Option<PathBuf>,
+        maybe_store: Option<EngineMessageStore>,
+        task_spawner: Box<dyn TaskSpawner>,
     ) -> Either<EngineStoreStream<Self>, Self>
     where
         Self: Sized,
     {
-        if let Some(path) = maybe_path {
-            Either::Left(self.store_messages(path))
+        if let Some(store) = maybe_store {
+            Either::Left(self.store_messages(store, task_spawner))
         } else {
             Either::Right(self)
         }
@@ -3,12 +3,16 @@
 use std::sync::Arc;
 
 use reth_auto_seal_consensus::AutoSealConsensus;
-use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
+use reth_basic_payload_builder::{
+    AdaptiveRebuildConfig, BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig,
+};
 use reth_beacon_consensus::EthBeaconConsensus;
 use reth_chainspec::ChainSpec;
+use reth_cli_util::get_secret_key;
 use reth_ethereum_engine_primitives::{
     EthBuiltPayload, EthPayloadAttributes, EthPayloadBuilderAttributes,
 };
+use reth_ethereum_payload_builder::{ProposerPayment, TransactionSelector};
 use reth_evm_ethereum::execute::EthExecutorProvider;
 use reth_network::NetworkHandle;
 use reth_node_api::{FullNodeComponents, NodeAddOns};
@@ -230,27 +234,68 @@ where
             reth_ethereum_payload_builder::EthereumPayloadBuilder::new(self.evm_config);
         let conf = ctx.payload_builder_config();
 
-        let payload_job_config = BasicPayloadJobGeneratorConfig::default()
-            .interval(conf.interval())
-            .deadline(conf.deadline())
-            .max_payload_tasks(conf.max_payload_tasks())
-            .extradata(conf.extradata_bytes());
-
-        let payload_generator = BasicPayloadJobGenerator::with_builder(
-            ctx.provider().clone(),
-            pool,
-            ctx.task_executor().clone(),
-            payload_job_config,
-            ctx.chain_spec(),
-            payload_builder,
+        if let Some(secret_key_path) = conf.payment_secret_key() {
+            // don't silently generate a key for an account that is expected to hold funds
+            if !secret_key_path.try_exists()? {
+                eyre::bail!("payment secret key file {} does not exist", secret_key_path.display())
+            }
+            let secret_key = get_secret_key(secret_key_path)?;
+            let payment = ProposerPayment::new(secret_key).with_margin(conf.payment_margin());
+            info!(target: "reth::cli", builder = %payment.address(), "Paying proposers from builder account");
+            spawn_payload_service(ctx, pool, payload_builder.with_selector(payment))
+        } else {
+            spawn_payload_service(ctx, pool, payload_builder)
+        }
+    }
+}
+
+/// Spawns the payload builder service with the given payload builder.
+fn spawn_payload_service<Node, Evm, Selector, Pool>(
+    ctx: &BuilderContext<Node>,
+    pool: Pool,
+    payload_builder: reth_ethereum_payload_builder::EthereumPayloadBuilder<Evm, Selector>,
+) -> eyre::Result<PayloadBuilderHandle<Node::Engine>>
+where
+    Node: FullNodeTypes,
+    Evm: ConfigureEvm,
+    Selector: TransactionSelector + Unpin + 'static,
+    Pool: TransactionPool + Unpin + 'static,
+    <Node as NodeTypes>::Engine: PayloadTypes<
+        BuiltPayload = EthBuiltPayload,
+        PayloadAttributes = EthPayloadAttributes,
+        PayloadBuilderAttributes = EthPayloadBuilderAttributes,
+    >,
+{
+    let conf = ctx.payload_builder_config();
+
+    let mut payload_job_config = BasicPayloadJobGeneratorConfig::default()
+        .interval(conf.interval())
+        .deadline(conf.deadline())
+        .max_payload_tasks(conf.max_payload_tasks())
+        .extradata(conf.extradata_bytes());
+
+    if conf.adaptive() {
+        payload_job_config = payload_job_config.adaptive(
+            AdaptiveRebuildConfig::default()
+                .with_min_fee_delta(conf.adaptive_min_fee_delta())
+                .with_min_rebuild_interval(conf.adaptive_min_interval()),
         );
-        let (payload_service, payload_builder) =
-            PayloadBuilderService::new(payload_generator, ctx.provider().canonical_state_stream());
+    }
 
-        ctx.task_executor().spawn_critical("payload builder service", Box::pin(payload_service));
+    let payload_generator = BasicPayloadJobGenerator::with_builder(
+        ctx.provider().clone(),
+        pool,
+        ctx.task_executor().clone(),
+        payload_job_config,
+        ctx.chain_spec(),
+        payload_builder,
+    );
+    let (payload_service, payload_builder) =
+        PayloadBuilderService::new(payload_generator, ctx.provider().canonical_state_stream());
 
-        Ok(payload_builder)
-    }
+    ctx.task_executor().spawn_critical("payload builder service", Box::pin(payload_service));
+
+    Ok(payload_builder)
 }
 
 /// A basic ethereum payload service.
@@ -10,8 +10,8 @@
 #![allow(clippy::useless_let_if_seq)]
 
 use reth_basic_payload_builder::{
-    commit_withdrawals, is_better_payload, BuildArguments, BuildOutcome, PayloadBuilder,
-    PayloadConfig, WithdrawalsOutcome,
+    commit_withdrawals, is_better_payload, BuildArguments, BuildOutcome, ExecutedPrefix,
+    PayloadBuilder, PayloadConfig, WithdrawalsOutcome,
 };
 use reth_errors::RethError;
 use reth_evm::{
@@ -33,8 +33,8 @@ use reth_primitives::{
     },
     eip4844::calculate_excess_blob_gas,
     proofs::{self, calculate_requests_root},
-    Block, EthereumHardforks, Header, IntoRecoveredTransaction, Receipt, EMPTY_OMMER_ROOT_HASH,
-    U256,
+    Block, EthereumHardforks, Header, IntoRecoveredTransaction, Receipt, TransactionSigned,
+    TransactionSignedEcRecovered, B256, EMPTY_OMMER_ROOT_HASH, U256,
 };
 use reth_provider::StateProviderFactory;
 use reth_revm::database::StateProviderDatabase;
@@ -42,22 +42,45 @@ use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
 use reth_trie::HashedPostState;
 use revm::{
     db::states::bundle_state::BundleRetention,
-    primitives::{EVMError, EnvWithHandlerCfg, InvalidTransaction, ResultAndState},
-    DatabaseCommit, State,
+    primitives::{
+        BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, ExecutionResult,
+        InvalidTransaction, ResultAndState,
+    },
+    Database, DatabaseCommit, State,
 };
+use std::collections::HashSet;
 use tracing::{debug, trace, warn};
 
+mod payment;
+pub use payment::ProposerPayment;
+
+mod selection;
+pub use selection::{
+    default_transaction_filter, DefaultTransactionSelector, SelectionContext, TransactionDecision,
+    TransactionSelector,
+};
+
 /// Ethereum payload builder
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct EthereumPayloadBuilder<EvmConfig = EthEvmConfig> {
+pub struct EthereumPayloadBuilder<EvmConfig = EthEvmConfig, Selector = DefaultTransactionSelector> {
     /// The type responsible for creating the evm.
     evm_config: EvmConfig,
+    /// Decides which transactions are included in the payload.
+    selector: Selector,
 }
 
 impl<EvmConfig> EthereumPayloadBuilder<EvmConfig> {
     /// `EthereumPayloadBuilder` constructor.
     pub const fn new(evm_config: EvmConfig) -> Self {
-        Self { evm_config }
+        Self { evm_config, selector: DefaultTransactionSelector }
+    }
+}
+
+impl<EvmConfig, Selector> EthereumPayloadBuilder<EvmConfig, Selector> {
+    /// Configures the [`TransactionSelector`] that decides which transactions are included in the
+    /// payload.
+    pub fn with_selector<S>(self, selector: S) -> EthereumPayloadBuilder<EvmConfig, S> {
+        EthereumPayloadBuilder { evm_config: self.evm_config, selector }
     }
 }
 
@@ -68,9 +91,11 @@ impl Default for EthereumPayloadBuilder {
 }
 
 // Default implementation of [PayloadBuilder] for unit type
-impl<EvmConfig, Pool, Client> PayloadBuilder<Pool, Client> for EthereumPayloadBuilder<EvmConfig>
+impl<EvmConfig, Selector, Pool, Client> PayloadBuilder<Pool, Client>
+    for EthereumPayloadBuilder<EvmConfig, Selector>
 where
     EvmConfig: ConfigureEvm,
+    Selector: TransactionSelector,
     Client: StateProviderFactory,
     Pool: TransactionPool,
 {
@@ -81,7 +106,7 @@ where
         &self,
         args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
     ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError> {
-        default_ethereum_payload_builder(self.evm_config.clone(), args)
+        ethereum_payload_builder_with_selector(self.evm_config.clone(), &self.selector, args)
     }
 
     fn build_empty_payload(
@@ -273,80 +298,167 @@ where
     Client: StateProviderFactory,
     Pool: TransactionPool,
 {
-    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload } = args;
+    ethereum_payload_builder_with_selector(evm_config, &DefaultTransactionSelector, args)
+}
+
+/// Constructs an Ethereum transaction payload using the best transactions from the pool, with the
+/// given [`TransactionSelector`] deciding which transactions are included.
+#[inline]
+pub fn ethereum_payload_builder_with_selector<EvmConfig, Selector, Pool, Client>(
+    evm_config: EvmConfig,
+    selector: &Selector,
+    args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
+) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    Selector: TransactionSelector,
+    Client: StateProviderFactory,
+    Pool: TransactionPool,
+{
+    let BuildArguments {
+        client,
+        pool,
+        mut cached_reads,
+        config,
+        cancel,
+        best_payload,
+        executed_prefix,
+        retain_executed_prefix,
+    } = args;
+
+    // only resume from transactions that were executed on the same parent
+    let executed_prefix =
+        executed_prefix.filter(|prefix| prefix.parent_hash == config.parent_block.hash());
 
     let state_provider = client.state_by_block_hash(config.parent_block.hash())?;
     let state = StateProviderDatabase::new(state_provider);
-    let mut db =
-        State::builder().with_database_ref(cached_reads.as_db(state)).with_bundle_update().build();
+    let mut state_builder =
+        State::builder().with_database_ref(cached_reads.as_db(state)).with_bundle_update();
+    if let Some(prefix) = &executed_prefix {
+        state_builder = state_builder.with_bundle_prestate(prefix.state.clone());
+    }
+    let mut db = state_builder.build();
     let extra_data = config.extra_data();
     let PayloadConfig {
-        initialized_block_env,
+        mut initialized_block_env,
         initialized_cfg,
         parent_block,
         attributes,
         chain_spec,
         ..
     } = config;
+    if let Some(beneficiary) = selector.beneficiary(&attributes) {
+        initialized_block_env.coinbase = beneficiary;
+    }
 
-    debug!(target: "payload_builder", id=%attributes.id, parent_hash = ?parent_block.hash(), parent_number = parent_block.number, "building new payload");
-    let mut cumulative_gas_used = 0;
-    let mut sum_blob_gas_used = 0;
+    debug!(target: "payload_builder", id=%attributes.id, parent_hash = ?parent_block.hash(), parent_number = parent_block.number, resume = executed_prefix.is_some(), "building new payload");
     let block_gas_limit: u64 =
         initialized_block_env.gas_limit.try_into().unwrap_or(chain_spec.max_gas_limit);
     let base_fee = initialized_block_env.basefee.to::<u64>();
+    let reserved_gas = selector.reserved_gas(&attributes);
 
-    let mut executed_txs = Vec::new();
+    let mut executed = ExecutedTransactions::default();
 
     let mut best_txs = pool.best_transactions_with_attributes(BestTransactionsAttributes::new(
         base_fee,
         initialized_block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
     ));
 
-    let mut total_fees = U256::ZERO;
-
     let block_number = initialized_block_env.number.to::<u64>();
 
-    // apply eip-4788 pre block contract call
-    pre_block_beacon_root_contract_call(
-        &mut db,
-        &evm_config,
-        &chain_spec,
-        &initialized_cfg,
-        &initialized_block_env,
-        attributes.parent_beacon_block_root,
-    )
-    .map_err(|err| {
-        warn!(target: "payload_builder",
-            parent_hash=%parent_block.hash(),
-            %err,
-            "failed to apply beacon root contract call for empty payload"
-        );
-        PayloadBuilderError::Internal(err.into())
-    })?;
-
-    // apply eip-2935 blockhashes update
-    pre_block_blockhashes_contract_call(
-        &mut db,
-        &evm_config,
-        &chain_spec,
-        &initialized_cfg,
-        &initialized_block_env,
-        parent_block.hash(),
-    )
-    .map_err(|err| {
-        warn!(target: "payload_builder", parent_hash=%parent_block.hash(), %err, "failed to update blockhashes for empty payload");
-        PayloadBuilderError::Internal(err.into())
-    })?;
-
-    let mut receipts = Vec::new();
+    let selection_context = |executed: &ExecutedTransactions| SelectionContext {
+        block_env: &initialized_block_env,
+        attributes: &attributes,
+        chain_id: initialized_cfg.chain_id,
+        block_gas_limit,
+        reserved_gas,
+        cumulative_gas_used: executed.cumulative_gas_used,
+        blob_gas_used: executed.blob_gas_used,
+        total_fees: executed.total_fees,
+    };
+
+    // transactions that are already part of the prefix and must not be executed again
+    let mut prefix_txs = HashSet::new();
+
+    if let Some(prefix) = executed_prefix {
+        // the pre block calls and pre block transactions are part of the prefix state
+        trace!(target: "payload_builder", txs = prefix.transactions.len(), "resuming from executed transactions");
+        prefix_txs.extend(prefix.transactions.iter().map(|tx| tx.hash));
+        executed = ExecutedTransactions {
+            transactions: prefix.transactions,
+            receipts: prefix.receipts,
+            cumulative_gas_used: prefix.cumulative_gas_used,
+            blob_gas_used: prefix.blob_gas_used,
+            total_fees: prefix.fees,
+        };
+        if executed.blob_gas_used == MAX_DATA_GAS_PER_BLOCK {
+            best_txs.skip_blobs();
+        }
+    } else {
+        // apply eip-4788 pre block contract call
+        pre_block_beacon_root_contract_call(
+            &mut db,
+            &evm_config,
+            &chain_spec,
+            &initialized_cfg,
+            &initialized_block_env,
+            attributes.parent_beacon_block_root,
+        )
+        .map_err(|err| {
+            warn!(target: "payload_builder",
+                parent_hash=%parent_block.hash(),
+                %err,
+                "failed to apply beacon root contract call for empty payload"
+            );
+            PayloadBuilderError::Internal(err.into())
+        })?;
+
+        // apply eip-2935 blockhashes update
+        pre_block_blockhashes_contract_call(
+            &mut db,
+            &evm_config,
+            &chain_spec,
+            &initialized_cfg,
+            &initialized_block_env,
+            parent_block.hash(),
+        )
+        .map_err(|err| {
+            warn!(target: "payload_builder", parent_hash=%parent_block.hash(), %err, "failed to update blockhashes for empty payload");
+            PayloadBuilderError::Internal(err.into())
+        })?;
+
+        for tx in selector.pre_block_transactions(&selection_context(&executed)) {
+            // blob transactions are not supported because their sidecars are taken from the pool,
+            // and the gas reserved for the end of block transactions must remain available
+            if tx.is_eip4844() ||
+                executed.cumulative_gas_used + reserved_gas + tx.gas_limit() > block_gas_limit
+            {
+                trace!(target: "payload_builder", ?tx, "skipping pre block transaction that does not fit into the block");
+                continue
+            }
+
+            let ResultAndState { result, state } = match transact(
+                &evm_config,
+                &mut db,
+                &initialized_cfg,
+                &initialized_block_env,
+                &tx,
+            ) {
+                Ok(res) => res,
+                Err(EVMError::Transaction(err)) => {
+                    trace!(target: "payload_builder", %err, ?tx, "skipping invalid pre block transaction");
+                    continue
+                }
+                Err(err) => return Err(PayloadBuilderError::EvmExecutionError(err)),
+            };
+            db.commit(state);
+            executed.push(tx, result, base_fee);
+        }
+    }
+
     while let Some(pool_tx) = best_txs.next() {
-        // ensure we still have capacity for this transaction
-        if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
-            // we can't fit this transaction into the block, so we need to mark it as invalid
-            // which also removes all dependent transaction from the iterator before we can
-            // continue
-            best_txs.mark_invalid(&pool_tx);
+        // skip transactions that were already executed as part of the prefix
+        if prefix_txs.contains(pool_tx.hash()) {
             continue
         }
 
@@ -358,31 +470,22 @@ where
         // convert tx to a signed transaction
         let tx = pool_tx.to_recovered_transaction();
 
-        // There's only limited amount of blob space available per block, so we need to check if
-        // the EIP-4844 can still fit in the block
-        if let Some(blob_tx) = tx.transaction.as_eip4844() {
-            let tx_blob_gas = blob_tx.blob_gas();
-            if sum_blob_gas_used + tx_blob_gas > MAX_DATA_GAS_PER_BLOCK {
-                // we can't fit this _blob_ transaction into the block, so we mark it as
-                // invalid, which removes its dependent transactions from
-                // the iterator. This is similar to the gas limit condition
-                // for regular transactions above.
-                trace!(target: "payload_builder", tx=?tx.hash, ?sum_blob_gas_used, ?tx_blob_gas, "skipping blob transaction because it would exceed the max data gas per block");
+        match selector.filter_transaction(&selection_context(&executed), &tx) {
+            TransactionDecision::Include => {}
+            TransactionDecision::Skip => continue,
+            TransactionDecision::SkipWithDescendants => {
                 best_txs.mark_invalid(&pool_tx);
                 continue
             }
         }
 
-        let env = EnvWithHandlerCfg::new_with_cfg_env(
-            initialized_cfg.clone(),
-            initialized_block_env.clone(),
-            evm_config.tx_env(&tx),
-        );
-
-        // Configure the environment for the block.
-        let mut evm = evm_config.evm_with_env(&mut db, env);
-
-        let ResultAndState { result, state } = match evm.transact() {
+        let ResultAndState { result, state } = match transact(
+            &evm_config,
+            &mut db,
+            &initialized_cfg,
+            &initialized_block_env,
+            &tx,
+        ) {
             Ok(res) => res,
             Err(err) => {
                 match err {
@@ -406,53 +509,79 @@ where
                 }
             }
         };
-        // drop evm so db is released.
-        drop(evm);
-        // commit changes
-        db.commit(state);
 
-        // add to the total blob gas used if the transaction successfully executed
-        if let Some(blob_tx) = tx.transaction.as_eip4844() {
-            let tx_blob_gas = blob_tx.blob_gas();
-            sum_blob_gas_used += tx_blob_gas;
-
-            // if we've reached the max data gas per block, we can skip blob txs entirely
-            if sum_blob_gas_used == MAX_DATA_GAS_PER_BLOCK {
-                best_txs.skip_blobs();
+        // discard the changes if the selector rejects the executed transaction
+        match selector.on_executed(&selection_context(&executed), &tx, &result) {
+            TransactionDecision::Include => {}
+            TransactionDecision::Skip => continue,
+            TransactionDecision::SkipWithDescendants => {
+                best_txs.mark_invalid(&pool_tx);
+                continue
             }
         }
 
-        let gas_used = result.gas_used();
-
-        // add gas used by the transaction to cumulative gas used, before creating the receipt
-        cumulative_gas_used += gas_used;
-
-        // Push transaction changeset and calculate header bloom filter for receipt.
-        #[allow(clippy::needless_update)] // side-effect of optimism fields
-        receipts.push(Some(Receipt {
-            tx_type: tx.tx_type(),
-            success: result.is_success(),
-            cumulative_gas_used,
-            logs: result.into_logs().into_iter().map(Into::into).collect(),
-            ..Default::default()
-        }));
-
-        // update add to total fees
-        let miner_fee = tx
-            .effective_tip_per_gas(Some(base_fee))
-            .expect("fee is always valid; execution succeeded");
-        total_fees += U256::from(miner_fee) * U256::from(gas_used);
+        // commit changes
+        db.commit(state);
+        executed.push(tx, result, base_fee);
 
-        // append transaction to the list of executed transactions
-        executed_txs.push(tx.into_signed());
+        // if we've reached the max data gas per block, we can skip blob txs entirely
+        if executed.blob_gas_used == MAX_DATA_GAS_PER_BLOCK {
+            best_txs.skip_blobs();
+        }
     }
 
     // check if we have a better block
-    if !is_better_payload(best_payload.as_ref(), total_fees) {
+    let payload_value = selector.payload_value(&selection_context(&executed));
+    if !is_better_payload(best_payload.as_ref(), payload_value) {
         // can skip building the block
-        return Ok(BuildOutcome::Aborted { fees: total_fees, cached_reads })
+        return Ok(BuildOutcome::Aborted { fees: payload_value, cached_reads })
     }
 
+    // merge the transitions of the executed transactions, so that subsequent attempts can resume
+    // from them
+    let executed_prefix = retain_executed_prefix.then(|| {
+        db.merge_transitions(BundleRetention::PlainState);
+        ExecutedPrefix {
+            parent_hash: parent_block.hash(),
+            transactions: executed.transactions.clone(),
+            receipts: executed.receipts.clone(),
+            state: db.bundle_state.clone(),
+            cumulative_gas_used: executed.cumulative_gas_used,
+            blob_gas_used: executed.blob_gas_used,
+            fees: executed.total_fees,
+        }
+    });
+
+    // apply the end of block transactions on top of the pool transactions
+    let end_of_block_txs =
+        selector.end_of_block_transactions(&selection_context(&executed), &mut db)?;
+    for tx in end_of_block_txs {
+        if tx.is_eip4844() {
+            return Err(PayloadBuilderError::other(EndOfBlockTransactionError::BlobTransaction(
+                tx.hash,
+            )))
+        }
+        if executed.cumulative_gas_used + tx.gas_limit() > block_gas_limit {
+            return Err(PayloadBuilderError::other(EndOfBlockTransactionError::GasLimitExceeded(
+                tx.hash,
+            )))
+        }
+
+        let ResultAndState { result, state } =
+            transact(&evm_config, &mut db, &initialized_cfg, &initialized_block_env, &tx)
+                .map_err(PayloadBuilderError::EvmExecutionError)?;
+        db.commit(state);
+        executed.push(tx, result, base_fee);
+    }
+
+    let ExecutedTransactions {
+        transactions: executed_txs,
+        receipts,
+        cumulative_gas_used,
+        blob_gas_used: sum_blob_gas_used,
+        ..
+    } = executed;
+
     // calculate the requests and the requests root
     let (requests, requests_root) = if chain_spec
         .is_prague_active_at_timestamp(attributes.timestamp)
@@ -564,10 +693,199 @@ where
     let sealed_block = block.seal_slow();
     debug!(target: "payload_builder", ?sealed_block, "sealed built block");
 
-    let mut payload = EthBuiltPayload::new(attributes.id, sealed_block, total_fees);
+    let mut payload = EthBuiltPayload::new(attributes.id, sealed_block, payload_value);
 
     // extend the payload with the blob sidecars from the executed txs
     payload.extend_sidecars(blob_sidecars);
 
-    Ok(BuildOutcome::Better { payload, cached_reads })
+    Ok(BuildOutcome::Better { payload, cached_reads, executed_prefix })
+}
+
+/// The transactions that were executed for a payload so far.
+#[derive(Debug, Default)]
+struct ExecutedTransactions {
+    /// The executed transactions, in order.
+    transactions: Vec<TransactionSigned>,
+    /// The receipts of the executed transactions.
+    receipts: Vec<Option<Receipt>>,
+    /// Gas used by the executed transactions.
+    cumulative_gas_used: u64,
+    /// Blob gas used by the executed transactions.
+    blob_gas_used: u64,
+    /// Fees paid by the executed transactions.
+    total_fees: U256,
+}
+
+impl ExecutedTransactions {
+    /// Appends a transaction whose state changes were committed.
+    fn push(&mut self, tx: TransactionSignedEcRecovered, result: ExecutionResult, base_fee: u64) {
+        // add to the total blob gas used if the transaction successfully executed
+        if let Some(blob_tx) = tx.transaction.as_eip4844() {
+            self.blob_gas_used += blob_tx.blob_gas();
+        }
+
+        let gas_used = result.gas_used();
+
+        // add gas used by the transaction to cumulative gas used, before creating the receipt
+        self.cumulative_gas_used += gas_used;
+
+        // Push transaction changeset and calculate header bloom filter for receipt.
+        self.receipts.push(Some(Receipt {
+            tx_type: tx.tx_type(),
+            success: result.is_success(),
+            cumulative_gas_used: self.cumulative_gas_used,
+            logs: result.into_logs().into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }));
+
+        // update add to total fees
+        let miner_fee = tx
+            .effective_tip_per_gas(Some(base_fee))
+            .expect("fee is always valid; execution succeeded");
+        self.total_fees += U256::from(miner_fee) * U256::from(gas_used);
+
+        // append transaction to the list of executed transactions
+        self.transactions.push(tx.into_signed());
+    }
+}
+
+/// Executes the transaction on top of the given database without committing the changes.
+fn transact<EvmConfig, DB>(
+    evm_config: &EvmConfig,
+    db: DB,
+    initialized_cfg: &CfgEnvWithHandlerCfg,
+    initialized_block_env: &BlockEnv,
+    tx: &TransactionSignedEcRecovered,
+) -> Result<ResultAndState, EVMError<DB::Error>>
+where
+    EvmConfig: ConfigureEvm,
+    DB: Database,
+{
+    let env = EnvWithHandlerCfg::new_with_cfg_env(
+        initialized_cfg.clone(),
+        initialized_block_env.clone(),
+        evm_config.tx_env(tx),
+    );
+
+    // Configure the environment for the block.
+    let mut evm = evm_config.evm_with_env(db, env);
+    evm.transact()
+}
+
+/// Errors of the end of block transactions of a [`TransactionSelector`].
+#[derive(Debug, thiserror::Error)]
+pub enum EndOfBlockTransactionError {
+    /// Blob transactions can't be included at the end of the block.
+    #[error("end of block transaction {0} is a blob transaction")]
+    BlobTransaction(B256),
+    /// The transaction does not fit into the remaining gas of the block.
+    #[error("end of block transaction {0} exceeds the block gas limit")]
+    GasLimitExceeded(B256),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_basic_payload_builder::Cancelled;
+    use reth_chainspec::{ChainSpecBuilder, MAINNET};
+    use reth_db_common::init::init_genesis;
+    use reth_primitives::{
+        constants::ETH_TO_WEI, Address, Bytes, Genesis, GenesisAccount, SealedBlock,
+    };
+    use reth_provider::{
+        providers::BlockchainProvider2, test_utils::create_test_provider_factory_with_chain_spec,
+    };
+    use reth_rpc_types::engine::PayloadAttributes;
+    use reth_transaction_pool::test_utils::{testing_pool, MockTransaction};
+    use std::{collections::BTreeMap, sync::Arc};
+
+    #[tokio::test]
+    async fn resumed_payload_matches_full_rebuild() {
+        let senders = [Address::repeat_byte(0x11), Address::repeat_byte(0x12)];
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(Genesis {
+                    gas_limit: 30_000_000,
+                    alloc: BTreeMap::from(senders.map(|sender| {
+                        (
+                            sender,
+                            GenesisAccount {
+                                balance: U256::from(ETH_TO_WEI),
+                                ..Default::default()
+                            },
+                        )
+                    })),
+                    ..MAINNET.genesis.clone()
+                })
+                .shanghai_activated()
+                .build(),
+        );
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(factory.clone()).unwrap();
+        let client = BlockchainProvider2::new(factory).unwrap();
+        let pool = testing_pool();
+
+        let parent = chain_spec.sealed_genesis_header();
+        let attributes = EthPayloadBuilderAttributes::new(
+            parent.hash(),
+            PayloadAttributes {
+                timestamp: parent.timestamp + 12,
+                prev_randao: B256::ZERO,
+                suggested_fee_recipient: Address::repeat_byte(0x22),
+                withdrawals: Some(Vec::new()),
+                parent_beacon_block_root: None,
+            },
+        );
+        let build = |best_payload: Option<EthBuiltPayload>,
+                     executed_prefix: Option<ExecutedPrefix>| {
+            let config = PayloadConfig::new(
+                Arc::new(SealedBlock::new(parent.clone(), Default::default())),
+                Bytes::default(),
+                attributes.clone(),
+                chain_spec.clone(),
+            );
+            let mut args = BuildArguments::new(
+                client.clone(),
+                pool.clone(),
+                Default::default(),
+                config,
+                Cancelled::default(),
+                best_payload,
+            );
+            if let Some(executed_prefix) = executed_prefix {
+                args = args.with_executed_prefix(executed_prefix);
+            }
+            args = args.with_retain_executed_prefix(true);
+            match default_ethereum_payload_builder(EthEvmConfig::default(), args) {
+                Ok(BuildOutcome::Better { payload, executed_prefix, .. }) => {
+                    (payload, executed_prefix.unwrap())
+                }
+                outcome => panic!("expected a better payload, got {outcome:?}"),
+            }
+        };
+        let add_transaction = |sender: Address, priority_fee: u128| {
+            pool.add_external_transaction(
+                MockTransaction::eip1559()
+                    .with_sender(sender)
+                    .with_gas_limit(21_000)
+                    .with_max_fee(10_000_000_000)
+                    .with_priority_fee(priority_fee),
+            )
+        };
+
+        add_transaction(senders[0], 2_000_000_000).await.unwrap();
+        let (first, prefix) = build(None, None);
+        assert_eq!(first.block().body.len(), 1);
+
+        // the new transaction pays less, so a full rebuild includes it after the first one as well
+        add_transaction(senders[1], 1_000_000_000).await.unwrap();
+        let (resumed, _) = build(Some(first), Some(prefix));
+        let (rebuilt, _) = build(None, None);
+
+        assert_eq!(resumed.block().body.len(), 2);
+        assert_eq!(resumed.block().state_root, rebuilt.block().state_root);
+        assert_eq!(resumed.block().hash(), rebuilt.block().hash());
+        assert_eq!(resumed.fees(), rebuilt.fees());
+    }
 }
@@ -0,0 +1,246 @@
+//! Payment of the block proposer by a local block builder.
+
+use crate::selection::{SelectionContext, TransactionSelector};
+use reth_payload_builder::{error::PayloadBuilderError, EthPayloadBuilderAttributes};
+use reth_primitives::{
+    public_key_to_address, sign_message, Address, Bytes, Transaction, TransactionSigned,
+    TransactionSignedEcRecovered, TxEip1559, TxKind, B256, U256,
+};
+use reth_provider::ProviderError;
+use revm::Database;
+use secp256k1::{SecretKey, SECP256K1};
+use tracing::debug;
+
+/// Gas limit of the payment transaction, a plain transfer.
+const PAYMENT_GAS_LIMIT: u64 = 21_000;
+
+/// A [`TransactionSelector`] that makes the payload builder act as a block builder that pays the
+/// proposer.
+///
+/// The fees of the block are collected by the builder account, and a transfer of the collected
+/// fees minus a margin from the builder account to the suggested fee recipient of the payload
+/// attributes is appended at the end of the block. The value of the built payload is the value of
+/// that payment.
+#[derive(Debug, Clone)]
+pub struct ProposerPayment {
+    /// The key that signs the payment transaction.
+    secret_key: SecretKey,
+    /// The builder account that collects the fees and pays the proposer.
+    address: Address,
+    /// The part of the collected fees that is kept by the builder.
+    margin: U256,
+}
+
+impl ProposerPayment {
+    /// Creates a new instance that signs the payments with the given key.
+    pub fn new(secret_key: SecretKey) -> Self {
+        let address = public_key_to_address(secret_key.public_key(SECP256K1));
+        Self { secret_key, address, margin: U256::ZERO }
+    }
+
+    /// Sets the part of the collected fees that is kept by the builder.
+    pub fn with_margin(mut self, margin: impl Into<U256>) -> Self {
+        self.margin = margin.into();
+        self
+    }
+
+    /// Returns the builder account that collects the fees and pays the proposer.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the value of the payment for the collected fees of the block, or `None` if nothing
+    /// is left after the margin and the gas costs of the payment itself.
+    fn payment_value(&self, total_fees: U256, base_fee: U256) -> Option<U256> {
+        let gas_cost = U256::from(PAYMENT_GAS_LIMIT) * base_fee;
+        total_fees
+            .checked_sub(self.margin)
+            .and_then(|value| value.checked_sub(gas_cost))
+            .filter(|value| !value.is_zero())
+    }
+}
+
+impl TransactionSelector for ProposerPayment {
+    fn beneficiary(&self, _attributes: &EthPayloadBuilderAttributes) -> Option<Address> {
+        Some(self.address)
+    }
+
+    fn reserved_gas(&self, _attributes: &EthPayloadBuilderAttributes) -> u64 {
+        PAYMENT_GAS_LIMIT
+    }
+
+    fn end_of_block_transactions(
+        &self,
+        ctx: &SelectionContext<'_>,
+        db: &mut dyn Database<Error = ProviderError>,
+    ) -> Result<Vec<TransactionSignedEcRecovered>, PayloadBuilderError> {
+        let Some(value) = self.payment_value(ctx.total_fees, ctx.block_env.basefee) else {
+            debug!(target: "payload_builder", fees = %ctx.total_fees, margin = %self.margin, "collected fees don't cover the proposer payment");
+            return Ok(Vec::new())
+        };
+
+        let nonce = db.basic(self.address)?.map(|account| account.nonce).unwrap_or_default();
+        let recipient = ctx.attributes.suggested_fee_recipient;
+        let transaction = Transaction::Eip1559(TxEip1559 {
+            chain_id: ctx.chain_id,
+            nonce,
+            gas_limit: PAYMENT_GAS_LIMIT,
+            max_fee_per_gas: ctx.block_env.basefee.to::<u128>(),
+            max_priority_fee_per_gas: 0,
+            to: TxKind::Call(recipient),
+            value,
+            access_list: Default::default(),
+            input: Bytes::new(),
+        });
+
+        let signature = sign_message(
+            B256::from_slice(&self.secret_key.secret_bytes()),
+            transaction.signature_hash(),
+        )
+        .map_err(PayloadBuilderError::other)?;
+        let transaction = TransactionSigned::from_transaction_and_signature(transaction, signature);
+
+        debug!(target: "payload_builder", tx = ?transaction.hash, %recipient, %value, "paying proposer");
+        Ok(vec![TransactionSignedEcRecovered::from_signed_transaction(transaction, self.address)])
+    }
+
+    fn payload_value(&self, ctx: &SelectionContext<'_>) -> U256 {
+        // the proposer only receives the payment, the rest of the fees stays with the builder
+        self.payment_value(ctx.total_fees, ctx.block_env.basefee).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EthereumPayloadBuilder;
+    use reth_basic_payload_builder::{
+        BuildArguments, BuildOutcome, Cancelled, PayloadBuilder, PayloadConfig,
+    };
+    use reth_chainspec::{ChainSpecBuilder, MAINNET};
+    use reth_db_common::init::init_genesis;
+    use reth_evm::execute::{BlockExecutionInput, BlockExecutorProvider, Executor};
+    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_primitives::{
+        constants::ETH_TO_WEI, BlockWithSenders, Genesis, GenesisAccount, SealedBlock,
+    };
+    use reth_provider::{
+        providers::BlockchainProvider2, test_utils::create_test_provider_factory_with_chain_spec,
+        StateProviderFactory, StateRootProvider,
+    };
+    use reth_revm::database::StateProviderDatabase;
+    use reth_rpc_types::engine::PayloadAttributes;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        TransactionPool,
+    };
+    use reth_trie::HashedPostState;
+    use std::{collections::BTreeMap, sync::Arc};
+
+    #[test]
+    fn payment_value_covers_margin_and_gas() {
+        let payment = ProposerPayment::new(SecretKey::from_slice(&[1; 32]).unwrap())
+            .with_margin(U256::from(1_000u64));
+        let base_fee = U256::from(10u64);
+
+        assert_eq!(payment.payment_value(U256::from(500u64), base_fee), None);
+        assert_eq!(payment.payment_value(U256::from(211_000u64), base_fee), None);
+        assert_eq!(payment.payment_value(U256::from(211_005u64), base_fee), Some(U256::from(5u64)));
+    }
+
+    #[tokio::test]
+    async fn builds_payload_with_payment() {
+        let sender = Address::repeat_byte(0x11);
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(Genesis {
+                    gas_limit: 30_000_000,
+                    alloc: BTreeMap::from([(
+                        sender,
+                        GenesisAccount { balance: U256::from(ETH_TO_WEI), ..Default::default() },
+                    )]),
+                    ..MAINNET.genesis.clone()
+                })
+                .shanghai_activated()
+                .build(),
+        );
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(factory.clone()).unwrap();
+        let client = BlockchainProvider2::new(factory).unwrap();
+
+        let pool = testing_pool();
+        pool.add_external_transaction(
+            MockTransaction::eip1559()
+                .with_sender(sender)
+                .with_gas_limit(PAYMENT_GAS_LIMIT)
+                .with_max_fee(10_000_000_000)
+                .with_priority_fee(2_000_000_000),
+        )
+        .await
+        .unwrap();
+
+        let payment = ProposerPayment::new(SecretKey::from_slice(&[1; 32]).unwrap());
+        let builder = payment.address();
+        let fee_recipient = Address::repeat_byte(0x22);
+        let parent = chain_spec.sealed_genesis_header();
+        let attributes = EthPayloadBuilderAttributes::new(
+            parent.hash(),
+            PayloadAttributes {
+                timestamp: parent.timestamp + 12,
+                prev_randao: B256::ZERO,
+                suggested_fee_recipient: fee_recipient,
+                withdrawals: Some(Vec::new()),
+                parent_beacon_block_root: None,
+            },
+        );
+        let config = PayloadConfig::new(
+            Arc::new(SealedBlock::new(parent.clone(), Default::default())),
+            Bytes::default(),
+            attributes,
+            chain_spec.clone(),
+        );
+        let args = BuildArguments::new(
+            client.clone(),
+            pool,
+            Default::default(),
+            config,
+            Cancelled::default(),
+            None,
+        );
+
+        let outcome = EthereumPayloadBuilder::default().with_selector(payment).try_build(args);
+        let Ok(BuildOutcome::Better { payload, .. }) = outcome else {
+            panic!("expected a better payload, got {outcome:?}")
+        };
+
+        // the builder collects the fees and pays the proposer in the last transaction
+        let block = payload.block();
+        assert_eq!(block.beneficiary, builder);
+        assert_eq!(block.body.len(), 2);
+        let payment_tx = block.body.last().unwrap();
+        assert_eq!(payment_tx.recover_signer(), Some(builder));
+        assert_eq!(payment_tx.to(), Some(fee_recipient));
+        assert!(!payload.fees().is_zero());
+        assert_eq!(payment_tx.value(), payload.fees());
+
+        // the state root includes the payment
+        let state_provider = client.state_by_block_hash(parent.hash()).unwrap();
+        let output = EthExecutorProvider::ethereum(chain_spec)
+            .executor(StateProviderDatabase::new(&*state_provider))
+            .execute(BlockExecutionInput {
+                block: &BlockWithSenders {
+                    block: block.clone().unseal(),
+                    senders: vec![sender, builder],
+                },
+                total_difficulty: U256::ZERO,
+            })
+            .unwrap();
+        assert_eq!(
+            block.state_root,
+            state_provider
+                .state_root(HashedPostState::from_bundle_state(&output.state.state))
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Hooks to customize which transactions are included in a payload.
+
+use reth_payload_builder::{error::PayloadBuilderError, EthPayloadBuilderAttributes};
+use reth_primitives::{
+    constants::eip4844::MAX_DATA_GAS_PER_BLOCK, Address, TransactionSignedEcRecovered, U256,
+};
+use reth_provider::ProviderError;
+use revm::{
+    primitives::{BlockEnv, ExecutionResult},
+    Database,
+};
+use tracing::trace;
+
+/// The state of the payload that is being built.
+///
+/// This is passed to all [`TransactionSelector`] hooks.
+#[derive(Debug, Clone, Copy)]
+pub struct SelectionContext<'a> {
+    /// The block environment of the payload.
+    pub block_env: &'a BlockEnv,
+    /// The attributes of the payload.
+    pub attributes: &'a EthPayloadBuilderAttributes,
+    /// The chain id of the payload.
+    pub chain_id: u64,
+    /// The gas limit of the block.
+    pub block_gas_limit: u64,
+    /// Gas that is reserved for the end of block transactions, see
+    /// [`TransactionSelector::reserved_gas`].
+    pub reserved_gas: u64,
+    /// Gas used by the transactions included so far.
+    pub cumulative_gas_used: u64,
+    /// Blob gas used by the transactions included so far.
+    pub blob_gas_used: u64,
+    /// Fees paid by the transactions included so far.
+    pub total_fees: U256,
+}
+
+/// What to do with a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionDecision {
+    /// Include the transaction.
+    Include,
+    /// Skip only this transaction and continue with the next best transaction.
+    ///
+    /// Later transactions of the same sender can't be included because of the resulting nonce gap,
+    /// they are still executed and then discarded as invalid.
+    Skip,
+    /// Skip the transaction and all transactions of the same sender that depend on it.
+    SkipWithDescendants,
+}
+
+/// Decides which transactions are included in a payload built by the
+/// [`EthereumPayloadBuilder`](crate::EthereumPayloadBuilder).
+///
+/// A payload is built in the following order:
+///  1. the pre block system calls
+///  2. [`TransactionSelector::pre_block_transactions`]
+///  3. the best transactions of the pool, each passed to
+///     [`TransactionSelector::filter_transaction`] before and to
+///     [`TransactionSelector::on_executed`] after execution
+///  4. [`TransactionSelector::payload_value`] and
+///     [`TransactionSelector::end_of_block_transactions`]
+///  5. the post block system calls and withdrawals
+///
+/// All hooks have default implementations that match the behavior of
+/// [`DefaultTransactionSelector`], so implementations only need to override what they want to
+/// change.
+pub trait TransactionSelector: Send + Sync + Clone {
+    /// Returns the beneficiary of the block that collects the fees.
+    ///
+    /// If this returns `None`, the suggested fee recipient of the attributes is used.
+    fn beneficiary(&self, _attributes: &EthPayloadBuilderAttributes) -> Option<Address> {
+        None
+    }
+
+    /// Returns the amount of gas that is kept free for the end of block transactions.
+    ///
+    /// Pool transactions are only included as long as this amount of gas remains available.
+    fn reserved_gas(&self, _attributes: &EthPayloadBuilderAttributes) -> u64 {
+        0
+    }
+
+    /// Returns transactions that are executed at the top of the block, before any transaction of
+    /// the pool.
+    ///
+    /// The transactions are executed in order, transactions that fail validation are skipped.
+    fn pre_block_transactions(
+        &self,
+        _ctx: &SelectionContext<'_>,
+    ) -> Vec<TransactionSignedEcRecovered> {
+        Vec::new()
+    }
+
+    /// Decides whether a pool transaction is executed.
+    ///
+    /// By default this only checks that the transaction fits into the remaining gas and blob gas
+    /// of the block, see [`default_transaction_filter`].
+    fn filter_transaction(
+        &self,
+        ctx: &SelectionContext<'_>,
+        tx: &TransactionSignedEcRecovered,
+    ) -> TransactionDecision {
+        default_transaction_filter(ctx, tx)
+    }
+
+    /// Decides whether the state changes of an executed pool transaction are committed to the
+    /// payload.
+    ///
+    /// If this does not return [`TransactionDecision::Include`], the transaction is discarded as if
+    /// it was never executed.
+    fn on_executed(
+        &self,
+        _ctx: &SelectionContext<'_>,
+        _tx: &TransactionSignedEcRecovered,
+        _result: &ExecutionResult,
+    ) -> TransactionDecision {
+        TransactionDecision::Include
+    }
+
+    /// Returns transactions that are executed at the end of the block, after all pool
+    /// transactions, for example payments of the block builder.
+    ///
+    /// The given database reflects the state after the pool transactions. Unlike pre block
+    /// transactions, these transactions are required: if one of them fails validation the build
+    /// attempt fails.
+    fn end_of_block_transactions(
+        &self,
+        _ctx: &SelectionContext<'_>,
+        _db: &mut dyn Database<Error = ProviderError>,
+    ) -> Result<Vec<TransactionSignedEcRecovered>, PayloadBuilderError> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the value of the payload for the proposer, which is compared against the best
+    /// payload so far and reported as the fees of the built payload.
+    ///
+    /// This is called with the state after the pool transactions, before the end of block
+    /// transactions. By default this is the sum of the fees paid by the included transactions.
+    fn payload_value(&self, ctx: &SelectionContext<'_>) -> U256 {
+        ctx.total_fees
+    }
+}
+
+/// The default [`TransactionSelector`] that includes the best transactions of the pool until the
+/// block is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DefaultTransactionSelector;
+
+impl TransactionSelector for DefaultTransactionSelector {}
+
+/// Checks that the transaction fits into the remaining gas and blob gas of the block.
+///
+/// Transactions that don't fit are skipped together with their descendants.
+pub fn default_transaction_filter(
+    ctx: &SelectionContext<'_>,
+    tx: &TransactionSignedEcRecovered,
+) -> TransactionDecision {
+    // ensure we still have capacity for this transaction
+    if ctx.cumulative_gas_used + ctx.reserved_gas + tx.gas_limit() > ctx.block_gas_limit {
+        // we can't fit this transaction into the block, so we need to mark it as invalid which
+        // also removes all dependent transaction from the iterator before we can continue
+        return TransactionDecision::SkipWithDescendants
+    }
+
+    // There's only limited amount of blob space available per block, so we need to check if the
+    // EIP-4844 can still fit in the block
+    if let Some(blob_tx) = tx.transaction.as_eip4844() {
+        let tx_blob_gas = blob_tx.blob_gas();
+        if ctx.blob_gas_used + tx_blob_gas > MAX_DATA_GAS_PER_BLOCK {
+            // we can't fit this _blob_ transaction into the block, so we mark it as invalid, which
+            // removes its dependent transactions from the iterator. This is similar to the gas
+            // limit condition for regular transactions above.
+            trace!(target: "payload_builder", tx=?tx.hash, sum_blob_gas_used=?ctx.blob_gas_used, ?tx_blob_gas, "skipping blob transaction because it would exceed the max data gas per block");
+            return TransactionDecision::SkipWithDescendants
+        }
+    }
+
+    TransactionDecision::Include
+}
@@ -9,7 +9,7 @@ use core::fmt::Display;
 
 use reth_primitives::{BlockNumber, BlockWithSenders, Receipt};
 use reth_prune_types::PruneModes;
-use revm_primitives::db::Database;
+use revm_primitives::{db::Database, EvmState};
 
 /// A general purpose executor trait that executes an input (e.g. block) and produces an output
 /// (e.g. state changes and receipts).
@@ -32,6 +32,46 @@ pub trait Executor<DB> {
     /// # Returns
     /// The output of the block execution.
     fn execute(self, input: Self::Input<'_>) -> Result<Self::Output, Self::Error>;
+
+    /// Consumes the type and executes the block, invoking the given hook with the state changes of
+    /// every executed transaction before they are committed.
+    ///
+    /// By default the hook is ignored and this behaves like [`Executor::execute`].
+    fn execute_with_state_hook<F>(
+        self,
+        input: Self::Input<'_>,
+        state_hook: F,
+    ) -> Result<Self::Output, Self::Error>
+    where
+        Self: Sized,
+        F: OnStateHook,
+    {
+        let _ = state_hook;
+        self.execute(input)
+    }
+}
+
+/// A hook that is called with the state changes of every executed transaction.
+pub trait OnStateHook {
+    /// Invoked with the state changes of a transaction before they are committed.
+    fn on_state(&mut self, state: &EvmState);
+}
+
+impl<F> OnStateHook for F
+where
+    F: FnMut(&EvmState),
+{
+    fn on_state(&mut self, state: &EvmState) {
+        self(state)
+    }
+}
+
+/// An [`OnStateHook`] that does nothing.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopHook;
+
+impl OnStateHook for NoopHook {
+    fn on_state(&mut self, _state: &EvmState) {}
 }
 
 /// A general purpose executor that can execute multiple inputs in sequence, validate the outputs,
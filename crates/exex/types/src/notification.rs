@@ -49,7 +49,7 @@ impl From<CanonStateNotification> for ExExNotification {
     fn from(notification: CanonStateNotification) -> Self {
         match notification {
             CanonStateNotification::Commit { new } => Self::ChainCommitted { new },
-            CanonStateNotification::Reorg { old, new } => Self::ChainReorged { old, new },
+            CanonStateNotification::Reorg { old, new, .. } => Self::ChainReorged { old, new },
         }
     }
 }
@@ -10,6 +10,7 @@
 
 type PeerId = alloy_primitives::B512;
 
+pub use ipnet::IpNet;
 use std::{collections::HashMap, net::IpAddr, time::Instant};
 
 /// Determines whether or not the IP is globally routable.
@@ -33,6 +34,8 @@ pub struct BanList {
     banned_ips: HashMap<IpAddr, Option<Instant>>,
     /// A set of [`PeerId`] whose packets get dropped instantly.
     banned_peers: HashMap<PeerId, Option<Instant>>,
+    /// A set of IP ranges whose packets get dropped instantly.
+    banned_ip_ranges: HashMap<IpNet, Option<Instant>>,
 }
 
 impl BanList {
@@ -48,11 +51,26 @@ impl BanList {
     }
 
     /// Creates a new ban list that bans the given peers and ips with an optional timeout.
-    pub const fn new_with_timeout(
+    pub fn new_with_timeout(
         banned_peers: HashMap<PeerId, Option<Instant>>,
         banned_ips: HashMap<IpAddr, Option<Instant>>,
     ) -> Self {
-        Self { banned_ips, banned_peers }
+        Self { banned_ips, banned_peers, banned_ip_ranges: HashMap::new() }
+    }
+
+    /// Returns all banned peers and until when they are banned, `None` if indefinitely.
+    pub fn banned_peers(&self) -> impl Iterator<Item = (PeerId, Option<Instant>)> + '_ {
+        self.banned_peers.iter().map(|(peer, until)| (*peer, *until))
+    }
+
+    /// Returns all banned IPs and until when they are banned, `None` if indefinitely.
+    pub fn banned_ips(&self) -> impl Iterator<Item = (IpAddr, Option<Instant>)> + '_ {
+        self.banned_ips.iter().map(|(ip, until)| (*ip, *until))
+    }
+
+    /// Returns all banned IP ranges and until when they are banned, `None` if indefinitely.
+    pub fn banned_ip_ranges(&self) -> impl Iterator<Item = (IpNet, Option<Instant>)> + '_ {
+        self.banned_ip_ranges.iter().map(|(range, until)| (*range, *until))
     }
 
     /// Removes all peers that are no longer banned.
@@ -85,10 +103,26 @@ impl BanList {
         evicted
     }
 
+    /// Removes all ip ranges that are no longer banned.
+    pub fn evict_ip_ranges(&mut self, now: Instant) -> Vec<IpNet> {
+        let mut evicted = Vec::new();
+        self.banned_ip_ranges.retain(|range, until| {
+            if let Some(until) = until {
+                if now > *until {
+                    evicted.push(*range);
+                    return false
+                }
+            }
+            true
+        });
+        evicted
+    }
+
     /// Removes all entries that should no longer be banned.
     ///
-    /// Returns the evicted entries.
+    /// Returns the evicted ips and peers, evicted ip ranges are dropped.
     pub fn evict(&mut self, now: Instant) -> (Vec<IpAddr>, Vec<PeerId>) {
+        self.evict_ip_ranges(now);
         let ips = self.evict_ips(now);
         let peers = self.evict_peers(now);
         (ips, peers)
@@ -103,7 +137,8 @@ impl BanList {
     /// checks the ban list to see if it contains the given ip
     #[inline]
     pub fn is_banned_ip(&self, ip: &IpAddr) -> bool {
-        self.banned_ips.contains_key(ip)
+        self.banned_ips.contains_key(ip) ||
+            (is_global(ip) && self.banned_ip_ranges.keys().any(|range| range.contains(ip)))
     }
 
     /// checks the ban list to see if it contains the given ip
@@ -117,6 +152,13 @@ impl BanList {
         self.banned_ips.remove(ip);
     }
 
+    /// Unbans the ip range.
+    ///
+    /// This only removes the exact range, IPs and ranges within it remain banned.
+    pub fn unban_ip_range(&mut self, range: &IpNet) {
+        self.banned_ip_ranges.remove(&range.trunc());
+    }
+
     /// Unbans the ip address
     pub fn unban_peer(&mut self, peer_id: &PeerId) {
         self.banned_peers.remove(peer_id);
@@ -159,6 +201,21 @@ impl BanList {
             self.banned_ips.insert(ip, until);
         }
     }
+
+    /// Bans the ip range indefinitely.
+    ///
+    /// Non-global IPs within the range are not banned.
+    pub fn ban_ip_range(&mut self, range: IpNet) {
+        self.ban_ip_range_with(range, None);
+    }
+
+    /// Bans the ip range indefinitely or until the given timeout.
+    ///
+    /// The range is truncated to its network address. Non-global IPs within the range are not
+    /// banned.
+    pub fn ban_ip_range_with(&mut self, range: IpNet, until: Option<Instant>) {
+        self.banned_ip_ranges.insert(range.trunc(), until);
+    }
 }
 
 #[cfg(test)]
@@ -208,4 +265,37 @@ mod tests {
         banlist.ban_ip(ip);
         assert!(!banlist.is_banned_ip(&ip));
     }
+    #[test]
+    fn can_ban_unban_ip_range() {
+        let range: IpNet = "1.1.1.7/24".parse().unwrap();
+        let mut banlist = BanList::default();
+        banlist.ban_ip_range(range);
+        assert!(banlist.is_banned_ip(&IpAddr::from([1, 1, 1, 1])));
+        assert!(banlist.is_banned_ip(&IpAddr::from([1, 1, 1, 255])));
+        assert!(!banlist.is_banned_ip(&IpAddr::from([1, 1, 2, 1])));
+        assert_eq!(banlist.banned_ip_ranges().count(), 1);
+
+        banlist.unban_ip_range(&"1.1.1.0/24".parse().unwrap());
+        assert!(!banlist.is_banned_ip(&IpAddr::from([1, 1, 1, 1])));
+    }
+
+    #[test]
+    fn range_does_not_ban_non_global() {
+        let mut banlist = BanList::default();
+        banlist.ban_ip_range("0.0.0.0/0".parse().unwrap());
+        assert!(banlist.is_banned_ip(&IpAddr::from([1, 1, 1, 1])));
+        assert!(!banlist.is_banned_ip(&IpAddr::from([10, 0, 0, 1])));
+        assert!(!banlist.is_banned_ip(&IpAddr::from([127, 0, 0, 1])));
+    }
+
+    #[test]
+    fn evict_ip_ranges() {
+        let now = Instant::now();
+        let mut banlist = BanList::default();
+        banlist.ban_ip_range_with("1.1.1.0/24".parse().unwrap(), Some(now));
+        banlist.ban_ip_range("2.2.2.0/24".parse().unwrap());
+        banlist.evict(now + std::time::Duration::from_secs(1));
+        assert!(!banlist.is_banned_ip(&IpAddr::from([1, 1, 1, 1])));
+        assert!(banlist.is_banned_ip(&IpAddr::from([2, 2, 2, 2])));
+    }
 }
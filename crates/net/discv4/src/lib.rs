@@ -374,6 +374,23 @@ impl Discv4 {
         self.send_to_service(cmd);
     }
 
+    /// Sets the udp port
+    ///
+    /// This will update our [`NodeRecord`]'s udp port, e.g. to the port mapped on the gateway.
+    pub fn set_udp_port(&self, port: u16) {
+        let cmd = Discv4Command::SetUdpPort(port);
+        self.send_to_service(cmd);
+    }
+
+    /// Sets the external ip address
+    ///
+    /// This will update our [`NodeRecord`]'s ip address, see also
+    /// [`Discv4Service::set_external_ip_addr`].
+    pub fn set_external_ip(&self, ip: IpAddr) {
+        let cmd = Discv4Command::SetExternalIp(ip);
+        self.send_to_service(cmd);
+    }
+
     /// Sets the pair in the EIP-868 [`Enr`] of the node.
     ///
     /// If the key already exists, this will update it.
@@ -1649,6 +1666,19 @@ impl Discv4Service {
                             let _ = self.local_eip_868_enr.set_tcp6(port, &self.secret_key);
                         }
                     }
+                    Discv4Command::SetUdpPort(port) => {
+                        debug!(target: "discv4", %port, "Update udp port");
+                        self.local_node_record.udp_port = port;
+                        if self.local_node_record.address.is_ipv4() {
+                            let _ = self.local_eip_868_enr.set_udp4(port, &self.secret_key);
+                        } else {
+                            let _ = self.local_eip_868_enr.set_udp6(port, &self.secret_key);
+                        }
+                        *self.shared_node_record.lock() = self.local_node_record;
+                    }
+                    Discv4Command::SetExternalIp(ip) => {
+                        self.set_external_ip_addr(ip);
+                    }
 
                     Discv4Command::Terminated => {
                         // terminate the service
@@ -1930,6 +1960,8 @@ impl Default for ReceiveCache {
 enum Discv4Command {
     Add(NodeRecord),
     SetTcpPort(u16),
+    SetUdpPort(u16),
+    SetExternalIp(IpAddr),
     SetEIP868RLPPair { key: Vec<u8>, rlp: Bytes },
     Ban(PeerId, IpAddr),
     BanPeer(PeerId),
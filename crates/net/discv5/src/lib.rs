@@ -122,6 +122,19 @@ impl Discv5 {
         self.set_eip868_in_local_enr(key, buf.into())
     }
 
+    /// Sets the socket of the local [`Enr`], e.g. to the address mapped on the gateway.
+    ///
+    /// Updates the tcp socket if `is_tcp` is set, otherwise the udp socket.
+    pub fn set_local_enr_socket(&self, socket: SocketAddr, is_tcp: bool) {
+        if self.discv5.update_local_enr_socket(socket, is_tcp) {
+            debug!(target: "discv5",
+                %socket,
+                is_tcp,
+                "updated local enr socket"
+            );
+        }
+    }
+
     /// Adds the peer and id to the ban list.
     ///
     /// This will prevent any future inclusion in the table
@@ -0,0 +1,262 @@
+//! Support for publishing node lists as [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459) trees.
+//!
+//! A [`DnsTree`] arranges the nodes and links in the same layout as `go-ethereum`'s `dnsdisc`, so
+//! that the same input produces the same records with either implementation.
+
+use crate::tree::{BranchEntry, DnsEntry, LinkEntry, NodeEntry, TreeRootEntry};
+use alloy_primitives::{keccak256, Bytes};
+use data_encoding::BASE32_NOPAD;
+use enr::Enr;
+use secp256k1::SecretKey;
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    fmt::Write,
+};
+
+/// Maximum number of children of a branch entry.
+///
+/// Each child takes 27 bytes, this keeps branch entries below the 370 bytes that fit into a single
+/// DNS response.
+pub const MAX_BRANCH_CHILDREN: usize = 13;
+
+/// Number of bytes of the keccak256 hash of an entry that make up its subdomain.
+const SUBDOMAIN_HASH_LEN: usize = 16;
+
+/// Maximum length of a single character-string in a TXT record.
+const MAX_TXT_STRING_LEN: usize = 255;
+
+/// A tree of nodes and links, ready to be published as TXT records.
+#[derive(Debug, Clone)]
+pub struct DnsTree {
+    /// The root entry, published at the domain itself.
+    root: TreeRootEntry,
+    /// All other entries, by their subdomain.
+    entries: BTreeMap<String, DnsEntry<SecretKey>>,
+}
+
+impl DnsTree {
+    /// Builds the unsigned tree of the given records and links.
+    ///
+    /// Records with the same node id are deduplicated, keeping the one with the highest sequence
+    /// number. Records are ordered by node id and links by their text, so the tree doesn't depend
+    /// on the order of the input.
+    pub fn new(
+        sequence_number: u64,
+        records: impl IntoIterator<Item = Enr<SecretKey>>,
+        links: impl IntoIterator<Item = LinkEntry>,
+    ) -> Self {
+        let mut nodes = BTreeMap::new();
+        for enr in records {
+            match nodes.entry(enr.node_id().raw()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(enr);
+                }
+                Entry::Occupied(mut entry) => {
+                    if enr.seq() > entry.get().seq() {
+                        entry.insert(enr);
+                    }
+                }
+            }
+        }
+        let nodes = nodes.into_values().map(|enr| DnsEntry::Node(NodeEntry { enr })).collect();
+
+        let mut links = links.into_iter().map(|link| (link.to_string(), link)).collect::<Vec<_>>();
+        links.sort_by(|a, b| a.0.cmp(&b.0));
+        links.dedup_by(|a, b| a.0 == b.0);
+        let links = links.into_iter().map(|(_, link)| DnsEntry::Link(link)).collect();
+
+        let mut tree = Self {
+            root: TreeRootEntry {
+                enr_root: String::new(),
+                link_root: String::new(),
+                sequence_number,
+                signature: Bytes::new(),
+            },
+            entries: BTreeMap::new(),
+        };
+        let enr_root = tree.build(nodes);
+        tree.root.enr_root = tree.insert(enr_root);
+        let link_root = tree.build(links);
+        tree.root.link_root = tree.insert(link_root);
+        tree
+    }
+
+    /// Signs the root entry with the given key.
+    pub fn sign(&mut self, key: &SecretKey) {
+        self.root.sign_recoverable(key);
+    }
+
+    /// Returns the root entry.
+    pub const fn root(&self) -> &TreeRootEntry {
+        &self.root
+    }
+
+    /// Returns all entries below the root, by their subdomain.
+    pub const fn entries(&self) -> &BTreeMap<String, DnsEntry<SecretKey>> {
+        &self.entries
+    }
+
+    /// Returns the TXT records of the tree published at `domain`, by their fully qualified name.
+    ///
+    /// The root is published at `domain` itself, every other entry at `<hash>.<domain>`.
+    pub fn records(&self, domain: &str) -> BTreeMap<String, String> {
+        std::iter::once((domain.to_string(), self.root.to_string()))
+            .chain(
+                self.entries
+                    .iter()
+                    .map(|(hash, entry)| (format!("{hash}.{domain}"), entry.to_string())),
+            )
+            .collect()
+    }
+
+    /// Returns the TXT records of the tree published at `domain` in zone file format.
+    pub fn to_zone_file(&self, domain: &str, ttl: u32) -> String {
+        let mut zone = String::new();
+        for (name, content) in self.records(domain) {
+            // character-strings are limited in length, longer content is split into several
+            let strings = content
+                .as_bytes()
+                .chunks(MAX_TXT_STRING_LEN)
+                .map(|chunk| format!("\"{}\"", String::from_utf8_lossy(chunk)))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let _ = writeln!(zone, "{name}. {ttl} IN TXT {strings}");
+        }
+        zone
+    }
+
+    /// Arranges the entries in a subtree of branches with at most [`MAX_BRANCH_CHILDREN`] children
+    /// and returns its root.
+    fn build(&mut self, mut entries: Vec<DnsEntry<SecretKey>>) -> DnsEntry<SecretKey> {
+        if entries.len() == 1 {
+            return entries.remove(0)
+        }
+        if entries.len() <= MAX_BRANCH_CHILDREN {
+            let children = entries.into_iter().map(|entry| self.insert(entry)).collect();
+            return DnsEntry::Branch(BranchEntry { children })
+        }
+
+        let mut subtrees = Vec::new();
+        while !entries.is_empty() {
+            let rest = entries.split_off(MAX_BRANCH_CHILDREN.min(entries.len()));
+            let subtree = self.build(std::mem::replace(&mut entries, rest));
+            subtrees.push(subtree);
+        }
+        self.build(subtrees)
+    }
+
+    /// Adds the entry to the tree and returns its subdomain.
+    fn insert(&mut self, entry: DnsEntry<SecretKey>) -> String {
+        let hash = subdomain(&entry);
+        self.entries.insert(hash.clone(), entry);
+        hash
+    }
+}
+
+/// Returns the subdomain of the entry, the base32 encoded prefix of the keccak256 hash of its text.
+pub fn subdomain(entry: &DnsEntry<SecretKey>) -> String {
+    BASE32_NOPAD.encode(&keccak256(entry.to_string())[..SUBDOMAIN_HASH_LEN])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enr::EnrKey;
+    use rand::{seq::SliceRandom, thread_rng};
+    use std::net::Ipv4Addr;
+
+    fn records(n: usize) -> Vec<Enr<SecretKey>> {
+        (0..n)
+            .map(|i| {
+                let key = SecretKey::new(&mut thread_rng());
+                Enr::builder().ip4(Ipv4Addr::LOCALHOST).tcp4(30303 + i as u16).build(&key).unwrap()
+            })
+            .collect()
+    }
+
+    /// Returns all leaves below the entry with the given subdomain.
+    fn leaves(tree: &DnsTree, hash: &str) -> Vec<String> {
+        match &tree.entries()[hash] {
+            DnsEntry::Branch(branch) => {
+                assert!(branch.children.len() <= MAX_BRANCH_CHILDREN);
+                branch.children.iter().flat_map(|child| leaves(tree, child)).collect()
+            }
+            entry => vec![entry.to_string()],
+        }
+    }
+
+    #[test]
+    fn build_signed_tree() {
+        let key = SecretKey::new(&mut thread_rng());
+        let records = records(30);
+        let link = LinkEntry {
+            domain: "nodes.example.org".to_string(),
+            pubkey: SecretKey::new(&mut thread_rng()).public(),
+        };
+
+        let mut tree = DnsTree::new(7, records.clone(), vec![link.clone()]);
+        tree.sign(&key);
+
+        // the root parses and is signed with a recoverable signature
+        let root: TreeRootEntry = tree.root().to_string().parse().unwrap();
+        assert_eq!(root.sequence_number, 7);
+        assert_eq!(root.signature.len(), 65);
+        assert!(root.verify::<SecretKey>(&key.public()));
+
+        // every entry parses and is published under its hash
+        for (hash, entry) in tree.entries() {
+            let parsed: DnsEntry<SecretKey> = entry.to_string().parse().unwrap();
+            assert_eq!(&subdomain(&parsed), hash);
+        }
+
+        let mut expected = records.iter().map(|enr| enr.to_base64()).collect::<Vec<_>>();
+        expected.sort();
+        let mut nodes = leaves(&tree, &root.enr_root);
+        nodes.sort();
+        assert_eq!(nodes, expected);
+        assert_eq!(leaves(&tree, &root.link_root), vec![link.to_string()]);
+    }
+
+    #[test]
+    fn build_is_deterministic() {
+        let key = SecretKey::new(&mut thread_rng());
+        let mut records = records(20);
+
+        let mut tree = DnsTree::new(1, records.clone(), vec![]);
+        tree.sign(&key);
+
+        records.shuffle(&mut thread_rng());
+        // duplicates are ignored
+        records.push(records[0].clone());
+        let mut shuffled = DnsTree::new(1, records, vec![]);
+        shuffled.sign(&key);
+
+        assert_eq!(tree.records("nodes.example.org"), shuffled.records("nodes.example.org"));
+    }
+
+    #[test]
+    fn keeps_highest_sequence_number() {
+        let key = SecretKey::new(&mut thread_rng());
+        let mut enr = Enr::builder().ip4(Ipv4Addr::LOCALHOST).tcp4(30303).build(&key).unwrap();
+        let old = enr.clone();
+        enr.set_tcp4(30304, &key).unwrap();
+
+        let tree = DnsTree::new(1, vec![enr.clone(), old], vec![]);
+        assert_eq!(leaves(&tree, &tree.root().enr_root), vec![enr.to_base64()]);
+    }
+
+    #[test]
+    fn zone_file() {
+        let mut tree = DnsTree::new(1, records(2), vec![]);
+        tree.sign(&SecretKey::new(&mut thread_rng()));
+
+        let zone = tree.to_zone_file("nodes.example.org", 300);
+        let lines = zone.lines().collect::<Vec<_>>();
+        // the root, a branch and two leaves
+        assert_eq!(lines.len(), 4);
+        assert!(zone.contains(&format!("nodes.example.org. 300 IN TXT \"{}\"", tree.root())));
+        for line in lines {
+            assert!(line.split('"').all(|s| s.len() <= MAX_TXT_STRING_LEN));
+        }
+    }
+}
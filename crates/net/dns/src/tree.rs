@@ -21,10 +21,10 @@ use crate::error::{
     ParseDnsEntryError::{FieldNotFound, UnknownEntry},
     ParseEntryResult,
 };
-use alloy_primitives::{hex, Bytes};
+use alloy_primitives::{hex, keccak256, Bytes};
 use data_encoding::{BASE32_NOPAD, BASE64URL_NOPAD};
 use enr::{Enr, EnrKey, EnrKeyUnambiguous, EnrPublicKey, Error as EnrError};
-use secp256k1::SecretKey;
+use secp256k1::{Message, SecretKey, SECP256K1};
 #[cfg(feature = "serde")]
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use std::{
@@ -141,6 +141,16 @@ impl TreeRootEntry {
         Ok(())
     }
 
+    /// Signs the content with the given key, producing the 65 byte recoverable signature that
+    /// EIP-1459 requires for published trees.
+    pub fn sign_recoverable(&mut self, key: &SecretKey) {
+        let msg = Message::from_digest(keccak256(self.content()).0);
+        let (recovery_id, sig) = SECP256K1.sign_ecdsa_recoverable(&msg, key).serialize_compact();
+        let mut signature = sig.to_vec();
+        signature.push(recovery_id.to_i32() as u8);
+        self.signature = signature.into();
+    }
+
     /// Verify the signature of the record.
     #[must_use]
     pub fn verify<K: EnrKey>(&self, pubkey: &K::PublicKey) -> bool {
@@ -1,8 +1,8 @@
 //! Implements the `GetReceipts` and `Receipts` message types.
 
-use alloy_rlp::{RlpDecodableWrapper, RlpEncodableWrapper};
+use alloy_rlp::{RlpDecodable, RlpDecodableWrapper, RlpEncodable, RlpEncodableWrapper};
 use reth_codecs_derive::add_arbitrary_tests;
-use reth_primitives::{ReceiptWithBloom, B256};
+use reth_primitives::{Log, Receipt, ReceiptWithBloom, TxType, B256};
 
 /// A request for transaction receipts from the given block hashes.
 #[derive(Clone, Debug, PartialEq, Eq, RlpEncodableWrapper, RlpDecodableWrapper, Default)]
@@ -25,9 +25,81 @@ pub struct Receipts(
     pub Vec<Vec<ReceiptWithBloom>>,
 );
 
+/// The `eth/69` response to [`GetReceipts`], which omits the bloom filter of each receipt since it
+/// can be recomputed from the logs.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodableWrapper, RlpDecodableWrapper, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct Receipts69(
+    /// Each receipt hash should correspond to a block hash in the request.
+    pub Vec<Vec<Receipt69>>,
+);
+
+impl From<Receipts69> for Receipts {
+    fn from(receipts: Receipts69) -> Self {
+        Self(
+            receipts
+                .0
+                .into_iter()
+                .map(|receipts| {
+                    receipts.into_iter().map(|receipt| receipt.into_with_bloom()).collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+/// A receipt as sent in [`Receipts69`], encoded as `[tx-type, status, cumulative-gas, logs]`.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct Receipt69 {
+    /// Receipt type.
+    pub tx_type: TxType,
+    /// If transaction is executed successfully.
+    pub success: bool,
+    /// Gas used
+    pub cumulative_gas_used: u64,
+    /// Log send from contracts.
+    pub logs: Vec<Log>,
+}
+
+impl Receipt69 {
+    /// Converts the receipt into a [`ReceiptWithBloom`], computing the bloom filter from the logs.
+    pub fn into_with_bloom(self) -> ReceiptWithBloom {
+        Receipt::from(self).with_bloom()
+    }
+}
+
+impl From<Receipt> for Receipt69 {
+    fn from(receipt: Receipt) -> Self {
+        Self {
+            tx_type: receipt.tx_type,
+            success: receipt.success,
+            cumulative_gas_used: receipt.cumulative_gas_used,
+            logs: receipt.logs,
+        }
+    }
+}
+
+impl From<Receipt69> for Receipt {
+    #[allow(clippy::needless_update)]
+    fn from(receipt: Receipt69) -> Self {
+        Self {
+            tx_type: receipt.tx_type,
+            success: receipt.success,
+            cumulative_gas_used: receipt.cumulative_gas_used,
+            logs: receipt.logs,
+            ..Default::default()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{message::RequestPair, GetReceipts, Receipts};
+    use crate::{message::RequestPair, GetReceipts, Receipt69, Receipts, Receipts69};
     use alloy_rlp::{Decodable, Encodable};
     use reth_primitives::{hex, Log, Receipt, ReceiptWithBloom, TxType};
 
@@ -150,4 +222,30 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    #[allow(clippy::needless_update)]
+    fn receipts69_into_receipts() {
+        let log = Log::new_unchecked(
+            hex!("0000000000000000000000000000000000000011").into(),
+            vec![hex!("000000000000000000000000000000000000000000000000000000000000dead").into()],
+            hex!("0100ff")[..].into(),
+        );
+        let receipt = Receipt {
+            tx_type: TxType::Eip1559,
+            success: true,
+            cumulative_gas_used: 21_000,
+            logs: vec![log],
+            ..Default::default()
+        };
+        let receipts = Receipts69(vec![vec![Receipt69::from(receipt.clone())]]);
+
+        let mut out = vec![];
+        receipts.encode(&mut out);
+        let decoded = Receipts69::decode(&mut out.as_slice()).unwrap();
+        assert_eq!(receipts, decoded);
+
+        // the bloom filter is recomputed from the logs
+        assert_eq!(Receipts::from(decoded), Receipts(vec![vec![receipt.with_bloom()]]));
+    }
 }
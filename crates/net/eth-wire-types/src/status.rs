@@ -1,10 +1,12 @@
 use crate::EthVersion;
 use alloy_chains::{Chain, NamedChain};
 use alloy_genesis::Genesis;
-use alloy_rlp::{RlpDecodable, RlpEncodable};
+use alloy_rlp::{Encodable, RlpDecodable, RlpEncodable};
 use reth_chainspec::{ChainSpec, MAINNET};
 use reth_codecs_derive::add_arbitrary_tests;
-use reth_primitives::{hex, EthereumHardfork, ForkId, Head, B256, U256};
+use reth_primitives::{
+    bytes::BufMut, hex, BlockNumber, EthereumHardfork, ForkId, Head, B256, U256,
+};
 use std::fmt::{Debug, Display};
 
 /// The status message is used in the eth protocol handshake to ensure that peers are on the same
@@ -82,6 +84,31 @@ impl Status {
             .total_difficulty(head.total_difficulty)
             .forkid(spec.fork_id(head))
     }
+
+    /// Converts the status into the [`StatusMessage`] to send for the given [`EthVersion`].
+    ///
+    /// Starting with `eth/69`, the status carries the range of blocks the node can serve instead
+    /// of the total difficulty.
+    pub fn into_message(
+        mut self,
+        version: EthVersion,
+        block_range: BlockRangeUpdate,
+    ) -> StatusMessage {
+        self.set_eth_version(version);
+        if version >= EthVersion::Eth69 {
+            StatusMessage::Eth69(StatusEth69 {
+                version: self.version,
+                chain: self.chain,
+                genesis: self.genesis,
+                forkid: self.forkid,
+                earliest: block_range.earliest,
+                latest: block_range.latest,
+                blockhash: block_range.latest_hash,
+            })
+        } else {
+            StatusMessage::Legacy(self)
+        }
+    }
 }
 
 impl Display for Status {
@@ -148,6 +175,204 @@ impl Default for Status {
     }
 }
 
+/// The `eth/69` status message, which replaces the total difficulty and the best block hash of
+/// [`Status`] with the range of blocks the peer is able to serve.
+///
+/// See also <https://eips.ethereum.org/EIPS/eip-7642>
+#[derive(Copy, Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct StatusEth69 {
+    /// The current protocol version, which is 69 or higher.
+    pub version: u8,
+
+    /// The chain id, as introduced in
+    /// [EIP155](https://eips.ethereum.org/EIPS/eip-155#list-of-chain-ids).
+    pub chain: Chain,
+
+    /// The genesis hash of the peer's chain.
+    pub genesis: B256,
+
+    /// The fork identifier as defined by
+    /// [EIP-2124](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-2124.md).
+    pub forkid: ForkId,
+
+    /// The earliest block the peer is able to serve.
+    pub earliest: BlockNumber,
+
+    /// The latest block the peer is able to serve.
+    pub latest: BlockNumber,
+
+    /// The hash of the latest block.
+    pub blockhash: B256,
+}
+
+impl StatusEth69 {
+    /// Returns the range of blocks the peer is able to serve.
+    pub const fn block_range(&self) -> BlockRangeUpdate {
+        BlockRangeUpdate {
+            earliest: self.earliest,
+            latest: self.latest,
+            latest_hash: self.blockhash,
+        }
+    }
+}
+
+/// The status message exchanged in the `eth` handshake, whose encoding depends on the negotiated
+/// [`EthVersion`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StatusMessage {
+    /// The status message used up to `eth/68`.
+    Legacy(Status),
+    /// The status message used since `eth/69`.
+    Eth69(StatusEth69),
+}
+
+impl StatusMessage {
+    /// Returns the protocol version of the status.
+    pub const fn version(&self) -> u8 {
+        match self {
+            Self::Legacy(status) => status.version,
+            Self::Eth69(status) => status.version,
+        }
+    }
+
+    /// Returns the chain of the status.
+    pub const fn chain(&self) -> Chain {
+        match self {
+            Self::Legacy(status) => status.chain,
+            Self::Eth69(status) => status.chain,
+        }
+    }
+
+    /// Returns the genesis hash of the status.
+    pub const fn genesis(&self) -> B256 {
+        match self {
+            Self::Legacy(status) => status.genesis,
+            Self::Eth69(status) => status.genesis,
+        }
+    }
+
+    /// Returns the fork id of the status.
+    pub const fn forkid(&self) -> ForkId {
+        match self {
+            Self::Legacy(status) => status.forkid,
+            Self::Eth69(status) => status.forkid,
+        }
+    }
+
+    /// Returns the range of blocks the peer is able to serve, if announced.
+    pub const fn block_range(&self) -> Option<BlockRangeUpdate> {
+        match self {
+            Self::Legacy(_) => None,
+            Self::Eth69(status) => Some(status.block_range()),
+        }
+    }
+
+    /// Returns the hash of the peer's best block.
+    ///
+    /// For `eth/69` this is the hash of the latest block the peer is able to serve.
+    pub const fn blockhash(&self) -> B256 {
+        match self {
+            Self::Legacy(status) => status.blockhash,
+            Self::Eth69(status) => status.blockhash,
+        }
+    }
+
+    /// Returns the total difficulty of the peer's best block, which is not part of the status
+    /// since `eth/69`.
+    pub const fn total_difficulty(&self) -> Option<U256> {
+        match self {
+            Self::Legacy(status) => Some(status.total_difficulty),
+            Self::Eth69(_) => None,
+        }
+    }
+}
+
+impl Default for StatusMessage {
+    fn default() -> Self {
+        Self::Legacy(Status::default())
+    }
+}
+
+impl From<Status> for StatusMessage {
+    fn from(status: Status) -> Self {
+        Self::Legacy(status)
+    }
+}
+
+impl From<StatusEth69> for StatusMessage {
+    fn from(status: StatusEth69) -> Self {
+        Self::Eth69(status)
+    }
+}
+
+impl Encodable for StatusMessage {
+    fn encode(&self, out: &mut dyn BufMut) {
+        match self {
+            Self::Legacy(status) => status.encode(out),
+            Self::Eth69(status) => status.encode(out),
+        }
+    }
+
+    fn length(&self) -> usize {
+        match self {
+            Self::Legacy(status) => status.length(),
+            Self::Eth69(status) => status.length(),
+        }
+    }
+}
+
+impl Display for StatusMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Legacy(status) => Display::fmt(status, f),
+            Self::Eth69(status) => write!(
+                f,
+                "Status {{ version: {}, chain: {}, genesis: {}, forkid: {:X?}, earliest: {}, latest: {}, blockhash: {} }}",
+                status.version,
+                status.chain,
+                hex::encode(status.genesis),
+                status.forkid,
+                status.earliest,
+                status.latest,
+                hex::encode(status.blockhash),
+            ),
+        }
+    }
+}
+
+/// Announces the range of blocks a peer is able to serve, introduced in `eth/69`.
+///
+/// This is sent whenever the range changes significantly, so peers don't request blocks that
+/// were pruned or aren't synced yet.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct BlockRangeUpdate {
+    /// The earliest block the peer is able to serve.
+    pub earliest: BlockNumber,
+    /// The latest block the peer is able to serve.
+    pub latest: BlockNumber,
+    /// The hash of the latest block.
+    pub latest_hash: B256,
+}
+
+impl BlockRangeUpdate {
+    /// Returns `true` if the earliest block is not after the latest block.
+    pub const fn is_valid(&self) -> bool {
+        self.earliest <= self.latest
+    }
+
+    /// Returns `true` if the given block is within the range.
+    pub const fn contains(&self, block: BlockNumber) -> bool {
+        self.earliest <= block && block <= self.latest
+    }
+}
+
 /// Builder for [`Status`] messages.
 ///
 /// # Example
@@ -228,7 +453,7 @@ impl StatusBuilder {
 
 #[cfg(test)]
 mod tests {
-    use crate::{EthVersion, Status};
+    use crate::{BlockRangeUpdate, EthVersion, Status, StatusEth69, StatusMessage};
     use alloy_genesis::Genesis;
     use alloy_rlp::{Decodable, Encodable};
     use rand::Rng;
@@ -404,4 +629,42 @@ mod tests {
         assert_eq!(status.blockhash, head_hash);
         assert_eq!(status.genesis, genesis_hash);
     }
+
+    #[test]
+    fn encode_decode_eth69_status_message() {
+        let status = StatusEth69 {
+            version: EthVersion::Eth69 as u8,
+            chain: Chain::from_named(NamedChain::Mainnet),
+            genesis: B256::random(),
+            forkid: ForkId { hash: ForkHash([0xb7, 0x15, 0x07, 0x7d]), next: 0 },
+            earliest: 15_537_394,
+            latest: 20_000_000,
+            blockhash: B256::random(),
+        };
+
+        let mut rlp_status = vec![];
+        StatusMessage::from(status).encode(&mut rlp_status);
+        let decoded = StatusEth69::decode(&mut &rlp_status[..]).unwrap();
+        assert_eq!(decoded, status);
+    }
+
+    #[test]
+    fn status_into_message() {
+        let status = Status::default();
+        let block_range =
+            BlockRangeUpdate { earliest: 0, latest: 100, latest_hash: B256::random() };
+
+        let message = status.into_message(EthVersion::Eth68, block_range);
+        assert_eq!(message, StatusMessage::Legacy(status));
+        assert_eq!(message.block_range(), None);
+
+        let message = status.into_message(EthVersion::Eth69, block_range);
+        assert_eq!(message.version(), EthVersion::Eth69 as u8);
+        assert_eq!(message.genesis(), status.genesis);
+        assert_eq!(message.forkid(), status.forkid);
+        assert_eq!(message.block_range(), Some(block_range));
+
+        assert_eq!(message.blockhash(), block_range.latest_hash);
+        assert_eq!(message.total_difficulty(), None);
+    }
 }
@@ -0,0 +1,750 @@
+//! Recording and replay of the messages exchanged over an `RLPx` session.
+//!
+//! A [`CaptureWriter`] installed on an [`UnauthedP2PStream`] records the `Hello` messages of the
+//! p2p handshake and, once the handshake is completed, every subprotocol message of the
+//! [`P2PStream`](crate::P2PStream) after decryption and decompression, starting with the `Status`
+//! messages of the eth handshake. The remaining p2p messages (ping, pong, disconnect) are not
+//! recorded.
+//!
+//! The records of all captures are written by the single thread of a [`CaptureSink`], so the
+//! stream never blocks on the capture. A capture stops recording once it reaches its maximum size,
+//! or if the writer thread can't keep up with the streams.
+//!
+//! A capture file starts with [`CAPTURE_MAGIC`], followed by length-prefixed records:
+//!
+//! ```text
+//! | length: u32 BE | timestamp: u64 BE | direction: u8 | protocol: u8 | message id: u8 | payload |
+//! ```
+//!
+//! where `length` covers everything following it, `timestamp` is the time the message passed the
+//! stream in microseconds since the unix epoch, and the message id is the p2p message id for p2p
+//! messages and relative to the start of the subprotocol message space for subprotocol messages.
+
+use crate::{
+    errors::{EthHandshakeError, EthStreamError, P2PHandshakeError, P2PStreamError},
+    p2pstream::P2PMessage,
+    protocol::Protocol,
+    EthMessage, EthVersion, HelloMessageWithProtocols, ProtocolMessage, UnauthedEthStream,
+    UnauthedP2PStream,
+};
+use alloy_rlp::Decodable;
+use futures::{Sink, SinkExt, Stream};
+use reth_primitives::{
+    bytes::{Buf, BufMut, Bytes, BytesMut},
+    ForkFilter,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender, SyncSender},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::debug;
+
+/// The bytes every capture file starts with.
+pub const CAPTURE_MAGIC: [u8; 8] = *b"rlpxcap1";
+
+/// The extension of capture files.
+pub const CAPTURE_FILE_EXTENSION: &str = "rlpx";
+
+/// Length of the fixed part of a record following the length prefix.
+const RECORD_HEADER_LEN: usize = 8 + 1 + 1;
+
+/// Maximum number of records of all captures that are queued for the writer thread.
+const MAX_QUEUED_RECORDS: usize = 4096;
+
+/// Whether a captured message was received from or sent to the peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureDirection {
+    /// The message was received from the peer.
+    Inbound,
+    /// The message was sent to the peer.
+    Outbound,
+}
+
+impl CaptureDirection {
+    const fn to_byte(self) -> u8 {
+        match self {
+            Self::Inbound => 0,
+            Self::Outbound => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> io::Result<Self> {
+        match byte {
+            0 => Ok(Self::Inbound),
+            1 => Ok(Self::Outbound),
+            _ => Err(invalid_data(format!("unknown capture direction {byte}"))),
+        }
+    }
+}
+
+/// The protocol a captured message belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureProtocol {
+    /// A message of the `p2p` protocol, the message id is the p2p message id.
+    P2P,
+    /// A subprotocol message, the message id is relative to the subprotocol message space.
+    Subprotocol,
+}
+
+impl CaptureProtocol {
+    const fn to_byte(self) -> u8 {
+        match self {
+            Self::P2P => 0,
+            Self::Subprotocol => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> io::Result<Self> {
+        match byte {
+            0 => Ok(Self::P2P),
+            1 => Ok(Self::Subprotocol),
+            _ => Err(invalid_data(format!("unknown capture protocol {byte}"))),
+        }
+    }
+}
+
+/// A single message of a capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedMessage {
+    /// Time the message passed the stream, since the unix epoch.
+    pub timestamp: Duration,
+    /// Whether the message was received or sent.
+    pub direction: CaptureDirection,
+    /// The protocol of the message.
+    pub protocol: CaptureProtocol,
+    /// The uncompressed message, starting with the message id.
+    pub message: Bytes,
+}
+
+impl CapturedMessage {
+    /// Encodes the record, including the length prefix.
+    pub fn encode(&self, out: &mut dyn BufMut) {
+        encode_record(self.timestamp, self.direction, self.protocol, &self.message, out)
+    }
+
+    /// Reads the next record, returns `None` at the end of the input.
+    fn read(reader: &mut impl Read) -> io::Result<Option<Self>> {
+        let mut len = [0u8; 4];
+        match reader.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        let len = u32::from_be_bytes(len) as usize;
+        if len < RECORD_HEADER_LEN {
+            return Err(invalid_data(format!("capture record too short: {len}")))
+        }
+
+        let mut record = vec![0u8; len];
+        reader.read_exact(&mut record)?;
+        let mut buf = &record[..];
+        let timestamp = Duration::from_micros(buf.get_u64());
+        let direction = CaptureDirection::from_byte(buf.get_u8())?;
+        let protocol = CaptureProtocol::from_byte(buf.get_u8())?;
+        Ok(Some(Self { timestamp, direction, protocol, message: Bytes::copy_from_slice(buf) }))
+    }
+}
+
+/// Writes the captures of any number of sessions on a single thread.
+///
+/// Clones share the writer thread, which exits once the sink and all of its [`CaptureWriter`]s
+/// are dropped.
+///
+/// If the sink was spawned for a directory, at most the configured number of capture files are
+/// kept in it, including the captures of previous runs: the oldest capture file is deleted, and
+/// stops being recorded if it is still open, before a new one is created.
+#[derive(Debug, Clone)]
+pub struct CaptureSink {
+    /// Sends commands to the writer thread.
+    to_writer: Sender<WriterCommand>,
+    /// Number of records that are queued for the writer thread.
+    queued: Arc<AtomicUsize>,
+    /// The id of the next capture.
+    next_id: Arc<AtomicU64>,
+}
+
+impl CaptureSink {
+    /// Spawns the writer thread, without a limit on the number of capture files.
+    pub fn spawn() -> io::Result<Self> {
+        Self::spawn_with(VecDeque::new(), usize::MAX)
+    }
+
+    /// Spawns the writer thread for the capture files in the given directory, keeping at most
+    /// `max_files` of them.
+    ///
+    /// Creates the directory if it doesn't exist.
+    pub fn spawn_in_dir(dir: impl AsRef<Path>, max_files: usize) -> io::Result<Self> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == CAPTURE_FILE_EXTENSION) {
+                files.push((entry.metadata()?.modified()?, path));
+            }
+        }
+        files.sort();
+        Self::spawn_with(files.into_iter().map(|(_, path)| path).collect(), max_files)
+    }
+
+    fn spawn_with(files: VecDeque<PathBuf>, max_files: usize) -> io::Result<Self> {
+        let (to_writer, rx) = mpsc::channel();
+        let queued = Arc::new(AtomicUsize::new(0));
+        let mut writer = CaptureThread {
+            captures: HashMap::new(),
+            files,
+            max_files: max_files.max(1),
+            queued: Arc::clone(&queued),
+        };
+        std::thread::Builder::new()
+            .name("rlpx-capture".to_string())
+            .spawn(move || writer.run(rx))?;
+        Ok(Self { to_writer, queued, next_id: Arc::new(AtomicU64::new(0)) })
+    }
+
+    /// Starts a capture file at the given path, without a maximum size.
+    ///
+    /// The path should have the [`CAPTURE_FILE_EXTENSION`] to be counted towards the maximum
+    /// number of files after a restart.
+    pub fn create(&self, path: impl Into<PathBuf>) -> io::Result<CaptureWriter> {
+        self.open(CaptureTarget::File(path.into()))
+    }
+
+    /// Starts a capture on the given writer, without a maximum size.
+    ///
+    /// The writer does not count towards the maximum number of files.
+    pub fn writer(&self, out: impl Write + Send + 'static) -> io::Result<CaptureWriter> {
+        self.open(CaptureTarget::Writer(Box::new(out)))
+    }
+
+    fn open(&self, target: CaptureTarget) -> io::Result<CaptureWriter> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.to_writer
+            .send(WriterCommand::Open { id, target })
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "capture writer stopped"))?;
+        Ok(CaptureWriter {
+            id,
+            sink: self.clone(),
+            size: CAPTURE_MAGIC.len() as u64,
+            max_size: u64::MAX,
+        })
+    }
+}
+
+/// Records the messages of a session to a capture of a [`CaptureSink`].
+///
+/// The capture is closed once the [`CaptureWriter`] is dropped.
+#[derive(Debug)]
+pub struct CaptureWriter {
+    /// The id of the capture.
+    id: u64,
+    /// The sink the capture is written by.
+    sink: CaptureSink,
+    /// Number of bytes recorded so far.
+    size: u64,
+    /// The maximum size of the capture, in bytes.
+    max_size: u64,
+}
+
+impl CaptureWriter {
+    /// Sets the maximum size of the capture, in bytes.
+    ///
+    /// Messages that would exceed the maximum size are not recorded.
+    pub const fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Records a message that passed the stream now.
+    ///
+    /// Returns an error if the capture reached its maximum size, or the message can't be queued
+    /// for writing, in which case the capture should be stopped.
+    pub fn record(
+        &mut self,
+        direction: CaptureDirection,
+        protocol: CaptureProtocol,
+        message: &[u8],
+    ) -> io::Result<()> {
+        let len = 4 + RECORD_HEADER_LEN + message.len();
+        if self.size.saturating_add(len as u64) > self.max_size {
+            return Err(io::Error::other(format!(
+                "capture reached its maximum size of {} bytes",
+                self.max_size
+            )))
+        }
+        if self.sink.queued.load(Ordering::Relaxed) >= MAX_QUEUED_RECORDS {
+            return Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                "capture writer is lagging behind",
+            ))
+        }
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let mut record = Vec::with_capacity(len);
+        encode_record(timestamp, direction, protocol, message, &mut record);
+        self.sink.queued.fetch_add(1, Ordering::Relaxed);
+        if self.sink.to_writer.send(WriterCommand::Record { id: self.id, record }).is_err() {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "capture writer stopped"))
+        }
+        self.size += len as u64;
+        Ok(())
+    }
+
+    /// Returns the number of bytes recorded so far, including the magic.
+    pub const fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Stops the capture and waits until all recorded messages are written.
+    ///
+    /// Note: this blocks the current thread.
+    pub fn finish(self) -> io::Result<()> {
+        let (done, rx) = mpsc::sync_channel(1);
+        let _ = self.sink.to_writer.send(WriterCommand::Close { id: self.id, done: Some(done) });
+        rx.recv().map_err(|_| io::Error::other("capture writer stopped"))?
+    }
+}
+
+impl Drop for CaptureWriter {
+    fn drop(&mut self) {
+        // no-op if the capture was already closed by `finish`
+        let _ = self.sink.to_writer.send(WriterCommand::Close { id: self.id, done: None });
+    }
+}
+
+/// Where a capture is written to.
+enum CaptureTarget {
+    /// A capture file that counts towards the maximum number of files.
+    File(PathBuf),
+    /// Any writer.
+    Writer(Box<dyn Write + Send>),
+}
+
+/// A command for the writer thread of a [`CaptureSink`].
+enum WriterCommand {
+    /// Starts a capture.
+    Open { id: u64, target: CaptureTarget },
+    /// Appends an encoded record to a capture.
+    Record { id: u64, record: Vec<u8> },
+    /// Flushes and closes a capture, reporting the outcome if requested.
+    Close { id: u64, done: Option<SyncSender<io::Result<()>>> },
+}
+
+/// An open capture of the writer thread.
+struct OpenCapture {
+    out: Box<dyn Write + Send>,
+    /// The path of the capture, if it is a file.
+    path: Option<PathBuf>,
+}
+
+/// The state of the writer thread of a [`CaptureSink`].
+struct CaptureThread {
+    /// The open captures by id.
+    captures: HashMap<u64, OpenCapture>,
+    /// The capture files in the directory, oldest first.
+    files: VecDeque<PathBuf>,
+    /// The maximum number of capture files.
+    max_files: usize,
+    /// Number of records that are queued for the writer thread.
+    queued: Arc<AtomicUsize>,
+}
+
+impl CaptureThread {
+    fn run(&mut self, rx: Receiver<WriterCommand>) {
+        for command in rx {
+            match command {
+                WriterCommand::Open { id, target } => {
+                    if let Err(err) = self.open(id, target) {
+                        debug!(%err, "failed to open capture");
+                    }
+                }
+                WriterCommand::Record { id, record } => {
+                    self.queued.fetch_sub(1, Ordering::Relaxed);
+                    let Some(capture) = self.captures.get_mut(&id) else { continue };
+                    if let Err(err) = capture.out.write_all(&record) {
+                        debug!(
+                            %err,
+                            path = ?capture.path,
+                            "failed to write capture, stopping capture"
+                        );
+                        self.captures.remove(&id);
+                    }
+                }
+                WriterCommand::Close { id, done } => {
+                    let res = match self.captures.remove(&id) {
+                        Some(mut capture) => capture.out.flush(),
+                        None => Ok(()),
+                    };
+                    if let Some(done) = done {
+                        let _ = done.send(res);
+                    }
+                }
+            }
+        }
+    }
+
+    fn open(&mut self, id: u64, target: CaptureTarget) -> io::Result<()> {
+        let (out, path): (Box<dyn Write + Send>, _) = match target {
+            CaptureTarget::File(path) => {
+                while self.files.len() >= self.max_files {
+                    let Some(oldest) = self.files.pop_front() else { break };
+                    self.captures.retain(|_, capture| capture.path.as_ref() != Some(&oldest));
+                    if let Err(err) = std::fs::remove_file(&oldest) {
+                        debug!(%err, path = ?oldest, "failed to remove capture");
+                    }
+                }
+                let file = File::create(&path)?;
+                self.files.push_back(path.clone());
+                (Box::new(BufWriter::new(file)), Some(path))
+            }
+            CaptureTarget::Writer(out) => (out, None),
+        };
+        let mut capture = OpenCapture { out, path };
+        capture.out.write_all(&CAPTURE_MAGIC)?;
+        self.captures.insert(id, capture);
+        Ok(())
+    }
+}
+
+/// Reads the messages of a capture.
+#[derive(Debug)]
+pub struct CaptureReader<R> {
+    reader: R,
+}
+
+impl CaptureReader<BufReader<File>> {
+    /// Opens the capture file at the given path.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Reads a capture from the given reader.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; CAPTURE_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != CAPTURE_MAGIC {
+            return Err(invalid_data("not an rlpx capture".to_string()))
+        }
+        Ok(Self { reader })
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = io::Result<CapturedMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        CapturedMessage::read(&mut self.reader).transpose()
+    }
+}
+
+/// Replays the inbound messages of a capture over the stream, acting as the captured peer.
+///
+/// The stream is expected to be connected to the node under test. The handshakes are performed
+/// with the captured `Hello` and `Status` of the peer, only the `eth` capabilities of the captured
+/// `Hello` are announced. The `Status` of the node is validated against the given fork filter.
+///
+/// The remaining subprotocol messages are sent as they were captured, bypassing the
+/// [`EthStream`](crate::EthStream) encoding, so malformed messages are reproduced as well. If
+/// `paced`, the time between the captured messages is preserved.
+///
+/// Returns the number of replayed messages after the handshakes.
+pub async fn replay<S>(
+    stream: UnauthedP2PStream<S>,
+    capture: impl IntoIterator<Item = CapturedMessage>,
+    fork_filter: ForkFilter,
+    paced: bool,
+) -> Result<usize, EthStreamError>
+where
+    S: Stream<Item = io::Result<BytesMut>> + Sink<Bytes, Error = io::Error> + Unpin,
+{
+    let mut inbound = capture.into_iter().filter(|msg| msg.direction == CaptureDirection::Inbound);
+
+    let hello = inbound
+        .by_ref()
+        .find(|msg| msg.protocol == CaptureProtocol::P2P)
+        .ok_or(P2PStreamError::HandshakeError(P2PHandshakeError::NoResponse))?;
+    let hello = match P2PMessage::decode(&mut &hello.message[..]) {
+        Ok(P2PMessage::Hello(hello)) => hello,
+        Ok(_) => {
+            return Err(P2PStreamError::HandshakeError(
+                P2PHandshakeError::NonHelloMessageInHandshake,
+            )
+            .into())
+        }
+        Err(err) => return Err(P2PStreamError::HandshakeError(err.into()).into()),
+    };
+    let hello = HelloMessageWithProtocols {
+        protocol_version: hello.protocol_version,
+        client_version: hello.client_version,
+        protocols: hello
+            .capabilities
+            .iter()
+            .filter(|cap| cap.is_eth())
+            .filter_map(|cap| EthVersion::try_from(cap.version as u8).ok())
+            .map(Protocol::eth)
+            .collect(),
+        port: hello.port,
+        id: hello.id,
+    };
+    let (p2p_stream, _) = stream.handshake(hello).await?;
+
+    let version = p2p_stream.shared_capabilities().eth_version()?;
+    let status = inbound
+        .by_ref()
+        .find(|msg| msg.protocol == CaptureProtocol::Subprotocol)
+        .ok_or(EthHandshakeError::NoResponse)?;
+    let status = match ProtocolMessage::decode_message(version, &mut &status.message[..])?.message {
+        EthMessage::Status(status) => status,
+        _ => return Err(EthHandshakeError::NonStatusMessageInHandshake.into()),
+    };
+    let (mut stream, _) = UnauthedEthStream::new(p2p_stream).handshake(status, fork_filter).await?;
+
+    let mut replayed = 0;
+    let mut last_timestamp = None;
+    for msg in inbound {
+        if msg.protocol != CaptureProtocol::Subprotocol {
+            continue
+        }
+        if paced {
+            if let Some(last) = last_timestamp {
+                tokio::time::sleep(msg.timestamp.saturating_sub(last)).await;
+            }
+            last_timestamp = Some(msg.timestamp);
+        }
+        stream.inner_mut().send(msg.message).await?;
+        replayed += 1;
+    }
+    Ok(replayed)
+}
+
+/// Encodes a record, including the length prefix.
+fn encode_record(
+    timestamp: Duration,
+    direction: CaptureDirection,
+    protocol: CaptureProtocol,
+    message: &[u8],
+    out: &mut dyn BufMut,
+) {
+    out.put_u32((RECORD_HEADER_LEN + message.len()) as u32);
+    out.put_u64(timestamp.as_micros() as u64);
+    out.put_u8(direction.to_byte());
+    out.put_u8(protocol.to_byte());
+    out.put_slice(message);
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{eth_handshake, eth_hello},
+        PassthroughCodec,
+    };
+    use futures::StreamExt;
+    use reth_eth_wire_types::{message::RequestPair, BlockHeaders};
+    use std::sync::{Arc, Mutex};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// A writer that can be inspected after the capture was installed.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn roundtrip_records() {
+        let buf = SharedBuf::default();
+        let mut writer = CaptureSink::spawn().unwrap().writer(buf.clone()).unwrap();
+        writer.record(CaptureDirection::Inbound, CaptureProtocol::P2P, &[0x00, 0xc0]).unwrap();
+        writer
+            .record(CaptureDirection::Outbound, CaptureProtocol::Subprotocol, &[0x04, 0xc1, 0x80])
+            .unwrap();
+        writer.finish().unwrap();
+
+        let data = buf.0.lock().unwrap().clone();
+        let messages =
+            CaptureReader::new(&data[..]).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].direction, CaptureDirection::Inbound);
+        assert_eq!(messages[0].protocol, CaptureProtocol::P2P);
+        assert_eq!(messages[0].message, Bytes::from_static(&[0x00, 0xc0]));
+        assert_eq!(messages[1].direction, CaptureDirection::Outbound);
+        assert_eq!(messages[1].protocol, CaptureProtocol::Subprotocol);
+        assert_eq!(messages[1].message, Bytes::from_static(&[0x04, 0xc1, 0x80]));
+        assert!(messages[0].timestamp <= messages[1].timestamp);
+
+        // a truncated record is an error
+        let truncated = &data[..data.len() - 1];
+        let mut reader = CaptureReader::new(truncated).unwrap();
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_err());
+
+        assert!(CaptureReader::new(&b"notacapture"[..]).is_err());
+    }
+
+    #[test]
+    fn max_size() {
+        let buf = SharedBuf::default();
+        // room for the magic and a single record with a two byte message
+        let max_size = (CAPTURE_MAGIC.len() + 4 + RECORD_HEADER_LEN + 2) as u64;
+        let mut writer =
+            CaptureSink::spawn().unwrap().writer(buf.clone()).unwrap().with_max_size(max_size);
+        writer
+            .record(CaptureDirection::Inbound, CaptureProtocol::Subprotocol, &[0x03, 0xc0])
+            .unwrap();
+        assert_eq!(writer.size(), max_size);
+        assert!(writer
+            .record(CaptureDirection::Inbound, CaptureProtocol::Subprotocol, &[0x03, 0xc0])
+            .is_err());
+        writer.finish().unwrap();
+
+        let data = buf.0.lock().unwrap().clone();
+        assert_eq!(data.len() as u64, max_size);
+        assert_eq!(CaptureReader::new(&data[..]).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn max_files() {
+        let dir = std::env::temp_dir().join(format!("rlpx-captures-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // a capture of a previous run
+        std::fs::write(dir.join("previous.rlpx"), CAPTURE_MAGIC).unwrap();
+        let files = || {
+            let mut names = std::fs::read_dir(&dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+
+        let sink = CaptureSink::spawn_in_dir(&dir, 2).unwrap();
+        let mut first = sink.create(dir.join("first.rlpx")).unwrap();
+        first.record(CaptureDirection::Inbound, CaptureProtocol::P2P, &[0x00, 0xc0]).unwrap();
+        // both captures are written by the same thread
+        sink.create(dir.join("second.rlpx")).unwrap().finish().unwrap();
+        first.finish().unwrap();
+        assert_eq!(files(), vec!["first.rlpx", "second.rlpx"]);
+        assert_eq!(CaptureReader::open(dir.join("first.rlpx")).unwrap().count(), 1);
+
+        sink.create(dir.join("third.rlpx")).unwrap().finish().unwrap();
+        assert_eq!(files(), vec!["second.rlpx", "third.rlpx"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn capture_and_replay() {
+        let (status, fork_filter) = eth_handshake();
+        // a malformed headers request, as sent by a misbehaving peer
+        let malformed = Bytes::from_static(&[0x03, 0xc2, 0x01, 0xc0]);
+        let response =
+            EthMessage::BlockHeaders(RequestPair { request_id: 1, message: BlockHeaders(vec![]) });
+
+        // the misbehaving peer
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let fork_filter_clone = fork_filter.clone();
+        let expected = response.clone();
+        let handle = tokio::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = PassthroughCodec::default().framed(incoming);
+            let (server_hello, _) = eth_hello();
+            let (p2p_stream, _) =
+                UnauthedP2PStream::new(stream).handshake(server_hello).await.unwrap();
+            let (mut peer, _) = UnauthedEthStream::new(p2p_stream)
+                .handshake(status.into(), fork_filter_clone)
+                .await
+                .unwrap();
+
+            assert_eq!(peer.next().await.unwrap().unwrap(), expected);
+            peer.inner_mut().send(malformed).await.unwrap();
+        });
+
+        // the node, its session is captured from the start
+        let buf = SharedBuf::default();
+        let (client_hello, _) = eth_hello();
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let (p2p_stream, _) = UnauthedP2PStream::new(PassthroughCodec::default().framed(outgoing))
+            .with_capture(CaptureSink::spawn().unwrap().writer(buf.clone()).unwrap())
+            .handshake(client_hello)
+            .await
+            .unwrap();
+        let (mut node, _) = UnauthedEthStream::new(p2p_stream)
+            .handshake(status.into(), fork_filter.clone())
+            .await
+            .unwrap();
+        node.send(response).await.unwrap();
+        assert!(matches!(node.next().await.unwrap(), Err(EthStreamError::InvalidMessage(_))));
+        handle.await.unwrap();
+        node.inner_mut().take_capture().unwrap().finish().unwrap();
+
+        let data = buf.0.lock().unwrap().clone();
+        let messages =
+            CaptureReader::new(&data[..]).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        let summary = messages.iter().map(|msg| (msg.direction, msg.protocol)).collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                // hello
+                (CaptureDirection::Outbound, CaptureProtocol::P2P),
+                (CaptureDirection::Inbound, CaptureProtocol::P2P),
+                // status
+                (CaptureDirection::Outbound, CaptureProtocol::Subprotocol),
+                (CaptureDirection::Inbound, CaptureProtocol::Subprotocol),
+                // headers
+                (CaptureDirection::Outbound, CaptureProtocol::Subprotocol),
+                (CaptureDirection::Inbound, CaptureProtocol::Subprotocol),
+            ]
+        );
+        assert_eq!(messages[5].message, Bytes::from_static(&[0x03, 0xc2, 0x01, 0xc0]));
+
+        // replaying the capture against a fresh node reproduces the handshakes and the malformed
+        // message
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let fork_filter_clone = fork_filter.clone();
+        let handle = tokio::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = PassthroughCodec::default().framed(incoming);
+            let (server_hello, _) = eth_hello();
+            let (p2p_stream, peer_hello) =
+                UnauthedP2PStream::new(stream).handshake(server_hello).await.unwrap();
+            let (mut node, peer_status) = UnauthedEthStream::new(p2p_stream)
+                .handshake(status.into(), fork_filter_clone)
+                .await
+                .unwrap();
+            assert!(matches!(node.next().await.unwrap(), Err(EthStreamError::InvalidMessage(_))));
+            (peer_hello, peer_status)
+        });
+
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let stream = UnauthedP2PStream::new(PassthroughCodec::default().framed(outgoing));
+        assert_eq!(replay(stream, messages, fork_filter, true).await.unwrap(), 1);
+        let (peer_hello, peer_status) = handle.await.unwrap();
+        assert_eq!(peer_hello.client_version, "eth/1.0.0");
+        assert_eq!(peer_status, status.into());
+    }
+}
@@ -35,6 +35,14 @@ pub enum EthStreamError {
         /// The number of transaction sizes.
         sizes_len: usize,
     },
+    #[error("invalid block range update: earliest {earliest} is after latest {latest}")]
+    /// Received a `BlockRangeUpdate` message whose earliest block is after its latest block.
+    InvalidBlockRangeUpdate {
+        /// The earliest block the peer claims to serve.
+        earliest: u64,
+        /// The latest block the peer claims to serve.
+        latest: u64,
+    },
     /// Error when data is not received from peer for a prolonged period.
     #[error("never received data from remote peer")]
     StreamTimeout,
@@ -91,6 +99,14 @@ pub enum EthHandshakeError {
     #[error("mismatched chain in status message: {0}")]
     /// Mismatch in chain details in status messages.
     MismatchedChain(GotExpected<Chain>),
+    #[error("invalid block range in status message: earliest {earliest} is after latest {latest}")]
+    /// The block range announced in the status message is invalid.
+    InvalidBlockRange {
+        /// The earliest block the peer claims to serve.
+        earliest: u64,
+        /// The latest block the peer claims to serve.
+        latest: u64,
+    },
     #[error("total difficulty bitlen is too large: got {got}, maximum {maximum}")]
     /// Excessively large total difficulty bit lengths.
     TotalDifficultyBitLenTooLarge {
@@ -2,7 +2,7 @@ use crate::{
     errors::{EthHandshakeError, EthStreamError},
     message::{EthBroadcastMessage, ProtocolBroadcastMessage},
     p2pstream::HANDSHAKE_TIMEOUT,
-    CanDisconnect, DisconnectReason, EthMessage, EthVersion, ProtocolMessage, Status,
+    CanDisconnect, DisconnectReason, EthMessage, EthVersion, ProtocolMessage, StatusMessage,
 };
 use futures::{ready, Sink, SinkExt, StreamExt};
 use pin_project::pin_project;
@@ -52,21 +52,24 @@ where
     /// Consumes the [`UnauthedEthStream`] and returns an [`EthStream`] after the `Status`
     /// handshake is completed successfully. This also returns the `Status` message sent by the
     /// remote peer.
+    ///
+    /// The `status` must be encoded for the negotiated version, see
+    /// [`Status::into_message`](crate::Status::into_message).
     pub async fn handshake(
         self,
-        status: Status,
+        status: StatusMessage,
         fork_filter: ForkFilter,
-    ) -> Result<(EthStream<S>, Status), EthStreamError> {
+    ) -> Result<(EthStream<S>, StatusMessage), EthStreamError> {
         self.handshake_with_timeout(status, fork_filter, HANDSHAKE_TIMEOUT).await
     }
 
     /// Wrapper around handshake which enforces a timeout.
     pub async fn handshake_with_timeout(
         self,
-        status: Status,
+        status: StatusMessage,
         fork_filter: ForkFilter,
         timeout_limit: Duration,
-    ) -> Result<(EthStream<S>, Status), EthStreamError> {
+    ) -> Result<(EthStream<S>, StatusMessage), EthStreamError> {
         timeout(timeout_limit, Self::handshake_without_timeout(self, status, fork_filter))
             .await
             .map_err(|_| EthStreamError::StreamTimeout)?
@@ -75,9 +78,9 @@ where
     /// Handshake with no timeout
     pub async fn handshake_without_timeout(
         mut self,
-        status: Status,
+        status: StatusMessage,
         fork_filter: ForkFilter,
-    ) -> Result<(EthStream<S>, Status), EthStreamError> {
+    ) -> Result<(EthStream<S>, StatusMessage), EthStreamError> {
         trace!(
             %status,
             "sending eth status to peer"
@@ -104,7 +107,7 @@ where
             return Err(EthStreamError::MessageTooBig(their_msg.len()))
         }
 
-        let version = EthVersion::try_from(status.version)?;
+        let version = EthVersion::try_from(status.version())?;
         let msg = match ProtocolMessage::decode_message(version, &mut their_msg.as_ref()) {
             Ok(m) => m,
             Err(err) => {
@@ -122,45 +125,58 @@ where
                     status=%resp,
                     "validating incoming eth status from peer"
                 );
-                if status.genesis != resp.genesis {
+                if status.genesis() != resp.genesis() {
                     self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                     return Err(EthHandshakeError::MismatchedGenesis(
-                        GotExpected { expected: status.genesis, got: resp.genesis }.into(),
+                        GotExpected { expected: status.genesis(), got: resp.genesis() }.into(),
                     )
                     .into())
                 }
 
-                if status.version != resp.version {
+                if status.version() != resp.version() {
                     self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                     return Err(EthHandshakeError::MismatchedProtocolVersion(GotExpected {
-                        got: resp.version,
-                        expected: status.version,
+                        got: resp.version(),
+                        expected: status.version(),
                     })
                     .into())
                 }
 
-                if status.chain != resp.chain {
+                if status.chain() != resp.chain() {
                     self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                     return Err(EthHandshakeError::MismatchedChain(GotExpected {
-                        got: resp.chain,
-                        expected: status.chain,
+                        got: resp.chain(),
+                        expected: status.chain(),
                     })
                     .into())
                 }
 
                 // TD at mainnet block #7753254 is 76 bits. If it becomes 100 million times
                 // larger, it will still fit within 100 bits
-                if status.total_difficulty.bit_len() > 100 {
-                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
-                    return Err(EthHandshakeError::TotalDifficultyBitLenTooLarge {
-                        got: status.total_difficulty.bit_len(),
-                        maximum: 100,
+                if let StatusMessage::Legacy(status) = status {
+                    if status.total_difficulty.bit_len() > 100 {
+                        self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                        return Err(EthHandshakeError::TotalDifficultyBitLenTooLarge {
+                            got: status.total_difficulty.bit_len(),
+                            maximum: 100,
+                        }
+                        .into())
+                    }
+                }
+
+                if let Some(block_range) = resp.block_range() {
+                    if !block_range.is_valid() {
+                        self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                        return Err(EthHandshakeError::InvalidBlockRange {
+                            earliest: block_range.earliest,
+                            latest: block_range.latest,
+                        }
+                        .into())
                     }
-                    .into())
                 }
 
                 if let Err(err) =
-                    fork_filter.validate(resp.forkid).map_err(EthHandshakeError::InvalidFork)
+                    fork_filter.validate(resp.forkid()).map_err(EthHandshakeError::InvalidFork)
                 {
                     self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                     return Err(err.into())
@@ -351,7 +367,7 @@ mod tests {
         hello::DEFAULT_TCP_PORT,
         p2pstream::UnauthedP2PStream,
         EthMessage, EthStream, EthVersion, HelloMessageWithProtocols, PassthroughCodec,
-        ProtocolVersion, Status,
+        ProtocolVersion, Status, StatusMessage,
     };
     use futures::{SinkExt, StreamExt};
     use reth_chainspec::NamedChain;
@@ -388,12 +404,12 @@ mod tests {
             let (incoming, _) = listener.accept().await.unwrap();
             let stream = PassthroughCodec::default().framed(incoming);
             let (_, their_status) = UnauthedEthStream::new(stream)
-                .handshake(status_clone, fork_filter_clone)
+                .handshake(status_clone.into(), fork_filter_clone)
                 .await
                 .unwrap();
 
             // just make sure it equals our status (our status is a clone of their status)
-            assert_eq!(their_status, status_clone);
+            assert_eq!(their_status, StatusMessage::from(status_clone));
         });
 
         let outgoing = TcpStream::connect(local_addr).await.unwrap();
@@ -401,10 +417,10 @@ mod tests {
 
         // try to connect
         let (_, their_status) =
-            UnauthedEthStream::new(sink).handshake(status, fork_filter).await.unwrap();
+            UnauthedEthStream::new(sink).handshake(status.into(), fork_filter).await.unwrap();
 
         // their status is a clone of our status, these should be equal
-        assert_eq!(their_status, status);
+        assert_eq!(their_status, StatusMessage::from(status));
 
         // wait for it to finish
         handle.await.unwrap();
@@ -435,12 +451,12 @@ mod tests {
             let (incoming, _) = listener.accept().await.unwrap();
             let stream = PassthroughCodec::default().framed(incoming);
             let (_, their_status) = UnauthedEthStream::new(stream)
-                .handshake(status_clone, fork_filter_clone)
+                .handshake(status_clone.into(), fork_filter_clone)
                 .await
                 .unwrap();
 
             // just make sure it equals our status, and that the handshake succeeded
-            assert_eq!(their_status, status_clone);
+            assert_eq!(their_status, StatusMessage::from(status_clone));
         });
 
         let outgoing = TcpStream::connect(local_addr).await.unwrap();
@@ -448,10 +464,10 @@ mod tests {
 
         // try to connect
         let (_, their_status) =
-            UnauthedEthStream::new(sink).handshake(status, fork_filter).await.unwrap();
+            UnauthedEthStream::new(sink).handshake(status.into(), fork_filter).await.unwrap();
 
         // their status is a clone of our status, these should be equal
-        assert_eq!(their_status, status);
+        assert_eq!(their_status, StatusMessage::from(status));
 
         // await the other handshake
         handle.await.unwrap();
@@ -481,8 +497,9 @@ mod tests {
             // roughly based off of the design of tokio::net::TcpListener
             let (incoming, _) = listener.accept().await.unwrap();
             let stream = PassthroughCodec::default().framed(incoming);
-            let handshake_res =
-                UnauthedEthStream::new(stream).handshake(status_clone, fork_filter_clone).await;
+            let handshake_res = UnauthedEthStream::new(stream)
+                .handshake(status_clone.into(), fork_filter_clone)
+                .await;
 
             // make sure the handshake fails due to td too high
             assert!(matches!(
@@ -497,7 +514,8 @@ mod tests {
         let sink = PassthroughCodec::default().framed(outgoing);
 
         // try to connect
-        let handshake_res = UnauthedEthStream::new(sink).handshake(status, fork_filter).await;
+        let handshake_res =
+            UnauthedEthStream::new(sink).handshake(status.into(), fork_filter).await;
 
         // this handshake should also fail due to td too high
         assert!(matches!(
@@ -632,7 +650,7 @@ mod tests {
             let unauthed_stream = UnauthedP2PStream::new(stream);
             let (p2p_stream, _) = unauthed_stream.handshake(server_hello).await.unwrap();
             let (mut eth_stream, _) = UnauthedEthStream::new(p2p_stream)
-                .handshake(status_copy, fork_filter_clone)
+                .handshake(status_copy.into(), fork_filter_clone)
                 .await
                 .unwrap();
 
@@ -661,7 +679,7 @@ mod tests {
         let (p2p_stream, _) = unauthed_stream.handshake(client_hello).await.unwrap();
 
         let (mut client_stream, _) =
-            UnauthedEthStream::new(p2p_stream).handshake(status, fork_filter).await.unwrap();
+            UnauthedEthStream::new(p2p_stream).handshake(status.into(), fork_filter).await.unwrap();
 
         client_stream.send(test_msg).await.unwrap();
 
@@ -696,12 +714,12 @@ mod tests {
             let (incoming, _) = listener.accept().await.unwrap();
             let stream = PassthroughCodec::default().framed(incoming);
             let (_, their_status) = UnauthedEthStream::new(stream)
-                .handshake(status_clone, fork_filter_clone)
+                .handshake(status_clone.into(), fork_filter_clone)
                 .await
                 .unwrap();
 
             // just make sure it equals our status (our status is a clone of their status)
-            assert_eq!(their_status, status_clone);
+            assert_eq!(their_status, StatusMessage::from(status_clone));
         });
 
         let outgoing = TcpStream::connect(local_addr).await.unwrap();
@@ -709,7 +727,7 @@ mod tests {
 
         // try to connect
         let handshake_result = UnauthedEthStream::new(sink)
-            .handshake_with_timeout(status, fork_filter, Duration::from_secs(1))
+            .handshake_with_timeout(status.into(), fork_filter, Duration::from_secs(1))
             .await;
 
         // Assert that a timeout error occurred
@@ -181,6 +181,18 @@ impl HelloMessageBuilder {
         self
     }
 
+    /// Adds the `eth/69` protocol, which is not advertised by default.
+    ///
+    /// If no protocols were set, the default protocols are advertised as well.
+    pub fn eth69(mut self) -> Self {
+        let eth69 = Protocol::from(EthVersion::Eth69);
+        let protocols = self.protocols.get_or_insert_with(default_protocols);
+        if !protocols.contains(&eth69) {
+            protocols.insert(0, eth69);
+        }
+        self
+    }
+
     /// Sets client version.
     pub fn client_version(mut self, client_version: impl Into<String>) -> Self {
         self.client_version = Some(client_version.into());
@@ -198,24 +210,30 @@ impl HelloMessageBuilder {
     /// Unset fields will be set to their default values:
     /// - `protocol_version`: [`ProtocolVersion::V5`]
     /// - `client_version`: [`RETH_CLIENT_VERSION`]
-    /// - `capabilities`: All [`EthVersion`]
+    /// - `capabilities`: All [`EthVersion`] up to [`EthVersion::LATEST`]
     pub fn build(self) -> HelloMessageWithProtocols {
         let Self { protocol_version, client_version, protocols, port, id } = self;
         HelloMessageWithProtocols {
             protocol_version: protocol_version.unwrap_or_default(),
             client_version: client_version.unwrap_or_else(|| RETH_CLIENT_VERSION.to_string()),
-            protocols: protocols.unwrap_or_else(|| {
-                vec![EthVersion::Eth68.into(), EthVersion::Eth67.into(), EthVersion::Eth66.into()]
-            }),
+            protocols: protocols.unwrap_or_else(default_protocols),
             port: port.unwrap_or(DEFAULT_TCP_PORT),
             id,
         }
     }
 }
 
+/// The protocols advertised if none are configured.
+fn default_protocols() -> Vec<Protocol> {
+    vec![EthVersion::Eth68.into(), EthVersion::Eth67.into(), EthVersion::Eth66.into()]
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{p2pstream::P2PMessage, Capability, EthVersion, HelloMessage, ProtocolVersion};
+    use crate::{
+        p2pstream::P2PMessage, Capability, EthVersion, HelloMessage, HelloMessageWithProtocols,
+        ProtocolVersion,
+    };
     use alloy_rlp::{Decodable, Encodable, EMPTY_STRING_CODE};
     use reth_network_peers::pk2id;
     use secp256k1::{SecretKey, SECP256K1};
@@ -258,6 +276,27 @@ mod tests {
         assert_eq!(hello_encoded.len(), hello.length());
     }
 
+    #[test]
+    fn eth69_is_opt_in() {
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let id = pk2id(&secret_key.public_key(SECP256K1));
+
+        let hello = HelloMessageWithProtocols::builder(id).build();
+        assert!(!hello.protocols.contains(&EthVersion::Eth69.into()));
+        assert_eq!(hello.protocols[0], EthVersion::Eth68.into());
+
+        let hello = HelloMessageWithProtocols::builder(id).eth69().eth69().build();
+        assert_eq!(
+            hello.protocols,
+            vec![
+                EthVersion::Eth69.into(),
+                EthVersion::Eth68.into(),
+                EthVersion::Eth67.into(),
+                EthVersion::Eth66.into(),
+            ]
+        );
+    }
+
     #[test]
     fn hello_message_id_prefix() {
         // ensure that the hello message id is prefixed
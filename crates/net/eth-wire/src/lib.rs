@@ -14,6 +14,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 pub mod capability;
+pub mod capture;
 mod disconnect;
 pub mod errors;
 mod ethstream;
@@ -1,5 +1,6 @@
 use crate::{
     capability::SharedCapabilities,
+    capture::{CaptureDirection, CaptureProtocol, CaptureWriter},
     disconnect::CanDisconnect,
     errors::{P2PHandshakeError, P2PStreamError},
     pinger::{Pinger, PingerEvent},
@@ -65,12 +66,23 @@ const MAX_P2P_CAPACITY: usize = 2;
 pub struct UnauthedP2PStream<S> {
     #[pin]
     inner: S,
+    /// Records the messages of the stream, starting with the `Hello` handshake, if enabled.
+    capture: Option<CaptureWriter>,
 }
 
 impl<S> UnauthedP2PStream<S> {
     /// Create a new `UnauthedP2PStream` from a type `S` which implements `Stream` and `Sink`.
     pub const fn new(inner: S) -> Self {
-        Self { inner }
+        Self { inner, capture: None }
+    }
+
+    /// Records the `Hello` handshake and all subprotocol messages of the resulting [`P2PStream`]
+    /// to the given capture.
+    ///
+    /// See [`capture`](crate::capture) for the format.
+    pub fn with_capture(mut self, capture: CaptureWriter) -> Self {
+        self.capture = Some(capture);
+        self
     }
 
     /// Returns a reference to the inner stream.
@@ -92,12 +104,25 @@ where
         trace!(?hello, "sending p2p hello to peer");
 
         // send our hello message with the Sink
-        self.inner.send(alloy_rlp::encode(P2PMessage::Hello(hello.message())).into()).await?;
+        let our_hello: Bytes = alloy_rlp::encode(P2PMessage::Hello(hello.message())).into();
+        record_capture(
+            &mut self.capture,
+            CaptureDirection::Outbound,
+            CaptureProtocol::P2P,
+            &our_hello,
+        );
+        self.inner.send(our_hello).await?;
 
         let first_message_bytes = tokio::time::timeout(HANDSHAKE_TIMEOUT, self.inner.next())
             .await
             .or(Err(P2PStreamError::HandshakeError(P2PHandshakeError::Timeout)))?
             .ok_or(P2PStreamError::HandshakeError(P2PHandshakeError::NoResponse))??;
+        record_capture(
+            &mut self.capture,
+            CaptureDirection::Inbound,
+            CaptureProtocol::P2P,
+            &first_message_bytes,
+        );
 
         // let's check the compressed length first, we will need to check again once confirming
         // that it contains snappy-compressed data (this will be the case for all non-p2p messages).
@@ -163,7 +188,8 @@ where
             Ok(cap) => Ok(cap),
         }?;
 
-        let stream = P2PStream::new(self.inner, shared_capability);
+        let mut stream = P2PStream::new(self.inner, shared_capability);
+        stream.capture = self.capture;
 
         Ok((stream, their_hello))
     }
@@ -250,6 +276,9 @@ pub struct P2PStream<S> {
     /// Whether this stream is currently in the process of disconnecting by sending a disconnect
     /// message.
     disconnecting: bool,
+
+    /// Records the subprotocol messages of this stream, if enabled.
+    capture: Option<CaptureWriter>,
 }
 
 impl<S> P2PStream<S> {
@@ -266,6 +295,7 @@ impl<S> P2PStream<S> {
             outgoing_messages: VecDeque::new(),
             outgoing_message_buffer_capacity: MAX_P2P_CAPACITY,
             disconnecting: false,
+            capture: None,
         }
     }
 
@@ -283,6 +313,19 @@ impl<S> P2PStream<S> {
         self.outgoing_message_buffer_capacity = capacity;
     }
 
+    /// Starts recording all subprotocol messages of this stream to the given capture.
+    ///
+    /// See [`capture`](crate::capture) for the format. To also record the `Hello` handshake, see
+    /// [`UnauthedP2PStream::with_capture`].
+    pub fn set_capture(&mut self, capture: CaptureWriter) {
+        self.capture = Some(capture);
+    }
+
+    /// Stops recording and returns the capture, if any.
+    pub fn take_capture(&mut self) -> Option<CaptureWriter> {
+        self.capture.take()
+    }
+
     /// Returns the shared capabilities for this stream.
     ///
     /// This includes all the shared capabilities that were negotiated during the handshake and
@@ -307,6 +350,23 @@ impl<S> P2PStream<S> {
     }
 }
 
+/// Records the message if a capture is enabled.
+///
+/// Failing to write the capture stops the capture, but doesn't affect the stream.
+fn record_capture(
+    capture: &mut Option<CaptureWriter>,
+    direction: CaptureDirection,
+    protocol: CaptureProtocol,
+    message: &[u8],
+) {
+    if let Some(writer) = capture {
+        if let Err(err) = writer.record(direction, protocol, message) {
+            debug!(%err, "failed to capture p2p message, stopping capture");
+            *capture = None;
+        }
+    }
+}
+
 /// Gracefully disconnects the connection by sending a disconnect message and stop reading new
 /// messages.
 pub trait DisconnectP2P {
@@ -514,6 +574,12 @@ where
                     //  * `qrs/65` is reserved message IDs 0x1a - 0x21.
                     //
                     decompress_buf[0] = bytes[0] - MAX_RESERVED_MESSAGE_ID - 1;
+                    record_capture(
+                        &mut this.capture,
+                        CaptureDirection::Inbound,
+                        CaptureProtocol::Subprotocol,
+                        &decompress_buf,
+                    );
 
                     return Poll::Ready(Some(Ok(decompress_buf)))
                 }
@@ -586,6 +652,12 @@ where
         }
 
         let this = self.project();
+        record_capture(
+            this.capture,
+            CaptureDirection::Outbound,
+            CaptureProtocol::Subprotocol,
+            &item,
+        );
 
         let mut compressed = BytesMut::zeroed(1 + snap::raw::max_compress_len(item.len() - 1));
         let compressed_size =
@@ -1,4 +1,4 @@
-//! Helpers for resolving the external IP.
+//! Helpers for resolving the external IP and mapping ports on the gateway.
 //!
 //! ## Feature Flags
 //!
@@ -12,6 +12,15 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+mod mapping;
+pub mod pmp;
+pub mod upnp;
+
+pub use mapping::{
+    GatewayConfig, PortMapping, PortMappingConfig, PortMappingError, PortMappingHandle,
+    PortMappingService, Protocol, DEFAULT_MAPPING_DESCRIPTION, DEFAULT_MAPPING_LEASE,
+};
+
 use std::{
     fmt,
     future::{poll_fn, Future},
@@ -38,8 +47,10 @@ pub enum NatResolver {
     /// Resolve with any available resolver.
     #[default]
     Any,
-    /// Resolve external IP via `UPnP`.
+    /// Resolve external IP and map ports via `UPnP`.
     Upnp,
+    /// Resolve external IP and map ports via NAT-PMP or PCP.
+    NatPmp,
     /// Resolve external IP via a network request.
     PublicIp,
     /// Use the given [`IpAddr`]
@@ -60,6 +71,7 @@ impl fmt::Display for NatResolver {
         match self {
             Self::Any => f.write_str("any"),
             Self::Upnp => f.write_str("upnp"),
+            Self::NatPmp => f.write_str("natpmp"),
             Self::PublicIp => f.write_str("publicip"),
             Self::ExternalIp(ip) => write!(f, "extip:{ip}"),
             Self::None => f.write_str("none"),
@@ -85,6 +97,7 @@ impl FromStr for NatResolver {
         let r = match s {
             "any" => Self::Any,
             "upnp" => Self::Upnp,
+            "natpmp" | "pcp" => Self::NatPmp,
             "none" => Self::None,
             "publicip" | "public-ip" => Self::PublicIp,
             s => {
@@ -179,7 +192,16 @@ pub async fn external_ip() -> Option<IpAddr> {
 /// Given a [`NatResolver`] attempts to produce an IP address (best effort).
 pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
     match resolver {
-        NatResolver::Any | NatResolver::Upnp | NatResolver::PublicIp => resolve_external_ip().await,
+        NatResolver::Any | NatResolver::PublicIp => resolve_external_ip().await,
+        NatResolver::Upnp | NatResolver::NatPmp => {
+            // ask the gateway first, it knows its external address
+            if let Some(config) = PortMappingConfig::from_resolver(resolver) {
+                if let Some(ip) = mapping::gateway_external_ip(&config.gateway).await {
+                    return Some(ip)
+                }
+            }
+            resolve_external_ip().await
+        }
         NatResolver::ExternalIp(ip) => Some(ip),
         NatResolver::None => None,
     }
@@ -230,6 +252,8 @@ mod tests {
     fn test_from_str() {
         assert_eq!(NatResolver::Any, "any".parse().unwrap());
         assert_eq!(NatResolver::None, "none".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp, "natpmp".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp.to_string(), "natpmp");
 
         let ip = NatResolver::ExternalIp(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
         let s = "extip:0.0.0.0";
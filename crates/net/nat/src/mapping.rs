@@ -0,0 +1,377 @@
+//! Port mappings on the gateway, renewed before their lease expires.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+    time::Instant,
+};
+use tracing::{debug, trace, warn};
+use url::Url;
+
+use crate::{pmp::PmpGateway, upnp::UpnpGateway, NatResolver};
+
+/// Default lease of a port mapping.
+///
+/// NAT-PMP recommends two hours, mappings are renewed after half of it.
+pub const DEFAULT_MAPPING_LEASE: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// Default description of port mappings, shown in the router's interface.
+pub const DEFAULT_MAPPING_DESCRIPTION: &str = "reth";
+
+/// Delay before retrying after the gateway failed to map a port.
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long the gateway is searched for.
+const GATEWAY_SEARCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Transport protocol of a port mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    /// TCP, used for `RLPx`.
+    Tcp,
+    /// UDP, used for discovery.
+    Udp,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp => f.write_str("TCP"),
+            Self::Udp => f.write_str("UDP"),
+        }
+    }
+}
+
+/// A port mapping created on the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortMapping {
+    /// Transport protocol of the mapping.
+    pub protocol: Protocol,
+    /// Local port that is mapped.
+    pub internal_port: u16,
+    /// Address under which the local port is reachable from the outside.
+    pub external_addr: SocketAddr,
+    /// How long the gateway keeps the mapping, `Duration::ZERO` if it doesn't expire.
+    pub lease: Duration,
+}
+
+/// Errors when talking to a gateway.
+#[derive(Debug, thiserror::Error)]
+pub enum PortMappingError {
+    /// No gateway was found.
+    #[error("no gateway found")]
+    NoGateway,
+    /// The gateway didn't respond in time.
+    #[error("gateway request timed out")]
+    Timeout,
+    /// The gateway doesn't support the protocol version.
+    #[error("unsupported protocol version")]
+    UnsupportedVersion,
+    /// The gateway rejected the request.
+    #[error("gateway rejected request with result code {0}")]
+    Rejected(u16),
+    /// The gateway sent a response that can't be parsed.
+    #[error("invalid gateway response: {0}")]
+    InvalidResponse(&'static str),
+    /// Failed to send or receive a datagram.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Failed to send or receive a UPnP request.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+}
+
+/// The gateway to create port mappings on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GatewayConfig {
+    /// A UPnP Internet Gateway Device.
+    ///
+    /// Searched with SSDP, unless the URL of its device description is given.
+    Upnp(Option<Url>),
+    /// A gateway that supports NAT-PMP or PCP.
+    ///
+    /// This is the default gateway of the host, unless its address is given.
+    NatPmp(Option<SocketAddr>),
+}
+
+/// Configures the port mappings on the gateway.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortMappingConfig {
+    /// The gateway to create port mappings on.
+    pub gateway: GatewayConfig,
+    /// Requested lease of the port mappings.
+    pub lease: Duration,
+    /// Description of the port mappings.
+    pub description: String,
+}
+
+impl PortMappingConfig {
+    /// Creates a new config for the gateway with default lease and description.
+    pub fn new(gateway: GatewayConfig) -> Self {
+        Self {
+            gateway,
+            lease: DEFAULT_MAPPING_LEASE,
+            description: DEFAULT_MAPPING_DESCRIPTION.to_string(),
+        }
+    }
+
+    /// Returns the config for the resolver, if it creates port mappings.
+    ///
+    /// This is the case for [`NatResolver::Upnp`] and [`NatResolver::NatPmp`].
+    pub fn from_resolver(resolver: NatResolver) -> Option<Self> {
+        match resolver {
+            NatResolver::Upnp => Some(Self::new(GatewayConfig::Upnp(None))),
+            NatResolver::NatPmp => Some(Self::new(GatewayConfig::NatPmp(None))),
+            _ => None,
+        }
+    }
+
+    /// Sets the requested lease of the port mappings.
+    pub const fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Sets the description of the port mappings.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+}
+
+/// A connected gateway.
+#[derive(Debug)]
+enum Gateway {
+    Upnp(UpnpGateway),
+    Pmp(PmpGateway),
+}
+
+impl Gateway {
+    async fn connect(config: &GatewayConfig) -> Result<Self, PortMappingError> {
+        match config {
+            GatewayConfig::Upnp(Some(location)) => {
+                Ok(Self::Upnp(UpnpGateway::from_location(location.clone()).await?))
+            }
+            GatewayConfig::Upnp(None) => {
+                Ok(Self::Upnp(UpnpGateway::search(GATEWAY_SEARCH_TIMEOUT).await?))
+            }
+            GatewayConfig::NatPmp(addr) => {
+                let addr = addr.or_else(PmpGateway::default_gateway_addr);
+                Ok(Self::Pmp(PmpGateway::new(addr.ok_or(PortMappingError::NoGateway)?)))
+            }
+        }
+    }
+
+    async fn map(
+        &mut self,
+        protocol: Protocol,
+        internal_port: u16,
+        lease: Duration,
+        description: &str,
+    ) -> Result<PortMapping, PortMappingError> {
+        match self {
+            Self::Upnp(gateway) => gateway.map(protocol, internal_port, lease, description).await,
+            Self::Pmp(gateway) => gateway.map(protocol, internal_port, lease).await,
+        }
+    }
+
+    async fn unmap(&mut self, mapping: &PortMapping) -> Result<(), PortMappingError> {
+        match self {
+            Self::Upnp(gateway) => {
+                gateway.unmap(mapping.protocol, mapping.external_addr.port()).await
+            }
+            Self::Pmp(gateway) => gateway.unmap(mapping.protocol, mapping.internal_port).await,
+        }
+    }
+}
+
+/// Handle to a spawned [`PortMappingService`].
+///
+/// Dropping the handle stops the service, which then removes its port mappings.
+#[derive(Debug)]
+pub struct PortMappingHandle {
+    shutdown: oneshot::Sender<oneshot::Sender<()>>,
+}
+
+impl PortMappingHandle {
+    /// Stops the service and waits until it removed its port mappings.
+    pub async fn shutdown(self) {
+        let (tx, rx) = oneshot::channel();
+        if self.shutdown.send(tx).is_ok() {
+            let _ = rx.await;
+        }
+    }
+}
+
+/// Maps local ports on the gateway and renews the mappings before their lease expires.
+///
+/// The mappings are removed when the [`PortMappingHandle`] is dropped or shut down. Every time the
+/// external address of a mapping changes, the new [`PortMapping`] is sent to the listener
+/// returned by [`PortMappingService::updates`].
+#[derive(Debug)]
+#[must_use = "Does nothing unless spawned or run"]
+pub struct PortMappingService {
+    config: PortMappingConfig,
+    /// Local ports to map.
+    ports: Vec<(Protocol, u16)>,
+    /// The gateway, connected on first use and after failures.
+    gateway: Option<Gateway>,
+    /// Active mappings by protocol and local port.
+    mappings: HashMap<(Protocol, u16), PortMapping>,
+    /// Listener for changed mappings.
+    updates: Option<mpsc::UnboundedSender<PortMapping>>,
+    shutdown: Option<oneshot::Receiver<oneshot::Sender<()>>>,
+}
+
+impl PortMappingService {
+    /// Creates a new service that maps the given local ports, and a handle to stop it.
+    pub fn new(
+        config: PortMappingConfig,
+        ports: impl IntoIterator<Item = (Protocol, u16)>,
+    ) -> (Self, PortMappingHandle) {
+        let (tx, rx) = oneshot::channel();
+        let service = Self {
+            config,
+            ports: ports.into_iter().collect(),
+            gateway: None,
+            mappings: Default::default(),
+            updates: None,
+            shutdown: Some(rx),
+        };
+        (service, PortMappingHandle { shutdown: tx })
+    }
+
+    /// Returns a receiver for mappings whose external address changed.
+    ///
+    /// This replaces any previously returned receiver.
+    pub fn updates(&mut self) -> mpsc::UnboundedReceiver<PortMapping> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.updates = Some(tx);
+        rx
+    }
+
+    /// Spawns the service onto a new task.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(self.run())
+    }
+
+    /// Maps the ports and renews them until shut down, then removes the mappings.
+    pub async fn run(mut self) {
+        let Some(mut shutdown) = self.shutdown.take() else { return };
+        let mut next_renewal = Instant::now();
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(next_renewal) => {
+                    next_renewal = Instant::now() + self.map_ports().await;
+                }
+                res = &mut shutdown => {
+                    self.unmap_ports().await;
+                    if let Ok(ack) = res {
+                        let _ = ack.send(());
+                    }
+                    return
+                }
+            }
+        }
+    }
+
+    /// Creates or renews all mappings and returns the delay until the next renewal.
+    async fn map_ports(&mut self) -> Duration {
+        let gateway = match self.gateway.as_mut() {
+            Some(gateway) => gateway,
+            None => match Gateway::connect(&self.config.gateway).await {
+                Ok(gateway) => self.gateway.insert(gateway),
+                Err(err) => {
+                    warn!(target: "net::nat", %err, "Failed to find gateway for port mapping");
+                    return RETRY_INTERVAL
+                }
+            },
+        };
+
+        let mut next_renewal = self.config.lease / 2;
+        for &(protocol, port) in &self.ports {
+            match gateway.map(protocol, port, self.config.lease, &self.config.description).await {
+                Ok(mapping) => {
+                    trace!(target: "net::nat", ?mapping, "Mapped port");
+                    if !mapping.lease.is_zero() {
+                        next_renewal = next_renewal.min(mapping.lease / 2);
+                    }
+                    let previous = self.mappings.insert((protocol, port), mapping);
+                    if previous.map(|m| m.external_addr) != Some(mapping.external_addr) {
+                        debug!(target: "net::nat", ?mapping, "New port mapping");
+                        if let Some(updates) = &self.updates {
+                            let _ = updates.send(mapping);
+                        }
+                    }
+                }
+                Err(err) => {
+                    warn!(target: "net::nat", %err, %protocol, %port, "Failed to map port");
+                    // the gateway may have changed, connect again on retry
+                    self.gateway = None;
+                    return RETRY_INTERVAL.min(next_renewal)
+                }
+            }
+        }
+
+        next_renewal
+    }
+
+    /// Removes all mappings from the gateway.
+    async fn unmap_ports(&mut self) {
+        let Some(gateway) = self.gateway.as_mut() else { return };
+        for (_, mapping) in self.mappings.drain() {
+            if let Err(err) = gateway.unmap(&mapping).await {
+                debug!(target: "net::nat", %err, ?mapping, "Failed to remove port mapping");
+            }
+        }
+    }
+}
+
+/// Returns the external IP address reported by the gateway.
+pub(crate) async fn gateway_external_ip(config: &GatewayConfig) -> Option<IpAddr> {
+    match Gateway::connect(config).await.ok()? {
+        Gateway::Upnp(gateway) => gateway.external_ip().await.ok(),
+        Gateway::Pmp(mut gateway) => gateway.external_ip().await.ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pmp::tests::MockPmpGateway;
+
+    #[tokio::test]
+    async fn renews_and_removes_mappings() {
+        let gateway = MockPmpGateway::spawn(false).await;
+        let config = PortMappingConfig::new(GatewayConfig::NatPmp(Some(gateway.addr)))
+            .with_lease(Duration::from_secs(2));
+        let (mut service, handle) =
+            PortMappingService::new(config, [(Protocol::Tcp, 30303), (Protocol::Udp, 30303)]);
+        let mut updates = service.updates();
+        service.spawn();
+
+        let tcp = updates.recv().await.unwrap();
+        assert_eq!(tcp.protocol, Protocol::Tcp);
+        assert_eq!(tcp.external_addr, SocketAddr::new(gateway.external_ip, 40303));
+        assert_eq!(tcp.lease, Duration::from_secs(2));
+        let udp = updates.recv().await.unwrap();
+        assert_eq!(udp.protocol, Protocol::Udp);
+        assert_eq!(gateway.mapped(), 2);
+
+        // renewed after half the lease, without an update since the address didn't change
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert_eq!(gateway.map_requests(), 4);
+        assert!(updates.try_recv().is_err());
+
+        handle.shutdown().await;
+        assert_eq!(gateway.mapped(), 0);
+    }
+}
@@ -0,0 +1,450 @@
+//! Port mapping with [NAT-PMP](https://www.rfc-editor.org/rfc/rfc6886) and its successor
+//! [PCP](https://www.rfc-editor.org/rfc/rfc6887).
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use tokio::{net::UdpSocket, time::Instant};
+
+use crate::mapping::{PortMapping, PortMappingError, Protocol};
+
+/// Port the gateway listens on for NAT-PMP and PCP requests.
+pub const GATEWAY_PORT: u16 = 5351;
+
+const NAT_PMP_VERSION: u8 = 0;
+const PCP_VERSION: u8 = 2;
+
+/// Set in the opcode of responses.
+const RESPONSE_BIT: u8 = 0x80;
+
+const NAT_PMP_OP_EXTERNAL_ADDRESS: u8 = 0;
+const NAT_PMP_OP_MAP_UDP: u8 = 1;
+const NAT_PMP_OP_MAP_TCP: u8 = 2;
+const PCP_OP_MAP: u8 = 1;
+
+/// Result code for a request with an unsupported version, in both protocols.
+const RESULT_UNSUPPORTED_VERSION: u16 = 1;
+
+/// Size of a PCP `MAP` request and response.
+const PCP_MAP_SIZE: usize = 60;
+
+/// Timeout of the first attempt, doubled for every retry.
+const INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Number of attempts before a request times out.
+const MAX_ATTEMPTS: usize = 4;
+
+/// The protocol spoken by the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Version {
+    NatPmp,
+    Pcp,
+}
+
+/// A gateway that supports NAT-PMP or PCP.
+///
+/// PCP is preferred, the gateway falls back to NAT-PMP if it only supports that.
+#[derive(Debug)]
+pub struct PmpGateway {
+    addr: SocketAddr,
+    /// The detected protocol of the gateway.
+    version: Option<Version>,
+    /// Identifies the PCP mappings of this client, the same nonce must be used for renewals.
+    nonce: [u8; 12],
+}
+
+impl PmpGateway {
+    /// Creates a new gateway at the given address.
+    pub fn new(addr: SocketAddr) -> Self {
+        Self { addr, version: None, nonce: rand::random() }
+    }
+
+    /// Returns the address of the host's default gateway.
+    ///
+    /// This reads the routing table from `/proc/net/route`, so it is only supported on Linux.
+    pub fn default_gateway_addr() -> Option<SocketAddr> {
+        let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+        routes.lines().skip(1).find_map(|route| {
+            let mut fields = route.split_whitespace().skip(1);
+            let (destination, gateway) = (fields.next()?, fields.next()?);
+            if destination != "00000000" {
+                return None
+            }
+            // the address is in host byte order
+            let gateway = u32::from_str_radix(gateway, 16).ok()?;
+            Some(SocketAddr::new(Ipv4Addr::from(gateway.to_le_bytes()).into(), GATEWAY_PORT))
+        })
+    }
+
+    /// Returns the external IP address of the gateway.
+    pub async fn external_ip(&mut self) -> Result<IpAddr, PortMappingError> {
+        let socket = self.connect().await?;
+        let response = request(&socket, &[NAT_PMP_VERSION, NAT_PMP_OP_EXTERNAL_ADDRESS], |res| {
+            res.len() >= 12 && res[1] == RESPONSE_BIT | NAT_PMP_OP_EXTERNAL_ADDRESS
+        })
+        .await?;
+        check_nat_pmp_result(&response)?;
+        Ok(Ipv4Addr::new(response[8], response[9], response[10], response[11]).into())
+    }
+
+    /// Maps the local port for the requested lease.
+    pub async fn map(
+        &mut self,
+        protocol: Protocol,
+        internal_port: u16,
+        lease: Duration,
+    ) -> Result<PortMapping, PortMappingError> {
+        let socket = self.connect().await?;
+        let lifetime = lease.as_secs().try_into().unwrap_or(u32::MAX);
+
+        if self.version != Some(Version::NatPmp) {
+            match self.map_pcp(&socket, protocol, internal_port, internal_port, lifetime).await {
+                Ok(mapping) => {
+                    self.version = Some(Version::Pcp);
+                    return Ok(mapping)
+                }
+                Err(PortMappingError::UnsupportedVersion) if self.version.is_none() => {
+                    self.version = Some(Version::NatPmp);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let (external_port, lifetime) =
+            map_nat_pmp(&socket, protocol, internal_port, internal_port, lifetime).await?;
+        // NAT-PMP doesn't include the external address in the response
+        let external_ip = self.external_ip().await?;
+
+        Ok(PortMapping {
+            protocol,
+            internal_port,
+            external_addr: SocketAddr::new(external_ip, external_port),
+            lease: Duration::from_secs(lifetime.into()),
+        })
+    }
+
+    /// Removes the mapping of the local port.
+    pub async fn unmap(
+        &mut self,
+        protocol: Protocol,
+        internal_port: u16,
+    ) -> Result<(), PortMappingError> {
+        let socket = self.connect().await?;
+        match self.version {
+            Some(Version::Pcp) => {
+                self.map_pcp(&socket, protocol, internal_port, 0, 0).await?;
+            }
+            _ => {
+                map_nat_pmp(&socket, protocol, internal_port, 0, 0).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends a PCP `MAP` request, a lifetime of zero removes the mapping.
+    async fn map_pcp(
+        &self,
+        socket: &UdpSocket,
+        protocol: Protocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime: u32,
+    ) -> Result<PortMapping, PortMappingError> {
+        let client_ip = socket.local_addr()?.ip();
+        // the unspecified address of the client's family lets the gateway pick the external one
+        let suggested_external_ip = match client_ip {
+            IpAddr::V4(_) => Ipv4Addr::UNSPECIFIED.to_ipv6_mapped(),
+            IpAddr::V6(_) => Ipv6Addr::UNSPECIFIED,
+        };
+
+        let mut req = [0u8; PCP_MAP_SIZE];
+        req[0] = PCP_VERSION;
+        req[1] = PCP_OP_MAP;
+        req[4..8].copy_from_slice(&lifetime.to_be_bytes());
+        req[8..24].copy_from_slice(&to_ipv6(client_ip).octets());
+        req[24..36].copy_from_slice(&self.nonce);
+        req[36] = pcp_protocol_number(protocol);
+        req[40..42].copy_from_slice(&internal_port.to_be_bytes());
+        req[42..44].copy_from_slice(&external_port.to_be_bytes());
+        req[44..60].copy_from_slice(&suggested_external_ip.octets());
+
+        let res = request(socket, &req, |res| {
+            // NAT-PMP gateways respond to unsupported versions with a NAT-PMP response
+            res.len() >= 4 && res[0] == NAT_PMP_VERSION ||
+                res.len() >= PCP_MAP_SIZE &&
+                    res[0] == PCP_VERSION &&
+                    res[1] == RESPONSE_BIT | PCP_OP_MAP &&
+                    res[24..36] == self.nonce
+        })
+        .await?;
+
+        if res[0] == NAT_PMP_VERSION {
+            return Err(PortMappingError::UnsupportedVersion)
+        }
+        match u16::from(res[3]) {
+            0 => {}
+            RESULT_UNSUPPORTED_VERSION => return Err(PortMappingError::UnsupportedVersion),
+            code => return Err(PortMappingError::Rejected(code)),
+        }
+
+        let lifetime = u32::from_be_bytes([res[4], res[5], res[6], res[7]]);
+        let external_port = u16::from_be_bytes([res[42], res[43]]);
+        let external_ip = Ipv6Addr::from(<[u8; 16]>::try_from(&res[44..60]).expect("16 bytes"));
+        let external_ip =
+            external_ip.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(external_ip));
+
+        Ok(PortMapping {
+            protocol,
+            internal_port,
+            external_addr: SocketAddr::new(external_ip, external_port),
+            lease: Duration::from_secs(lifetime.into()),
+        })
+    }
+
+    /// Returns a new socket connected to the gateway.
+    async fn connect(&self) -> Result<UdpSocket, PortMappingError> {
+        let local_addr: SocketAddr = match self.addr {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        let socket = UdpSocket::bind(local_addr).await?;
+        socket.connect(self.addr).await?;
+        Ok(socket)
+    }
+}
+
+/// Sends a NAT-PMP mapping request and returns the mapped external port and lifetime, a lifetime
+/// of zero removes the mapping.
+async fn map_nat_pmp(
+    socket: &UdpSocket,
+    protocol: Protocol,
+    internal_port: u16,
+    external_port: u16,
+    lifetime: u32,
+) -> Result<(u16, u32), PortMappingError> {
+    let op = match protocol {
+        Protocol::Tcp => NAT_PMP_OP_MAP_TCP,
+        Protocol::Udp => NAT_PMP_OP_MAP_UDP,
+    };
+
+    let mut req = [0u8; 12];
+    req[0] = NAT_PMP_VERSION;
+    req[1] = op;
+    req[4..6].copy_from_slice(&internal_port.to_be_bytes());
+    req[6..8].copy_from_slice(&external_port.to_be_bytes());
+    req[8..12].copy_from_slice(&lifetime.to_be_bytes());
+
+    let res = request(socket, &req, |res| {
+        res.len() >= 16 && res[1] == RESPONSE_BIT | op && res[8..10] == req[4..6]
+    })
+    .await?;
+    check_nat_pmp_result(&res)?;
+
+    let external_port = u16::from_be_bytes([res[10], res[11]]);
+    let lifetime = u32::from_be_bytes([res[12], res[13], res[14], res[15]]);
+    Ok((external_port, lifetime))
+}
+
+/// Sends the request to the gateway until it responds, and returns the first datagram accepted by
+/// `is_response`.
+async fn request(
+    socket: &UdpSocket,
+    req: &[u8],
+    is_response: impl Fn(&[u8]) -> bool,
+) -> Result<Vec<u8>, PortMappingError> {
+    let mut buf = [0u8; 1100];
+    let mut timeout = INITIAL_TIMEOUT;
+    for _ in 0..MAX_ATTEMPTS {
+        socket.send(req).await?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            match tokio::time::timeout_at(deadline, socket.recv(&mut buf)).await {
+                Ok(Ok(len)) if is_response(&buf[..len]) => return Ok(buf[..len].to_vec()),
+                Ok(Ok(_)) => continue,
+                Ok(Err(err)) => return Err(err.into()),
+                Err(_) => break,
+            }
+        }
+        timeout *= 2;
+    }
+    Err(PortMappingError::Timeout)
+}
+
+fn check_nat_pmp_result(res: &[u8]) -> Result<(), PortMappingError> {
+    match u16::from_be_bytes([res[2], res[3]]) {
+        0 => Ok(()),
+        RESULT_UNSUPPORTED_VERSION => Err(PortMappingError::UnsupportedVersion),
+        code => Err(PortMappingError::Rejected(code)),
+    }
+}
+
+const fn pcp_protocol_number(protocol: Protocol) -> u8 {
+    match protocol {
+        Protocol::Tcp => 6,
+        Protocol::Udp => 17,
+    }
+}
+
+/// PCP represents IPv4 addresses as IPv4-mapped IPv6 addresses.
+const fn to_ipv6(ip: IpAddr) -> Ipv6Addr {
+    match ip {
+        IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+        IpAddr::V6(ip) => ip,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    /// A gateway that maps every port to the port `10000` above it.
+    #[derive(Debug)]
+    pub(crate) struct MockPmpGateway {
+        pub(crate) addr: SocketAddr,
+        pub(crate) external_ip: IpAddr,
+        state: Arc<Mutex<MockState>>,
+    }
+
+    #[derive(Debug, Default)]
+    struct MockState {
+        /// Lifetimes of the mappings by protocol number and internal port.
+        mappings: HashMap<(u8, u16), u32>,
+        map_requests: usize,
+    }
+
+    impl MockPmpGateway {
+        /// Spawns a gateway that supports NAT-PMP, and PCP if `pcp` is set.
+        pub(crate) async fn spawn(pcp: bool) -> Self {
+            let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+            let gateway = Self {
+                addr: socket.local_addr().unwrap(),
+                external_ip: Ipv4Addr::new(203, 0, 113, 7).into(),
+                state: Default::default(),
+            };
+
+            let state = gateway.state.clone();
+            let external_ip = gateway.external_ip;
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1100];
+                while let Ok((len, from)) = socket.recv_from(&mut buf).await {
+                    let res = respond(&buf[..len], pcp, external_ip, &mut state.lock().unwrap());
+                    let _ = socket.send_to(&res, from).await;
+                }
+            });
+
+            gateway
+        }
+
+        pub(crate) fn mapped(&self) -> usize {
+            self.state.lock().unwrap().mappings.len()
+        }
+
+        pub(crate) fn map_requests(&self) -> usize {
+            self.state.lock().unwrap().map_requests
+        }
+    }
+
+    fn respond(req: &[u8], pcp: bool, external_ip: IpAddr, state: &mut MockState) -> Vec<u8> {
+        let IpAddr::V4(external_ipv4) = external_ip else { unreachable!() };
+
+        if req[0] == PCP_VERSION && pcp {
+            let lifetime = u32::from_be_bytes(req[4..8].try_into().unwrap());
+            let internal_port = u16::from_be_bytes([req[40], req[41]]);
+            state.apply(req[36], internal_port, lifetime);
+
+            let mut res = req.to_vec();
+            res[1] = RESPONSE_BIT | PCP_OP_MAP;
+            res[8..24].fill(0);
+            res[42..44].copy_from_slice(&(internal_port + 10000).to_be_bytes());
+            res[44..60].copy_from_slice(&external_ipv4.to_ipv6_mapped().octets());
+            return res
+        }
+
+        let mut res = vec![NAT_PMP_VERSION, RESPONSE_BIT | req[1], 0, 0, 0, 0, 0, 0];
+        if req[0] != NAT_PMP_VERSION {
+            res[3] = RESULT_UNSUPPORTED_VERSION as u8;
+            return res
+        }
+
+        match req[1] {
+            NAT_PMP_OP_EXTERNAL_ADDRESS => res.extend_from_slice(&external_ipv4.octets()),
+            op => {
+                let internal_port = u16::from_be_bytes([req[4], req[5]]);
+                let lifetime = u32::from_be_bytes(req[8..12].try_into().unwrap());
+                let protocol = if op == NAT_PMP_OP_MAP_TCP { 6 } else { 17 };
+                state.apply(protocol, internal_port, lifetime);
+
+                res.extend_from_slice(&req[4..6]);
+                res.extend_from_slice(&(internal_port + 10000).to_be_bytes());
+                res.extend_from_slice(&lifetime.to_be_bytes());
+            }
+        }
+        res
+    }
+
+    impl MockState {
+        fn apply(&mut self, protocol: u8, internal_port: u16, lifetime: u32) {
+            if lifetime == 0 {
+                self.mappings.remove(&(protocol, internal_port));
+            } else {
+                self.map_requests += 1;
+                self.mappings.insert((protocol, internal_port), lifetime);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn map_with_pcp() {
+        let mock = MockPmpGateway::spawn(true).await;
+        let mut gateway = PmpGateway::new(mock.addr);
+
+        let mapping = gateway.map(Protocol::Udp, 30303, Duration::from_secs(60)).await.unwrap();
+        assert_eq!(gateway.version, Some(Version::Pcp));
+        assert_eq!(
+            mapping,
+            PortMapping {
+                protocol: Protocol::Udp,
+                internal_port: 30303,
+                external_addr: SocketAddr::new(mock.external_ip, 40303),
+                lease: Duration::from_secs(60),
+            }
+        );
+        assert_eq!(mock.mapped(), 1);
+
+        gateway.unmap(Protocol::Udp, 30303).await.unwrap();
+        assert_eq!(mock.mapped(), 0);
+    }
+
+    #[tokio::test]
+    async fn map_falls_back_to_nat_pmp() {
+        let mock = MockPmpGateway::spawn(false).await;
+        let mut gateway = PmpGateway::new(mock.addr);
+
+        assert_eq!(gateway.external_ip().await.unwrap(), mock.external_ip);
+
+        let mapping = gateway.map(Protocol::Tcp, 30303, Duration::from_secs(60)).await.unwrap();
+        assert_eq!(gateway.version, Some(Version::NatPmp));
+        assert_eq!(mapping.external_addr, SocketAddr::new(mock.external_ip, 40303));
+        assert_eq!(mapping.lease, Duration::from_secs(60));
+        assert_eq!(mock.mapped(), 1);
+
+        gateway.unmap(Protocol::Tcp, 30303).await.unwrap();
+        assert_eq!(mock.mapped(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn request_times_out() {
+        // nothing listens on the socket
+        let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let mut gateway = PmpGateway::new(socket.local_addr().unwrap());
+
+        let err = gateway.external_ip().await.unwrap_err();
+        assert!(matches!(err, PortMappingError::Timeout), "{err:?}");
+    }
+}
@@ -0,0 +1,451 @@
+//! Port mapping with a UPnP Internet Gateway Device.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+
+use tokio::{net::UdpSocket, time::Instant};
+use tracing::trace;
+use url::{Host, Url};
+
+use crate::mapping::{PortMapping, PortMappingError, Protocol};
+
+/// Multicast address of SSDP.
+const SSDP_ADDR: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900);
+
+/// The device to search for with SSDP.
+const SEARCH_TARGET: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+
+/// Services that can map ports, in order of preference.
+const SERVICE_TYPES: &[&str] = &[
+    "urn:schemas-upnp-org:service:WANIPConnection:2",
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+
+/// Error code of gateways that only support mappings without a lease.
+const ONLY_PERMANENT_LEASES_SUPPORTED: u16 = 725;
+
+/// Timeout of HTTP requests to the gateway.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A UPnP Internet Gateway Device.
+#[derive(Debug, Clone)]
+pub struct UpnpGateway {
+    client: reqwest::Client,
+    /// URL of the port mapping service.
+    control_url: Url,
+    /// Type of the port mapping service.
+    service_type: &'static str,
+    /// Local IP address the gateway forwards mapped ports to.
+    local_ip: IpAddr,
+}
+
+impl UpnpGateway {
+    /// Searches the local network for a gateway with SSDP and connects to the first one that
+    /// responds.
+    pub async fn search(timeout: Duration) -> Result<Self, PortMappingError> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        let search = format!(
+            "M-SEARCH * HTTP/1.1\r\nHOST: {SSDP_ADDR}\r\nST: {SEARCH_TARGET}\r\n\
+             MAN: \"ssdp:discover\"\r\nMX: 2\r\n\r\n"
+        );
+        socket.send_to(search.as_bytes(), SSDP_ADDR).await?;
+
+        let deadline = Instant::now() + timeout;
+        let mut buf = [0u8; 2048];
+        while let Ok(res) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+            let (len, from) = res?;
+            let Some(location) = parse_location(&buf[..len]) else { continue };
+            match Self::from_location(location).await {
+                Ok(gateway) => return Ok(gateway),
+                Err(err) => trace!(target: "net::nat", %err, %from, "Skipping UPnP device"),
+            }
+        }
+
+        Err(PortMappingError::NoGateway)
+    }
+
+    /// Connects to the gateway with the device description at the given URL.
+    pub async fn from_location(location: Url) -> Result<Self, PortMappingError> {
+        let client = reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+        let description =
+            client.get(location.clone()).send().await?.error_for_status()?.text().await?;
+
+        let (service_type, control_url) = parse_control_url(&description)
+            .ok_or(PortMappingError::InvalidResponse("no port mapping service"))?;
+        let control_url = location
+            .join(control_url)
+            .map_err(|_| PortMappingError::InvalidResponse("invalid control URL"))?;
+        let local_ip = local_ip_towards(&control_url).await?;
+
+        Ok(Self { client, control_url, service_type, local_ip })
+    }
+
+    /// Returns the external IP address of the gateway.
+    pub async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        let res = self.soap_request("GetExternalIPAddress", &[]).await?;
+        xml_value(&res, "NewExternalIPAddress")
+            .and_then(|ip| ip.parse().ok())
+            .ok_or(PortMappingError::InvalidResponse("invalid external IP address"))
+    }
+
+    /// Maps the local port to the same external port for the requested lease.
+    ///
+    /// If the gateway only supports permanent mappings, the mapping is created without a lease.
+    pub async fn map(
+        &self,
+        protocol: Protocol,
+        internal_port: u16,
+        lease: Duration,
+        description: &str,
+    ) -> Result<PortMapping, PortMappingError> {
+        let lease = match self.add_port_mapping(protocol, internal_port, lease, description).await {
+            Ok(()) => lease,
+            Err(PortMappingError::Rejected(ONLY_PERMANENT_LEASES_SUPPORTED)) => {
+                self.add_port_mapping(protocol, internal_port, Duration::ZERO, description).await?;
+                Duration::ZERO
+            }
+            Err(err) => return Err(err),
+        };
+        let external_ip = self.external_ip().await?;
+
+        Ok(PortMapping {
+            protocol,
+            internal_port,
+            external_addr: SocketAddr::new(external_ip, internal_port),
+            lease,
+        })
+    }
+
+    /// Removes the mapping of the external port.
+    pub async fn unmap(
+        &self,
+        protocol: Protocol,
+        external_port: u16,
+    ) -> Result<(), PortMappingError> {
+        let args = [
+            ("NewRemoteHost", String::new()),
+            ("NewExternalPort", external_port.to_string()),
+            ("NewProtocol", protocol.to_string()),
+        ];
+        self.soap_request("DeletePortMapping", &args).await?;
+        Ok(())
+    }
+
+    async fn add_port_mapping(
+        &self,
+        protocol: Protocol,
+        port: u16,
+        lease: Duration,
+        description: &str,
+    ) -> Result<(), PortMappingError> {
+        let args = [
+            ("NewRemoteHost", String::new()),
+            ("NewExternalPort", port.to_string()),
+            ("NewProtocol", protocol.to_string()),
+            ("NewInternalPort", port.to_string()),
+            ("NewInternalClient", self.local_ip.to_string()),
+            ("NewEnabled", "1".to_string()),
+            ("NewPortMappingDescription", xml_escape(description)),
+            ("NewLeaseDuration", lease.as_secs().min(u32::MAX.into()).to_string()),
+        ];
+        self.soap_request("AddPortMapping", &args).await?;
+        Ok(())
+    }
+
+    /// Invokes the action on the port mapping service and returns the response body.
+    async fn soap_request(
+        &self,
+        action: &str,
+        args: &[(&str, String)],
+    ) -> Result<String, PortMappingError> {
+        let args: String =
+            args.iter().map(|(name, value)| format!("<{name}>{value}</{name}>")).collect();
+        let body = format!(
+            "<?xml version=\"1.0\"?>\
+             <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+             s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\"><s:Body>\
+             <u:{action} xmlns:u=\"{service}\">{args}</u:{action}>\
+             </s:Body></s:Envelope>",
+            service = self.service_type,
+        );
+
+        let res = self
+            .client
+            .post(self.control_url.clone())
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header("SOAPAction", format!("\"{}#{action}\"", self.service_type))
+            .body(body)
+            .send()
+            .await?;
+        let status = res.status();
+        let text = res.text().await?;
+        if status.is_success() {
+            return Ok(text)
+        }
+
+        let code = xml_value(&text, "errorCode")
+            .and_then(|code| code.parse().ok())
+            .ok_or(PortMappingError::InvalidResponse("SOAP fault without error code"))?;
+        Err(PortMappingError::Rejected(code))
+    }
+}
+
+/// Returns the local IP address that is used to reach the host of the URL.
+async fn local_ip_towards(url: &Url) -> Result<IpAddr, PortMappingError> {
+    let port = url.port_or_known_default().unwrap_or(80);
+    let remote: SocketAddr = match url.host() {
+        Some(Host::Ipv4(ip)) => (ip, port).into(),
+        Some(Host::Ipv6(ip)) => (ip, port).into(),
+        Some(Host::Domain(domain)) => tokio::net::lookup_host((domain, port))
+            .await?
+            .next()
+            .ok_or(PortMappingError::NoGateway)?,
+        None => return Err(PortMappingError::InvalidResponse("control URL without host")),
+    };
+
+    let local: SocketAddr = match remote {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (std::net::Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    // connecting a UDP socket doesn't send anything, but selects the route
+    let socket = UdpSocket::bind(local).await?;
+    socket.connect(remote).await?;
+    Ok(socket.local_addr()?.ip())
+}
+
+/// Returns the `LOCATION` header of an SSDP response.
+fn parse_location(res: &[u8]) -> Option<Url> {
+    let res = std::str::from_utf8(res).ok()?;
+    res.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if !name.trim().eq_ignore_ascii_case("location") {
+            return None
+        }
+        Url::parse(value.trim()).ok()
+    })
+}
+
+/// Returns the type and control URL of the preferred port mapping service in the device
+/// description.
+fn parse_control_url(description: &str) -> Option<(&'static str, &str)> {
+    SERVICE_TYPES.iter().find_map(|&service_type| {
+        description.split("<service>").skip(1).find_map(|service| {
+            if xml_value(service, "serviceType")? != service_type {
+                return None
+            }
+            Some((service_type, xml_value(service, "controlURL")?))
+        })
+    })
+}
+
+/// Returns the text of the first element with the given name.
+fn xml_value<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start_tag = format!("<{name}>");
+    let start = xml.find(&start_tag)? + start_tag.len();
+    let end = start + xml[start..].find("</")?;
+    Some(xml[start..end].trim())
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    const DESCRIPTION: &str = r#"<?xml version="1.0"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:InternetGatewayDevice:1</deviceType>
+    <deviceList><device>
+      <deviceType>urn:schemas-upnp-org:device:WANDevice:1</deviceType>
+      <deviceList><device>
+        <deviceType>urn:schemas-upnp-org:device:WANConnectionDevice:1</deviceType>
+        <serviceList>
+          <service>
+            <serviceType>urn:schemas-upnp-org:service:WANCommonInterfaceConfig:1</serviceType>
+            <controlURL>/ctl/CmnIfCfg</controlURL>
+          </service>
+          <service>
+            <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+            <controlURL>/ctl/IPConn</controlURL>
+          </service>
+        </serviceList>
+      </device></deviceList>
+    </device></deviceList>
+  </device>
+</root>"#;
+
+    /// A gateway that serves the device description and the port mapping service over HTTP.
+    #[derive(Debug)]
+    struct MockUpnpGateway {
+        location: Url,
+        state: Arc<Mutex<MockState>>,
+    }
+
+    #[derive(Debug, Default)]
+    struct MockState {
+        only_permanent_leases: bool,
+        /// Leases of the mappings by protocol and external port.
+        mappings: HashMap<(String, u16), u64>,
+    }
+
+    impl MockUpnpGateway {
+        async fn spawn(only_permanent_leases: bool) -> Self {
+            let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let state =
+                Arc::new(Mutex::new(MockState { only_permanent_leases, ..Default::default() }));
+
+            let server_state = state.clone();
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(serve(stream, server_state.clone()));
+                }
+            });
+
+            let location = format!("http://{addr}/rootDesc.xml").parse().unwrap();
+            Self { location, state }
+        }
+
+        fn lease(&self, protocol: &str, port: u16) -> Option<u64> {
+            self.state.lock().unwrap().mappings.get(&(protocol.to_string(), port)).copied()
+        }
+    }
+
+    async fn serve(mut stream: TcpStream, state: Arc<Mutex<MockState>>) {
+        let mut req = Vec::new();
+        let mut buf = [0u8; 1024];
+        let (head, body) = loop {
+            let len = stream.read(&mut buf).await.unwrap();
+            req.extend_from_slice(&buf[..len]);
+            let text = String::from_utf8_lossy(&req).to_string();
+            let Some((head, body)) = text.split_once("\r\n\r\n") else { continue };
+            let content_length = head
+                .lines()
+                .find_map(|line| {
+                    line.to_lowercase().strip_prefix("content-length:")?.trim().parse().ok()
+                })
+                .unwrap_or(0);
+            if body.len() >= content_length {
+                break (head.to_string(), body.to_string())
+            }
+        };
+
+        let (status, body) = if head.starts_with("GET /rootDesc.xml") {
+            ("200 OK", DESCRIPTION.to_string())
+        } else {
+            respond(&head, &body, &mut state.lock().unwrap())
+        };
+        let res = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: text/xml\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(res.as_bytes()).await.unwrap();
+    }
+
+    fn respond(head: &str, body: &str, state: &mut MockState) -> (&'static str, String) {
+        let action = head
+            .lines()
+            .find_map(|line| line.to_lowercase().strip_prefix("soapaction:").map(str::to_string))
+            .and_then(|action| Some(action.split_once('#')?.1.trim_matches('"').to_string()))
+            .unwrap();
+        let arg = |name| xml_value(body, name).unwrap().to_string();
+
+        let value = match action.as_str() {
+            "getexternalipaddress" => {
+                "<NewExternalIPAddress>203.0.113.7</NewExternalIPAddress>".to_string()
+            }
+            "addportmapping" => {
+                let lease: u64 = arg("NewLeaseDuration").parse().unwrap();
+                if lease != 0 && state.only_permanent_leases {
+                    let fault = "<s:Envelope><s:Body><s:Fault><detail>\
+                         <UPnPError><errorCode>725</errorCode></UPnPError>\
+                         </detail></s:Fault></s:Body></s:Envelope>";
+                    return ("500 Internal Server Error", fault.to_string())
+                }
+                assert_eq!(arg("NewInternalClient"), "127.0.0.1");
+                let port = arg("NewExternalPort").parse().unwrap();
+                state.mappings.insert((arg("NewProtocol"), port), lease);
+                String::new()
+            }
+            "deleteportmapping" => {
+                let port = arg("NewExternalPort").parse().unwrap();
+                state.mappings.remove(&(arg("NewProtocol"), port));
+                String::new()
+            }
+            action => panic!("unexpected action {action}"),
+        };
+        (
+            "200 OK",
+            format!("<s:Envelope><s:Body><u:Response>{value}</u:Response></s:Body></s:Envelope>"),
+        )
+    }
+
+    #[test]
+    fn parse_ssdp_response() {
+        let res = b"HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\n\
+            ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\
+            Location: http://192.168.1.1:5000/rootDesc.xml\r\n\r\n";
+        assert_eq!(
+            parse_location(res),
+            Some("http://192.168.1.1:5000/rootDesc.xml".parse().unwrap())
+        );
+        assert_eq!(parse_location(b"HTTP/1.1 200 OK\r\n\r\n"), None);
+    }
+
+    #[test]
+    fn parse_description() {
+        assert_eq!(
+            parse_control_url(DESCRIPTION),
+            Some(("urn:schemas-upnp-org:service:WANIPConnection:1", "/ctl/IPConn"))
+        );
+    }
+
+    #[tokio::test]
+    async fn map_with_upnp() {
+        let mock = MockUpnpGateway::spawn(false).await;
+        let gateway = UpnpGateway::from_location(mock.location.clone()).await.unwrap();
+        assert_eq!(gateway.control_url.path(), "/ctl/IPConn");
+
+        let mapping =
+            gateway.map(Protocol::Tcp, 30303, Duration::from_secs(3600), "reth").await.unwrap();
+        assert_eq!(
+            mapping,
+            PortMapping {
+                protocol: Protocol::Tcp,
+                internal_port: 30303,
+                external_addr: "203.0.113.7:30303".parse().unwrap(),
+                lease: Duration::from_secs(3600),
+            }
+        );
+        assert_eq!(mock.lease("TCP", 30303), Some(3600));
+
+        gateway.unmap(Protocol::Tcp, 30303).await.unwrap();
+        assert_eq!(mock.lease("TCP", 30303), None);
+    }
+
+    #[tokio::test]
+    async fn map_permanent_lease() {
+        let mock = MockUpnpGateway::spawn(true).await;
+        let gateway = UpnpGateway::from_location(mock.location.clone()).await.unwrap();
+
+        let mapping =
+            gateway.map(Protocol::Udp, 30303, Duration::from_secs(3600), "reth").await.unwrap();
+        assert_eq!(mapping.lease, Duration::ZERO);
+        assert_eq!(mock.lease("UDP", 30303), Some(0));
+    }
+}
@@ -7,6 +7,9 @@ pub enum NetworkError {
     /// Indicates that the sender has been dropped.
     #[error("sender has been dropped")]
     ChannelClosed,
+    /// Indicates that no peers config file is configured or that writing it failed.
+    #[error("peers config file: {0}")]
+    PeersConfigFile(String),
 }
 
 impl<T> From<mpsc::error::SendError<T>> for NetworkError {
@@ -5,12 +5,12 @@ use std::{fmt, net::SocketAddr, sync::Arc};
 use reth_eth_wire_types::{
     message::RequestPair, BlockBodies, BlockHeaders, Capabilities, DisconnectReason, EthMessage,
     EthVersion, GetBlockBodies, GetBlockHeaders, GetNodeData, GetPooledTransactions, GetReceipts,
-    NodeData, PooledTransactions, Receipts, Status,
+    NodeData, PooledTransactions, Receipts, Receipts69, StatusMessage,
 };
 use reth_ethereum_forks::ForkId;
 use reth_network_p2p::error::{RequestError, RequestResult};
 use reth_network_peers::PeerId;
-use reth_network_types::PeerAddr;
+use reth_network_types::{PeerAddr, PeerKind};
 use reth_tokio_util::EventStream;
 use tokio::sync::{mpsc, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
@@ -52,9 +52,11 @@ pub enum NetworkEvent {
         /// A request channel to the session task.
         messages: PeerRequestSender,
         /// The status of the peer to which a session was established.
-        status: Arc<Status>,
+        status: Arc<StatusMessage>,
         /// negotiated eth version of the session
         version: EthVersion,
+        /// The kind of the peer to which a session was established.
+        peer_kind: PeerKind,
     },
     /// Event emitted when a new peer is added
     PeerAdded(PeerId),
@@ -144,6 +146,15 @@ pub enum PeerRequest {
         /// The channel to send the response for receipts.
         response: oneshot::Sender<RequestResult<Receipts>>,
     },
+    /// Requests receipts from the peer without the bloom field, as introduced in `eth/69`.
+    ///
+    /// The response should be sent through the channel.
+    GetReceipts69 {
+        /// The request for receipts.
+        request: GetReceipts,
+        /// The channel to send the response for receipts.
+        response: oneshot::Sender<RequestResult<Receipts69>>,
+    },
 }
 
 // === impl PeerRequest ===
@@ -162,6 +173,7 @@ impl PeerRequest {
             Self::GetPooledTransactions { response, .. } => response.send(Err(err)).ok(),
             Self::GetNodeData { response, .. } => response.send(Err(err)).ok(),
             Self::GetReceipts { response, .. } => response.send(Err(err)).ok(),
+            Self::GetReceipts69 { response, .. } => response.send(Err(err)).ok(),
         };
     }
 
@@ -183,7 +195,7 @@ impl PeerRequest {
             Self::GetNodeData { request, .. } => {
                 EthMessage::GetNodeData(RequestPair { request_id, message: request.clone() })
             }
-            Self::GetReceipts { request, .. } => {
+            Self::GetReceipts { request, .. } | Self::GetReceipts69 { request, .. } => {
                 EthMessage::GetReceipts(RequestPair { request_id, message: request.clone() })
             }
         }
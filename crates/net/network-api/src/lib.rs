@@ -24,7 +24,10 @@ pub mod test_utils;
 pub use alloy_rpc_types_admin::EthProtocolInfo;
 use reth_network_p2p::sync::NetworkSyncUpdater;
 pub use reth_network_p2p::BlockClient;
-pub use reth_network_types::{PeerKind, Reputation, ReputationChangeKind};
+pub use reth_network_types::{
+    Bans, IpNet, ManagedPeerKind, ManagedPeers, ManagedPeersUpdate, PeerKind, Reputation,
+    ReputationChangeKind,
+};
 
 pub use downloaders::BlockDownloaderProvider;
 pub use error::NetworkError;
@@ -33,9 +36,14 @@ pub use events::{
     PeerRequestSender,
 };
 
-use std::{future::Future, net::SocketAddr, sync::Arc, time::Instant};
+use std::{
+    future::Future,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use reth_eth_wire_types::{capability::Capabilities, DisconnectReason, EthVersion, Status};
+use reth_eth_wire_types::{capability::Capabilities, DisconnectReason, EthVersion, StatusMessage};
 use reth_network_peers::NodeRecord;
 
 /// The `PeerId` type.
@@ -207,6 +215,54 @@ pub trait Peers: PeersInfo {
         &self,
         peer_id: PeerId,
     ) -> impl Future<Output = Result<Option<Reputation>, NetworkError>> + Send;
+
+    /// Bans the peer, indefinitely or for the given duration, and disconnects it if connected.
+    ///
+    /// Does nothing by default.
+    fn ban_peer(&self, _peer_id: PeerId, _duration: Option<Duration>) {}
+
+    /// Lifts the ban of the peer, including a ban due to its reputation.
+    ///
+    /// Does nothing by default.
+    fn unban_peer(&self, _peer_id: PeerId) {}
+
+    /// Bans all IPs in the range, indefinitely or for the given duration, and disconnects all
+    /// peers connected from it.
+    ///
+    /// Non-global IPs within the range are not banned. Does nothing by default.
+    fn ban_ip_range(&self, _range: IpNet, _duration: Option<Duration>) {}
+
+    /// Lifts the ban of the IP range.
+    ///
+    /// Does nothing by default.
+    fn unban_ip_range(&self, _range: IpNet) {}
+
+    /// Returns all banned peers, IPs and IP ranges.
+    ///
+    /// Returns no bans by default.
+    fn bans(&self) -> impl Future<Output = Result<Bans, NetworkError>> + Send {
+        async { Ok(Bans::default()) }
+    }
+
+    /// Applies the update to the peers of the peers config file and writes the file.
+    ///
+    /// Returns `true` if the update changed the configured peers, fails if no peers config file
+    /// is configured, which is the default.
+    fn update_managed_peers(
+        &self,
+        _update: ManagedPeersUpdate,
+    ) -> impl Future<Output = Result<bool, NetworkError>> + Send {
+        async { Err(NetworkError::PeersConfigFile("no peers config file configured".to_string())) }
+    }
+
+    /// Returns the peers of the peers config file, `None` if no file is configured.
+    ///
+    /// Returns `None` by default.
+    fn managed_peers(
+        &self,
+    ) -> impl Future<Output = Result<Option<ManagedPeers>, NetworkError>> + Send {
+        async { Ok(None) }
+    }
 }
 
 /// Info about an active peer session.
@@ -231,7 +287,7 @@ pub struct PeerInfo {
     /// The negotiated eth version.
     pub eth_version: EthVersion,
     /// The Status message the peer sent for the `eth` handshake
-    pub status: Arc<Status>,
+    pub status: Arc<StatusMessage>,
     /// The timestamp when the session to that peer has been established.
     pub session_established: Instant,
     /// The peer's connection kind
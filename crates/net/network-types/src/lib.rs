@@ -27,6 +27,8 @@ pub use peers::{
     kind::PeerKind,
     reputation::{is_banned_reputation, ReputationChangeOutcome, DEFAULT_REPUTATION},
     state::PeerConnectionState,
-    ConnectionsConfig, Peer, PeersConfig,
+    Ban, Bans, ConnectionsConfig, ManagedPeer, ManagedPeerKind, ManagedPeers, ManagedPeersUpdate,
+    Peer, PeersConfig, PersistedPeer, PersistedPeers, PERSISTED_PEERS_VERSION,
 };
-pub use session::{SessionLimits, SessionsConfig};
+pub use reth_net_banlist::IpNet;
+pub use session::{SessionLimits, SessionsConfig, Socks5Auth, Socks5Proxy, Socks5ProxyParseError};
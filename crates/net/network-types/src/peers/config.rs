@@ -11,7 +11,12 @@ use reth_net_banlist::BanList;
 use reth_network_peers::{NodeRecord, TrustedPeer};
 use tracing::info;
 
-use crate::{BackoffKind, ReputationChangeWeights};
+#[cfg(feature = "serde")]
+use crate::peers::persist::PERSISTED_PEERS_VERSION;
+use crate::{
+    peers::persist::{PersistedPeer, PersistedPeers},
+    BackoffKind, ReputationChangeWeights,
+};
 
 /// Maximum number of available slots for outbound sessions.
 pub const DEFAULT_MAX_COUNT_PEERS_OUTBOUND: u32 = 100;
@@ -141,6 +146,9 @@ pub struct PeersConfig {
     /// Basic nodes to connect to.
     #[cfg_attr(feature = "serde", serde(skip))]
     pub basic_nodes: HashSet<NodeRecord>,
+    /// State of known peers restored from a previous run.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub persisted_peers: Vec<PersistedPeer>,
     /// How long to ban bad peers.
     #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
     pub ban_duration: Duration,
@@ -170,6 +178,7 @@ impl Default for PeersConfig {
             trusted_nodes: Default::default(),
             trusted_nodes_only: false,
             basic_nodes: Default::default(),
+            persisted_peers: Default::default(),
             max_backoff_count: 5,
         }
     }
@@ -240,6 +249,16 @@ impl PeersConfig {
         self
     }
 
+    /// Nodes available at launch, together with their state and the bans of a previous run.
+    ///
+    /// Bans that already expired are ignored.
+    pub fn with_persisted_peers(mut self, persisted: PersistedPeers) -> Self {
+        persisted.bans.apply(&mut self.ban_list);
+        self.basic_nodes = persisted.peers.iter().map(|peer| peer.record).collect();
+        self.persisted_peers = persisted.peers;
+        self
+    }
+
     /// Configures the max allowed backoff count.
     pub const fn with_max_backoff_count(mut self, max_backoff_count: u8) -> Self {
         self.max_backoff_count = max_backoff_count;
@@ -267,6 +286,9 @@ impl PeersConfig {
     }
 
     /// Read from file nodes available at launch. Ignored if None.
+    ///
+    /// The file either contains just the node records or, with the `serde` feature, the
+    /// [`PersistedPeers`] of a previous run. Fails if the file was written by a newer version.
     pub fn with_basic_nodes_from_file(
         self,
         optional_file: Option<impl AsRef<Path>>,
@@ -278,8 +300,20 @@ impl PeersConfig {
             Err(e) => Err(e)?,
         };
         info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved peers");
-        let nodes: HashSet<NodeRecord> = serde_json::from_reader(reader)?;
-        Ok(self.with_basic_nodes(nodes))
+        #[cfg(feature = "serde")]
+        let config = match serde_json::from_reader(reader)? {
+            PeersFile::Nodes(nodes) => self.with_basic_nodes(nodes),
+            PeersFile::Persisted(persisted) if persisted.version > PERSISTED_PEERS_VERSION => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("unsupported peers file version {}", persisted.version),
+                ))
+            }
+            PeersFile::Persisted(persisted) => self.with_persisted_peers(persisted),
+        };
+        #[cfg(not(feature = "serde"))]
+        let config = self.with_basic_nodes(serde_json::from_reader::<_, HashSet<_>>(reader)?);
+        Ok(config)
     }
 
     /// Returns settings for testing
@@ -292,3 +326,63 @@ impl PeersConfig {
         }
     }
 }
+
+/// Contents of a peers file.
+#[cfg(feature = "serde")]
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+enum PeersFile {
+    /// Just the node records, as written by earlier versions.
+    Nodes(HashSet<NodeRecord>),
+    /// The state of the peers and the bans.
+    Persisted(PersistedPeers),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NODE: &str = "enode://6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0@10.3.58.6:30303";
+
+    #[test]
+    fn load_legacy_peers_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known-peers.json");
+        std::fs::write(&path, format!("[\"{NODE}\"]")).unwrap();
+
+        let config = PeersConfig::default().with_basic_nodes_from_file(Some(&path)).unwrap();
+        assert_eq!(config.basic_nodes, HashSet::from([NODE.parse().unwrap()]));
+        assert!(config.persisted_peers.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn load_persisted_peers_file() {
+        let record: NodeRecord = NODE.parse().unwrap();
+        let peer = PersistedPeer::new(record, -500, None, 1);
+        let mut ban_list = BanList::default();
+        ban_list.ban_ip("1.1.1.1".parse().unwrap());
+        let persisted = PersistedPeers::new(vec![peer.clone()], (&ban_list).into());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known-peers.json");
+        std::fs::write(&path, serde_json::to_string(&persisted).unwrap()).unwrap();
+
+        let config = PeersConfig::default().with_basic_nodes_from_file(Some(&path)).unwrap();
+        assert_eq!(config.basic_nodes, HashSet::from([record]));
+        assert_eq!(config.persisted_peers, vec![peer]);
+        assert!(config.ban_list.is_banned_ip(&"1.1.1.1".parse().unwrap()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn reject_newer_peers_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known-peers.json");
+        let contents = format!("{{\"version\":{},\"peers\":[]}}", PERSISTED_PEERS_VERSION + 1);
+        std::fs::write(&path, contents).unwrap();
+
+        let err = PeersConfig::default().with_basic_nodes_from_file(Some(&path)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,216 @@
+//! Trusted, static and banned peers that are configured in a peers config file.
+
+#[cfg(feature = "serde")]
+use std::{fs, io, path::Path};
+
+use reth_network_peers::{NodeRecord, PeerId, TrustedPeer};
+
+/// The trusted, static and banned peers of the peers config file.
+///
+/// The file is TOML, e.g.:
+///
+/// ```toml
+/// trusted = ["enode://<id>@reth.example.com:30303"]
+/// static = ["enode://<id>@10.0.0.1:30303"]
+/// banned = ["0x<id>"]
+/// ```
+///
+/// Entries are [`TrustedPeer`]s as read from the file, or [`NodeRecord`]s once their hosts are
+/// resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct ManagedPeers<T = TrustedPeer> {
+    /// Trusted peers, see [`PeerKind::Trusted`](crate::PeerKind::Trusted).
+    pub trusted: Vec<T>,
+    /// Static peers, see [`PeerKind::Static`](crate::PeerKind::Static).
+    #[cfg_attr(feature = "serde", serde(rename = "static"))]
+    pub static_peers: Vec<T>,
+    /// Peers that are banned indefinitely.
+    pub banned: Vec<PeerId>,
+}
+
+#[cfg(feature = "serde")]
+impl ManagedPeers {
+    /// Reads the peers from the TOML file, returns no peers if the file does not exist.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err),
+        };
+        toml::from_str(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Writes the peers to the TOML file, creating its parent directories if necessary.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let contents = toml::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)
+    }
+}
+
+impl<T: ManagedPeer> ManagedPeers<T> {
+    /// Applies the update and returns `true` if it changed the peers.
+    ///
+    /// A peer is either trusted or static, adding it as one removes it from the other.
+    pub fn apply(&mut self, update: &ManagedPeersUpdate) -> bool {
+        match update {
+            ManagedPeersUpdate::AddTrusted(record) => {
+                let removed = remove_peer(&mut self.static_peers, record.id);
+                insert_peer(&mut self.trusted, T::from(*record)) || removed
+            }
+            ManagedPeersUpdate::AddStatic(record) => {
+                let removed = remove_peer(&mut self.trusted, record.id);
+                insert_peer(&mut self.static_peers, T::from(*record)) || removed
+            }
+            ManagedPeersUpdate::AddBanned(peer_id) => {
+                if self.banned.contains(peer_id) {
+                    return false
+                }
+                self.banned.push(*peer_id);
+                true
+            }
+            ManagedPeersUpdate::Remove(ManagedPeerKind::Trusted, peer_id) => {
+                remove_peer(&mut self.trusted, *peer_id)
+            }
+            ManagedPeersUpdate::Remove(ManagedPeerKind::Static, peer_id) => {
+                remove_peer(&mut self.static_peers, *peer_id)
+            }
+            ManagedPeersUpdate::Remove(ManagedPeerKind::Banned, peer_id) => {
+                let len = self.banned.len();
+                self.banned.retain(|id| id != peer_id);
+                self.banned.len() != len
+            }
+        }
+    }
+}
+
+impl<T> Default for ManagedPeers<T> {
+    fn default() -> Self {
+        Self { trusted: Vec::new(), static_peers: Vec::new(), banned: Vec::new() }
+    }
+}
+
+/// An entry of the trusted or static peers of [`ManagedPeers`].
+pub trait ManagedPeer: From<NodeRecord> + PartialEq {
+    /// Returns the id of the peer.
+    fn peer_id(&self) -> PeerId;
+}
+
+impl ManagedPeer for TrustedPeer {
+    fn peer_id(&self) -> PeerId {
+        self.id
+    }
+}
+
+impl ManagedPeer for NodeRecord {
+    fn peer_id(&self) -> PeerId {
+        self.id
+    }
+}
+
+/// Inserts the peer or replaces the entry with the same id, returns `true` if this changed the
+/// peers.
+fn insert_peer<T: ManagedPeer>(peers: &mut Vec<T>, peer: T) -> bool {
+    match peers.iter_mut().find(|existing| existing.peer_id() == peer.peer_id()) {
+        Some(existing) if *existing == peer => false,
+        Some(existing) => {
+            *existing = peer;
+            true
+        }
+        None => {
+            peers.push(peer);
+            true
+        }
+    }
+}
+
+/// Removes the peer with the given id, returns `true` if it was present.
+fn remove_peer<T: ManagedPeer>(peers: &mut Vec<T>, peer_id: PeerId) -> bool {
+    let len = peers.len();
+    peers.retain(|peer| peer.peer_id() != peer_id);
+    peers.len() != len
+}
+
+/// The list of [`ManagedPeers`] a peer is configured in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum ManagedPeerKind {
+    /// The trusted peers.
+    Trusted,
+    /// The static peers.
+    Static,
+    /// The banned peers.
+    Banned,
+}
+
+/// A change to the [`ManagedPeers`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManagedPeersUpdate {
+    /// Adds the peer to the trusted peers.
+    AddTrusted(NodeRecord),
+    /// Adds the peer to the static peers.
+    AddStatic(NodeRecord),
+    /// Adds the peer to the banned peers.
+    AddBanned(PeerId),
+    /// Removes the peer from the list of the given kind.
+    Remove(ManagedPeerKind, PeerId),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(byte: u8) -> NodeRecord {
+        NodeRecord::new(([10, 0, 0, byte], 30303).into(), PeerId::repeat_byte(byte))
+    }
+
+    #[test]
+    fn apply_updates() {
+        let mut peers = ManagedPeers::<NodeRecord>::default();
+        assert!(peers.apply(&ManagedPeersUpdate::AddTrusted(record(1))));
+        assert!(!peers.apply(&ManagedPeersUpdate::AddTrusted(record(1))));
+        assert_eq!(peers.trusted, vec![record(1)]);
+
+        // moving a peer to the static peers removes it from the trusted peers
+        assert!(peers.apply(&ManagedPeersUpdate::AddStatic(record(1))));
+        assert!(peers.trusted.is_empty());
+        assert_eq!(peers.static_peers, vec![record(1)]);
+
+        assert!(peers.apply(&ManagedPeersUpdate::AddBanned(record(2).id)));
+        assert_eq!(peers.banned, vec![record(2).id]);
+
+        assert!(!peers.apply(&ManagedPeersUpdate::Remove(ManagedPeerKind::Trusted, record(1).id)));
+        assert!(peers.apply(&ManagedPeersUpdate::Remove(ManagedPeerKind::Static, record(1).id)));
+        assert!(peers.apply(&ManagedPeersUpdate::Remove(ManagedPeerKind::Banned, record(2).id)));
+        assert_eq!(peers, ManagedPeers::default());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn load_and_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("peers.toml");
+        assert_eq!(ManagedPeers::load(&path).unwrap(), ManagedPeers::default());
+
+        let mut peers = ManagedPeers::default();
+        peers.trusted.push("enode://6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0@localhost:30303".parse().unwrap());
+        peers.static_peers.push(record(1).into());
+        peers.banned.push(PeerId::repeat_byte(2));
+        peers.save(&path).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("static = ["));
+        assert_eq!(ManagedPeers::load(&path).unwrap(), peers);
+
+        fs::write(&path, "trusted = 1").unwrap();
+        assert_eq!(ManagedPeers::load(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,10 +1,14 @@
 pub mod addr;
 pub mod config;
 pub mod kind;
+pub mod managed;
+pub mod persist;
 pub mod reputation;
 pub mod state;
 
 pub use config::{ConnectionsConfig, PeersConfig};
+pub use managed::{ManagedPeer, ManagedPeerKind, ManagedPeers, ManagedPeersUpdate};
+pub use persist::{Ban, Bans, PersistedPeer, PersistedPeers, PERSISTED_PEERS_VERSION};
 pub use reputation::{Reputation, ReputationChange, ReputationChangeKind, ReputationChangeWeights};
 
 use reth_ethereum_forks::ForkId;
@@ -0,0 +1,227 @@
+//! Peer state that is persisted across restarts.
+
+use std::{
+    net::IpAddr,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use reth_net_banlist::{BanList, IpNet};
+use reth_network_peers::{NodeRecord, PeerId};
+
+/// The version of the [`PersistedPeers`] format.
+///
+/// Peers files without a version contain just a list of node records.
+pub const PERSISTED_PEERS_VERSION: u64 = 1;
+
+/// The known peers and bans, as written to the peers file.
+///
+/// Points in time are stored as unix timestamps in seconds, so they survive a restart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedPeers {
+    /// The version of the format, see [`PERSISTED_PEERS_VERSION`].
+    pub version: u64,
+    /// The known peers.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub peers: Vec<PersistedPeer>,
+    /// The banned peers, IPs and IP ranges.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub bans: Bans,
+}
+
+impl PersistedPeers {
+    /// Creates the current version of the persisted peers.
+    pub const fn new(peers: Vec<PersistedPeer>, bans: Bans) -> Self {
+        Self { version: PERSISTED_PEERS_VERSION, peers, bans }
+    }
+}
+
+impl Default for PersistedPeers {
+    fn default() -> Self {
+        Self::new(Vec::new(), Bans::default())
+    }
+}
+
+/// A snapshot of a [`BanList`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Bans {
+    /// Banned peers.
+    pub banned_peers: Vec<Ban<PeerId>>,
+    /// Banned IPs.
+    pub banned_ips: Vec<Ban<IpAddr>>,
+    /// Banned IP ranges.
+    pub banned_ip_ranges: Vec<Ban<IpNet>>,
+}
+
+impl Bans {
+    /// Adds all bans that did not expire yet to the [`BanList`].
+    pub fn apply(&self, ban_list: &mut BanList) {
+        for ban in &self.banned_peers {
+            if let Some(until) = ban.until() {
+                ban_list.ban_peer_with(ban.target, until);
+            }
+        }
+        for ban in &self.banned_ips {
+            if let Some(until) = ban.until() {
+                ban_list.ban_ip_with(ban.target, until);
+            }
+        }
+        for ban in &self.banned_ip_ranges {
+            if let Some(until) = ban.until() {
+                ban_list.ban_ip_range_with(ban.target, until);
+            }
+        }
+    }
+}
+
+impl From<&BanList> for Bans {
+    fn from(ban_list: &BanList) -> Self {
+        Self {
+            banned_peers: ban_list.banned_peers().map(Ban::from).collect(),
+            banned_ips: ban_list.banned_ips().map(Ban::from).collect(),
+            banned_ip_ranges: ban_list.banned_ip_ranges().map(Ban::from).collect(),
+        }
+    }
+}
+
+/// The state of a known peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedPeer {
+    /// Where to reach the peer.
+    pub record: NodeRecord,
+    /// Reputation of the peer.
+    pub reputation: i32,
+    /// Unix timestamp until which the peer is backed off, if it is.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub backoff_until: Option<u64>,
+    /// Number of times the peer was backed off due to a severe
+    /// [`BackoffKind`](crate::BackoffKind).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub severe_backoff_counter: u8,
+}
+
+impl PersistedPeer {
+    /// Creates the persisted state of a peer that is backed off until the given [`Instant`], if
+    /// set.
+    pub fn new(
+        record: NodeRecord,
+        reputation: i32,
+        backoff_until: Option<Instant>,
+        severe_backoff_counter: u8,
+    ) -> Self {
+        Self {
+            record,
+            reputation,
+            backoff_until: backoff_until.map(instant_to_unix),
+            severe_backoff_counter,
+        }
+    }
+
+    /// Returns the [`Instant`] until which the peer is backed off, `None` if the backoff already
+    /// expired.
+    pub fn backoff_until(&self) -> Option<Instant> {
+        self.backoff_until.and_then(unix_to_instant)
+    }
+}
+
+/// A ban of a peer, IP or IP range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ban<T> {
+    /// What is banned.
+    pub target: T,
+    /// Unix timestamp until which the ban lasts, `None` if the ban is indefinite.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub until: Option<u64>,
+}
+
+impl<T> Ban<T> {
+    /// Returns when the ban ends, `Some(None)` if it is indefinite and `None` if it already
+    /// expired.
+    pub fn until(&self) -> Option<Option<Instant>> {
+        match self.until {
+            Some(until) => unix_to_instant(until).map(Some),
+            None => Some(None),
+        }
+    }
+}
+
+impl<T> From<(T, Option<Instant>)> for Ban<T> {
+    fn from((target, until): (T, Option<Instant>)) -> Self {
+        Self { target, until: until.map(instant_to_unix) }
+    }
+}
+
+/// Converts the [`Instant`] to a unix timestamp in seconds.
+fn instant_to_unix(instant: Instant) -> u64 {
+    let now = Instant::now();
+    let unix_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let unix = match instant.checked_duration_since(now) {
+        Some(ahead) => unix_now + ahead,
+        None => unix_now.saturating_sub(now - instant),
+    };
+    unix.as_secs()
+}
+
+/// Converts the unix timestamp in seconds to an [`Instant`], `None` if it lies in the past.
+fn unix_to_instant(secs: u64) -> Option<Instant> {
+    let unix_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let ahead = Duration::from_secs(secs).checked_sub(unix_now)?;
+    Instant::now().checked_add(ahead)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_bans() {
+        let peer = PeerId::repeat_byte(1);
+        let mut ban_list = BanList::default();
+        ban_list.ban_peer(peer);
+        ban_list.ban_ip_until(IpAddr::from([1, 1, 1, 1]), Instant::now() + Duration::from_secs(60));
+        ban_list.ban_ip_range("2.2.2.0/24".parse().unwrap());
+
+        let mut bans = Bans::from(&ban_list);
+        let expired = PeerId::repeat_byte(2);
+        bans.banned_peers.push(Ban { target: expired, until: Some(1) });
+
+        #[cfg(feature = "serde")]
+        let bans: Bans = serde_json::from_str(&serde_json::to_string(&bans).unwrap()).unwrap();
+
+        let mut restored = BanList::default();
+        bans.apply(&mut restored);
+        assert!(restored.is_banned_peer(&peer));
+        assert!(!restored.is_banned_peer(&expired));
+        assert!(restored.is_banned_ip(&IpAddr::from([1, 1, 1, 1])));
+        assert!(restored.is_banned_ip(&IpAddr::from([2, 2, 2, 2])));
+        assert!(!restored.is_banned_ip(&IpAddr::from([3, 3, 3, 3])));
+    }
+
+    #[test]
+    fn backoff_roundtrip() {
+        let record: NodeRecord = "enode://6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0@10.3.58.6:30303".parse().unwrap();
+
+        let backed_off =
+            PersistedPeer::new(record, -100, Some(Instant::now() + Duration::from_secs(600)), 2);
+        assert!(backed_off.backoff_until().is_some());
+
+        let expired = PersistedPeer { backoff_until: Some(1), ..backed_off };
+        assert!(expired.backoff_until().is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn persisted_peers_roundtrip() {
+        let record: NodeRecord = "enode://6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0@10.3.58.6:30303".parse().unwrap();
+        let peer =
+            PersistedPeer::new(record, -100, Some(Instant::now() + Duration::from_secs(600)), 2);
+        let persisted = PersistedPeers::new(vec![peer], Bans::default());
+
+        let json = serde_json::to_value(&persisted).unwrap();
+        assert_eq!(json["version"], PERSISTED_PEERS_VERSION);
+        assert_eq!(serde_json::from_value::<PersistedPeers>(json).unwrap(), persisted);
+    }
+}
@@ -35,6 +35,9 @@ const BAD_PROTOCOL_REPUTATION_CHANGE: i32 = i32::MIN;
 // todo: current value is a hint, needs to be set properly
 const BAD_ANNOUNCEMENT_REPUTATION_CHANGE: i32 = REPUTATION_UNIT;
 
+/// The reputation change to apply to a peer that exceeded its budget for served requests.
+const EXCEEDED_REQUEST_LIMIT_REPUTATION_CHANGE: i32 = 2 * REPUTATION_UNIT;
+
 /// The maximum reputation change that can be applied to a trusted peer.
 /// This is used to prevent a single bad message from a trusted peer to cause a significant change.
 /// This gives a trusted peer more leeway when interacting with the node, which is useful for in
@@ -83,6 +86,8 @@ pub enum ReputationChangeKind {
     FailedToConnect,
     /// Connection dropped by peer.
     Dropped,
+    /// Peer sent more requests than we're willing to serve.
+    ExceededRequestLimit,
     /// Reset the reputation to the default value.
     Reset,
     /// Apply a reputation change by value
@@ -124,6 +129,8 @@ pub struct ReputationChangeWeights {
     pub dropped: Reputation,
     /// Weight for [`ReputationChangeKind::BadAnnouncement`]
     pub bad_announcement: Reputation,
+    /// Weight for [`ReputationChangeKind::ExceededRequestLimit`]
+    pub exceeded_request_limit: Reputation,
 }
 
 // === impl ReputationChangeWeights ===
@@ -144,6 +151,7 @@ impl ReputationChangeWeights {
             ReputationChangeKind::Reset => DEFAULT_REPUTATION.into(),
             ReputationChangeKind::Other(val) => val.into(),
             ReputationChangeKind::BadAnnouncement => self.bad_announcement.into(),
+            ReputationChangeKind::ExceededRequestLimit => self.exceeded_request_limit.into(),
         }
     }
 }
@@ -160,6 +168,7 @@ impl Default for ReputationChangeWeights {
             failed_to_connect: FAILED_TO_CONNECT_REPUTATION_CHANGE,
             dropped: REMOTE_DISCONNECT_REPUTATION_CHANGE,
             bad_announcement: BAD_ANNOUNCEMENT_REPUTATION_CHANGE,
+            exceeded_request_limit: EXCEEDED_REQUEST_LIMIT_REPUTATION_CHANGE,
         }
     }
 }
@@ -1,7 +1,10 @@
 //! Configuration types for peer sessions manager.
 
-use crate::peers::config::{DEFAULT_MAX_COUNT_PEERS_INBOUND, DEFAULT_MAX_COUNT_PEERS_OUTBOUND};
-use std::time::Duration;
+use crate::{
+    peers::config::{DEFAULT_MAX_COUNT_PEERS_INBOUND, DEFAULT_MAX_COUNT_PEERS_OUTBOUND},
+    session::Socks5Proxy,
+};
+use std::{path::PathBuf, time::Duration};
 
 /// Default request timeout for a single request.
 ///
@@ -16,6 +19,12 @@ pub const PENDING_SESSION_TIMEOUT: Duration = Duration::from_secs(20);
 /// This is the time a peer has to answer a response.
 pub const PROTOCOL_BREACH_REQUEST_TIMEOUT: Duration = Duration::from_secs(2 * 60);
 
+/// Default maximum size of a single session capture file, in bytes.
+pub const DEFAULT_CAPTURE_MAX_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Default maximum number of session capture files.
+pub const DEFAULT_CAPTURE_MAX_FILES: usize = 256;
+
 /// The default maximum number of peers.
 const DEFAULT_MAX_PEERS: usize =
     DEFAULT_MAX_COUNT_PEERS_OUTBOUND as usize + DEFAULT_MAX_COUNT_PEERS_INBOUND as usize;
@@ -50,6 +59,23 @@ pub struct SessionsConfig {
     pub protocol_breach_request_timeout: Duration,
     /// The timeout after which a pending session attempt is considered failed.
     pub pending_session_timeout: Duration,
+    /// Directory to record the messages of every active session to, for debugging.
+    ///
+    /// Each session is recorded to its own capture file, starting with the handshakes. By default
+    /// nothing is recorded.
+    pub capture_dir: Option<PathBuf>,
+    /// The maximum size of a single capture file, in bytes.
+    ///
+    /// Once a capture reaches this size, no further messages of the session are recorded.
+    pub capture_max_size: u64,
+    /// The maximum number of capture files in the capture directory.
+    ///
+    /// The oldest capture file is deleted before a new one is created.
+    pub capture_max_files: usize,
+    /// SOCKS5 proxy to dial outbound connections through.
+    ///
+    /// By default, peers are dialed directly.
+    pub proxy: Option<Socks5Proxy>,
 }
 
 impl Default for SessionsConfig {
@@ -69,6 +95,10 @@ impl Default for SessionsConfig {
             initial_internal_request_timeout: INITIAL_REQUEST_TIMEOUT,
             protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
             pending_session_timeout: PENDING_SESSION_TIMEOUT,
+            capture_dir: None,
+            capture_max_size: DEFAULT_CAPTURE_MAX_SIZE,
+            capture_max_files: DEFAULT_CAPTURE_MAX_FILES,
+            proxy: None,
         }
     }
 }
@@ -84,6 +114,30 @@ impl SessionsConfig {
         self
     }
 
+    /// Records the messages of every active session to a capture file in the given directory.
+    pub fn with_capture_dir(mut self, dir: PathBuf) -> Self {
+        self.capture_dir = Some(dir);
+        self
+    }
+
+    /// Sets the maximum size of a single capture file, in bytes.
+    pub const fn with_capture_max_size(mut self, max_size: u64) -> Self {
+        self.capture_max_size = max_size;
+        self
+    }
+
+    /// Sets the maximum number of capture files in the capture directory.
+    pub const fn with_capture_max_files(mut self, max_files: usize) -> Self {
+        self.capture_max_files = max_files;
+        self
+    }
+
+    /// Dials outbound connections through the given SOCKS5 proxy.
+    pub fn with_proxy(mut self, proxy: Socks5Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
     /// Helper function to set the buffer size for the bounded communication channel between the
     /// manager and its sessions for events emitted by the sessions.
     ///
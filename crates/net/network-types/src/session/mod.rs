@@ -2,3 +2,6 @@
 
 pub mod config;
 pub use config::{SessionLimits, SessionsConfig};
+
+pub mod proxy;
+pub use proxy::{Socks5Auth, Socks5Proxy, Socks5ProxyParseError};
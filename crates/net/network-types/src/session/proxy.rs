@@ -0,0 +1,144 @@
+//! Proxy configuration for outbound sessions.
+
+use std::{
+    fmt,
+    net::{SocketAddr, ToSocketAddrs},
+    str::FromStr,
+};
+
+/// A SOCKS5 proxy that outbound `RLPx` connections are dialed through.
+///
+/// Can be parsed from `[socks5://][username:password@]host:port`.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Socks5Proxy {
+    /// Address of the proxy.
+    pub addr: SocketAddr,
+    /// Credentials to authenticate with, if the proxy requires them.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub auth: Option<Socks5Auth>,
+}
+
+impl Socks5Proxy {
+    /// Creates a proxy without authentication.
+    pub const fn new(addr: SocketAddr) -> Self {
+        Self { addr, auth: None }
+    }
+
+    /// Sets the username and password to authenticate with.
+    pub fn with_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.auth = Some(Socks5Auth { username: username.into(), password: password.into() });
+        self
+    }
+}
+
+impl fmt::Debug for Socks5Proxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Socks5Proxy")
+            .field("addr", &self.addr)
+            .field("username", &self.auth.as_ref().map(|auth| &auth.username))
+            .finish_non_exhaustive()
+    }
+}
+
+impl FromStr for Socks5Proxy {
+    type Err = Socks5ProxyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = match s.split_once("://") {
+            Some(("socks5", rest)) => rest,
+            Some((scheme, _)) => return Err(Socks5ProxyParseError::Scheme(scheme.to_string())),
+            None => s,
+        };
+
+        let (auth, host) = match s.rsplit_once('@') {
+            Some((auth, host)) => {
+                let (username, password) =
+                    auth.split_once(':').ok_or(Socks5ProxyParseError::MissingPassword)?;
+                let auth =
+                    Socks5Auth { username: username.to_string(), password: password.to_string() };
+                (Some(auth), host)
+            }
+            None => (None, s),
+        };
+
+        let addr = match host.parse::<SocketAddr>() {
+            Ok(addr) => addr,
+            Err(_) => host
+                .to_socket_addrs()
+                .ok()
+                .and_then(|mut addrs| addrs.next())
+                .ok_or_else(|| Socks5ProxyParseError::Address(host.to_string()))?,
+        };
+
+        Ok(Self { addr, auth })
+    }
+}
+
+/// Username and password to authenticate with a SOCKS5 proxy, see
+/// [RFC 1929](https://datatracker.ietf.org/doc/html/rfc1929).
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Socks5Auth {
+    /// The username.
+    pub username: String,
+    /// The password.
+    pub password: String,
+}
+
+impl fmt::Debug for Socks5Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Socks5Auth").field("username", &self.username).finish_non_exhaustive()
+    }
+}
+
+/// Error returned when parsing a [`Socks5Proxy`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Socks5ProxyParseError {
+    /// The URL has a scheme other than `socks5`.
+    Scheme(String),
+    /// The credentials are missing a password.
+    MissingPassword,
+    /// The address could not be resolved.
+    Address(String),
+}
+
+impl fmt::Display for Socks5ProxyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scheme(scheme) => write!(f, "unsupported proxy scheme: {scheme}"),
+            Self::MissingPassword => f.write_str("proxy credentials must be username:password"),
+            Self::Address(addr) => write!(f, "invalid proxy address: {addr}"),
+        }
+    }
+}
+
+impl std::error::Error for Socks5ProxyParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_proxy() {
+        let proxy: Socks5Proxy = "127.0.0.1:1080".parse().unwrap();
+        assert_eq!(proxy, Socks5Proxy::new(([127, 0, 0, 1], 1080).into()));
+
+        let proxy: Socks5Proxy = "socks5://user:p@ss@[::1]:1080".parse().unwrap();
+        assert_eq!(
+            proxy,
+            Socks5Proxy::new("[::1]:1080".parse().unwrap()).with_auth("user", "p@ss")
+        );
+        assert!(!format!("{proxy:?}").contains("p@ss"));
+
+        assert_eq!(
+            "http://127.0.0.1:1080".parse::<Socks5Proxy>(),
+            Err(Socks5ProxyParseError::Scheme("http".to_string()))
+        );
+        assert_eq!(
+            "user@127.0.0.1:1080".parse::<Socks5Proxy>(),
+            Err(Socks5ProxyParseError::MissingPassword)
+        );
+        assert!("127.0.0.1".parse::<Socks5Proxy>().is_err());
+    }
+}
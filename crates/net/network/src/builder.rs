@@ -67,7 +67,8 @@ impl<Tx, Eth> NetworkBuilder<Tx, Eth> {
         NetworkBuilder { network, request_handler, transactions }
     }
 
-    /// Creates a new [`EthRequestHandler`] and wires it to the network.
+    /// Creates a new [`EthRequestHandler`] with the configured
+    /// [`EthRequestLimits`](crate::eth_requests::EthRequestLimits) and wires it to the network.
     pub fn request_handler<Client>(
         self,
         client: Client,
@@ -76,7 +77,10 @@ impl<Tx, Eth> NetworkBuilder<Tx, Eth> {
         let (tx, rx) = mpsc::channel(ETH_REQUEST_CHANNEL_CAPACITY);
         network.set_eth_request_handler(tx);
         let peers = network.handle().peers_handle().clone();
-        let request_handler = EthRequestHandler::new(client, peers, rx);
+        let mut request_handler = EthRequestHandler::new(client, peers, rx);
+        if let Some(limits) = network.eth_request_limits() {
+            request_handler = request_handler.with_limits(limits);
+        }
         NetworkBuilder { network, request_handler, transactions }
     }
 }
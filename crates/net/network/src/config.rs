@@ -1,21 +1,24 @@
 //! Network config support
 
-use std::{collections::HashSet, net::SocketAddr, sync::Arc};
+use std::{collections::HashSet, net::SocketAddr, path::PathBuf, sync::Arc};
 
 use reth_chainspec::{ChainSpec, MAINNET};
 use reth_discv4::{Discv4Config, Discv4ConfigBuilder, NatResolver, DEFAULT_DISCOVERY_ADDRESS};
 use reth_discv5::NetworkStackId;
 use reth_dns_discovery::DnsDiscoveryConfig;
 use reth_eth_wire::{HelloMessage, HelloMessageWithProtocols, Status};
+use reth_net_nat::PortMappingConfig;
 use reth_network_peers::{mainnet_nodes, pk2id, sepolia_nodes, PeerId, TrustedPeer};
 use reth_network_types::{PeersConfig, SessionsConfig};
 use reth_primitives::{ForkFilter, Head};
+use reth_prune_types::PruneModes;
 use reth_storage_api::{BlockNumReader, BlockReader, HeaderProvider};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use secp256k1::SECP256K1;
 
 use crate::{
     error::NetworkError,
+    eth_requests::EthRequestLimits,
     import::{BlockImport, ProofOfStakeBlockImport},
     transactions::TransactionsManagerConfig,
     NetworkHandle, NetworkManager,
@@ -50,10 +53,15 @@ pub struct NetworkConfig<C> {
     pub discovery_v4_config: Option<Discv4Config>,
     /// How to set up discovery version 5.
     pub discovery_v5_config: Option<reth_discv5::Config>,
+    /// How to map the `RLPx` and discovery ports on the gateway.
+    pub nat_port_mapping: Option<PortMappingConfig>,
     /// Address to listen for incoming connections
     pub listener_addr: SocketAddr,
     /// How to instantiate peer manager.
     pub peers_config: PeersConfig,
+    /// The TOML file with trusted, static and banned peers that is watched for changes, see
+    /// [`ManagedPeers`](reth_network_types::ManagedPeers).
+    pub peers_config_file: Option<PathBuf>,
     /// How to configure the [`SessionManager`](crate::session::SessionManager).
     pub sessions_config: SessionsConfig,
     /// The chain spec
@@ -81,6 +89,11 @@ pub struct NetworkConfig<C> {
     pub tx_gossip_disabled: bool,
     /// How to instantiate transactions manager.
     pub transactions_manager_config: TransactionsManagerConfig,
+    /// Limits for serving `eth` requests, or `None` to serve them without limits.
+    pub eth_request_limits: Option<EthRequestLimits>,
+    /// The prune modes of the node, which determine the earliest block announced to `eth/69`
+    /// peers.
+    pub prune_modes: PruneModes,
 }
 
 // === impl NetworkConfig ===
@@ -160,6 +173,8 @@ pub struct NetworkConfigBuilder {
     discovery_v4_builder: Option<Discv4ConfigBuilder>,
     /// How to set up discovery version 5.
     discovery_v5_builder: Option<reth_discv5::ConfigBuilder>,
+    /// How to map ports on the gateway.
+    nat_port_mapping: Option<PortMappingConfig>,
     /// All boot nodes to start network discovery with.
     boot_nodes: HashSet<TrustedPeer>,
     /// Address to use for discovery
@@ -168,6 +183,8 @@ pub struct NetworkConfigBuilder {
     listener_addr: Option<SocketAddr>,
     /// How to instantiate peer manager.
     peers_config: Option<PeersConfig>,
+    /// The watched TOML file with trusted, static and banned peers.
+    peers_config_file: Option<PathBuf>,
     /// How to configure the sessions manager
     sessions_config: Option<SessionsConfig>,
     /// The network's chain spec
@@ -188,6 +205,10 @@ pub struct NetworkConfigBuilder {
     block_import: Option<Box<dyn BlockImport>>,
     /// How to instantiate transactions manager.
     transactions_manager_config: TransactionsManagerConfig,
+    /// Limits for serving `eth` requests.
+    eth_request_limits: Option<EthRequestLimits>,
+    /// The prune modes of the node.
+    prune_modes: PruneModes,
 }
 
 // === impl NetworkConfigBuilder ===
@@ -206,10 +227,12 @@ impl NetworkConfigBuilder {
             dns_discovery_config: Some(Default::default()),
             discovery_v4_builder: Some(Default::default()),
             discovery_v5_builder: None,
+            nat_port_mapping: None,
             boot_nodes: Default::default(),
             discovery_addr: None,
             listener_addr: None,
             peers_config: None,
+            peers_config_file: None,
             sessions_config: None,
             chain_spec: MAINNET.clone(),
             network_mode: Default::default(),
@@ -220,6 +243,8 @@ impl NetworkConfigBuilder {
             tx_gossip_disabled: false,
             block_import: None,
             transactions_manager_config: Default::default(),
+            eth_request_limits: None,
+            prune_modes: PruneModes::none(),
         }
     }
 
@@ -284,6 +309,16 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets the TOML file with trusted, static and banned peers.
+    ///
+    /// The peers are applied on launch and whenever the file changes. Changes made via
+    /// [`Peers::update_managed_peers`](reth_network_api::Peers::update_managed_peers) are written
+    /// back to the file.
+    pub fn peers_config_file(mut self, path: Option<PathBuf>) -> Self {
+        self.peers_config_file = path;
+        self
+    }
+
     /// Sets the executor to use for spawning tasks.
     ///
     /// If `None`, then [`tokio::spawn`] is used for spawning tasks.
@@ -304,6 +339,22 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets the limits for serving `eth` requests, or `None` to serve them without limits.
+    ///
+    /// Requests are served without limits by default.
+    pub const fn eth_request_limits(mut self, limits: Option<EthRequestLimits>) -> Self {
+        self.eth_request_limits = limits;
+        self
+    }
+
+    /// Sets the prune modes of the node.
+    ///
+    /// Blocks whose receipts are pruned are not announced as available to `eth/69` peers.
+    pub fn prune_modes(mut self, prune_modes: PruneModes) -> Self {
+        self.prune_modes = prune_modes;
+        self
+    }
+
     /// Sets the discovery and listener address
     ///
     /// This is a convenience function for both [`NetworkConfigBuilder::listener_addr`] and
@@ -351,10 +402,20 @@ impl NetworkConfigBuilder {
     ///
     /// This is a convenience function for setting the external ip resolver on the default
     /// [`Discv4Config`] config.
+    ///
+    /// If the resolver talks to the gateway, i.e. [`NatResolver::Upnp`] or
+    /// [`NatResolver::NatPmp`], the `RLPx` and discovery ports are also mapped on it.
     pub fn external_ip_resolver(mut self, resolver: NatResolver) -> Self {
         self.discovery_v4_builder
             .get_or_insert_with(Discv4Config::builder)
             .external_ip_resolver(Some(resolver));
+        self.nat_port_mapping = PortMappingConfig::from_resolver(resolver);
+        self
+    }
+
+    /// Sets how to map the `RLPx` and discovery ports on the gateway.
+    pub fn nat_port_mapping(mut self, config: Option<PortMappingConfig>) -> Self {
+        self.nat_port_mapping = config;
         self
     }
 
@@ -480,10 +541,12 @@ impl NetworkConfigBuilder {
             mut dns_discovery_config,
             discovery_v4_builder,
             mut discovery_v5_builder,
+            nat_port_mapping,
             boot_nodes,
             discovery_addr,
             listener_addr,
             peers_config,
+            peers_config_file,
             sessions_config,
             chain_spec,
             network_mode,
@@ -494,6 +557,8 @@ impl NetworkConfigBuilder {
             tx_gossip_disabled,
             block_import,
             transactions_manager_config,
+            eth_request_limits,
+            prune_modes,
         } = self;
 
         discovery_v5_builder = discovery_v5_builder.map(|mut builder| {
@@ -543,9 +608,11 @@ impl NetworkConfigBuilder {
             dns_discovery_config,
             discovery_v4_config: discovery_v4_builder.map(|builder| builder.build()),
             discovery_v5_config: discovery_v5_builder.map(|builder| builder.build()),
+            nat_port_mapping,
             discovery_v4_addr: discovery_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS),
             listener_addr,
             peers_config: peers_config.unwrap_or_default(),
+            peers_config_file,
             sessions_config: sessions_config.unwrap_or_default(),
             chain_spec,
             block_import: block_import.unwrap_or_else(|| Box::<ProofOfStakeBlockImport>::default()),
@@ -557,6 +624,8 @@ impl NetworkConfigBuilder {
             fork_filter,
             tx_gossip_disabled,
             transactions_manager_config,
+            eth_request_limits,
+            prune_modes,
         }
     }
 }
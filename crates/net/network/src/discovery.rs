@@ -2,10 +2,12 @@
 
 use std::{
     collections::VecDeque,
+    future::Future,
     net::{IpAddr, SocketAddr},
     pin::Pin,
     sync::Arc,
     task::{ready, Context, Poll},
+    time::Duration,
 };
 
 use enr::Enr;
@@ -15,20 +17,29 @@ use reth_discv5::{DiscoveredPeer, Discv5};
 use reth_dns_discovery::{
     DnsDiscoveryConfig, DnsDiscoveryHandle, DnsDiscoveryService, DnsNodeRecordUpdate, DnsResolver,
 };
+use reth_net_nat::{
+    PortMapping, PortMappingConfig, PortMappingHandle, PortMappingService, Protocol,
+};
 use reth_network_api::{DiscoveredEvent, DiscoveryEvent};
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_network_types::PeerAddr;
 use reth_primitives::{EnrForkIdEntry, ForkId};
 use secp256k1::SecretKey;
 use tokio::{sync::mpsc, task::JoinHandle};
-use tokio_stream::{wrappers::ReceiverStream, Stream};
-use tracing::trace;
+use tokio_stream::{
+    wrappers::{ReceiverStream, UnboundedReceiverStream},
+    Stream,
+};
+use tracing::{debug, trace};
 
 use crate::{
     cache::LruMap,
     error::{NetworkError, ServiceKind},
 };
 
+/// How long to wait for the port mappings to be removed from the gateway on shutdown.
+const PORT_MAPPING_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Default max capacity for cache of discovered peers.
 ///
 /// Default is 10 000 peers.
@@ -62,6 +73,8 @@ pub struct Discovery {
     dns_discovery_updates: Option<ReceiverStream<DnsNodeRecordUpdate>>,
     /// The handle to the spawned DNS discovery service
     _dns_disc_service: Option<JoinHandle<()>>,
+    /// Port mappings on the gateway, announced in the local records.
+    nat_port_mapping: Option<NatPortMapping>,
     /// Events buffered until polled.
     queued_events: VecDeque<DiscoveryEvent>,
     /// List of listeners subscribed to discovery events.
@@ -80,6 +93,7 @@ impl Discovery {
         discv4_config: Option<Discv4Config>,
         discv5_config: Option<reth_discv5::Config>, // contains discv5 listen address
         dns_discovery_config: Option<DnsDiscoveryConfig>,
+        nat_port_mapping: Option<PortMappingConfig>,
     ) -> Result<Self, NetworkError> {
         // setup discv4 with the discovery address and tcp port
         let local_enr =
@@ -100,6 +114,7 @@ impl Discovery {
             Ok((Some(discv4), Some(discv4_updates), Some(discv4_service)))
         };
 
+        let discv5_port = discv5_config.as_ref().map(|config| config.discovery_socket().port());
         let discv5_future = async {
             let Some(config) = discv5_config else { return Ok::<_, NetworkError>((None, None)) };
             let (discv5, discv5_updates, _local_enr_discv5) = Discv5::start(&sk, config).await?;
@@ -123,6 +138,16 @@ impl Discovery {
                 (None, None, None)
             };
 
+        // map the ports on the gateway
+        let nat_port_mapping = nat_port_mapping.map(|config| {
+            NatPortMapping::spawn(
+                config,
+                tcp_addr.port(),
+                discv4.as_ref().map(|discv4| discv4.local_addr().port()),
+                discv5_port,
+            )
+        });
+
         Ok(Self {
             discovery_listeners: Default::default(),
             local_enr,
@@ -136,6 +161,7 @@ impl Discovery {
             _dns_disc_service,
             _dns_discovery,
             dns_discovery_updates,
+            nat_port_mapping,
         })
     }
 
@@ -179,6 +205,60 @@ impl Discovery {
         }
     }
 
+    /// Stops mapping ports on the gateway.
+    ///
+    /// The returned future resolves once the port mappings are removed, or after
+    /// [`PORT_MAPPING_SHUTDOWN_TIMEOUT`] if the gateway doesn't respond.
+    pub(crate) fn stop_port_mapping(&mut self) -> impl Future<Output = ()> + Send + 'static {
+        let nat_port_mapping = self.nat_port_mapping.take();
+        async move {
+            let Some(nat_port_mapping) = nat_port_mapping else { return };
+            if tokio::time::timeout(
+                PORT_MAPPING_SHUTDOWN_TIMEOUT,
+                nat_port_mapping.handle.shutdown(),
+            )
+            .await
+            .is_err()
+            {
+                debug!(target: "net::discovery", "Timed out removing port mappings");
+            }
+        }
+    }
+
+    /// Announces a port mapped on the gateway in the local records of the discovery services.
+    fn on_port_mapping(&self, mapping: PortMapping) {
+        let Some(ports) = &self.nat_port_mapping else { return };
+        let PortMapping { protocol, internal_port, external_addr, .. } = mapping;
+        debug!(target: "net::discovery", ?mapping, "Announcing mapped port");
+
+        match protocol {
+            Protocol::Tcp if internal_port == ports.tcp_port => {
+                if let Some(discv4) = &self.discv4 {
+                    discv4.set_external_ip(external_addr.ip());
+                    discv4.set_tcp_port(external_addr.port());
+                }
+                if let Some(discv5) = &self.discv5 {
+                    discv5.set_local_enr_socket(external_addr, true);
+                }
+            }
+            Protocol::Tcp => {}
+            Protocol::Udp => {
+                // discv4 and discv5 may share the port
+                if Some(internal_port) == ports.discv4_port {
+                    if let Some(discv4) = &self.discv4 {
+                        discv4.set_external_ip(external_addr.ip());
+                        discv4.set_udp_port(external_addr.port());
+                    }
+                }
+                if Some(internal_port) == ports.discv5_port {
+                    if let Some(discv5) = &self.discv5 {
+                        discv5.set_local_enr_socket(external_addr, false);
+                    }
+                }
+            }
+        }
+    }
+
     /// Returns a shared reference to the discv4.
     pub fn discv4(&self) -> Option<Discv4> {
         self.discv4.clone()
@@ -282,6 +362,13 @@ impl Discovery {
                 self.on_node_record_update(update.node_record, update.fork_id);
             }
 
+            // announce the ports mapped on the gateway
+            while let Some(Poll::Ready(Some(mapping))) =
+                self.nat_port_mapping.as_mut().map(|nat| nat.updates.poll_next_unpin(cx))
+            {
+                self.on_port_mapping(mapping);
+            }
+
             if self.queued_events.is_empty() {
                 return Poll::Pending
             }
@@ -289,6 +376,43 @@ impl Discovery {
     }
 }
 
+/// A spawned [`PortMappingService`] for the `RLPx` and discovery ports.
+#[derive(Debug)]
+struct NatPortMapping {
+    /// Removes the port mappings when shut down or dropped.
+    handle: PortMappingHandle,
+    /// Mappings with a new external address.
+    updates: UnboundedReceiverStream<PortMapping>,
+    /// Local `RLPx` port.
+    tcp_port: u16,
+    /// Local discv4 port.
+    discv4_port: Option<u16>,
+    /// Local discv5 port.
+    discv5_port: Option<u16>,
+}
+
+impl NatPortMapping {
+    fn spawn(
+        config: PortMappingConfig,
+        tcp_port: u16,
+        discv4_port: Option<u16>,
+        discv5_port: Option<u16>,
+    ) -> Self {
+        let ports = std::iter::once((Protocol::Tcp, tcp_port))
+            .chain(discv4_port.map(|port| (Protocol::Udp, port)))
+            .chain(
+                discv5_port
+                    .filter(|port| Some(*port) != discv4_port)
+                    .map(|port| (Protocol::Udp, port)),
+            );
+        let (mut service, handle) = PortMappingService::new(config, ports);
+        let updates = UnboundedReceiverStream::new(service.updates());
+        service.spawn();
+
+        Self { handle, updates, tcp_port, discv4_port, discv5_port }
+    }
+}
+
 impl Stream for Discovery {
     type Item = DiscoveryEvent;
 
@@ -323,6 +447,7 @@ impl Discovery {
             _dns_discovery: None,
             dns_discovery_updates: None,
             _dns_disc_service: None,
+            nat_port_mapping: None,
             discovery_listeners: Default::default(),
         }
     }
@@ -347,6 +472,7 @@ mod tests {
             Default::default(),
             None,
             Default::default(),
+            None,
         )
         .await
         .unwrap();
@@ -377,6 +503,7 @@ mod tests {
             Some(discv4_config),
             Some(discv5_config),
             None,
+            None,
         )
         .await
         .expect("should build discv5 with discv4 downgrade")
@@ -0,0 +1,330 @@
+//! Budgets for serving `eth` requests.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use reth_network_peers::PeerId;
+
+use crate::metrics::EthRequestPeerMetrics;
+
+/// Default number of bytes a single peer can be served per second.
+pub const DEFAULT_PEER_BYTES_PER_SEC: u64 = 2 * 1024 * 1024;
+
+/// Default number of bytes a single peer can be served in a burst.
+pub const DEFAULT_PEER_BURST_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Default number of bytes all peers combined can be served per second.
+pub const DEFAULT_GLOBAL_BYTES_PER_SEC: u64 = 32 * 1024 * 1024;
+
+/// Default number of bytes all peers combined can be served in a burst.
+pub const DEFAULT_GLOBAL_BURST_BYTES: u64 = 64 * 1024 * 1024;
+
+/// How often the budgets of idle peers are dropped.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The minimum time between two penalties of a peer that exceeds its budget.
+const PENALTY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Limits for serving `eth` requests.
+///
+/// The cost of a request is the number of bytes served in its response. Each peer has its own
+/// token bucket, and all peers share a global one, so that a few peers syncing from the node can't
+/// saturate its disk I/O.
+///
+/// Requests that exceed a budget are deferred until it refilled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EthRequestLimits {
+    /// Number of bytes a single peer can be served per second.
+    pub peer_bytes_per_sec: u64,
+    /// Number of bytes a single peer can be served in a burst.
+    pub peer_burst_bytes: u64,
+    /// Number of bytes all peers combined can be served per second.
+    pub global_bytes_per_sec: u64,
+    /// Number of bytes all peers combined can be served in a burst.
+    pub global_burst_bytes: u64,
+}
+
+impl Default for EthRequestLimits {
+    fn default() -> Self {
+        Self {
+            peer_bytes_per_sec: DEFAULT_PEER_BYTES_PER_SEC,
+            peer_burst_bytes: DEFAULT_PEER_BURST_BYTES,
+            global_bytes_per_sec: DEFAULT_GLOBAL_BYTES_PER_SEC,
+            global_burst_bytes: DEFAULT_GLOBAL_BURST_BYTES,
+        }
+    }
+}
+
+/// The budget for the next response to a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ServeBudget {
+    /// The response can be up to the given number of bytes.
+    Allowed(usize),
+    /// The peer exhausted its own budget.
+    ///
+    /// `penalize` is only set for the first limited request of the peer in a
+    /// [`PENALTY_INTERVAL`], so that a single burst doesn't get the peer banned.
+    PeerLimited {
+        /// Whether the peer should be penalized for the request.
+        penalize: bool,
+    },
+    /// The global budget is exhausted.
+    GlobalLimited,
+}
+
+/// Tracks the per peer and global budgets for serving `eth` requests.
+#[derive(Debug)]
+pub(crate) struct ServeLimiter {
+    limits: EthRequestLimits,
+    /// Budget shared by all peers.
+    global: TokenBucket,
+    /// Budgets of the peers that were served recently.
+    peers: HashMap<PeerId, PeerBudget>,
+    /// When the budgets of idle peers were last dropped.
+    last_prune: Instant,
+}
+
+impl ServeLimiter {
+    /// Creates a new limiter with full budgets.
+    pub(crate) fn new(limits: EthRequestLimits) -> Self {
+        let now = Instant::now();
+        Self {
+            limits,
+            global: TokenBucket::new(limits.global_burst_bytes, limits.global_bytes_per_sec, now),
+            peers: Default::default(),
+            last_prune: now,
+        }
+    }
+
+    /// Returns the budget for the next response to the peer.
+    pub(crate) fn acquire(&mut self, peer_id: PeerId, now: Instant) -> ServeBudget {
+        self.prune(now);
+
+        let limits = self.limits;
+        let peer =
+            self.peers.entry(peer_id).or_insert_with(|| PeerBudget::new(peer_id, limits, now));
+        peer.bucket.refill(now);
+        if peer.bucket.available() == 0 {
+            let penalize = peer.last_penalty.map_or(true, |last_penalty| {
+                now.saturating_duration_since(last_penalty) >= PENALTY_INTERVAL
+            });
+            if penalize {
+                peer.last_penalty = Some(now);
+            }
+            return ServeBudget::PeerLimited { penalize };
+        }
+
+        self.global.refill(now);
+        if self.global.available() == 0 {
+            return ServeBudget::GlobalLimited
+        }
+
+        let available = peer.bucket.available().min(self.global.available());
+        ServeBudget::Allowed(available.try_into().unwrap_or(usize::MAX))
+    }
+
+    /// Charges the bytes served to the peer to its own and the global budget.
+    pub(crate) fn record(&mut self, peer_id: &PeerId, bytes: usize) {
+        let bytes = bytes as u64;
+        self.global.consume(bytes);
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.bucket.consume(bytes);
+            peer.metrics.served_bytes_total.increment(bytes);
+        }
+    }
+
+    /// Records a request of the peer that was deferred because it exceeded its budget.
+    pub(crate) fn record_limited(&self, peer_id: &PeerId) {
+        if let Some(peer) = self.peers.get(peer_id) {
+            peer.metrics.limited_requests_total.increment(1);
+        }
+    }
+
+    /// Drops the budgets of idle peers, if the [`PRUNE_INTERVAL`] elapsed.
+    fn prune(&mut self, now: Instant) {
+        if now.saturating_duration_since(self.last_prune) < PRUNE_INTERVAL {
+            return
+        }
+        self.last_prune = now;
+        self.drop_idle_peers(now);
+    }
+
+    /// Drops the budgets of peers that refilled completely, since they are equal to a new budget.
+    fn drop_idle_peers(&mut self, now: Instant) {
+        self.peers.retain(|_, peer| {
+            peer.bucket.refill(now);
+            !peer.bucket.is_full()
+        });
+    }
+}
+
+/// The budget of a single peer.
+#[derive(Debug)]
+struct PeerBudget {
+    bucket: TokenBucket,
+    /// When the peer was last penalized for exceeding its budget.
+    last_penalty: Option<Instant>,
+    metrics: EthRequestPeerMetrics,
+}
+
+impl PeerBudget {
+    fn new(peer_id: PeerId, limits: EthRequestLimits, now: Instant) -> Self {
+        Self {
+            bucket: TokenBucket::new(limits.peer_burst_bytes, limits.peer_bytes_per_sec, now),
+            last_penalty: None,
+            metrics: EthRequestPeerMetrics::new_with_labels(&[("peer_id", peer_id.to_string())]),
+        }
+    }
+}
+
+/// A token bucket that holds up to `capacity` tokens and is refilled with `refill_per_sec` tokens
+/// per second.
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: u64,
+    refill_per_sec: u64,
+    tokens: u64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a new full bucket.
+    const fn new(capacity: u64, refill_per_sec: u64, now: Instant) -> Self {
+        Self { capacity, refill_per_sec, tokens: capacity, last_refill: now }
+    }
+
+    /// Adds the tokens accumulated since the last refill.
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let refill = (elapsed.as_secs_f64() * self.refill_per_sec as f64) as u64;
+        // only advance if tokens were added, so fractions of a token aren't lost
+        if refill > 0 {
+            self.tokens = self.tokens.saturating_add(refill).min(self.capacity);
+            self.last_refill = now;
+        }
+    }
+
+    /// Returns the number of available tokens.
+    const fn available(&self) -> u64 {
+        self.tokens
+    }
+
+    /// Returns `true` if the bucket is at capacity.
+    const fn is_full(&self) -> bool {
+        self.tokens >= self.capacity
+    }
+
+    /// Removes the given number of tokens, emptying the bucket if there are not enough.
+    fn consume(&mut self, tokens: u64) {
+        self.tokens = self.tokens.saturating_sub(tokens);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> EthRequestLimits {
+        EthRequestLimits {
+            peer_bytes_per_sec: 100,
+            peer_burst_bytes: 1_000,
+            global_bytes_per_sec: 1_000,
+            global_burst_bytes: 1_500,
+        }
+    }
+
+    #[test]
+    fn token_bucket_refills_up_to_capacity() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(1_000, 100, now);
+        bucket.consume(1_500);
+        assert_eq!(bucket.available(), 0);
+
+        bucket.refill(now + Duration::from_millis(5));
+        assert_eq!(bucket.available(), 0);
+
+        bucket.refill(now + Duration::from_secs(2));
+        assert_eq!(bucket.available(), 200);
+
+        bucket.refill(now + Duration::from_secs(60));
+        assert!(bucket.is_full());
+        assert_eq!(bucket.available(), 1_000);
+    }
+
+    #[test]
+    fn peer_limited() {
+        let now = Instant::now();
+        let mut limiter = ServeLimiter::new(limits());
+        let peer = PeerId::random();
+
+        assert_eq!(limiter.acquire(peer, now), ServeBudget::Allowed(1_000));
+        limiter.record(&peer, 1_200);
+        assert_eq!(limiter.acquire(peer, now), ServeBudget::PeerLimited { penalize: true });
+        // the peer is penalized only once per interval
+        assert_eq!(limiter.acquire(peer, now), ServeBudget::PeerLimited { penalize: false });
+
+        // the budget of other peers is not affected
+        let other = PeerId::random();
+        assert_eq!(limiter.acquire(other, now), ServeBudget::Allowed(300));
+
+        // the peer can be served again once its budget refilled
+        assert_eq!(limiter.acquire(peer, now + Duration::from_secs(1)), ServeBudget::Allowed(100));
+    }
+
+    #[test]
+    fn peer_penalized_once_per_interval() {
+        let now = Instant::now();
+        let mut limiter = ServeLimiter::new(EthRequestLimits { peer_bytes_per_sec: 0, ..limits() });
+        let peer = PeerId::random();
+
+        limiter.acquire(peer, now);
+        limiter.record(&peer, 1_000);
+        assert_eq!(limiter.acquire(peer, now), ServeBudget::PeerLimited { penalize: true });
+        assert_eq!(
+            limiter.acquire(peer, now + PENALTY_INTERVAL / 2),
+            ServeBudget::PeerLimited { penalize: false }
+        );
+        assert_eq!(
+            limiter.acquire(peer, now + PENALTY_INTERVAL),
+            ServeBudget::PeerLimited { penalize: true }
+        );
+    }
+
+    #[test]
+    fn global_limited() {
+        let now = Instant::now();
+        let mut limiter = ServeLimiter::new(limits());
+        let peer1 = PeerId::random();
+        let peer2 = PeerId::random();
+
+        assert_eq!(limiter.acquire(peer1, now), ServeBudget::Allowed(1_000));
+        limiter.record(&peer1, 800);
+        assert_eq!(limiter.acquire(peer2, now), ServeBudget::Allowed(700));
+        limiter.record(&peer2, 700);
+
+        assert_eq!(limiter.acquire(peer2, now), ServeBudget::GlobalLimited);
+        assert_eq!(limiter.acquire(peer1, now), ServeBudget::GlobalLimited);
+    }
+
+    #[test]
+    fn prune_idle_peers() {
+        let now = Instant::now();
+        let mut limiter = ServeLimiter::new(limits());
+        let peer1 = PeerId::random();
+        let peer2 = PeerId::random();
+
+        limiter.acquire(peer1, now);
+        limiter.acquire(peer2, now);
+        limiter.record(&peer2, 1_000);
+
+        // peer1 didn't use its budget, peer2 needs 10 seconds to refill
+        limiter.drop_idle_peers(now + Duration::from_secs(1));
+        assert!(!limiter.peers.contains_key(&peer1));
+        assert!(limiter.peers.contains_key(&peer2));
+
+        limiter.drop_idle_peers(now + Duration::from_secs(10));
+        assert!(limiter.peers.is_empty());
+    }
+}
@@ -0,0 +1,632 @@
+//! Blocks/Headers management for the p2p network.
+
+mod limits;
+
+pub use limits::{
+    EthRequestLimits, DEFAULT_GLOBAL_BURST_BYTES, DEFAULT_GLOBAL_BYTES_PER_SEC,
+    DEFAULT_PEER_BURST_BYTES, DEFAULT_PEER_BYTES_PER_SEC,
+};
+
+use std::{
+    collections::{HashSet, VecDeque},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use alloy_rlp::Encodable;
+use futures::{FutureExt, StreamExt};
+use reth_eth_wire::{
+    BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders, GetNodeData, GetReceipts,
+    HeadersDirection, NodeData, Receipt69, Receipts, Receipts69,
+};
+use reth_network_api::test_utils::PeersHandle;
+use reth_network_p2p::error::RequestResult;
+use reth_network_peers::PeerId;
+use reth_network_types::ReputationChangeKind;
+use reth_primitives::{BlockBody, BlockHashOrNumber, Header, Receipt};
+use reth_storage_api::{BlockReader, HeaderProvider, ReceiptProvider};
+use tokio::{
+    sync::{mpsc::Receiver, oneshot},
+    time::Sleep,
+};
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::{
+    budget::DEFAULT_BUDGET_TRY_DRAIN_DOWNLOADERS,
+    eth_requests::limits::{ServeBudget, ServeLimiter},
+    metered_poll_nested_stream_with_budget,
+    metrics::EthRequestHandlerMetrics,
+};
+
+// Limits: <https://github.com/ethereum/go-ethereum/blob/b0d44338bbcefee044f1f635a84487cbbd8f0538/eth/protocols/eth/handler.go#L34-L56>
+
+/// Maximum number of receipts to serve.
+///
+/// Used to limit lookups.
+const MAX_RECEIPTS_SERVE: usize = 1024;
+
+/// Maximum number of block headers to serve.
+///
+/// Used to limit lookups.
+const MAX_HEADERS_SERVE: usize = 1024;
+
+/// Maximum number of block headers to serve.
+///
+/// Used to limit lookups. With 24KB block sizes nowadays, the practical limit will always be
+/// `SOFT_RESPONSE_LIMIT`.
+const MAX_BODIES_SERVE: usize = 1024;
+
+/// Maximum size of replies to data retrievals.
+const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Maximum number of requests that wait for a budget to refill.
+///
+/// Requests received while the queue is full are dropped.
+const MAX_DEFERRED_REQUESTS: usize = 1024;
+
+/// Interval at which deferred requests are retried.
+const DEFERRED_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Manages eth related requests on top of the p2p network.
+///
+/// This can be spawned to another task and is supposed to be run as background service.
+#[derive(Debug)]
+#[must_use = "Manager does nothing unless polled."]
+pub struct EthRequestHandler<C> {
+    /// The client type that can interact with the chain.
+    client: C,
+    /// Used for reporting peers that exceed their serving budget.
+    peers: PeersHandle,
+    /// Incoming request from the [`NetworkManager`](crate::NetworkManager).
+    incoming_requests: ReceiverStream<IncomingEthRequest>,
+    /// Budgets for serving requests, if limited.
+    limiter: Option<ServeLimiter>,
+    /// Requests that exceeded a budget and are served once it refilled, in order of arrival.
+    deferred: VecDeque<IncomingEthRequest>,
+    /// Timer for retrying the deferred requests, set while requests are deferred.
+    retry_timer: Option<Pin<Box<Sleep>>>,
+    /// Metrics for the eth request handler.
+    metrics: EthRequestHandlerMetrics,
+}
+
+// === impl EthRequestHandler ===
+impl<C> EthRequestHandler<C> {
+    /// Create a new instance that serves requests without limits.
+    pub fn new(client: C, peers: PeersHandle, incoming: Receiver<IncomingEthRequest>) -> Self {
+        Self {
+            client,
+            peers,
+            incoming_requests: ReceiverStream::new(incoming),
+            limiter: None,
+            deferred: Default::default(),
+            retry_timer: None,
+            metrics: Default::default(),
+        }
+    }
+
+    /// Sets the limits for serving requests.
+    pub fn with_limits(mut self, limits: EthRequestLimits) -> Self {
+        self.limiter = Some(ServeLimiter::new(limits));
+        self
+    }
+
+    /// Returns the maximum number of bytes to serve to the peer for its next request, or `None`
+    /// if the request must wait because a budget is exhausted.
+    ///
+    /// Peers that exceeded their own budget with a new request are penalized, at most once per
+    /// penalty interval. Retries of deferred requests are not penalized or counted again.
+    fn acquire_budget(&mut self, peer_id: PeerId, deferred: bool) -> Option<usize> {
+        let Some(limiter) = &mut self.limiter else { return Some(SOFT_RESPONSE_LIMIT) };
+        match limiter.acquire(peer_id, Instant::now()) {
+            ServeBudget::Allowed(bytes) => Some(bytes.min(SOFT_RESPONSE_LIMIT)),
+            ServeBudget::PeerLimited { penalize } => {
+                if !deferred {
+                    self.metrics.eth_requests_peer_limited_total.increment(1);
+                    limiter.record_limited(&peer_id);
+                    if penalize {
+                        self.peers
+                            .reputation_change(peer_id, ReputationChangeKind::ExceededRequestLimit);
+                    }
+                }
+                None
+            }
+            ServeBudget::GlobalLimited => {
+                if !deferred {
+                    self.metrics.eth_requests_global_limited_total.increment(1);
+                }
+                None
+            }
+        }
+    }
+
+    /// Queues a request that exceeded a budget, so it's served once the budget refilled.
+    fn defer(&mut self, request: IncomingEthRequest) {
+        if self.deferred.len() >= MAX_DEFERRED_REQUESTS {
+            // dropping the response channel lets the session skip the request
+            self.metrics.eth_requests_dropped_total.increment(1);
+            return
+        }
+        self.deferred.push_back(request);
+    }
+
+    /// Returns `true` if a request of the peer is waiting for a budget to refill.
+    fn has_deferred(&self, peer_id: PeerId) -> bool {
+        self.deferred.iter().any(|request| request.peer_id() == peer_id)
+    }
+}
+
+impl<C> EthRequestHandler<C>
+where
+    C: BlockReader + HeaderProvider + ReceiptProvider,
+{
+    /// Handles a request received from the network.
+    fn on_request(&mut self, request: IncomingEthRequest) {
+        match &request {
+            IncomingEthRequest::GetBlockHeaders { .. } => {
+                self.metrics.eth_headers_requests_received_total.increment(1)
+            }
+            IncomingEthRequest::GetBlockBodies { .. } => {
+                self.metrics.eth_bodies_requests_received_total.increment(1)
+            }
+            IncomingEthRequest::GetNodeData { .. } => {
+                self.metrics.eth_node_data_requests_received_total.increment(1);
+                return
+            }
+            IncomingEthRequest::GetReceipts { .. } | IncomingEthRequest::GetReceipts69 { .. } => {
+                self.metrics.eth_receipts_requests_received_total.increment(1)
+            }
+        }
+
+        // new requests of a peer must not overtake its deferred ones
+        if self.has_deferred(request.peer_id()) {
+            self.defer(request);
+        } else if let Err(request) = self.try_serve(request, false) {
+            self.defer(request);
+        }
+    }
+
+    /// Retries the deferred requests in order of arrival.
+    ///
+    /// Once a request of a peer can't be served, its later requests are kept waiting. Requests
+    /// whose session is gone are dropped.
+    fn retry_deferred(&mut self) {
+        let mut limited = HashSet::new();
+        for request in std::mem::take(&mut self.deferred) {
+            if request.is_closed() {
+                continue
+            }
+            let peer_id = request.peer_id();
+            if limited.contains(&peer_id) {
+                self.deferred.push_back(request);
+            } else if let Err(request) = self.try_serve(request, true) {
+                limited.insert(peer_id);
+                self.deferred.push_back(request);
+            }
+        }
+    }
+
+    /// Serves the request, or returns it if a budget is exhausted.
+    fn try_serve(
+        &mut self,
+        request: IncomingEthRequest,
+        deferred: bool,
+    ) -> Result<(), IncomingEthRequest> {
+        let Some(max_bytes) = self.acquire_budget(request.peer_id(), deferred) else {
+            return Err(request)
+        };
+        match request {
+            IncomingEthRequest::GetBlockHeaders { peer_id, request, response } => {
+                self.on_headers_request(peer_id, request, response, max_bytes)
+            }
+            IncomingEthRequest::GetBlockBodies { peer_id, request, response } => {
+                self.on_bodies_request(peer_id, request, response, max_bytes)
+            }
+            IncomingEthRequest::GetNodeData { .. } => {}
+            IncomingEthRequest::GetReceipts { peer_id, request, response } => {
+                self.on_receipts_request(peer_id, request, response, max_bytes)
+            }
+            IncomingEthRequest::GetReceipts69 { peer_id, request, response } => {
+                self.on_receipts69_request(peer_id, request, response, max_bytes)
+            }
+        }
+        Ok(())
+    }
+
+    /// Charges the bytes served to the peer to the budgets, if limited.
+    fn record_served(&mut self, peer_id: PeerId, served_bytes: usize) {
+        if let Some(limiter) = &mut self.limiter {
+            limiter.record(&peer_id, served_bytes);
+        }
+    }
+
+    /// Returns the list of requested headers, up to `max_bytes`
+    fn get_headers_response(&self, request: GetBlockHeaders, max_bytes: usize) -> Vec<Header> {
+        let GetBlockHeaders { start_block, limit, skip, direction } = request;
+
+        let mut headers = Vec::new();
+
+        let mut block: BlockHashOrNumber = match start_block {
+            BlockHashOrNumber::Hash(start) => start.into(),
+            BlockHashOrNumber::Number(num) => {
+                let Some(hash) = self.client.block_hash(num).unwrap_or_default() else {
+                    return headers
+                };
+                hash.into()
+            }
+        };
+
+        let skip = skip as u64;
+        let mut total_bytes = 0;
+
+        for _ in 0..limit {
+            if let Some(header) = self.client.header_by_hash_or_number(block).unwrap_or_default() {
+                match direction {
+                    HeadersDirection::Rising => {
+                        if let Some(next) = (header.number + 1).checked_add(skip) {
+                            block = next.into()
+                        } else {
+                            break
+                        }
+                    }
+                    HeadersDirection::Falling => {
+                        if skip > 0 {
+                            // prevent under flows for block.number == 0 and `block.number - skip <
+                            // 0`
+                            if let Some(next) =
+                                header.number.checked_sub(1).and_then(|num| num.checked_sub(skip))
+                            {
+                                block = next.into()
+                            } else {
+                                break
+                            }
+                        } else {
+                            block = header.parent_hash.into()
+                        }
+                    }
+                }
+
+                total_bytes += header.length();
+                headers.push(header);
+
+                if headers.len() >= MAX_HEADERS_SERVE || total_bytes > max_bytes {
+                    break
+                }
+            } else {
+                break
+            }
+        }
+
+        headers
+    }
+
+    fn on_headers_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetBlockHeaders,
+        response: oneshot::Sender<RequestResult<BlockHeaders>>,
+        max_bytes: usize,
+    ) {
+        let headers = BlockHeaders(self.get_headers_response(request, max_bytes));
+
+        let served_bytes = headers.length();
+        self.record_served(peer_id, served_bytes);
+        self.metrics.eth_headers_served_bytes_total.increment(served_bytes as u64);
+
+        let _ = response.send(Ok(headers));
+    }
+
+    fn on_bodies_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetBlockBodies,
+        response: oneshot::Sender<RequestResult<BlockBodies>>,
+        max_bytes: usize,
+    ) {
+        let mut bodies = Vec::new();
+
+        let mut total_bytes = 0;
+
+        for hash in request.0 {
+            if let Some(block) = self.client.block_by_hash(hash).unwrap_or_default() {
+                let body: BlockBody = block.into();
+
+                total_bytes += body.length();
+                bodies.push(body);
+
+                if bodies.len() >= MAX_BODIES_SERVE || total_bytes > max_bytes {
+                    break
+                }
+            } else {
+                break
+            }
+        }
+
+        let bodies = BlockBodies(bodies);
+
+        let served_bytes = bodies.length();
+        self.record_served(peer_id, served_bytes);
+        self.metrics.eth_bodies_served_bytes_total.increment(served_bytes as u64);
+
+        let _ = response.send(Ok(bodies));
+    }
+
+    fn on_receipts_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetReceipts,
+        response: oneshot::Sender<RequestResult<Receipts>>,
+        max_bytes: usize,
+    ) {
+        let receipts = Receipts(
+            self.get_receipts_response(request, max_bytes, |receipt| receipt.with_bloom()),
+        );
+
+        self.record_receipts_served(peer_id, receipts.length());
+
+        let _ = response.send(Ok(receipts));
+    }
+
+    fn on_receipts69_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetReceipts,
+        response: oneshot::Sender<RequestResult<Receipts69>>,
+        max_bytes: usize,
+    ) {
+        // `eth/69` receipts don't include the bloom, so there is no need to compute it
+        let receipts = Receipts69(self.get_receipts_response(request, max_bytes, Receipt69::from));
+
+        self.record_receipts_served(peer_id, receipts.length());
+
+        let _ = response.send(Ok(receipts));
+    }
+
+    fn record_receipts_served(&mut self, peer_id: PeerId, served_bytes: usize) {
+        self.record_served(peer_id, served_bytes);
+        self.metrics.eth_receipts_served_bytes_total.increment(served_bytes as u64);
+    }
+
+    /// Looks up the receipts of the requested blocks and converts them with `transform`, up to
+    /// `max_bytes`.
+    fn get_receipts_response<T, F>(
+        &self,
+        request: GetReceipts,
+        max_bytes: usize,
+        transform: F,
+    ) -> Vec<Vec<T>>
+    where
+        T: Encodable,
+        F: Fn(Receipt) -> T,
+    {
+        let mut receipts = Vec::new();
+
+        let mut total_bytes = 0;
+
+        for hash in request.0 {
+            if let Some(receipts_by_block) =
+                self.client.receipts_by_block(BlockHashOrNumber::Hash(hash)).unwrap_or_default()
+            {
+                let receipt = receipts_by_block.into_iter().map(&transform).collect::<Vec<_>>();
+
+                total_bytes += receipt.length();
+                receipts.push(receipt);
+
+                if receipts.len() >= MAX_RECEIPTS_SERVE || total_bytes > max_bytes {
+                    break
+                }
+            } else {
+                break
+            }
+        }
+
+        receipts
+    }
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<C> Future for EthRequestHandler<C>
+where
+    C: BlockReader + HeaderProvider + Unpin,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if this.retry_timer.as_mut().is_some_and(|timer| timer.poll_unpin(cx).is_ready()) {
+            this.retry_timer = None;
+            this.retry_deferred();
+        }
+
+        let mut acc = Duration::ZERO;
+        let maybe_more_incoming_requests = metered_poll_nested_stream_with_budget!(
+            acc,
+            "net::eth",
+            "Incoming eth requests stream",
+            DEFAULT_BUDGET_TRY_DRAIN_DOWNLOADERS,
+            this.incoming_requests.poll_next_unpin(cx),
+            |incoming| this.on_request(incoming),
+        );
+
+        this.metrics.acc_duration_poll_eth_req_handler.set(acc.as_secs_f64());
+        this.metrics.eth_requests_deferred.set(this.deferred.len() as f64);
+
+        // retry the deferred requests until all of them are served
+        if !this.deferred.is_empty() {
+            let timer = this
+                .retry_timer
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep(DEFERRED_RETRY_INTERVAL)));
+            if timer.poll_unpin(cx).is_ready() {
+                cx.waker().wake_by_ref();
+            }
+        }
+
+        // stream is fully drained and import futures pending
+        if maybe_more_incoming_requests {
+            // make sure we're woken up again
+            cx.waker().wake_by_ref();
+            return Poll::Pending
+        }
+
+        Poll::Pending
+    }
+}
+
+/// All `eth` request related to blocks delegated by the network.
+#[derive(Debug)]
+pub enum IncomingEthRequest {
+    /// Request Block headers from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetBlockHeaders {
+        /// The ID of the peer to request block headers from.
+        peer_id: PeerId,
+        /// The specific block headers requested.
+        request: GetBlockHeaders,
+        /// The channel sender for the response containing block headers.
+        response: oneshot::Sender<RequestResult<BlockHeaders>>,
+    },
+    /// Request Block bodies from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetBlockBodies {
+        /// The ID of the peer to request block bodies from.
+        peer_id: PeerId,
+        /// The specific block bodies requested.
+        request: GetBlockBodies,
+        /// The channel sender for the response containing block bodies.
+        response: oneshot::Sender<RequestResult<BlockBodies>>,
+    },
+    /// Request Node Data from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetNodeData {
+        /// The ID of the peer to request node data from.
+        peer_id: PeerId,
+        /// The specific node data requested.
+        request: GetNodeData,
+        /// The channel sender for the response containing node data.
+        response: oneshot::Sender<RequestResult<NodeData>>,
+    },
+    /// Request Receipts from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetReceipts {
+        /// The ID of the peer to request receipts from.
+        peer_id: PeerId,
+        /// The specific receipts requested.
+        request: GetReceipts,
+        /// The channel sender for the response containing receipts.
+        response: oneshot::Sender<RequestResult<Receipts>>,
+    },
+    /// Request Receipts without bloom from an `eth/69` peer.
+    ///
+    /// The response should be sent through the channel.
+    GetReceipts69 {
+        /// The ID of the peer to request receipts from.
+        peer_id: PeerId,
+        /// The specific receipts requested.
+        request: GetReceipts,
+        /// The channel sender for the response containing receipts.
+        response: oneshot::Sender<RequestResult<Receipts69>>,
+    },
+}
+
+impl IncomingEthRequest {
+    /// Returns the ID of the peer that sent the request.
+    pub const fn peer_id(&self) -> PeerId {
+        match self {
+            Self::GetBlockHeaders { peer_id, .. } |
+            Self::GetBlockBodies { peer_id, .. } |
+            Self::GetNodeData { peer_id, .. } |
+            Self::GetReceipts { peer_id, .. } |
+            Self::GetReceipts69 { peer_id, .. } => *peer_id,
+        }
+    }
+
+    /// Returns `true` if the response can no longer be sent, because the session is gone.
+    pub fn is_closed(&self) -> bool {
+        match self {
+            Self::GetBlockHeaders { response, .. } => response.is_closed(),
+            Self::GetBlockBodies { response, .. } => response.is_closed(),
+            Self::GetNodeData { response, .. } => response.is_closed(),
+            Self::GetReceipts { response, .. } => response.is_closed(),
+            Self::GetReceipts69 { response, .. } => response.is_closed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peers::PeersManager;
+    use reth_network_types::PeersConfig;
+    use reth_primitives::B256;
+    use reth_provider::test_utils::MockEthProvider;
+    use tokio::sync::mpsc;
+
+    fn headers_request(
+        peer_id: PeerId,
+        hash: B256,
+    ) -> (IncomingEthRequest, oneshot::Receiver<RequestResult<BlockHeaders>>) {
+        let (response, rx) = oneshot::channel();
+        let request = GetBlockHeaders {
+            start_block: hash.into(),
+            limit: 1,
+            skip: 0,
+            direction: HeadersDirection::Rising,
+        };
+        (IncomingEthRequest::GetBlockHeaders { peer_id, request, response }, rx)
+    }
+
+    #[tokio::test]
+    async fn defers_requests_over_budget() {
+        let client = MockEthProvider::default();
+        let hash = B256::with_last_byte(1);
+        client.add_header(hash, Header::default());
+
+        let (tx, rx) = mpsc::channel(10);
+        let peers = PeersManager::new(PeersConfig::default()).handle();
+        // a single header exhausts the peer's burst, which refills within the retry interval
+        let limits = EthRequestLimits {
+            peer_bytes_per_sec: 10_000,
+            peer_burst_bytes: 100,
+            ..Default::default()
+        };
+        let handler = EthRequestHandler::new(client, peers, rx).with_limits(limits);
+        tokio::spawn(handler);
+
+        let peer_id = PeerId::random();
+        let (first, first_rx) = headers_request(peer_id, hash);
+        let (second, mut second_rx) = headers_request(peer_id, hash);
+        tx.send(first).await.unwrap();
+        tx.send(second).await.unwrap();
+
+        assert_eq!(first_rx.await.unwrap().unwrap().0.len(), 1);
+        // the second request is not answered with an empty response, but once the budget refilled
+        assert!(second_rx.try_recv().is_err());
+        assert_eq!(second_rx.await.unwrap().unwrap().0.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn serves_without_limits() {
+        let client = MockEthProvider::default();
+        let hash = B256::with_last_byte(1);
+        client.add_header(hash, Header::default());
+
+        let (tx, rx) = mpsc::channel(10);
+        let peers = PeersManager::new(PeersConfig::default()).handle();
+        tokio::spawn(EthRequestHandler::new(client, peers, rx));
+
+        let peer_id = PeerId::random();
+        for _ in 0..10 {
+            let (request, response) = headers_request(peer_id, hash);
+            tx.send(request).await.unwrap();
+            assert_eq!(response.await.unwrap().unwrap().0.len(), 1);
+        }
+    }
+}
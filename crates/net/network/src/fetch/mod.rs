@@ -6,6 +6,7 @@ pub use client::FetchClient;
 
 use std::{
     collections::{HashMap, VecDeque},
+    ops::RangeInclusive,
     sync::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc,
@@ -14,7 +15,7 @@ use std::{
 };
 
 use futures::StreamExt;
-use reth_eth_wire::{GetBlockBodies, GetBlockHeaders};
+use reth_eth_wire::{BlockRangeUpdate, GetBlockBodies, GetBlockHeaders};
 use reth_network_api::test_utils::PeersHandle;
 use reth_network_p2p::{
     error::{EthResponseValidator, PeerRequestResult, RequestError, RequestResult},
@@ -23,7 +24,7 @@ use reth_network_p2p::{
 };
 use reth_network_peers::PeerId;
 use reth_network_types::ReputationChangeKind;
-use reth_primitives::{BlockBody, Header, B256};
+use reth_primitives::{BlockBody, BlockHashOrNumber, Header, B256};
 use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
@@ -90,6 +91,7 @@ impl StateFetcher {
                 best_number,
                 timeout,
                 last_response_likely_bad: false,
+                block_range: None,
             },
         );
     }
@@ -124,6 +126,21 @@ impl StateFetcher {
         false
     }
 
+    /// Updates the range of blocks the peer is able to serve, announced by `eth/69` peers.
+    pub(crate) fn update_peer_block_range(
+        &mut self,
+        peer_id: &PeerId,
+        block_range: BlockRangeUpdate,
+    ) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            if block_range.latest > peer.best_number {
+                peer.best_hash = block_range.latest_hash;
+                peer.best_number = block_range.latest;
+            }
+            peer.block_range = Some(block_range);
+        }
+    }
+
     /// Invoked when an active session is about to be disconnected.
     pub(crate) fn on_pending_disconnect(&mut self, peer_id: &PeerId) {
         if let Some(peer) = self.peers.get_mut(peer_id) {
@@ -134,8 +151,13 @@ impl StateFetcher {
     /// Returns the _next_ idle peer that's ready to accept a request,
     /// prioritizing those with the lowest timeout/latency and those that recently responded with
     /// adequate data.
-    fn next_best_peer(&self) -> Option<PeerId> {
-        let mut idle = self.peers.iter().filter(|(_, peer)| peer.state.is_idle());
+    ///
+    /// If a `block` is given, peers that announced they can't serve it are skipped.
+    fn next_best_peer(&self, block: Option<u64>) -> Option<PeerId> {
+        let mut idle = self
+            .peers
+            .iter()
+            .filter(|(_, peer)| peer.state.is_idle() && block.map_or(true, |b| peer.can_serve(b)));
 
         let mut best_peer = idle.next()?;
 
@@ -164,7 +186,8 @@ impl StateFetcher {
             return PollAction::NoRequests
         }
 
-        let Some(peer_id) = self.next_best_peer() else { return PollAction::NoPeersAvailable };
+        let block = self.queued_requests.front().and_then(|req| req.block_number());
+        let Some(peer_id) = self.next_best_peer(block) else { return PollAction::NoPeersAvailable };
 
         let request = self.queued_requests.pop_front().expect("not empty");
         let request = self.prepare_block_request(peer_id, request);
@@ -348,12 +371,22 @@ struct Peer {
     /// downloaded), but we still want to avoid requesting from the same peer again if it has the
     /// lowest timeout.
     last_response_likely_bad: bool,
+    /// The range of blocks the peer announced it can serve, only known for `eth/69` peers.
+    block_range: Option<BlockRangeUpdate>,
 }
 
 impl Peer {
     fn timeout(&self) -> u64 {
         self.timeout.load(Ordering::Relaxed)
     }
+
+    /// Returns `false` if the peer announced that it no longer serves the given block.
+    ///
+    /// Only the earliest block is checked, since the peer may have imported blocks after its
+    /// latest announced block.
+    fn can_serve(&self, block: u64) -> bool {
+        self.block_range.map_or(true, |range| range.earliest <= block)
+    }
 }
 
 /// Tracks the state of an individual peer
@@ -416,6 +449,8 @@ pub(crate) enum DownloadRequest {
         request: Vec<B256>,
         response: oneshot::Sender<PeerRequestResult<Vec<BlockBody>>>,
         priority: Priority,
+        /// The range of block numbers the requested hashes belong to, if known.
+        range_hint: Option<RangeInclusive<u64>>,
     },
 }
 
@@ -430,6 +465,20 @@ impl DownloadRequest {
         }
     }
 
+    /// Returns the number of the first requested block, if known.
+    fn block_number(&self) -> Option<u64> {
+        match self {
+            Self::GetBlockHeaders {
+                request: HeadersRequest { start: BlockHashOrNumber::Number(number), .. },
+                ..
+            } => Some(*number),
+            Self::GetBlockBodies { range_hint, .. } => {
+                range_hint.as_ref().map(|range| *range.start())
+            }
+            _ => None,
+        }
+    }
+
     /// Returns the requested priority of this request
     const fn get_priority(&self) -> &Priority {
         match self {
@@ -486,6 +535,7 @@ mod tests {
                 request: vec![],
                 response: tx,
                 priority: Priority::default(),
+                range_hint: None,
             });
             assert!(fetcher.poll(cx).is_pending());
 
@@ -504,17 +554,17 @@ mod tests {
         fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(1)));
         fetcher.new_active_peer(peer2, B256::random(), 2, Arc::new(AtomicU64::new(1)));
 
-        let first_peer = fetcher.next_best_peer().unwrap();
+        let first_peer = fetcher.next_best_peer(None).unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
         // Pending disconnect for first_peer
         fetcher.on_pending_disconnect(&first_peer);
         // first_peer now isn't idle, so we should get other peer
-        let second_peer = fetcher.next_best_peer().unwrap();
+        let second_peer = fetcher.next_best_peer(None).unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
         assert_ne!(first_peer, second_peer);
         // without idle peers, returns None
         fetcher.on_pending_disconnect(&second_peer);
-        assert_eq!(fetcher.next_best_peer(), None);
+        assert_eq!(fetcher.next_best_peer(None), None);
     }
 
     #[tokio::test]
@@ -533,13 +583,66 @@ mod tests {
         fetcher.new_active_peer(peer3, B256::random(), 3, Arc::new(AtomicU64::new(50)));
 
         // Must always get peer1 (lowest timeout)
-        assert_eq!(fetcher.next_best_peer(), Some(peer1));
-        assert_eq!(fetcher.next_best_peer(), Some(peer1));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer1));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer1));
         // peer2's timeout changes below peer1's
         peer2_timeout.store(10, Ordering::Relaxed);
         // Then we get peer 2 always (now lowest)
-        assert_eq!(fetcher.next_best_peer(), Some(peer2));
-        assert_eq!(fetcher.next_best_peer(), Some(peer2));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer2));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer2));
+    }
+
+    #[tokio::test]
+    async fn test_peer_block_range() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let peer1 = B512::random();
+        let peer2 = B512::random();
+
+        fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(30)));
+        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::new(AtomicU64::new(50)));
+
+        // peer1 has the lowest timeout but pruned everything below block 100
+        let latest_hash = B256::random();
+        fetcher.update_peer_block_range(
+            &peer1,
+            BlockRangeUpdate { earliest: 100, latest: 200, latest_hash },
+        );
+        assert_eq!(fetcher.peers[&peer1].best_number, 200);
+        assert_eq!(fetcher.peers[&peer1].best_hash, latest_hash);
+
+        assert_eq!(fetcher.next_best_peer(Some(50)), Some(peer2));
+        assert_eq!(fetcher.next_best_peer(Some(100)), Some(peer1));
+        assert_eq!(fetcher.next_best_peer(Some(300)), Some(peer1));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer1));
+    }
+
+    #[tokio::test]
+    async fn test_bodies_request_block_range() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let peer1 = B512::random();
+        let peer2 = B512::random();
+
+        fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(30)));
+        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::new(AtomicU64::new(50)));
+        fetcher.update_peer_block_range(
+            &peer1,
+            BlockRangeUpdate { earliest: 100, latest: 200, latest_hash: B256::random() },
+        );
+
+        let (tx, _rx) = oneshot::channel();
+        fetcher.queued_requests.push_back(DownloadRequest::GetBlockBodies {
+            request: vec![B256::random()],
+            response: tx,
+            priority: Priority::default(),
+            range_hint: Some(50..=60),
+        });
+        let PollAction::Ready(FetchAction::BlockRequest { peer_id, .. }) = fetcher.poll_action()
+        else {
+            panic!("expected a request")
+        };
+        assert_eq!(peer_id, peer2);
     }
 
     #[tokio::test]
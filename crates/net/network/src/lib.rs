@@ -130,6 +130,7 @@ mod discovery;
 mod fetch;
 mod flattened_response;
 mod listener;
+mod managed_peers;
 mod manager;
 mod metrics;
 mod network;
@@ -143,7 +144,10 @@ pub use reth_network_api::{
     NetworkEventListenerProvider, NetworkInfo, PeerRequest, PeerRequestSender, Peers, PeersInfo,
 };
 pub use reth_network_p2p::sync::{NetworkSyncUpdater, SyncState};
-pub use reth_network_types::{PeersConfig, SessionsConfig};
+pub use reth_network_types::{
+    session::config::{DEFAULT_CAPTURE_MAX_FILES, DEFAULT_CAPTURE_MAX_SIZE},
+    PeersConfig, SessionsConfig, Socks5Proxy,
+};
 pub use session::{
     ActiveSessionHandle, ActiveSessionMessage, Direction, EthRlpxConnection, PeerInfo,
     PendingSessionEvent, PendingSessionHandle, PendingSessionHandshakeError, SessionCommand,
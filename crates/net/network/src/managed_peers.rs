@@ -0,0 +1,119 @@
+//! Support for the peers config file with trusted, static and banned peers, see [`ManagedPeers`].
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use futures::future::join_all;
+use reth_network_peers::{NodeRecord, TrustedPeer};
+use reth_network_types::ManagedPeers;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use crate::network::NetworkHandleMessage;
+
+/// Interval at which the peers config file is checked for changes.
+pub(crate) const PEERS_CONFIG_FILE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The peers config file and the peers it configures.
+#[derive(Debug)]
+pub(crate) struct PeersConfigFile {
+    /// Path to the TOML file.
+    pub(crate) path: PathBuf,
+    /// The peers as last read from or written to the file.
+    pub(crate) peers: ManagedPeers,
+}
+
+/// Resolves the hosts of the trusted and static peers, peers that can't be resolved are skipped.
+pub(crate) async fn resolve(peers: &ManagedPeers) -> ManagedPeers<NodeRecord> {
+    async fn resolve_all(peers: &[TrustedPeer]) -> Vec<NodeRecord> {
+        let resolved = join_all(peers.iter().map(|peer| peer.resolve())).await;
+        resolved
+            .into_iter()
+            .zip(peers)
+            .filter_map(|(record, peer)| match record {
+                Ok(record) => Some(record),
+                Err(err) => {
+                    warn!(target: "net::peers", %peer, ?err, "Failed to resolve configured peer");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    ManagedPeers {
+        trusted: resolve_all(&peers.trusted).await,
+        static_peers: resolve_all(&peers.static_peers).await,
+        banned: peers.banned.clone(),
+    }
+}
+
+/// Returns the modification time of the file, `None` if it does not exist.
+pub(crate) fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Checks the peers config file for changes on every interval and sends the reloaded peers to the
+/// [`NetworkManager`](crate::NetworkManager).
+///
+/// Returns once the manager is dropped.
+pub(crate) async fn watch(
+    path: PathBuf,
+    interval: Duration,
+    mut last_modified: Option<SystemTime>,
+    to_manager: mpsc::UnboundedSender<NetworkHandleMessage>,
+) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        if to_manager.is_closed() {
+            return
+        }
+
+        let modified = modified(&path);
+        if modified == last_modified {
+            continue
+        }
+        last_modified = modified;
+
+        match ManagedPeers::load(&path) {
+            Ok(peers) => {
+                debug!(target: "net::peers", path=%path.display(), "reloading peers config file");
+                let resolved = resolve(&peers).await;
+                let _ = to_manager.send(NetworkHandleMessage::ReloadManagedPeers(peers, resolved));
+            }
+            Err(err) => {
+                warn!(target: "net::peers", path=%path.display(), %err, "Failed to read peers config file");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_network_peers::PeerId;
+
+    #[tokio::test]
+    async fn reload_on_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("peers.toml");
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tokio::spawn(watch(path.clone(), Duration::from_millis(10), None, tx));
+
+        let mut peers = ManagedPeers::default();
+        peers.static_peers.push("enode://6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0@127.0.0.1:30303".parse().unwrap());
+        peers.banned.push(PeerId::random());
+        peers.save(&path).unwrap();
+
+        let Some(NetworkHandleMessage::ReloadManagedPeers(reloaded, resolved)) = rx.recv().await
+        else {
+            panic!("expected reloaded peers")
+        };
+        assert_eq!(reloaded, peers);
+        assert_eq!(resolved.static_peers[0].id, peers.static_peers[0].id);
+        assert_eq!(resolved.banned, peers.banned);
+    }
+}
@@ -16,6 +16,7 @@
 //! to the local node. Once a (tcp) connection is established, both peers start to authenticate a [RLPx session](https://github.com/ethereum/devp2p/blob/master/rlpx.md) via a handshake. If the handshake was successful, both peers announce their capabilities and are now ready to exchange sub-protocol messages via the `RLPx` session.
 
 use std::{
+    io,
     net::SocketAddr,
     path::Path,
     pin::Pin,
@@ -27,16 +28,18 @@ use std::{
     time::{Duration, Instant},
 };
 
-use futures::{Future, StreamExt};
+use futures::{Future, FutureExt, StreamExt};
 use parking_lot::Mutex;
-use reth_eth_wire::{capability::CapabilityMessage, Capabilities, DisconnectReason};
+use reth_eth_wire::{
+    capability::CapabilityMessage, BlockRangeUpdate, Capabilities, DisconnectReason,
+};
 use reth_fs_util::{self as fs, FsPathError};
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
 use reth_network_api::{
     test_utils::PeersHandle, EthProtocolInfo, NetworkEvent, NetworkStatus, PeerInfo, PeerRequest,
 };
 use reth_network_peers::{NodeRecord, PeerId};
-use reth_network_types::ReputationChangeKind;
+use reth_network_types::{ManagedPeers, ManagedPeersUpdate, ReputationChangeKind};
 use reth_storage_api::BlockNumReader;
 use reth_tasks::shutdown::GracefulShutdown;
 use reth_tokio_util::EventSender;
@@ -50,16 +53,17 @@ use crate::{
     config::NetworkConfig,
     discovery::Discovery,
     error::{NetworkError, ServiceKind},
-    eth_requests::IncomingEthRequest,
+    eth_requests::{EthRequestLimits, IncomingEthRequest},
     import::{BlockImport, BlockImportOutcome, BlockValidation},
     listener::ConnectionListener,
+    managed_peers::{self, PeersConfigFile, PEERS_CONFIG_FILE_POLL_INTERVAL},
     message::{NewBlockMessage, PeerMessage},
     metrics::{DisconnectMetrics, NetworkMetrics, NETWORK_POOL_TRANSACTIONS_SCOPE},
     network::{NetworkHandle, NetworkHandleMessage},
     peers::PeersManager,
     poll_nested_stream_with_budget,
     protocol::IntoRlpxSubProtocol,
-    session::SessionManager,
+    session::{earliest_available_block, SessionManager},
     state::NetworkState,
     swarm::{Swarm, SwarmEvent},
     transactions::NetworkTransactionEvent,
@@ -104,6 +108,9 @@ pub struct NetworkManager {
     /// requests. This channel size is set at
     /// [`ETH_REQUEST_CHANNEL_CAPACITY`](crate::builder::ETH_REQUEST_CHANNEL_CAPACITY)
     to_eth_request_handler: Option<mpsc::Sender<IncomingEthRequest>>,
+    /// Limits for serving `eth` requests that are applied to the
+    /// [`EthRequestHandler`](crate::eth_requests::EthRequestHandler).
+    eth_request_limits: Option<EthRequestLimits>,
     /// Tracks the number of active session (connected peers).
     ///
     /// This is updated via internal events and shared via `Arc` with the [`NetworkHandle`]
@@ -113,6 +120,8 @@ pub struct NetworkManager {
     metrics: NetworkMetrics,
     /// Disconnect metrics for the Network
     disconnect_metrics: DisconnectMetrics,
+    /// The watched peers config file, if configured.
+    peers_config_file: Option<PeersConfigFile>,
 }
 
 // === impl NetworkManager ===
@@ -130,6 +139,11 @@ impl NetworkManager {
         self.to_eth_request_handler = Some(tx);
     }
 
+    /// Returns the limits for serving `eth` requests, or `None` if they are served without limits.
+    pub const fn eth_request_limits(&self) -> Option<EthRequestLimits> {
+        self.eth_request_limits
+    }
+
     /// Adds an additional protocol handler to the `RLPx` sub-protocol list.
     pub fn add_rlpx_sub_protocol(&mut self, protocol: impl IntoRlpxSubProtocol) {
         self.swarm.add_rlpx_sub_protocol(protocol)
@@ -175,8 +189,10 @@ impl NetworkManager {
             discovery_v4_addr,
             mut discovery_v4_config,
             mut discovery_v5_config,
+            nat_port_mapping,
             listener_addr,
             peers_config,
+            peers_config_file,
             sessions_config,
             chain_spec,
             block_import,
@@ -190,11 +206,40 @@ impl NetworkManager {
             extra_protocols,
             tx_gossip_disabled,
             transactions_manager_config: _,
+            eth_request_limits,
+            prune_modes,
         } = config;
 
-        let peers_manager = PeersManager::new(peers_config);
+        let mut peers_manager = PeersManager::new(peers_config);
         let peers_handle = peers_manager.handle();
 
+        let (to_manager_tx, from_handle_rx) = mpsc::unbounded_channel();
+
+        // apply the peers config file and watch it for changes
+        let peers_config_file = match peers_config_file {
+            Some(path) => {
+                let last_modified = managed_peers::modified(&path);
+                let peers = ManagedPeers::load(&path).map_err(|err| {
+                    io::Error::new(
+                        err.kind(),
+                        format!("failed to read peers config file {}: {err}", path.display()),
+                    )
+                })?;
+                peers_manager.set_managed_peers(managed_peers::resolve(&peers).await);
+                executor.spawn(
+                    managed_peers::watch(
+                        path.clone(),
+                        PEERS_CONFIG_FILE_POLL_INTERVAL,
+                        last_modified,
+                        to_manager_tx.clone(),
+                    )
+                    .boxed(),
+                );
+                Some(PeersConfigFile { path, peers })
+            }
+            None => None,
+        };
+
         let incoming = ConnectionListener::bind(listener_addr).await.map_err(|err| {
             NetworkError::from_io_error(err, ServiceKind::Listener(listener_addr))
         })?;
@@ -224,6 +269,7 @@ impl NetworkManager {
             discovery_v4_config,
             discovery_v5_config,
             dns_discovery_config,
+            nat_port_mapping,
         )
         .await?;
         // need to retrieve the addr here since provided port could be `0`
@@ -232,11 +278,22 @@ impl NetworkManager {
 
         let num_active_peers = Arc::new(AtomicUsize::new(0));
 
+        // the range of blocks announced to `eth/69` peers, all blocks up to the head whose
+        // receipts are not pruned are served
+        let latest = client.block_number(status.blockhash).ok().flatten().unwrap_or_default();
+        let block_range = BlockRangeUpdate {
+            earliest: earliest_available_block(&prune_modes, latest),
+            latest,
+            latest_hash: status.blockhash,
+        };
+
         let sessions = SessionManager::new(
             secret_key,
             sessions_config,
             executor,
             status,
+            block_range,
+            prune_modes,
             hello_message,
             fork_filter,
             extra_protocols,
@@ -251,8 +308,6 @@ impl NetworkManager {
 
         let swarm = Swarm::new(incoming, sessions, state);
 
-        let (to_manager_tx, from_handle_rx) = mpsc::unbounded_channel();
-
         let event_sender: EventSender<NetworkEvent> = Default::default();
 
         let handle = NetworkHandle::new(
@@ -277,9 +332,11 @@ impl NetworkManager {
             event_sender,
             to_transactions_manager: None,
             to_eth_request_handler: None,
+            eth_request_limits,
             num_active_peers,
             metrics: Default::default(),
             disconnect_metrics: Default::default(),
+            peers_config_file,
         })
     }
 
@@ -355,10 +412,42 @@ impl NetworkManager {
         self.swarm.state().peers().handle()
     }
 
-    /// Collect the peers from the [`NetworkManager`] and write them to the given
-    /// `persistent_peers_file`.
+    /// Applies the update to the peers of the peers config file, writes the file and applies the
+    /// update to the peer set.
+    ///
+    /// Returns `true` if the update changed the configured peers.
+    fn update_managed_peers(
+        &mut self,
+        update: ManagedPeersUpdate,
+    ) -> Result<bool, reth_network_api::NetworkError> {
+        let Some(file) = &mut self.peers_config_file else {
+            return Err(reth_network_api::NetworkError::PeersConfigFile(
+                "no peers config file configured".to_string(),
+            ))
+        };
+
+        let mut peers = file.peers.clone();
+        if !peers.apply(&update) {
+            return Ok(false)
+        }
+        peers.save(&file.path).map_err(|err| {
+            reth_network_api::NetworkError::PeersConfigFile(format!(
+                "failed to write {}: {err}",
+                file.path.display()
+            ))
+        })?;
+        file.peers = peers;
+
+        let mut resolved = self.swarm.state().peers().managed_peers().clone();
+        resolved.apply(&update);
+        self.swarm.state_mut().peers_mut().set_managed_peers(resolved);
+        Ok(true)
+    }
+
+    /// Collect the peers, their reputation and backoff state and the bans from the
+    /// [`NetworkManager`] and write them to the given `persistent_peers_file`.
     pub fn write_peers_to_file(&self, persistent_peers_file: &Path) -> Result<(), FsPathError> {
-        let known_peers = self.all_peers().collect::<Vec<_>>();
+        let known_peers = self.swarm.state().peers().persisted_peers();
         persistent_peers_file.parent().map(fs::create_dir_all).transpose()?;
         reth_fs_util::write_json_file(persistent_peers_file, &known_peers)?;
         Ok(())
@@ -456,6 +545,13 @@ impl NetworkManager {
                     response,
                 })
             }
+            PeerRequest::GetReceipts69 { request, response } => {
+                self.delegate_eth_request(IncomingEthRequest::GetReceipts69 {
+                    peer_id,
+                    request,
+                    response,
+                })
+            }
             PeerRequest::GetPooledTransactions { request, response } => {
                 self.notify_tx_manager(NetworkTransactionEvent::GetPooledTransactions {
                     peer_id,
@@ -533,6 +629,9 @@ impl NetworkManager {
             PeerMessage::EthRequest(req) => {
                 self.on_eth_request(peer_id, req);
             }
+            PeerMessage::BlockRangeUpdate(block_range) => {
+                self.swarm.state_mut().on_block_range_update(peer_id, block_range);
+            }
             PeerMessage::ReceivedTransaction(msg) => {
                 self.notify_tx_manager(NetworkTransactionEvent::IncomingTransactions {
                     peer_id,
@@ -608,7 +707,12 @@ impl NetworkManager {
                 self.swarm.sessions_mut().disconnect_all(Some(DisconnectReason::ClientQuitting));
                 // drop pending connections
                 self.swarm.sessions_mut().disconnect_all_pending();
-                let _ = tx.send(());
+                // remove the port mappings on the gateway before acknowledging the shutdown
+                let stop_port_mapping = self.swarm.state_mut().discovery_mut().stop_port_mapping();
+                self.swarm.sessions().spawn(async move {
+                    stop_port_mapping.await;
+                    let _ = tx.send(());
+                });
             }
             NetworkHandleMessage::ReputationChange(peer_id, kind) => {
                 self.swarm.state_mut().peers_mut().apply_reputation_change(&peer_id, kind);
@@ -616,6 +720,35 @@ impl NetworkManager {
             NetworkHandleMessage::GetReputationById(peer_id, tx) => {
                 let _ = tx.send(self.swarm.state_mut().peers().get_reputation(&peer_id));
             }
+            NetworkHandleMessage::BanPeer(peer_id, duration) => {
+                let until = duration.map(|duration| std::time::Instant::now() + duration);
+                self.swarm.state_mut().peers_mut().ban_peer_with(peer_id, until);
+            }
+            NetworkHandleMessage::UnbanPeer(peer_id) => {
+                self.swarm.state_mut().peers_mut().lift_peer_ban(peer_id);
+            }
+            NetworkHandleMessage::BanIpRange(range, duration) => {
+                let until = duration.map(|duration| std::time::Instant::now() + duration);
+                self.swarm.state_mut().peers_mut().ban_ip_range_with(range, until);
+            }
+            NetworkHandleMessage::UnbanIpRange(range) => {
+                self.swarm.state_mut().peers_mut().lift_ip_range_ban(range);
+            }
+            NetworkHandleMessage::GetBans(tx) => {
+                let _ = tx.send(self.swarm.state().peers().bans());
+            }
+            NetworkHandleMessage::ReloadManagedPeers(peers, resolved) => {
+                if let Some(file) = &mut self.peers_config_file {
+                    file.peers = peers;
+                    self.swarm.state_mut().peers_mut().set_managed_peers(resolved);
+                }
+            }
+            NetworkHandleMessage::UpdateManagedPeers(update, tx) => {
+                let _ = tx.send(self.update_managed_peers(update));
+            }
+            NetworkHandleMessage::GetManagedPeers(tx) => {
+                let _ = tx.send(self.peers_config_file.as_ref().map(|file| file.peers.clone()));
+            }
             NetworkHandleMessage::FetchClient(tx) => {
                 let _ = tx.send(self.fetch_client());
             }
@@ -713,6 +846,14 @@ impl NetworkManager {
 
                 self.update_active_connection_metrics();
 
+                let peer_kind = self
+                    .swarm
+                    .state()
+                    .peers()
+                    .peer_by_id(peer_id)
+                    .map(|(_, kind)| kind)
+                    .unwrap_or_default();
+
                 self.event_sender.notify(NetworkEvent::SessionEstablished {
                     peer_id,
                     remote_addr,
@@ -721,6 +862,7 @@ impl NetworkManager {
                     version,
                     status,
                     messages,
+                    peer_kind,
                 });
             }
             SwarmEvent::PeerAdded(peer_id) => {
@@ -968,6 +1110,9 @@ impl NetworkManager {
             },
         }
 
+        // remove the port mappings on the gateway before the node exits
+        self.swarm.state_mut().discovery_mut().stop_port_mapping().await;
+
         let res = shutdown_hook(self);
         drop(graceful_guard);
         res
@@ -10,9 +10,10 @@ use std::{
 
 use futures::FutureExt;
 use reth_eth_wire::{
-    capability::RawCapabilityMessage, message::RequestPair, BlockBodies, BlockHeaders, EthMessage,
-    GetBlockBodies, GetBlockHeaders, NewBlock, NewBlockHashes, NewPooledTransactionHashes,
-    NodeData, PooledTransactions, Receipts, SharedTransactions, Transactions,
+    capability::RawCapabilityMessage, message::RequestPair, BlockBodies, BlockHeaders,
+    BlockRangeUpdate, EthMessage, GetBlockBodies, GetBlockHeaders, NewBlock, NewBlockHashes,
+    NewPooledTransactionHashes, NodeData, PooledTransactions, Receipt69, Receipts, Receipts69,
+    SharedTransactions, Transactions,
 };
 use reth_network_api::PeerRequest;
 use reth_network_p2p::error::{RequestError, RequestResult};
@@ -55,6 +56,8 @@ pub enum PeerMessage {
     PooledTransactions(NewPooledTransactionHashes),
     /// All `eth` request variants.
     EthRequest(PeerRequest),
+    /// Announce the range of blocks that can be served, since `eth/69`.
+    BlockRangeUpdate(BlockRangeUpdate),
     /// Other than eth namespace message
     Other(RawCapabilityMessage),
 }
@@ -101,6 +104,11 @@ pub enum PeerResponse {
         /// The receiver channel for the response to a receipts request.
         response: oneshot::Receiver<RequestResult<Receipts>>,
     },
+    /// Represents a response to a request for `eth/69` receipts.
+    Receipts69 {
+        /// The receiver channel for the response to a receipts request.
+        response: oneshot::Receiver<RequestResult<Receipts69>>,
+    },
 }
 
 // === impl PeerResponse ===
@@ -133,6 +141,9 @@ impl PeerResponse {
             Self::Receipts { response } => {
                 poll_request!(response, Receipts, cx)
             }
+            Self::Receipts69 { response } => {
+                poll_request!(response, Receipts69, cx)
+            }
         };
         Poll::Ready(res)
     }
@@ -151,6 +162,8 @@ pub enum PeerResponseResult {
     NodeData(RequestResult<Vec<Bytes>>),
     /// Represents a result containing receipts or an error.
     Receipts(RequestResult<Vec<Vec<ReceiptWithBloom>>>),
+    /// Represents a result containing `eth/69` receipts or an error.
+    Receipts69(RequestResult<Vec<Vec<Receipt69>>>),
 }
 
 // === impl PeerResponseResult ===
@@ -185,6 +198,9 @@ impl PeerResponseResult {
             Self::Receipts(resp) => {
                 to_message!(resp, Receipts, id)
             }
+            Self::Receipts69(resp) => {
+                to_message!(resp, Receipts69, id)
+            }
         }
     }
 
@@ -196,6 +212,7 @@ impl PeerResponseResult {
             Self::PooledTransactions(res) => res.as_ref().err(),
             Self::NodeData(res) => res.as_ref().err(),
             Self::Receipts(res) => res.as_ref().err(),
+            Self::Receipts69(res) => res.as_ref().err(),
         }
     }
 
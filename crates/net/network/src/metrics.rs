@@ -324,11 +324,43 @@ pub struct EthRequestHandlerMetrics {
     /// Number of `GetNodeData` requests received
     pub(crate) eth_node_data_requests_received_total: Counter,
 
+    /// Number of bytes served for `GetBlockHeaders` requests
+    pub(crate) eth_headers_served_bytes_total: Counter,
+
+    /// Number of bytes served for `GetReceipts` requests
+    pub(crate) eth_receipts_served_bytes_total: Counter,
+
+    /// Number of bytes served for `GetBlockBodies` requests
+    pub(crate) eth_bodies_served_bytes_total: Counter,
+
+    /// Number of requests that were deferred because the peer exceeded its budget
+    pub(crate) eth_requests_peer_limited_total: Counter,
+
+    /// Number of requests that were deferred because the global budget was exceeded
+    pub(crate) eth_requests_global_limited_total: Counter,
+
+    /// Number of requests waiting for a budget to refill
+    pub(crate) eth_requests_deferred: Gauge,
+
+    /// Number of requests that were dropped because too many requests were deferred
+    pub(crate) eth_requests_dropped_total: Counter,
+
     /// Duration in seconds of call to poll
     /// [`EthRequestHandler`](crate::eth_requests::EthRequestHandler).
     pub(crate) acc_duration_poll_eth_req_handler: Gauge,
 }
 
+/// Per peer metrics for the `EthRequestHandler`
+#[derive(Metrics)]
+#[metrics(scope = "network.eth_requests.peer")]
+pub struct EthRequestPeerMetrics {
+    /// Number of bytes served to the peer
+    pub(crate) served_bytes_total: Counter,
+
+    /// Number of requests of the peer that were deferred because it exceeded its budget
+    pub(crate) limited_requests_total: Counter,
+}
+
 /// Eth67 announcement metrics, track entries by `TxType`
 #[derive(Metrics)]
 #[metrics(scope = "network.transaction_fetcher")]
@@ -4,6 +4,7 @@ use std::{
         atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
+    time::Duration,
 };
 
 use enr::Enr;
@@ -21,7 +22,10 @@ use reth_network_p2p::{
     BlockClient,
 };
 use reth_network_peers::{NodeRecord, PeerId};
-use reth_network_types::{PeerAddr, PeerKind, Reputation, ReputationChangeKind};
+use reth_network_types::{
+    Bans, IpNet, ManagedPeers, ManagedPeersUpdate, PeerAddr, PeerKind, Reputation,
+    ReputationChangeKind,
+};
 use reth_primitives::{Head, TransactionSigned, B256};
 use reth_tokio_util::{EventSender, EventStream};
 use secp256k1::SecretKey;
@@ -327,6 +331,40 @@ impl Peers for NetworkHandle {
         let _ = self.manager().send(NetworkHandleMessage::GetReputationById(peer_id, tx));
         Ok(rx.await?)
     }
+
+    fn ban_peer(&self, peer_id: PeerId, duration: Option<Duration>) {
+        self.send_message(NetworkHandleMessage::BanPeer(peer_id, duration));
+    }
+
+    fn unban_peer(&self, peer_id: PeerId) {
+        self.send_message(NetworkHandleMessage::UnbanPeer(peer_id));
+    }
+
+    fn ban_ip_range(&self, range: IpNet, duration: Option<Duration>) {
+        self.send_message(NetworkHandleMessage::BanIpRange(range, duration));
+    }
+
+    fn unban_ip_range(&self, range: IpNet) {
+        self.send_message(NetworkHandleMessage::UnbanIpRange(range));
+    }
+
+    async fn bans(&self) -> Result<Bans, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.manager().send(NetworkHandleMessage::GetBans(tx));
+        Ok(rx.await?)
+    }
+
+    async fn update_managed_peers(&self, update: ManagedPeersUpdate) -> Result<bool, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.manager().send(NetworkHandleMessage::UpdateManagedPeers(update, tx));
+        rx.await?
+    }
+
+    async fn managed_peers(&self) -> Result<Option<ManagedPeers>, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.manager().send(NetworkHandleMessage::GetManagedPeers(tx));
+        Ok(rx.await?)
+    }
 }
 
 impl PeersHandleProvider for NetworkHandle {
@@ -487,6 +525,23 @@ pub(crate) enum NetworkHandleMessage {
     GetPeerInfosByPeerKind(PeerKind, oneshot::Sender<Vec<PeerInfo>>),
     /// Gets the reputation for a specific peer via a oneshot sender.
     GetReputationById(PeerId, oneshot::Sender<Option<Reputation>>),
+    /// Bans a peer, indefinitely or for the given duration, and disconnects it.
+    BanPeer(PeerId, Option<Duration>),
+    /// Lifts the ban of a peer.
+    UnbanPeer(PeerId),
+    /// Bans an IP range, indefinitely or for the given duration, and disconnects its peers.
+    BanIpRange(IpNet, Option<Duration>),
+    /// Lifts the ban of an IP range.
+    UnbanIpRange(IpNet),
+    /// Gets all bans via a oneshot sender.
+    GetBans(oneshot::Sender<Bans>),
+    /// Replaces the peers of the peers config file, with their resolved records, after the file
+    /// changed.
+    ReloadManagedPeers(ManagedPeers, ManagedPeers<NodeRecord>),
+    /// Applies an update to the peers of the peers config file and writes the file.
+    UpdateManagedPeers(ManagedPeersUpdate, oneshot::Sender<Result<bool, NetworkError>>),
+    /// Gets the peers of the peers config file via a oneshot sender.
+    GetManagedPeers(oneshot::Sender<Option<ManagedPeers>>),
     /// Retrieves the `TransactionsHandle` via a oneshot sender.
     GetTransactionsHandle(oneshot::Sender<Option<TransactionsHandle>>),
     /// Initiates a graceful shutdown of the network via a oneshot sender.
@@ -11,16 +11,18 @@ use std::{
 
 use futures::StreamExt;
 use reth_eth_wire::{errors::EthStreamError, DisconnectReason};
-use reth_net_banlist::BanList;
+use reth_net_banlist::{BanList, IpNet};
 use reth_network_api::test_utils::{PeerCommand, PeersHandle};
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_network_types::{
+    is_banned_reputation,
     peers::{
         config::PeerBackoffDurations,
         reputation::{DEFAULT_REPUTATION, MAX_TRUSTED_PEER_REPUTATION_CHANGE},
     },
-    ConnectionsConfig, Peer, PeerAddr, PeerConnectionState, PeerKind, PeersConfig,
-    ReputationChangeKind, ReputationChangeOutcome, ReputationChangeWeights,
+    Bans, ConnectionsConfig, ManagedPeers, Peer, PeerAddr, PeerConnectionState, PeerKind,
+    PeersConfig, PersistedPeer, PersistedPeers, ReputationChangeKind, ReputationChangeOutcome,
+    ReputationChangeWeights,
 };
 use reth_primitives::ForkId;
 use thiserror::Error;
@@ -84,6 +86,15 @@ pub struct PeersManager {
     max_backoff_count: u8,
     /// Tracks the connection state of the node
     net_connection_state: NetworkConnectionState,
+    /// The peers configured in the peers config file.
+    managed_peers: ManagedPeers<NodeRecord>,
+    /// The state of the trusted and static peers of the peers config file before the file
+    /// configured them, restored once they are removed from the file.
+    managed_peer_origins: HashMap<PeerId, ManagedPeerOrigin>,
+    /// The bans that were added by the peers config file.
+    ///
+    /// These are lifted once they are removed from the file and are not persisted.
+    managed_bans: HashSet<PeerId>,
 }
 
 impl PeersManager {
@@ -99,6 +110,7 @@ impl PeersManager {
             trusted_nodes,
             trusted_nodes_only,
             basic_nodes,
+            persisted_peers,
             max_backoff_count,
         } = config;
         let (manager_tx, handle_rx) = mpsc::unbounded_channel();
@@ -130,6 +142,23 @@ impl PeersManager {
             });
         }
 
+        // restore the state of the peers known from the previous run
+        let mut backed_off_peers = HashMap::new();
+        for persisted in persisted_peers {
+            let peer_id = persisted.record.id;
+            let Some(peer) = peers.get_mut(&peer_id) else { continue };
+            // the reputation of a peer whose ban expired while offline is reset, like it would
+            // have been on eviction from the ban list
+            if !is_banned_reputation(persisted.reputation) || ban_list.is_banned_peer(&peer_id) {
+                peer.reputation = persisted.reputation;
+            }
+            peer.severe_backoff_counter = persisted.severe_backoff_counter;
+            if let Some(until) = persisted.backoff_until() {
+                peer.backed_off = true;
+                backed_off_peers.insert(peer_id, until);
+            }
+        }
+
         Self {
             peers,
             trusted_peer_ids,
@@ -141,13 +170,16 @@ impl PeersManager {
             release_interval: tokio::time::interval_at(now + unban_interval, unban_interval),
             connection_info: ConnectionInfo::new(connection_info),
             ban_list,
-            backed_off_peers: Default::default(),
+            backed_off_peers,
             ban_duration,
             backoff_durations,
             trusted_nodes_only,
             last_tick: Instant::now(),
             max_backoff_count,
             net_connection_state: NetworkConnectionState::default(),
+            managed_peers: ManagedPeers::default(),
+            managed_peer_origins: HashMap::default(),
+            managed_bans: HashSet::default(),
         }
     }
 
@@ -174,6 +206,42 @@ impl PeersManager {
         })
     }
 
+    /// Returns a snapshot of the known peers and the bans, to be restored after a restart.
+    ///
+    /// Peers and bans that were only added by the peers config file are not included, they are
+    /// applied from the file again.
+    pub(crate) fn persisted_peers(&self) -> PersistedPeers {
+        let peers = self
+            .peers
+            .iter()
+            .filter(|(peer_id, _)| {
+                self.managed_peer_origins.get(peer_id).map_or(true, |origin| origin.kind.is_some())
+            })
+            .map(|(peer_id, peer)| {
+                let record = NodeRecord::new_with_ports(
+                    peer.addr.tcp().ip(),
+                    peer.addr.tcp().port(),
+                    peer.addr.udp().map(|addr| addr.port()),
+                    *peer_id,
+                );
+                PersistedPeer::new(
+                    record,
+                    peer.reputation,
+                    self.backed_off_peers.get(peer_id).copied(),
+                    peer.severe_backoff_counter,
+                )
+            })
+            .collect();
+        let mut bans = self.bans();
+        bans.banned_peers.retain(|ban| !self.managed_bans.contains(&ban.target));
+        PersistedPeers::new(peers, bans)
+    }
+
+    /// Returns a snapshot of the ban list.
+    pub(crate) fn bans(&self) -> Bans {
+        (&self.ban_list).into()
+    }
+
     /// Returns the `NodeRecord` and `PeerKind` for the given peer id
     pub(crate) fn peer_by_id(&self, peer_id: PeerId) -> Option<(NodeRecord, PeerKind)> {
         self.peers.get(&peer_id).map(|v| {
@@ -369,6 +437,60 @@ impl PeersManager {
         self.queued_actions.push_back(PeerAction::UnBanPeer { peer_id });
     }
 
+    /// Bans the peer indefinitely or until the given timeout and disconnects it.
+    ///
+    /// This replaces a ban of the peers config file.
+    pub(crate) fn ban_peer_with(&mut self, peer_id: PeerId, until: Option<std::time::Instant>) {
+        self.managed_bans.remove(&peer_id);
+        self.apply_peer_ban(peer_id, until);
+    }
+
+    /// Adds the ban of the peer to the ban list and disconnects it.
+    fn apply_peer_ban(&mut self, peer_id: PeerId, until: Option<std::time::Instant>) {
+        trace!(target: "net::peers", ?peer_id, ?until, "banning peer");
+        self.ban_list.ban_peer_with(peer_id, until);
+        self.queued_actions.push_back(PeerAction::BanPeer { peer_id });
+        self.disconnect_banned_peers();
+    }
+
+    /// Lifts the ban of the peer, including a ban due to its reputation.
+    pub(crate) fn lift_peer_ban(&mut self, peer_id: PeerId) {
+        trace!(target: "net::peers", ?peer_id, "lifting ban of peer");
+        self.managed_bans.remove(&peer_id);
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.unban();
+        }
+        self.unban_peer(peer_id);
+    }
+
+    /// Bans all IPs in the range indefinitely or until the given timeout and disconnects the
+    /// peers connected from the range.
+    pub(crate) fn ban_ip_range_with(&mut self, range: IpNet, until: Option<std::time::Instant>) {
+        trace!(target: "net::peers", %range, ?until, "banning ip range");
+        self.ban_list.ban_ip_range_with(range, until);
+        self.disconnect_banned_peers();
+    }
+
+    /// Lifts the ban of the IP range.
+    pub(crate) fn lift_ip_range_ban(&mut self, range: IpNet) {
+        trace!(target: "net::peers", %range, "lifting ban of ip range");
+        self.ban_list.unban_ip_range(&range);
+    }
+
+    /// Disconnects all connected peers that are on the ban list.
+    fn disconnect_banned_peers(&mut self) {
+        for (peer_id, peer) in &mut self.peers {
+            if peer.state.is_connected() && self.ban_list.is_banned(peer_id, &peer.addr.tcp().ip())
+            {
+                peer.state.disconnect();
+                self.queued_actions.push_back(PeerAction::Disconnect {
+                    peer_id: *peer_id,
+                    reason: Some(DisconnectReason::DisconnectRequested),
+                });
+            }
+        }
+    }
+
     /// Tick function to update reputation of all connected peers.
     /// Peers are rewarded with reputation increases for the time they are connected since the last
     /// tick. This is to prevent peers from being disconnected eventually due to slashed
@@ -415,6 +537,7 @@ impl PeersManager {
                         rep,
                         ReputationChangeKind::Dropped |
                             ReputationChangeKind::BadAnnouncement |
+                            ReputationChangeKind::ExceededRequestLimit |
                             ReputationChangeKind::Timeout |
                             ReputationChangeKind::AlreadySeenTransaction
                     ) {
@@ -784,6 +907,102 @@ impl PeersManager {
         self.trusted_peer_ids.remove(&peer_id);
     }
 
+    /// Returns the peers configured in the peers config file.
+    pub(crate) const fn managed_peers(&self) -> &ManagedPeers<NodeRecord> {
+        &self.managed_peers
+    }
+
+    /// Replaces the peers configured in the peers config file.
+    ///
+    /// New or changed peers are added with their configured kind and new bans are applied. Peers
+    /// and bans that are no longer configured are reverted to their state before the file
+    /// configured them, so peers and bans that were added by other means are kept.
+    pub(crate) fn set_managed_peers(&mut self, managed: ManagedPeers<NodeRecord>) {
+        let previous = std::mem::take(&mut self.managed_peers);
+        let is_configured = |peer_id: PeerId| {
+            managed.trusted.iter().chain(&managed.static_peers).any(|record| record.id == peer_id)
+        };
+        let addr = |record: &NodeRecord| {
+            PeerAddr::new_with_ports(record.address, record.tcp_port, Some(record.udp_port))
+        };
+
+        for record in previous.trusted.iter().chain(&previous.static_peers) {
+            if !is_configured(record.id) {
+                self.unmanage_peer(record.id);
+            }
+        }
+
+        for record in &managed.trusted {
+            if !previous.trusted.contains(record) {
+                self.manage_peer(record.id);
+                self.add_peer_kind(record.id, PeerKind::Trusted, addr(record), None);
+            }
+        }
+
+        for record in &managed.static_peers {
+            // a peer that is configured as trusted and static is trusted
+            if managed.trusted.iter().any(|trusted| trusted.id == record.id) ||
+                previous.static_peers.contains(record)
+            {
+                continue
+            }
+            self.manage_peer(record.id);
+            self.remove_peer_from_trusted_set(record.id);
+            self.add_peer_kind(record.id, PeerKind::Static, addr(record), None);
+        }
+
+        for peer_id in &managed.banned {
+            // a peer that is already banned keeps its ban
+            if !previous.banned.contains(peer_id) && !self.ban_list.is_banned_peer(peer_id) {
+                self.managed_bans.insert(*peer_id);
+                self.apply_peer_ban(*peer_id, None);
+            }
+        }
+        for peer_id in &previous.banned {
+            if !managed.banned.contains(peer_id) && self.managed_bans.contains(peer_id) {
+                self.lift_peer_ban(*peer_id);
+            }
+        }
+
+        self.managed_peers = managed;
+    }
+
+    /// Records the state of the peer before the peers config file configures it.
+    fn manage_peer(&mut self, peer_id: PeerId) {
+        if let Entry::Vacant(entry) = self.managed_peer_origins.entry(peer_id) {
+            entry.insert(ManagedPeerOrigin {
+                kind: self.peers.get(&peer_id).map(|peer| peer.kind),
+                trusted: self.trusted_peer_ids.contains(&peer_id),
+            });
+        }
+    }
+
+    /// Reverts the peer to its state before the peers config file configured it.
+    ///
+    /// A peer that was only added by the file is removed and disconnected.
+    fn unmanage_peer(&mut self, peer_id: PeerId) {
+        let Some(origin) = self.managed_peer_origins.remove(&peer_id) else { return };
+        trace!(target: "net::peers", ?peer_id, ?origin, "reverting unconfigured peer");
+
+        match origin.kind {
+            Some(kind) => {
+                if let Some(peer) = self.peers.get_mut(&peer_id) {
+                    peer.kind = kind;
+                }
+            }
+            None => {
+                self.remove_peer_from_trusted_set(peer_id);
+                self.remove_peer(peer_id);
+            }
+        }
+
+        if origin.trusted {
+            self.trusted_peer_ids.insert(peer_id);
+        } else {
+            self.trusted_peer_ids.remove(&peer_id);
+        }
+    }
+
     /// Returns the idle peer with the highest reputation.
     ///
     /// Peers that are `trusted` or `static`, see [`PeerKind`], are prioritized as long as they're
@@ -794,9 +1013,10 @@ impl PeersManager {
     ///
     /// Returns `None` if no peer is available.
     fn best_unconnected(&mut self) -> Option<(PeerId, &mut Peer)> {
-        let mut unconnected = self.peers.iter_mut().filter(|(_, peer)| {
+        let mut unconnected = self.peers.iter_mut().filter(|(peer_id, peer)| {
             !peer.is_backed_off() &&
                 !peer.is_banned() &&
+                !self.ban_list.is_banned(peer_id, &peer.addr.tcp().ip()) &&
                 peer.state.is_unconnected() &&
                 (!self.trusted_nodes_only || peer.is_trusted())
         });
@@ -934,6 +1154,15 @@ impl PeersManager {
     }
 }
 
+/// The state of a peer before it was configured in the peers config file.
+#[derive(Debug, Clone, Copy)]
+struct ManagedPeerOrigin {
+    /// The kind of the peer, `None` if the peer was not known.
+    kind: Option<PeerKind>,
+    /// Whether the peer was in the trusted set.
+    trusted: bool,
+}
+
 impl Default for PeersManager {
     fn default() -> Self {
         Self::new(Default::default())
@@ -1105,9 +1334,10 @@ mod tests {
     };
     use reth_net_banlist::BanList;
     use reth_network_api::Direction;
-    use reth_network_peers::{PeerId, TrustedPeer};
+    use reth_network_peers::{NodeRecord, PeerId, TrustedPeer};
     use reth_network_types::{
-        peers::reputation::DEFAULT_REPUTATION, BackoffKind, ReputationChangeKind,
+        peers::reputation::{BANNED_REPUTATION, DEFAULT_REPUTATION},
+        BackoffKind, PersistedPeer, PersistedPeers, ReputationChangeKind,
     };
     use reth_primitives::B512;
     use url::Host;
@@ -2248,6 +2478,78 @@ mod tests {
         assert!(peer_manager.peers.get_mut(&peer_id).unwrap().reputation >= DEFAULT_REPUTATION);
     }
 
+    #[tokio::test]
+    async fn test_set_managed_peers() {
+        let trusted = NodeRecord::new(([127, 0, 1, 1], 30303).into(), PeerId::random());
+        let static_peer = NodeRecord::new(([127, 0, 1, 2], 30303).into(), PeerId::random());
+        let banned = PeerId::random();
+        let mut peers = PeersManager::default();
+
+        peers.set_managed_peers(ManagedPeers {
+            trusted: vec![trusted],
+            static_peers: vec![static_peer],
+            banned: vec![banned],
+        });
+        assert_eq!(peers.peers.get(&trusted.id).unwrap().kind, PeerKind::Trusted);
+        assert_eq!(peers.peers.get(&static_peer.id).unwrap().kind, PeerKind::Static);
+        assert!(peers.trusted_peer_ids.contains(&trusted.id));
+        assert!(peers.ban_list.is_banned_peer(&banned));
+
+        peers.peers.get_mut(&static_peer.id).unwrap().state = PeerConnectionState::Out;
+        peers.queued_actions.clear();
+
+        // the trusted peer becomes static, the static peer and the ban are dropped
+        peers.set_managed_peers(ManagedPeers {
+            trusted: vec![],
+            static_peers: vec![trusted],
+            banned: vec![],
+        });
+        assert_eq!(peers.peers.get(&trusted.id).unwrap().kind, PeerKind::Static);
+        assert!(!peers.trusted_peer_ids.contains(&trusted.id));
+        assert!(peers.peers.get(&static_peer.id).unwrap().remove_after_disconnect);
+        assert!(peers.queued_actions.iter().any(|action| matches!(
+            action,
+            PeerAction::Disconnect { peer_id, .. } if *peer_id == static_peer.id
+        )));
+        assert!(!peers.ban_list.is_banned_peer(&banned));
+        assert_eq!(peers.managed_peers().static_peers, vec![trusted]);
+    }
+
+    #[tokio::test]
+    async fn test_set_managed_peers_keeps_other_state() {
+        let known = NodeRecord::new(([127, 0, 1, 1], 30303).into(), PeerId::random());
+        let added = NodeRecord::new(([127, 0, 1, 2], 30303).into(), PeerId::random());
+        let admin_banned = PeerId::random();
+        let file_banned = PeerId::random();
+        let mut peers = PeersManager::default();
+        peers.add_peer(known.id, PeerAddr::from_tcp(known.tcp_addr()), None);
+        peers.ban_peer_with(admin_banned, None);
+
+        peers.set_managed_peers(ManagedPeers {
+            trusted: vec![known],
+            static_peers: vec![added],
+            banned: vec![admin_banned, file_banned],
+        });
+        assert_eq!(peers.peers.get(&known.id).unwrap().kind, PeerKind::Trusted);
+        assert!(peers.ban_list.is_banned_peer(&file_banned));
+
+        // only the state that was not added by the file is persisted
+        let persisted = peers.persisted_peers();
+        assert!(persisted.peers.iter().any(|peer| peer.record.id == known.id));
+        assert!(!persisted.peers.iter().any(|peer| peer.record.id == added.id));
+        let persisted_bans =
+            persisted.bans.banned_peers.iter().map(|ban| ban.target).collect::<Vec<_>>();
+        assert_eq!(persisted_bans, vec![admin_banned]);
+
+        // removing the entries only reverts what the file added
+        peers.set_managed_peers(ManagedPeers::default());
+        assert_eq!(peers.peers.get(&known.id).unwrap().kind, PeerKind::Basic);
+        assert!(!peers.trusted_peer_ids.contains(&known.id));
+        assert!(!peers.peers.contains_key(&added.id));
+        assert!(peers.ban_list.is_banned_peer(&admin_banned));
+        assert!(!peers.ban_list.is_banned_peer(&file_banned));
+    }
+
     #[tokio::test]
     async fn test_remove_incoming_after_disconnect() {
         let peer_id = PeerId::random();
@@ -2451,4 +2753,65 @@ mod tests {
         assert_eq!(record.tcp_addr(), socket_addr);
         assert_eq!(record.udp_addr(), socket_addr);
     }
+
+    #[tokio::test]
+    async fn test_restore_persisted_peers() {
+        let backed_off = NodeRecord::new("1.1.1.1:30303".parse().unwrap(), PeerId::random());
+        let banned = NodeRecord::new("2.2.2.2:30303".parse().unwrap(), PeerId::random());
+        let ban_expired = NodeRecord::new("3.3.3.3:30303".parse().unwrap(), PeerId::random());
+        let until = std::time::Instant::now() + Duration::from_secs(600);
+
+        let mut ban_list = BanList::default();
+        ban_list.ban_peer_until(banned.id, until);
+        let persisted = PersistedPeers::new(
+            vec![
+                PersistedPeer::new(backed_off, -100, Some(until), 2),
+                PersistedPeer::new(banned, 2 * BANNED_REPUTATION, None, 0),
+                PersistedPeer::new(ban_expired, 2 * BANNED_REPUTATION, None, 0),
+            ],
+            (&ban_list).into(),
+        );
+        let peers = PeersManager::new(PeersConfig::test().with_persisted_peers(persisted));
+
+        let peer = &peers.peers[&backed_off.id];
+        assert_eq!(peer.reputation, -100);
+        assert_eq!(peer.severe_backoff_counter, 2);
+        assert!(peer.is_backed_off());
+        assert!(peers.backed_off_peers.contains_key(&backed_off.id));
+
+        assert!(peers.peers[&banned.id].is_banned());
+        assert!(peers.ban_list.is_banned_peer(&banned.id));
+        assert_eq!(peers.peers[&ban_expired.id].reputation, DEFAULT_REPUTATION);
+
+        let snapshot = peers.persisted_peers();
+        assert_eq!(snapshot.peers.len(), 3);
+        assert_eq!(snapshot.bans.banned_peers.len(), 1);
+        let peer = snapshot.peers.iter().find(|peer| peer.record == backed_off).unwrap();
+        assert_eq!(peer.reputation, -100);
+        assert!(peer.backoff_until().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_ban_ip_range_disconnects() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 30303);
+        let mut peers = PeersManager::new(PeersConfig::test());
+        peers.add_and_connect(peer, PeerAddr::from_tcp(socket_addr), None);
+        peers.on_active_outgoing_established(peer);
+        peers.queued_actions.clear();
+
+        peers.ban_ip_range_with("1.2.3.0/24".parse().unwrap(), None);
+        assert!(matches!(
+            peers.queued_actions.pop_front(),
+            Some(PeerAction::Disconnect { peer_id, .. }) if peer_id == peer
+        ));
+        assert_eq!(peers.peers[&peer].state, PeerConnectionState::DisconnectingOut);
+
+        // banned peers are not dialed again
+        peers.peers.get_mut(&peer).unwrap().state = PeerConnectionState::Idle;
+        assert!(peers.best_unconnected().is_none());
+
+        peers.lift_ip_range_ban("1.2.3.0/24".parse().unwrap());
+        assert!(peers.best_unconnected().is_some());
+    }
 }
@@ -15,7 +15,7 @@ use futures::{stream::Fuse, SinkExt, StreamExt};
 use reth_eth_wire::{
     errors::{EthHandshakeError, EthStreamError, P2PStreamError},
     message::{EthBroadcastMessage, RequestPair},
-    Capabilities, DisconnectP2P, DisconnectReason, EthMessage,
+    Capabilities, DisconnectP2P, DisconnectReason, EthMessage, EthVersion, Receipts,
 };
 use reth_metrics::common::mpsc::MeteredPollSender;
 use reth_network_api::PeerRequest;
@@ -228,11 +228,43 @@ impl ActiveSession {
                 on_response!(resp, GetNodeData)
             }
             EthMessage::GetReceipts(req) => {
-                on_request!(req, Receipts, GetReceipts)
+                if self.conn.version() >= EthVersion::Eth69 {
+                    on_request!(req, Receipts69, GetReceipts69)
+                } else {
+                    on_request!(req, Receipts, GetReceipts)
+                }
             }
             EthMessage::Receipts(resp) => {
                 on_response!(resp, GetReceipts)
             }
+            EthMessage::Receipts69(resp) => {
+                let is_receipts69_request = matches!(
+                    self.inflight_requests.get(&resp.request_id),
+                    Some(InflightRequest {
+                        request: RequestState::Waiting(PeerRequest::GetReceipts69 { .. }),
+                        ..
+                    })
+                );
+                if is_receipts69_request {
+                    on_response!(resp, GetReceipts69)
+                } else {
+                    // the bloom is computed locally for regular receipts requests
+                    let resp = resp.map(Receipts::from);
+                    on_response!(resp, GetReceipts)
+                }
+            }
+            EthMessage::BlockRangeUpdate(msg) => {
+                if !msg.is_valid() {
+                    return OnIncomingMessageOutcome::BadMessage {
+                        error: EthStreamError::InvalidBlockRangeUpdate {
+                            earliest: msg.earliest,
+                            latest: msg.latest,
+                        },
+                        message: EthMessage::BlockRangeUpdate(msg),
+                    }
+                }
+                self.try_emit_broadcast(PeerMessage::BlockRangeUpdate(msg)).into()
+            }
         }
     }
 
@@ -270,6 +302,11 @@ impl ActiveSession {
             PeerMessage::SendTransactions(msg) => {
                 self.queued_outgoing.push_back(EthBroadcastMessage::Transactions(msg).into());
             }
+            PeerMessage::BlockRangeUpdate(msg) => {
+                if self.conn.version() >= EthVersion::Eth69 {
+                    self.queued_outgoing.push_back(EthMessage::BlockRangeUpdate(msg).into());
+                }
+            }
             PeerMessage::ReceivedTransaction(_) => {
                 unreachable!("Not emitted by network")
             }
@@ -760,15 +797,20 @@ fn calculate_new_timeout(current_timeout: Duration, estimated_rtt: Duration) ->
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::session::{handle::PendingSessionEvent, start_pending_incoming_session};
+    use crate::session::{
+        handle::PendingSessionEvent, start_pending_incoming_session, CaptureConfig,
+    };
     use reth_chainspec::MAINNET;
     use reth_ecies::stream::ECIESStream;
     use reth_eth_wire::{
-        EthStream, GetBlockBodies, HelloMessageWithProtocols, P2PStream, Status, StatusBuilder,
-        UnauthedEthStream, UnauthedP2PStream,
+        capture::{CaptureDirection, CaptureProtocol, CaptureReader},
+        EthMessageID, EthStream, GetBlockBodies, HelloMessageWithProtocols, P2PStream, Status,
+        StatusBuilder, UnauthedEthStream, UnauthedP2PStream,
     };
     use reth_network_peers::pk2id;
-    use reth_network_types::session::config::PROTOCOL_BREACH_REQUEST_TIMEOUT;
+    use reth_network_types::session::config::{
+        DEFAULT_CAPTURE_MAX_FILES, DEFAULT_CAPTURE_MAX_SIZE, PROTOCOL_BREACH_REQUEST_TIMEOUT,
+    };
     use reth_primitives::{EthereumHardfork, ForkFilter};
     use secp256k1::{SecretKey, SECP256K1};
     use tokio::{
@@ -791,6 +833,7 @@ mod tests {
         hello: HelloMessageWithProtocols,
         status: Status,
         fork_filter: ForkFilter,
+        capture: Option<CaptureConfig>,
         next_id: usize,
     }
 
@@ -824,7 +867,7 @@ mod tests {
                 let (p2p_stream, _) = UnauthedP2PStream::new(sink).handshake(hello).await.unwrap();
 
                 let (client_stream, _) = UnauthedEthStream::new(p2p_stream)
-                    .handshake(status, fork_filter)
+                    .handshake(status.into(), fork_filter)
                     .await
                     .unwrap();
                 f(client_stream).await
@@ -846,8 +889,10 @@ mod tests {
                 self.secret_key,
                 self.hello.clone(),
                 self.status,
+                Default::default(),
                 self.fork_filter.clone(),
                 Default::default(),
+                self.capture.clone(),
             ));
 
             let mut stream = ReceiverStream::new(pending_sessions_rx);
@@ -921,6 +966,7 @@ mod tests {
                 fork_filter: MAINNET
                     .hardfork_fork_filter(EthereumHardfork::Frontier)
                     .expect("The Frontier fork filter should exist on mainnet"),
+                capture: None,
             }
         }
     }
@@ -1137,6 +1183,64 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_capture() {
+        let mut builder = SessionBuilder::default();
+        let dir = tempfile::tempdir().unwrap();
+        builder.capture = Some(
+            CaptureConfig::spawn(
+                dir.path().to_path_buf(),
+                DEFAULT_CAPTURE_MAX_FILES,
+                DEFAULT_CAPTURE_MAX_SIZE,
+            )
+            .unwrap(),
+        );
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let fut = builder.with_client_stream(local_addr, move |mut client_stream| async move {
+            client_stream
+                .send(EthMessage::NewPooledTransactionHashes68(Default::default()))
+                .await
+                .unwrap();
+            client_stream.into_inner().disconnect(DisconnectReason::UselessPeer).await.unwrap();
+        });
+        tokio::task::spawn(fut);
+
+        let (incoming, _) = listener.accept().await.unwrap();
+        let mut session = builder.connect_incoming(incoming).await;
+        (&mut session).await;
+        session.conn.inner_mut().take_capture().unwrap().finish().unwrap();
+
+        let path = std::fs::read_dir(dir.path()).unwrap().next().unwrap().unwrap().path();
+        let messages = CaptureReader::open(path).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        let handshake = messages[..4]
+            .iter()
+            .map(|msg| (msg.direction, msg.protocol, msg.message[0]))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            handshake,
+            vec![
+                (CaptureDirection::Outbound, CaptureProtocol::P2P, 0x00),
+                (CaptureDirection::Inbound, CaptureProtocol::P2P, 0x00),
+                (
+                    CaptureDirection::Outbound,
+                    CaptureProtocol::Subprotocol,
+                    EthMessageID::Status as u8
+                ),
+                (
+                    CaptureDirection::Inbound,
+                    CaptureProtocol::Subprotocol,
+                    EthMessageID::Status as u8
+                ),
+            ]
+        );
+        let last = messages.last().unwrap();
+        assert_eq!(last.direction, CaptureDirection::Inbound);
+        assert_eq!(last.message[0], EthMessageID::NewPooledTransactionHashes as u8);
+    }
+
     #[test]
     fn timeout_calculation_sanity_tests() {
         let rtt = Duration::from_secs(5);
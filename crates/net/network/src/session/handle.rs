@@ -5,7 +5,7 @@ use std::{io, net::SocketAddr, sync::Arc, time::Instant};
 use reth_ecies::ECIESError;
 use reth_eth_wire::{
     capability::CapabilityMessage, errors::EthStreamError, Capabilities, DisconnectReason,
-    EthVersion, Status,
+    EthVersion, StatusMessage,
 };
 use reth_network_api::PeerInfo;
 use reth_network_peers::{NodeRecord, PeerId};
@@ -76,7 +76,7 @@ pub struct ActiveSessionHandle {
     /// The local address of the connection.
     pub(crate) local_addr: Option<SocketAddr>,
     /// The Status message the peer sent for the `eth` handshake
-    pub(crate) status: Arc<Status>,
+    pub(crate) status: Arc<StatusMessage>,
 }
 
 // === impl ActiveSessionHandle ===
@@ -176,7 +176,7 @@ pub enum PendingSessionEvent {
         /// All capabilities the peer announced
         capabilities: Arc<Capabilities>,
         /// The Status message the peer sent for the `eth` handshake
-        status: Arc<Status>,
+        status: Arc<StatusMessage>,
         /// The actual connection stream which can be used to send and receive `eth` protocol
         /// messages
         conn: EthRlpxConnection,
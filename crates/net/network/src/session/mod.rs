@@ -4,6 +4,7 @@ mod active;
 mod conn;
 mod counter;
 mod handle;
+mod socks5;
 
 pub use conn::EthRlpxConnection;
 pub use handle::{
@@ -17,6 +18,7 @@ use std::{
     collections::HashMap,
     future::Future,
     net::SocketAddr,
+    path::PathBuf,
     sync::{atomic::AtomicU64, Arc},
     task::{Context, Poll},
     time::{Duration, Instant},
@@ -26,15 +28,19 @@ use counter::SessionCounter;
 use futures::{future::Either, io, FutureExt, StreamExt};
 use reth_ecies::{stream::ECIESStream, ECIESError};
 use reth_eth_wire::{
-    capability::CapabilityMessage, errors::EthStreamError, multiplex::RlpxProtocolMultiplexer,
-    Capabilities, DisconnectReason, EthVersion, HelloMessageWithProtocols, Status,
-    UnauthedEthStream, UnauthedP2PStream,
+    capability::CapabilityMessage,
+    capture::{CaptureSink, CaptureWriter, CAPTURE_FILE_EXTENSION},
+    errors::EthStreamError,
+    multiplex::RlpxProtocolMultiplexer,
+    BlockRangeUpdate, Capabilities, DisconnectReason, EthVersion, HelloMessageWithProtocols,
+    Status, StatusMessage, UnauthedEthStream, UnauthedP2PStream,
 };
 use reth_metrics::common::mpsc::MeteredPollSender;
 use reth_network_api::PeerRequestSender;
 use reth_network_peers::PeerId;
-use reth_network_types::SessionsConfig;
-use reth_primitives::{ForkFilter, ForkId, ForkTransition, Head};
+use reth_network_types::{SessionsConfig, Socks5Proxy};
+use reth_primitives::{BlockNumber, ForkFilter, ForkId, ForkTransition, Head};
+use reth_prune_types::{PruneMode, PruneModes};
 use reth_tasks::TaskSpawner;
 use rustc_hash::FxHashMap;
 use secp256k1::SecretKey;
@@ -45,7 +51,7 @@ use tokio::{
 };
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::sync::PollSender;
-use tracing::{debug, instrument, trace};
+use tracing::{debug, instrument, trace, warn};
 
 use crate::{
     message::PeerMessage,
@@ -54,6 +60,62 @@ use crate::{
     session::active::ActiveSession,
 };
 
+/// Number of blocks the head has to advance before a new [`BlockRangeUpdate`] is announced to
+/// `eth/69` peers.
+const BLOCK_RANGE_UPDATE_INTERVAL: u64 = 32;
+
+/// Returns the earliest block that is announced to `eth/69` peers when the head is at `head`.
+///
+/// Blocks are only served in full if their receipts are not pruned, either entirely or by the
+/// receipts log filter, which only keeps the receipts of selected contracts.
+pub(crate) fn earliest_available_block(prune_modes: &PruneModes, head: BlockNumber) -> BlockNumber {
+    prune_modes
+        .receipts
+        .iter()
+        .chain(prune_modes.receipts_log_filter.0.values())
+        .map(|mode| match mode {
+            PruneMode::Full => head,
+            PruneMode::Distance(distance) => (head + 1).saturating_sub(*distance),
+            PruneMode::Before(block) => *block,
+        })
+        .max()
+        .unwrap_or_default()
+        .min(head)
+}
+
+/// Where and how much of the sessions to record, see [`reth_eth_wire::capture`].
+#[derive(Debug, Clone)]
+pub(crate) struct CaptureConfig {
+    /// Directory to create the capture files in.
+    pub(crate) dir: PathBuf,
+    /// Writes the captures of all sessions, and limits the number of capture files in `dir`.
+    pub(crate) sink: CaptureSink,
+    /// The maximum size of a single capture file, in bytes.
+    pub(crate) max_size: u64,
+}
+
+impl CaptureConfig {
+    /// Spawns the capture writer for the given directory, keeping at most `max_files` capture
+    /// files in it.
+    pub(crate) fn spawn(dir: PathBuf, max_files: usize, max_size: u64) -> io::Result<Self> {
+        let sink = CaptureSink::spawn_in_dir(&dir, max_files)?;
+        Ok(Self { dir, sink, max_size })
+    }
+
+    /// Creates a new capture file for a session with the given peer.
+    ///
+    /// Returns the path of the capture file together with its writer.
+    pub(crate) fn create(&self, peer_id: PeerId) -> io::Result<(PathBuf, CaptureWriter)> {
+        let started = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = self.dir.join(format!("{peer_id}-{started}.{CAPTURE_FILE_EXTENSION}"));
+        let writer = self.sink.create(path.clone())?.with_max_size(self.max_size);
+        Ok((path, writer))
+    }
+}
+
 /// Internal identifier for active sessions.
 #[derive(Debug, Clone, Copy, PartialOrd, PartialEq, Eq, Hash)]
 pub struct SessionId(usize);
@@ -78,6 +140,13 @@ pub struct SessionManager {
     secret_key: SecretKey,
     /// The `Status` message to send to peers.
     status: Status,
+    /// The range of blocks we can serve, sent to `eth/69` peers instead of the total difficulty.
+    block_range: BlockRangeUpdate,
+    /// The prune modes of the node, which move the earliest block of the range as the head
+    /// advances.
+    prune_modes: PruneModes,
+    /// The latest block of the last [`BlockRangeUpdate`] announced to active `eth/69` sessions.
+    last_announced_block_range: u64,
     /// The `HelloMessage` message to send to peers.
     hello_message: HelloMessageWithProtocols,
     /// The [`ForkFilter`] used to validate the peer's `Status` message.
@@ -109,6 +178,10 @@ pub struct SessionManager {
     active_session_rx: ReceiverStream<ActiveSessionMessage>,
     /// Additional `RLPx` sub-protocols to be used by the session manager.
     extra_protocols: RlpxSubProtocols,
+    /// Where to record the messages of sessions to, if enabled.
+    capture: Option<CaptureConfig>,
+    /// SOCKS5 proxy to dial outbound sessions through, if configured.
+    proxy: Option<Socks5Proxy>,
     /// Metrics for the session manager.
     metrics: SessionManagerMetrics,
 }
@@ -123,6 +196,8 @@ impl SessionManager {
         config: SessionsConfig,
         executor: Box<dyn TaskSpawner>,
         status: Status,
+        block_range: BlockRangeUpdate,
+        prune_modes: PruneModes,
         hello_message: HelloMessageWithProtocols,
         fork_filter: ForkFilter,
         extra_protocols: RlpxSubProtocols,
@@ -139,6 +214,9 @@ impl SessionManager {
             pending_session_timeout: config.pending_session_timeout,
             secret_key,
             status,
+            block_range,
+            prune_modes,
+            last_announced_block_range: block_range.latest,
             hello_message,
             fork_filter,
             session_command_buffer: config.session_command_buffer,
@@ -150,6 +228,14 @@ impl SessionManager {
             active_session_tx: MeteredPollSender::new(active_session_tx, "network_active_session"),
             active_session_rx: ReceiverStream::new(active_session_rx),
             extra_protocols,
+            capture: config.capture_dir.and_then(|dir| {
+                CaptureConfig::spawn(dir, config.capture_max_files, config.capture_max_size)
+                    .inspect_err(
+                        |err| warn!(target: "net::session", %err, "failed to start session capture"),
+                    )
+                    .ok()
+            }),
+            proxy: config.proxy,
             metrics: Default::default(),
         }
     }
@@ -172,6 +258,11 @@ impl SessionManager {
         self.status
     }
 
+    /// Returns the range of blocks announced to `eth/69` peers.
+    pub const fn block_range(&self) -> BlockRangeUpdate {
+        self.block_range
+    }
+
     /// Returns the secret key used for authenticating sessions.
     pub const fn secret_key(&self) -> SecretKey {
         self.secret_key
@@ -200,7 +291,7 @@ impl SessionManager {
 
     /// Spawns the given future onto a new task that is tracked in the `spawned_tasks`
     /// [`JoinSet`](tokio::task::JoinSet).
-    fn spawn<F>(&self, f: F)
+    pub(crate) fn spawn<F>(&self, f: F)
     where
         F: Future<Output = ()> + Send + 'static,
     {
@@ -214,11 +305,33 @@ impl SessionManager {
     pub(crate) fn on_status_update(&mut self, head: Head) -> Option<ForkTransition> {
         self.status.blockhash = head.hash;
         self.status.total_difficulty = head.total_difficulty;
+        self.block_range.earliest = earliest_available_block(&self.prune_modes, head.number);
+        self.block_range.latest = head.number;
+        self.block_range.latest_hash = head.hash;
         let transition = self.fork_filter.set_head(head);
         self.status.forkid = self.fork_filter.current();
+
+        if head.number.abs_diff(self.last_announced_block_range) >= BLOCK_RANGE_UPDATE_INTERVAL {
+            self.announce_block_range();
+        }
+
         transition
     }
 
+    /// Sends the current [`BlockRangeUpdate`] to all active `eth/69` sessions.
+    fn announce_block_range(&mut self) {
+        self.last_announced_block_range = self.block_range.latest;
+        let peers = self
+            .active_sessions
+            .iter()
+            .filter(|(_, session)| session.version >= EthVersion::Eth69)
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<_>>();
+        for peer_id in peers {
+            self.send_message(&peer_id, PeerMessage::BlockRangeUpdate(self.block_range));
+        }
+    }
+
     /// An incoming TCP connection was received. This starts the authentication process to turn this
     /// stream into an active peer session.
     ///
@@ -244,8 +357,10 @@ impl SessionManager {
         let secret_key = self.secret_key;
         let hello_message = self.hello_message.clone();
         let status = self.status;
+        let block_range = self.block_range;
         let fork_filter = self.fork_filter.clone();
         let extra_handlers = self.extra_protocols.on_incoming(remote_addr);
+        let capture = self.capture.clone();
         self.spawn(pending_session_with_timeout(
             self.pending_session_timeout,
             session_id,
@@ -261,8 +376,10 @@ impl SessionManager {
                 secret_key,
                 hello_message,
                 status,
+                block_range,
                 fork_filter,
                 extra_handlers,
+                capture,
             ),
         ));
 
@@ -286,7 +403,10 @@ impl SessionManager {
             let hello_message = self.hello_message.clone();
             let fork_filter = self.fork_filter.clone();
             let status = self.status;
+            let block_range = self.block_range;
             let extra_handlers = self.extra_protocols.on_outgoing(remote_addr, remote_peer_id);
+            let proxy = self.proxy.clone();
+            let capture = self.capture.clone();
             self.spawn(pending_session_with_timeout(
                 self.pending_session_timeout,
                 session_id,
@@ -299,11 +419,14 @@ impl SessionManager {
                     session_id,
                     remote_addr,
                     remote_peer_id,
+                    proxy,
                     secret_key,
                     hello_message,
                     status,
+                    block_range,
                     fork_filter,
                     extra_handlers,
+                    capture,
                 ),
             ));
 
@@ -630,7 +753,7 @@ pub enum SessionEvent {
         /// negotiated eth version
         version: EthVersion,
         /// The Status message the peer sent during the `eth` handshake
-        status: Arc<Status>,
+        status: Arc<StatusMessage>,
         /// The channel for sending messages to the peer with the session
         messages: PeerRequestSender,
         /// The direction of the session, either `Inbound` or `Outgoing`
@@ -783,8 +906,10 @@ pub(crate) async fn start_pending_incoming_session(
     secret_key: SecretKey,
     hello: HelloMessageWithProtocols,
     status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    capture: Option<CaptureConfig>,
 ) {
     authenticate(
         disconnect_rx,
@@ -796,13 +921,17 @@ pub(crate) async fn start_pending_incoming_session(
         Direction::Incoming,
         hello,
         status,
+        block_range,
         fork_filter,
         extra_handlers,
+        capture,
     )
     .await
 }
 
 /// Starts the authentication process for a connection initiated by a remote peer.
+///
+/// If a proxy is configured, the connection is dialed through it.
 #[instrument(skip_all, fields(%remote_addr, peer_id), target = "net")]
 #[allow(clippy::too_many_arguments)]
 async fn start_pending_outbound_session(
@@ -811,13 +940,20 @@ async fn start_pending_outbound_session(
     session_id: SessionId,
     remote_addr: SocketAddr,
     remote_peer_id: PeerId,
+    proxy: Option<Socks5Proxy>,
     secret_key: SecretKey,
     hello: HelloMessageWithProtocols,
     status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    capture: Option<CaptureConfig>,
 ) {
-    let stream = match TcpStream::connect(remote_addr).await {
+    let stream = match &proxy {
+        Some(proxy) => socks5::connect(proxy, remote_addr).await,
+        None => TcpStream::connect(remote_addr).await,
+    };
+    let stream = match stream {
         Ok(stream) => {
             if let Err(err) = stream.set_nodelay(true) {
                 tracing::warn!(target: "net::session", "set nodelay failed: {:?}", err);
@@ -846,8 +982,10 @@ async fn start_pending_outbound_session(
         Direction::Outgoing(remote_peer_id),
         hello,
         status,
+        block_range,
         fork_filter,
         extra_handlers,
+        capture,
     )
     .await
 }
@@ -864,8 +1002,10 @@ async fn authenticate(
     direction: Direction,
     hello: HelloMessageWithProtocols,
     status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    capture: Option<CaptureConfig>,
 ) {
     let local_addr = stream.local_addr().ok();
     let stream = match get_eciess_stream(stream, secret_key, direction).await {
@@ -883,7 +1023,19 @@ async fn authenticate(
         }
     };
 
-    let unauthed = UnauthedP2PStream::new(stream);
+    let mut unauthed = UnauthedP2PStream::new(stream);
+    if let Some(capture) = capture {
+        let peer_id = unauthed.inner().remote_id();
+        match capture.create(peer_id) {
+            Ok((path, writer)) => {
+                debug!(target: "net::session", ?peer_id, ?path, "capturing session");
+                unauthed = unauthed.with_capture(writer);
+            }
+            Err(err) => {
+                debug!(target: "net::session", ?peer_id, %err, "failed to capture session")
+            }
+        }
+    }
 
     let auth = authenticate_stream(
         unauthed,
@@ -893,6 +1045,7 @@ async fn authenticate(
         direction,
         hello,
         status,
+        block_range,
         fork_filter,
         extra_handlers,
     )
@@ -944,7 +1097,8 @@ async fn authenticate_stream(
     local_addr: Option<SocketAddr>,
     direction: Direction,
     mut hello: HelloMessageWithProtocols,
-    mut status: Status,
+    status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     mut extra_handlers: RlpxSubProtocolHandlers,
 ) -> PendingSessionEvent {
@@ -981,7 +1135,7 @@ async fn authenticate_stream(
         // if the hello handshake was successful we can try status handshake
         //
         // Before trying status handshake, set up the version to negotiated shared version
-        status.set_eth_version(eth_version);
+        let status = status.into_message(eth_version, block_range);
         let eth_unauthed = UnauthedEthStream::new(p2p_stream);
         let (eth_stream, their_status) = match eth_unauthed.handshake(status, fork_filter).await {
             Ok(stream_res) => stream_res,
@@ -1010,6 +1164,7 @@ async fn authenticate_stream(
                 .ok();
         }
 
+        let status = status.into_message(eth_version, block_range);
         let (multiplex_stream, their_status) =
             match multiplex_stream.into_eth_satellite_stream(status, fork_filter).await {
                 Ok((multiplex_stream, their_status)) => (multiplex_stream, their_status),
@@ -1038,3 +1193,38 @@ async fn authenticate_stream(
         client_id: their_hello.client_version,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Address;
+    use reth_prune_types::ReceiptsLogPruneConfig;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn earliest_block_from_prune_modes() {
+        assert_eq!(earliest_available_block(&PruneModes::none(), 1000), 0);
+
+        let full = PruneModes { receipts: Some(PruneMode::Full), ..PruneModes::none() };
+        assert_eq!(earliest_available_block(&full, 1000), 1000);
+
+        let distance = PruneModes { receipts: Some(PruneMode::Distance(64)), ..PruneModes::none() };
+        assert_eq!(earliest_available_block(&distance, 1000), 937);
+        assert_eq!(earliest_available_block(&distance, 10), 0);
+
+        let before = PruneModes { receipts: Some(PruneMode::Before(500)), ..PruneModes::none() };
+        assert_eq!(earliest_available_block(&before, 1000), 500);
+        assert_eq!(earliest_available_block(&before, 100), 100);
+
+        // receipts of other contracts are pruned up to the highest block of the log filter
+        let log_filter = PruneModes {
+            receipts: Some(PruneMode::Before(500)),
+            receipts_log_filter: ReceiptsLogPruneConfig(BTreeMap::from([
+                (Address::with_last_byte(1), PruneMode::Before(700)),
+                (Address::with_last_byte(2), PruneMode::Distance(900)),
+            ])),
+            ..PruneModes::none()
+        };
+        assert_eq!(earliest_available_block(&log_filter, 1000), 700);
+    }
+}
@@ -0,0 +1,203 @@
+//! Minimal SOCKS5 client for dialing outbound sessions through a proxy.
+//!
+//! Implements the `CONNECT` command of [RFC 1928](https://datatracker.ietf.org/doc/html/rfc1928)
+//! and username/password authentication of [RFC 1929](https://datatracker.ietf.org/doc/html/rfc1929).
+
+use std::{
+    io,
+    net::{IpAddr, SocketAddr},
+};
+
+use reth_network_types::Socks5Proxy;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+/// SOCKS protocol version.
+const SOCKS_VERSION: u8 = 0x05;
+/// Version of the username/password authentication sub-negotiation.
+const AUTH_VERSION: u8 = 0x01;
+
+/// No authentication required.
+const METHOD_NO_AUTH: u8 = 0x00;
+/// Username/password authentication.
+const METHOD_USERNAME_PASSWORD: u8 = 0x02;
+/// No acceptable authentication method.
+const METHOD_NO_ACCEPTABLE: u8 = 0xff;
+
+/// The `CONNECT` command.
+const CMD_CONNECT: u8 = 0x01;
+
+/// IPv4 address type.
+const ATYP_IPV4: u8 = 0x01;
+/// Domain name address type.
+const ATYP_DOMAIN: u8 = 0x03;
+/// IPv6 address type.
+const ATYP_IPV6: u8 = 0x04;
+
+/// Opens a TCP connection to the target through the SOCKS5 proxy.
+///
+/// Once this returns, the stream is tunneled to the target and can be used as if it was connected
+/// directly.
+pub(crate) async fn connect(proxy: &Socks5Proxy, target: SocketAddr) -> io::Result<TcpStream> {
+    let mut stream = TcpStream::connect(proxy.addr).await?;
+    handshake(&mut stream, proxy, target).await?;
+    Ok(stream)
+}
+
+/// Negotiates the authentication method, authenticates and requests a tunnel to the target.
+async fn handshake(
+    stream: &mut TcpStream,
+    proxy: &Socks5Proxy,
+    target: SocketAddr,
+) -> io::Result<()> {
+    // greeting
+    let method = if proxy.auth.is_some() { METHOD_USERNAME_PASSWORD } else { METHOD_NO_AUTH };
+    stream.write_all(&[SOCKS_VERSION, 1, method]).await?;
+
+    let mut choice = [0u8; 2];
+    stream.read_exact(&mut choice).await?;
+    if choice[0] != SOCKS_VERSION {
+        return Err(invalid_data("unexpected SOCKS version"))
+    }
+    match choice[1] {
+        METHOD_NO_AUTH => {}
+        METHOD_USERNAME_PASSWORD => {
+            let Some(auth) = &proxy.auth else {
+                return Err(invalid_data("proxy chose a method that was not offered"))
+            };
+            let username = auth.username.as_bytes();
+            let password = auth.password.as_bytes();
+            let (Ok(username_len), Ok(password_len)) =
+                (u8::try_from(username.len()), u8::try_from(password.len()))
+            else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "proxy username and password must be at most 255 bytes",
+                ))
+            };
+
+            let mut request = Vec::with_capacity(3 + username.len() + password.len());
+            request.extend_from_slice(&[AUTH_VERSION, username_len]);
+            request.extend_from_slice(username);
+            request.push(password_len);
+            request.extend_from_slice(password);
+            stream.write_all(&request).await?;
+
+            let mut status = [0u8; 2];
+            stream.read_exact(&mut status).await?;
+            if status[1] != 0x00 {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "proxy rejected the credentials",
+                ))
+            }
+        }
+        METHOD_NO_ACCEPTABLE => {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "proxy accepts none of the offered authentication methods",
+            ))
+        }
+        _ => return Err(invalid_data("proxy chose a method that was not offered")),
+    }
+
+    // connect request
+    let mut request = vec![SOCKS_VERSION, CMD_CONNECT, 0x00];
+    match target.ip() {
+        IpAddr::V4(ip) => {
+            request.push(ATYP_IPV4);
+            request.extend_from_slice(&ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            request.push(ATYP_IPV6);
+            request.extend_from_slice(&ip.octets());
+        }
+    }
+    request.extend_from_slice(&target.port().to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != SOCKS_VERSION {
+        return Err(invalid_data("unexpected SOCKS version"))
+    }
+    if reply[1] != 0x00 {
+        return Err(reply_error(reply[1]))
+    }
+
+    // skip the address the proxy bound for the tunnel
+    let addr_len = match reply[3] {
+        ATYP_IPV4 => 4,
+        ATYP_IPV6 => 16,
+        ATYP_DOMAIN => stream.read_u8().await? as usize,
+        _ => return Err(invalid_data("unknown address type")),
+    };
+    let mut bound = vec![0u8; addr_len + 2];
+    stream.read_exact(&mut bound).await?;
+
+    Ok(())
+}
+
+/// Returns the error for a failed `CONNECT` reply.
+fn reply_error(code: u8) -> io::Error {
+    let (kind, msg) = match code {
+        0x02 => (io::ErrorKind::PermissionDenied, "connection not allowed by ruleset"),
+        0x03 => (io::ErrorKind::Other, "network unreachable"),
+        0x04 => (io::ErrorKind::Other, "host unreachable"),
+        0x05 => (io::ErrorKind::ConnectionRefused, "connection refused"),
+        0x06 => (io::ErrorKind::TimedOut, "TTL expired"),
+        0x07 => (io::ErrorKind::Unsupported, "command not supported"),
+        0x08 => (io::ErrorKind::Unsupported, "address type not supported"),
+        _ => (io::ErrorKind::Other, "general SOCKS server failure"),
+    };
+    io::Error::new(kind, format!("proxy failed to connect: {msg}"))
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::Socks5Server;
+    use tokio::net::TcpListener;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn connect_through_proxy() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let target = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4];
+            stream.read_exact(&mut buf).await.unwrap();
+            stream.write_all(&buf).await.unwrap();
+        });
+
+        let server = Socks5Server::spawn_with_auth("user", "password").await;
+        let proxy = Socks5Proxy::new(server.local_addr()).with_auth("user", "password");
+
+        let mut stream = connect(&proxy, target).await.unwrap();
+        stream.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+        assert_eq!(server.connections(), vec![target]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reject_credentials() {
+        let server = Socks5Server::spawn_with_auth("user", "password").await;
+
+        let proxy = Socks5Proxy::new(server.local_addr()).with_auth("user", "wrong");
+        let err = connect(&proxy, ([127, 0, 0, 1], 30303).into()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+        let proxy = Socks5Proxy::new(server.local_addr());
+        let err = connect(&proxy, ([127, 0, 0, 1], 30303).into()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(server.connections().is_empty());
+    }
+}
@@ -13,7 +13,10 @@ use std::{
 };
 
 use rand::seq::SliceRandom;
-use reth_eth_wire::{BlockHashNumber, Capabilities, DisconnectReason, NewBlockHashes, Status};
+use reth_eth_wire::{
+    BlockHashNumber, BlockRangeUpdate, Capabilities, DisconnectReason, NewBlockHashes,
+    StatusMessage,
+};
 use reth_network_api::{DiscoveredEvent, DiscoveryEvent, PeerRequest, PeerRequestSender};
 use reth_network_peers::PeerId;
 use reth_network_types::{PeerAddr, PeerKind};
@@ -78,7 +81,7 @@ pub struct NetworkState {
     /// The client type that can interact with the chain.
     ///
     /// This type is used to fetch the block number after we established a session and received the
-    /// [Status] block hash.
+    /// [`StatusMessage`] block hash.
     client: BlockNumReader,
     /// Network discovery.
     discovery: Discovery,
@@ -142,7 +145,7 @@ impl NetworkState {
         &mut self,
         peer: PeerId,
         capabilities: Arc<Capabilities>,
-        status: Arc<Status>,
+        status: Arc<StatusMessage>,
         request_tx: PeerRequestSender,
         timeout: Arc<AtomicU64>,
     ) {
@@ -150,13 +153,16 @@ impl NetworkState {
 
         // find the corresponding block number
         let block_number =
-            self.client.block_number(status.blockhash).ok().flatten().unwrap_or_default();
-        self.state_fetcher.new_active_peer(peer, status.blockhash, block_number, timeout);
+            self.client.block_number(status.blockhash()).ok().flatten().unwrap_or_default();
+        self.state_fetcher.new_active_peer(peer, status.blockhash(), block_number, timeout);
+        if let Some(block_range) = status.block_range() {
+            self.state_fetcher.update_peer_block_range(&peer, block_range);
+        }
 
         self.active_peers.insert(
             peer,
             ActivePeer {
-                best_hash: status.blockhash,
+                best_hash: status.blockhash(),
                 capabilities,
                 request_tx,
                 pending_response: None,
@@ -251,6 +257,11 @@ impl NetworkState {
         self.state_fetcher.update_peer_block(peer_id, hash, number);
     }
 
+    /// Invoked for a `BlockRangeUpdate` message of an `eth/69` peer.
+    pub(crate) fn on_block_range_update(&mut self, peer_id: PeerId, block_range: BlockRangeUpdate) {
+        self.state_fetcher.update_peer_block_range(&peer_id, block_range);
+    }
+
     /// Invoked when a new [`ForkId`] is activated.
     pub(crate) fn update_fork_id(&self, fork_id: ForkId) {
         self.discovery.update_fork_id(fork_id)
@@ -8,7 +8,7 @@ use std::{
 
 use futures::Stream;
 use reth_eth_wire::{
-    capability::CapabilityMessage, errors::EthStreamError, Capabilities, EthVersion, Status,
+    capability::CapabilityMessage, errors::EthStreamError, Capabilities, EthVersion, StatusMessage,
 };
 use reth_network_api::PeerRequestSender;
 use reth_network_peers::PeerId;
@@ -390,7 +390,7 @@ pub(crate) enum SwarmEvent {
         /// negotiated eth version
         version: EthVersion,
         messages: PeerRequestSender,
-        status: Arc<Status>,
+        status: Arc<StatusMessage>,
         direction: Direction,
     },
     SessionClosed {
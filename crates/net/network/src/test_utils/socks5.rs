@@ -0,0 +1,143 @@
+//! A local SOCKS5 proxy stand-in for testing proxied connections.
+
+use std::{
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
+
+use parking_lot::Mutex;
+use tokio::{
+    io::{copy_bidirectional, AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+/// A SOCKS5 proxy that tunnels `CONNECT` requests to IP addresses and records their targets.
+///
+/// Only supports what the session dialer needs. The proxy is shut down when this is dropped.
+#[derive(Debug)]
+pub struct Socks5Server {
+    local_addr: SocketAddr,
+    connections: Arc<Mutex<Vec<SocketAddr>>>,
+    task: JoinHandle<()>,
+}
+
+impl Socks5Server {
+    /// Spawns a proxy on localhost that requires no authentication.
+    pub async fn spawn() -> Self {
+        Self::spawn_inner(None).await
+    }
+
+    /// Spawns a proxy on localhost that requires the given username and password.
+    pub async fn spawn_with_auth(username: &str, password: &str) -> Self {
+        Self::spawn_inner(Some((username.to_string(), password.to_string()))).await
+    }
+
+    async fn spawn_inner(auth: Option<(String, String)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("failed to bind proxy");
+        let local_addr = listener.local_addr().expect("failed to get proxy address");
+        let connections = Arc::new(Mutex::new(Vec::new()));
+
+        let auth = Arc::new(auth);
+        let recorded = Arc::clone(&connections);
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let auth = Arc::clone(&auth);
+                let recorded = Arc::clone(&recorded);
+                tokio::spawn(async move {
+                    let _ = serve(stream, auth.as_ref().as_ref(), &recorded).await;
+                });
+            }
+        });
+
+        Self { local_addr, connections, task }
+    }
+
+    /// Returns the address the proxy listens on.
+    pub const fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Returns the targets of all tunnels the proxy opened.
+    pub fn connections(&self) -> Vec<SocketAddr> {
+        self.connections.lock().clone()
+    }
+}
+
+impl Drop for Socks5Server {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Serves a single client connection.
+async fn serve(
+    mut stream: TcpStream,
+    auth: Option<&(String, String)>,
+    connections: &Mutex<Vec<SocketAddr>>,
+) -> io::Result<()> {
+    // greeting
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).await?;
+    let mut methods = vec![0u8; header[1] as usize];
+    stream.read_exact(&mut methods).await?;
+
+    let method = if auth.is_some() { 0x02 } else { 0x00 };
+    if !methods.contains(&method) {
+        stream.write_all(&[0x05, 0xff]).await?;
+        return Ok(())
+    }
+    stream.write_all(&[0x05, method]).await?;
+
+    if let Some((username, password)) = auth {
+        let version = stream.read_u8().await?;
+        let mut client_username = vec![0u8; stream.read_u8().await? as usize];
+        stream.read_exact(&mut client_username).await?;
+        let mut client_password = vec![0u8; stream.read_u8().await? as usize];
+        stream.read_exact(&mut client_password).await?;
+
+        if version != 0x01 ||
+            client_username != username.as_bytes() ||
+            client_password != password.as_bytes()
+        {
+            stream.write_all(&[0x01, 0x01]).await?;
+            return Ok(())
+        }
+        stream.write_all(&[0x01, 0x00]).await?;
+    }
+
+    // connect request
+    let mut request = [0u8; 4];
+    stream.read_exact(&mut request).await?;
+    let ip = match request[3] {
+        0x01 => {
+            let mut octets = [0u8; 4];
+            stream.read_exact(&mut octets).await?;
+            Ipv4Addr::from(octets).into()
+        }
+        0x04 => {
+            let mut octets = [0u8; 16];
+            stream.read_exact(&mut octets).await?;
+            Ipv6Addr::from(octets).into()
+        }
+        _ => {
+            stream.write_all(&[0x05, 0x08, 0x00, 0x01, 0, 0, 0, 0, 0, 0]).await?;
+            return Ok(())
+        }
+    };
+    let target = SocketAddr::new(ip, stream.read_u16().await?);
+
+    let mut upstream = match TcpStream::connect(target).await {
+        Ok(upstream) => upstream,
+        Err(_) => {
+            stream.write_all(&[0x05, 0x05, 0x00, 0x01, 0, 0, 0, 0, 0, 0]).await?;
+            return Ok(())
+        }
+    };
+    connections.lock().push(target);
+    stream.write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0]).await?;
+
+    copy_bidirectional(&mut stream, &mut upstream).await?;
+    Ok(())
+}
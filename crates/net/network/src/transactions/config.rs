@@ -1,7 +1,7 @@
 use derive_more::Constructor;
 
 use super::{
-    DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
+    policy::TransactionPropagationPolicy, DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
     DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
     SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
 };
@@ -17,6 +17,9 @@ pub struct TransactionsManagerConfig {
     pub transaction_fetcher_config: TransactionFetcherConfig,
     /// Max number of seen transactions to store for each peer.
     pub max_transactions_seen_by_peer_history: u32,
+    /// Which peers transactions are propagated to.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub propagation_policy: TransactionPropagationPolicy,
 }
 
 impl Default for TransactionsManagerConfig {
@@ -24,6 +27,7 @@ impl Default for TransactionsManagerConfig {
         Self {
             transaction_fetcher_config: TransactionFetcherConfig::default(),
             max_transactions_seen_by_peer_history: DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
+            propagation_policy: TransactionPropagationPolicy::default(),
         }
     }
 }
@@ -6,6 +6,8 @@ pub mod config;
 pub mod constants;
 /// Component responsible for fetching transactions from [`NewPooledTransactionHashes`].
 pub mod fetcher;
+/// Policies that decide which peers transactions are propagated to.
+pub mod policy;
 pub mod validation;
 
 pub use self::constants::{
@@ -13,9 +15,11 @@ pub use self::constants::{
     SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
 };
 pub use config::{TransactionFetcherConfig, TransactionsManagerConfig};
+pub use policy::{PropagationScope, TransactionPropagationPolicy};
 pub use validation::*;
 
 pub(crate) use fetcher::{FetchEvent, TransactionFetcher};
+use policy::BroadcastBandwidth;
 
 use self::constants::{tx_manager::*, DEFAULT_SOFT_LIMIT_BYTE_SIZE_TRANSACTIONS_BROADCAST_MESSAGE};
 use constants::SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE;
@@ -46,7 +50,7 @@ use reth_network_p2p::{
     sync::SyncStateProvider,
 };
 use reth_network_peers::PeerId;
-use reth_network_types::ReputationChangeKind;
+use reth_network_types::{PeerKind, ReputationChangeKind};
 use reth_primitives::{
     PooledTransactionsElement, TransactionSigned, TransactionSignedEcRecovered, TxHash, B256,
 };
@@ -54,7 +58,7 @@ use reth_tokio_util::EventStream;
 use reth_transaction_pool::{
     error::{PoolError, PoolResult},
     GetPooledTransactionLimit, PoolTransaction, PropagateKind, PropagatedTransactions,
-    TransactionPool, ValidPoolTransaction,
+    TransactionOrigin, TransactionPool, ValidPoolTransaction,
 };
 use tokio::sync::{mpsc, oneshot, oneshot::error::RecvError};
 use tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
@@ -249,6 +253,8 @@ pub struct TransactionsManager<Pool> {
     transaction_events: UnboundedMeteredReceiver<NetworkTransactionEvent>,
     /// Max number of seen transactions to store for each peer.
     max_transactions_seen_by_peer_history: u32,
+    /// Decides which peers transactions are propagated to.
+    propagation_policy: TransactionPropagationPolicy,
     /// `TransactionsManager` metrics
     metrics: TransactionsManagerMetrics,
 }
@@ -301,6 +307,7 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
             ),
             max_transactions_seen_by_peer_history: transactions_manager_config
                 .max_transactions_seen_by_peer_history,
+            propagation_policy: transactions_manager_config.propagation_policy,
             metrics,
         }
     }
@@ -416,6 +423,8 @@ where
     /// See [`NewPooledTransactionHashes`]
     ///
     /// Note: EIP-4844 are disallowed from being broadcast in full and are only ever sent as hashes, see also <https://eips.ethereum.org/EIPS/eip-4844#networking>.
+    ///
+    /// Transactions are only propagated to the peers the [`TransactionPropagationPolicy`] allows.
     fn propagate_transactions(
         &mut self,
         to_propagate: Vec<PropagateTransaction>,
@@ -435,7 +444,9 @@ where
             let mut builder = if peer_idx > max_num_full {
                 PropagateTransactionsBuilder::pooled(peer.version)
             } else {
-                PropagateTransactionsBuilder::full(peer.version)
+                let size_limit =
+                    peer.broadcast_size_limit(self.propagation_policy.max_broadcast_bytes_per_peer);
+                PropagateTransactionsBuilder::full_with_size_limit(peer.version, size_limit)
             };
 
             // Iterate through the transactions to propagate and fill the hashes and full
@@ -443,7 +454,10 @@ where
             // peer.
             for tx in &to_propagate {
                 // Only proceed if the transaction is not in the peer's list of seen transactions
-                if !peer.seen_transactions.contains(&tx.hash()) {
+                // and may be propagated to the peer
+                if !peer.seen_transactions.contains(&tx.hash()) &&
+                    self.propagation_policy.allows(tx.origin, tx.is_eip4844(), peer.peer_kind)
+                {
                     // add transaction to the list of hashes to propagate
                     builder.push(tx);
                 }
@@ -454,6 +468,7 @@ where
                 continue
             }
 
+            let full_size = builder.full_size();
             let PropagateTransactions { pooled, full } = builder.build();
 
             // send hashes if any
@@ -485,6 +500,8 @@ where
 
                 trace!(target: "net::tx", ?peer_id, num_txs=?new_full_transactions.len(), "Propagating full transactions to peer");
 
+                peer.broadcast_bandwidth.record(full_size);
+
                 // send full transactions
                 self.network.send_transactions(*peer_id, new_full_transactions);
             }
@@ -510,13 +527,18 @@ where
         let mut propagated = PropagatedTransactions::default();
 
         // filter all transactions unknown to the peer
-        let mut full_transactions = FullTransactionsBuilder::new(peer.version);
+        let size_limit =
+            peer.broadcast_size_limit(self.propagation_policy.max_broadcast_bytes_per_peer);
+        let mut full_transactions =
+            PropagateTransactionsBuilder::full_with_size_limit(peer.version, size_limit);
 
         let to_propagate = self.pool.get_all(txs).into_iter().map(PropagateTransaction::new);
 
         // Iterate through the transactions to propagate and fill the hashes and full transaction
         for tx in to_propagate {
-            if !peer.seen_transactions.contains(&tx.hash()) {
+            if !peer.seen_transactions.contains(&tx.hash()) &&
+                self.propagation_policy.allows(tx.origin, tx.is_eip4844(), peer.peer_kind)
+            {
                 full_transactions.push(&tx);
             }
         }
@@ -526,6 +548,7 @@ where
             return None
         }
 
+        let full_size = full_transactions.full_size();
         let PropagateTransactions { pooled, full } = full_transactions.build();
 
         // send hashes if any
@@ -546,6 +569,7 @@ where
                 // mark transaction as seen by peer
                 peer.seen_transactions.insert(tx.hash());
             }
+            peer.broadcast_bandwidth.record(full_size);
             // send full transactions
             self.network.send_transactions(peer_id, new_full_transactions);
         }
@@ -579,6 +603,9 @@ where
             let mut hashes = PooledTransactionsHashesBuilder::new(peer.version);
 
             for tx in to_propagate {
+                if !self.propagation_policy.allows(tx.origin, tx.is_eip4844(), peer.peer_kind) {
+                    continue
+                }
                 if !peer.seen_transactions.insert(tx.hash()) {
                     hashes.push(&tx);
                 }
@@ -907,7 +934,12 @@ where
                 self.peers.remove(&peer_id);
             }
             NetworkEvent::SessionEstablished {
-                peer_id, client_version, messages, version, ..
+                peer_id,
+                client_version,
+                messages,
+                version,
+                peer_kind,
+                ..
             } => {
                 // Insert a new peer into the peerset.
                 let peer = PeerMetadata::new(
@@ -915,6 +947,7 @@ where
                     version,
                     client_version,
                     self.max_transactions_seen_by_peer_history,
+                    peer_kind,
                 );
                 let peer = match self.peers.entry(peer_id) {
                     Entry::Occupied(mut entry) => {
@@ -941,10 +974,22 @@ where
 
                 let mut msg_builder = PooledTransactionsHashesBuilder::new(version);
                 for pooled_tx in pooled_txs {
+                    if !self.propagation_policy.allows(
+                        pooled_tx.origin,
+                        pooled_tx.is_eip4844(),
+                        peer_kind,
+                    ) {
+                        continue
+                    }
                     peer.seen_transactions.insert(*pooled_tx.hash());
                     msg_builder.push_pooled(pooled_tx);
                 }
 
+                if msg_builder.is_empty() {
+                    // none of the transactions may be propagated to the peer
+                    return
+                }
+
                 let msg = msg_builder.build();
                 self.network.send_transactions_hashes(peer_id, msg);
             }
@@ -1387,6 +1432,7 @@ where
 struct PropagateTransaction {
     size: usize,
     transaction: Arc<TransactionSigned>,
+    origin: TransactionOrigin,
 }
 
 // === impl PropagateTransaction ===
@@ -1396,13 +1442,18 @@ impl PropagateTransaction {
         self.transaction.hash()
     }
 
+    fn is_eip4844(&self) -> bool {
+        self.transaction.is_eip4844()
+    }
+
     /// Create a new instance from a pooled transaction
     fn new<T: PoolTransaction<Consensus = TransactionSignedEcRecovered>>(
         tx: Arc<ValidPoolTransaction<T>>,
     ) -> Self {
         let size = tx.encoded_length();
+        let origin = tx.origin;
         let transaction = Arc::new(tx.transaction.clone().into_consensus().into_signed());
-        Self { size, transaction }
+        Self { size, transaction, origin }
     }
 }
 
@@ -1421,10 +1472,20 @@ impl PropagateTransactionsBuilder {
     }
 
     /// Create a builder that sends transactions in full and records transactions that don't fit.
+    #[cfg(test)]
     fn full(version: EthVersion) -> Self {
         Self::Full(FullTransactionsBuilder::new(version))
     }
 
+    /// Create a builder that sends transactions in full up to the given soft size limit, or only
+    /// hashes if the limit is zero.
+    fn full_with_size_limit(version: EthVersion, size_limit: usize) -> Self {
+        if size_limit == 0 {
+            return Self::pooled(version)
+        }
+        Self::Full(FullTransactionsBuilder::new(version).with_size_limit(size_limit))
+    }
+
     /// Appends a transaction to the list.
     fn push(&mut self, transaction: &PropagateTransaction) {
         match self {
@@ -1441,6 +1502,14 @@ impl PropagateTransactionsBuilder {
         }
     }
 
+    /// Returns the byte size of the transactions that are sent in full.
+    const fn full_size(&self) -> usize {
+        match self {
+            Self::Pooled(_) => 0,
+            Self::Full(builder) => builder.total_size,
+        }
+    }
+
     /// Consumes the type and returns the built messages that should be sent to the peer.
     fn build(self) -> PropagateTransactions {
         match self {
@@ -1467,6 +1536,8 @@ struct PropagateTransactions {
 #[derive(Debug, Clone)]
 struct FullTransactionsBuilder {
     /// The soft limit to enforce for a single broadcast message of full transactions.
+    size_limit: usize,
+    /// The byte size of all transactions to be broadcasted.
     total_size: usize,
     /// All transactions to be broadcasted.
     transactions: Vec<Arc<TransactionSigned>>,
@@ -1480,12 +1551,19 @@ impl FullTransactionsBuilder {
     /// Create a builder for the negotiated version of the peer's session
     fn new(version: EthVersion) -> Self {
         Self {
+            size_limit: DEFAULT_SOFT_LIMIT_BYTE_SIZE_TRANSACTIONS_BROADCAST_MESSAGE,
             total_size: 0,
             pooled: PooledTransactionsHashesBuilder::new(version),
             transactions: vec![],
         }
     }
 
+    /// Sets the soft limit for the byte size of the broadcast message.
+    const fn with_size_limit(mut self, size_limit: usize) -> Self {
+        self.size_limit = size_limit;
+        self
+    }
+
     /// Append a transaction to the list of full transaction if the total message bytes size doesn't
     /// exceed the soft maximum target byte size. The limit is soft, meaning if one single
     /// transaction goes over the limit, it will be broadcasted in its own [`Transactions`]
@@ -1509,9 +1587,7 @@ impl FullTransactionsBuilder {
         }
 
         let new_size = self.total_size + transaction.size;
-        if new_size > DEFAULT_SOFT_LIMIT_BYTE_SIZE_TRANSACTIONS_BROADCAST_MESSAGE &&
-            self.total_size > 0
-        {
+        if new_size > self.size_limit && self.total_size > 0 {
             // transaction does not fit into the message
             self.pooled.push(transaction);
             return
@@ -1580,7 +1656,7 @@ impl PooledTransactionsHashesBuilder {
     fn new(version: EthVersion) -> Self {
         match version {
             EthVersion::Eth66 | EthVersion::Eth67 => Self::Eth66(Default::default()),
-            EthVersion::Eth68 => Self::Eth68(Default::default()),
+            EthVersion::Eth68 | EthVersion::Eth69 => Self::Eth68(Default::default()),
         }
     }
 
@@ -1622,6 +1698,10 @@ pub struct PeerMetadata {
     version: EthVersion,
     /// The peer's client version.
     client_version: Arc<str>,
+    /// The kind of the peer.
+    peer_kind: PeerKind,
+    /// Bytes of full transactions recently broadcast to the peer.
+    broadcast_bandwidth: BroadcastBandwidth,
 }
 
 impl PeerMetadata {
@@ -1631,12 +1711,25 @@ impl PeerMetadata {
         version: EthVersion,
         client_version: Arc<str>,
         max_transactions_seen_by_peer: u32,
+        peer_kind: PeerKind,
     ) -> Self {
         Self {
             seen_transactions: LruCache::new(max_transactions_seen_by_peer),
             request_tx,
             version,
             client_version,
+            peer_kind,
+            broadcast_bandwidth: BroadcastBandwidth::default(),
+        }
+    }
+
+    /// Returns the soft limit for the byte size of full transactions that may be broadcast to the
+    /// peer now, given the max bytes per peer.
+    fn broadcast_size_limit(&mut self, max_broadcast_bytes_per_peer: Option<usize>) -> usize {
+        let size_limit = DEFAULT_SOFT_LIMIT_BYTE_SIZE_TRANSACTIONS_BROADCAST_MESSAGE;
+        match max_broadcast_bytes_per_peer {
+            Some(max_bytes) => self.broadcast_bandwidth.remaining(max_bytes).min(size_limit),
+            None => size_limit,
         }
     }
 }
@@ -1801,6 +1894,7 @@ mod tests {
                 version,
                 Arc::from(""),
                 DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
+                PeerKind::Basic,
             ),
             to_mock_session_rx,
         )
@@ -1855,6 +1949,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => {
                     // to insert a new peer in transactions peerset
                     transactions.on_network_event(NetworkEvent::SessionEstablished {
@@ -1865,6 +1960,7 @@ mod tests {
                         messages,
                         status,
                         version,
+                        peer_kind,
                     })
                 }
                 NetworkEvent::PeerAdded(_peer_id) => continue,
@@ -1941,6 +2037,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => {
                     // to insert a new peer in transactions peerset
                     transactions.on_network_event(NetworkEvent::SessionEstablished {
@@ -1951,6 +2048,7 @@ mod tests {
                         messages,
                         status,
                         version,
+                        peer_kind,
                     })
                 }
                 NetworkEvent::PeerAdded(_peer_id) => continue,
@@ -2025,6 +2123,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => {
                     // to insert a new peer in transactions peerset
                     transactions.on_network_event(NetworkEvent::SessionEstablished {
@@ -2035,6 +2134,7 @@ mod tests {
                         messages,
                         status,
                         version,
+                        peer_kind,
                     })
                 }
                 NetworkEvent::PeerAdded(_peer_id) => continue,
@@ -2116,6 +2216,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => transactions.on_network_event(NetworkEvent::SessionEstablished {
                     peer_id,
                     remote_addr,
@@ -2124,6 +2225,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 }),
                 NetworkEvent::PeerAdded(_peer_id) => continue,
                 ev => {
@@ -2361,6 +2463,7 @@ mod tests {
             messages: PeerRequestSender::new(peer_id, tx),
             status: Arc::new(Default::default()),
             version: EthVersion::Eth68,
+            peer_kind: PeerKind::Basic,
         });
 
         let mut propagate = vec![];
@@ -2389,4 +2492,66 @@ mod tests {
         let propagated = tx_manager.propagate_transactions(propagate);
         assert!(propagated.0.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_propagate_local_to_trusted_only() {
+        let (mut tx_manager, _network) = new_tx_manager().await;
+        tx_manager.propagation_policy =
+            TransactionPropagationPolicy::default().with_local(PropagationScope::Trusted);
+
+        let basic_peer = PeerId::random();
+        let (peer, _basic_rx) = new_mock_session(basic_peer, EthVersion::Eth68);
+        tx_manager.peers.insert(basic_peer, peer);
+        let trusted_peer = PeerId::random();
+        let (mut peer, _trusted_rx) = new_mock_session(trusted_peer, EthVersion::Eth68);
+        peer.peer_kind = PeerKind::Trusted;
+        tx_manager.peers.insert(trusted_peer, peer);
+
+        let mut factory = MockTransactionFactory::default();
+        let local_tx = Arc::new(
+            factory.validated_with_origin(TransactionOrigin::Local, MockTransaction::eip1559()),
+        );
+        let external_tx = Arc::new(factory.create_eip1559());
+        let propagate = vec![
+            PropagateTransaction::new(local_tx.clone()),
+            PropagateTransaction::new(external_tx.clone()),
+        ];
+
+        let propagated = tx_manager.propagate_transactions(propagate);
+        let prop_txs = propagated.0.get(local_tx.transaction.hash()).unwrap();
+        assert_eq!(prop_txs.len(), 1);
+        assert_eq!(prop_txs[0].peer(), &trusted_peer);
+        assert_eq!(propagated.0.get(external_tx.transaction.hash()).unwrap().len(), 2);
+
+        let peer = tx_manager.peers.get(&basic_peer).unwrap();
+        assert!(!peer.seen_transactions.contains(local_tx.transaction.hash()));
+    }
+
+    #[tokio::test]
+    async fn test_propagate_max_broadcast_bytes_per_peer() {
+        let (mut tx_manager, _network) = new_tx_manager().await;
+        tx_manager.propagation_policy =
+            TransactionPropagationPolicy::default().with_max_broadcast_bytes_per_peer(Some(1));
+
+        let peer_id = PeerId::random();
+        let (peer, _rx) = new_mock_session(peer_id, EthVersion::Eth68);
+        tx_manager.peers.insert(peer_id, peer);
+
+        let mut factory = MockTransactionFactory::default();
+        let first_tx = Arc::new(factory.create_eip1559());
+        let second_tx = Arc::new(factory.create_eip1559());
+        let propagated = tx_manager.propagate_transactions(vec![
+            PropagateTransaction::new(first_tx.clone()),
+            PropagateTransaction::new(second_tx.clone()),
+        ]);
+
+        // the first transaction overshoots the limit, the rest is announced as hashes
+        assert!(propagated.0.get(first_tx.transaction.hash()).unwrap()[0].is_full());
+        assert!(propagated.0.get(second_tx.transaction.hash()).unwrap()[0].is_hash());
+
+        let third_tx = Arc::new(factory.create_eip1559());
+        let propagated =
+            tx_manager.propagate_transactions(vec![PropagateTransaction::new(third_tx.clone())]);
+        assert!(propagated.0.get(third_tx.transaction.hash()).unwrap()[0].is_hash());
+    }
 }
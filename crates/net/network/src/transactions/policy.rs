@@ -0,0 +1,196 @@
+//! Policies that decide which peers transactions are propagated to.
+
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use reth_network_types::PeerKind;
+use reth_transaction_pool::TransactionOrigin;
+
+/// Window over which [`TransactionPropagationPolicy::max_broadcast_bytes_per_peer`] is enforced.
+const BROADCAST_BANDWIDTH_WINDOW: Duration = Duration::from_secs(1);
+
+/// Decides which peers a transaction is propagated to, depending on the kind of the peer and the
+/// origin and type of the transaction.
+///
+/// The default policy propagates all transactions to all peers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TransactionPropagationPolicy {
+    /// Peers that transactions submitted to this node, with a local or private
+    /// [`TransactionOrigin`], are propagated to.
+    pub local: PropagationScope,
+    /// Peers that transactions received from the network are propagated to.
+    pub external: PropagationScope,
+    /// Peers that EIP-4844 blob transactions are announced to.
+    ///
+    /// This applies in addition to the scope of the transaction's origin.
+    pub blob: PropagationScope,
+    /// Max number of bytes of full transactions broadcast to a single peer per second.
+    ///
+    /// Transactions that exceed this are announced by hash instead. The limit is soft, a single
+    /// transaction may overshoot the remaining bytes.
+    pub max_broadcast_bytes_per_peer: Option<usize>,
+}
+
+impl TransactionPropagationPolicy {
+    /// Sets the peers local transactions are propagated to.
+    pub const fn with_local(mut self, scope: PropagationScope) -> Self {
+        self.local = scope;
+        self
+    }
+
+    /// Sets the peers external transactions are propagated to.
+    pub const fn with_external(mut self, scope: PropagationScope) -> Self {
+        self.external = scope;
+        self
+    }
+
+    /// Sets the peers blob transactions are announced to.
+    pub const fn with_blob(mut self, scope: PropagationScope) -> Self {
+        self.blob = scope;
+        self
+    }
+
+    /// Sets the max number of bytes of full transactions broadcast to a single peer per second.
+    pub const fn with_max_broadcast_bytes_per_peer(mut self, max_bytes: Option<usize>) -> Self {
+        self.max_broadcast_bytes_per_peer = max_bytes;
+        self
+    }
+
+    /// Returns `true` if a transaction of the given origin and type may be propagated to a peer
+    /// of the given kind.
+    pub fn allows(&self, origin: TransactionOrigin, is_blob: bool, peer: PeerKind) -> bool {
+        let scope = match origin {
+            TransactionOrigin::Local | TransactionOrigin::Private => self.local,
+            TransactionOrigin::External => self.external,
+        };
+        scope.includes(peer) && (!is_blob || self.blob.includes(peer))
+    }
+}
+
+/// The peers that transactions are propagated to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum PropagationScope {
+    /// All peers.
+    #[default]
+    All,
+    /// Only trusted peers.
+    Trusted,
+    /// No peers.
+    None,
+}
+
+impl PropagationScope {
+    /// Returns `true` if peers of the given kind are included.
+    pub const fn includes(&self, peer: PeerKind) -> bool {
+        match self {
+            Self::All => true,
+            Self::Trusted => peer.is_trusted(),
+            Self::None => false,
+        }
+    }
+}
+
+impl fmt::Display for PropagationScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("all"),
+            Self::Trusted => f.write_str("trusted"),
+            Self::None => f.write_str("none"),
+        }
+    }
+}
+
+impl FromStr for PropagationScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::All),
+            "trusted" => Ok(Self::Trusted),
+            "none" => Ok(Self::None),
+            s => Err(format!("unknown propagation scope: {s}, expected all, trusted or none")),
+        }
+    }
+}
+
+/// Tracks the bytes of full transactions broadcast to a peer in the current window.
+#[derive(Debug)]
+pub(crate) struct BroadcastBandwidth {
+    /// Start of the current window.
+    window_start: Instant,
+    /// Bytes broadcast in the current window.
+    bytes: usize,
+}
+
+impl BroadcastBandwidth {
+    /// Returns how many more bytes may be broadcast in the current window, given the limit.
+    pub(crate) fn remaining(&mut self, limit: usize) -> usize {
+        let now = Instant::now();
+        if now.duration_since(self.window_start) >= BROADCAST_BANDWIDTH_WINDOW {
+            self.window_start = now;
+            self.bytes = 0;
+        }
+        limit.saturating_sub(self.bytes)
+    }
+
+    /// Records the bytes broadcast to the peer.
+    pub(crate) fn record(&mut self, bytes: usize) {
+        self.bytes = self.bytes.saturating_add(bytes);
+    }
+}
+
+impl Default for BroadcastBandwidth {
+    fn default() -> Self {
+        Self { window_start: Instant::now(), bytes: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_scopes() {
+        let policy = TransactionPropagationPolicy::default()
+            .with_local(PropagationScope::Trusted)
+            .with_external(PropagationScope::None);
+
+        assert!(policy.allows(TransactionOrigin::Local, false, PeerKind::Trusted));
+        assert!(!policy.allows(TransactionOrigin::Local, false, PeerKind::Basic));
+        assert!(!policy.allows(TransactionOrigin::Private, false, PeerKind::Static));
+        assert!(!policy.allows(TransactionOrigin::External, false, PeerKind::Trusted));
+
+        let policy = TransactionPropagationPolicy::default().with_blob(PropagationScope::Trusted);
+        assert!(policy.allows(TransactionOrigin::External, false, PeerKind::Basic));
+        assert!(!policy.allows(TransactionOrigin::External, true, PeerKind::Basic));
+        assert!(policy.allows(TransactionOrigin::External, true, PeerKind::Trusted));
+    }
+
+    #[test]
+    fn parse_scope() {
+        for scope in [PropagationScope::All, PropagationScope::Trusted, PropagationScope::None] {
+            assert_eq!(scope.to_string().parse::<PropagationScope>().unwrap(), scope);
+        }
+        assert!("static".parse::<PropagationScope>().is_err());
+    }
+
+    #[test]
+    fn broadcast_bandwidth() {
+        let mut bandwidth = BroadcastBandwidth::default();
+        assert_eq!(bandwidth.remaining(100), 100);
+        bandwidth.record(60);
+        assert_eq!(bandwidth.remaining(100), 40);
+        bandwidth.record(60);
+        assert_eq!(bandwidth.remaining(100), 0);
+
+        bandwidth.window_start -= BROADCAST_BANDWIDTH_WINDOW;
+        assert_eq!(bandwidth.remaining(100), 100);
+    }
+}
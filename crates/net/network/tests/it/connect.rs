@@ -9,9 +9,11 @@ use reth_discv4::Discv4Config;
 use reth_eth_wire::{DisconnectReason, HeadersDirection};
 use reth_net_banlist::BanList;
 use reth_network::{
-    test_utils::{enr_to_peer_id, NetworkEventStream, PeerConfig, Testnet, GETH_TIMEOUT},
+    test_utils::{
+        enr_to_peer_id, NetworkEventStream, PeerConfig, Socks5Server, Testnet, GETH_TIMEOUT,
+    },
     BlockDownloaderProvider, NetworkConfigBuilder, NetworkEvent, NetworkEventListenerProvider,
-    NetworkManager, PeersConfig,
+    NetworkManager, PeersConfig, SessionsConfig, Socks5Proxy,
 };
 use reth_network_api::{NetworkInfo, Peers, PeersInfo};
 use reth_network_p2p::{
@@ -679,6 +681,36 @@ async fn test_rejected_by_already_connect() {
     assert_eq!(handle.num_connected_peers(), 2);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_outgoing_connect_through_socks5_proxy() {
+    reth_tracing::init_test_tracing();
+    let proxy = Socks5Server::spawn_with_auth("user", "password").await;
+    let other_peer = new_random_peer(10, vec![]).await;
+
+    let secret_key = SecretKey::new(&mut rand::thread_rng());
+    let sessions_config = SessionsConfig::default()
+        .with_proxy(Socks5Proxy::new(proxy.local_addr()).with_auth("user", "password"));
+    let config = NetworkConfigBuilder::new(secret_key)
+        .listener_port(0)
+        .disable_discovery()
+        .sessions_config(sessions_config)
+        .build_with_noop_provider();
+    let peer = NetworkManager::new(config).await.unwrap();
+
+    let handle = peer.handle().clone();
+    let other_peer_handle = other_peer.handle().clone();
+
+    tokio::task::spawn(peer);
+    tokio::task::spawn(other_peer);
+
+    let mut events = NetworkEventStream::new(handle.event_listener());
+
+    handle.add_peer(*other_peer_handle.peer_id(), other_peer_handle.local_addr());
+    let peer_id = events.next_session_established().await.unwrap();
+    assert_eq!(peer_id, *other_peer_handle.peer_id());
+    assert_eq!(proxy.connections(), vec![other_peer_handle.local_addr()]);
+}
+
 async fn new_random_peer(max_in_bound: usize, trusted_nodes: Vec<TrustedPeer>) -> NetworkManager {
     let secret_key = SecretKey::new(&mut rand::thread_rng());
     let peers_config =
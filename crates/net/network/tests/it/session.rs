@@ -33,7 +33,7 @@ async fn test_session_established_with_highest_version() {
             }
             NetworkEvent::SessionEstablished { peer_id, status, .. } => {
                 assert_eq!(handle1.peer_id(), &peer_id);
-                assert_eq!(status.version, EthVersion::Eth68 as u8);
+                assert_eq!(status.version(), EthVersion::Eth68 as u8);
             }
             ev => {
                 panic!("unexpected event {ev:?}")
@@ -71,7 +71,51 @@ async fn test_session_established_with_different_capability() {
             }
             NetworkEvent::SessionEstablished { peer_id, status, .. } => {
                 assert_eq!(handle1.peer_id(), &peer_id);
-                assert_eq!(status.version, EthVersion::Eth66 as u8);
+                assert_eq!(status.version(), EthVersion::Eth66 as u8);
+            }
+            ev => {
+                panic!("unexpected event: {ev:?}")
+            }
+        }
+    }
+
+    handle.terminate().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_session_established_with_eth69() {
+    reth_tracing::init_test_tracing();
+
+    let mut net = Testnet::create(0).await;
+
+    for _ in 0..2 {
+        let config = PeerConfig::with_protocols(
+            NoopProvider::default(),
+            [EthVersion::Eth69.into(), EthVersion::Eth68.into()],
+        );
+        net.add_peer_with_config(config).await.unwrap();
+    }
+
+    let mut handles = net.handles();
+    let handle0 = handles.next().unwrap();
+    let handle1 = handles.next().unwrap();
+    drop(handles);
+
+    let handle = net.spawn();
+
+    let mut events = handle0.event_listener().take(2);
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+
+    while let Some(event) = events.next().await {
+        match event {
+            NetworkEvent::PeerAdded(peer_id) => {
+                assert_eq!(handle1.peer_id(), &peer_id);
+            }
+            NetworkEvent::SessionEstablished { peer_id, status, .. } => {
+                assert_eq!(handle1.peer_id(), &peer_id);
+                assert_eq!(status.version(), EthVersion::Eth69 as u8);
+                assert_eq!(status.total_difficulty(), None);
+                assert!(status.block_range().is_some());
             }
             ev => {
                 panic!("unexpected event: {ev:?}")
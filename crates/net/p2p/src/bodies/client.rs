@@ -1,4 +1,5 @@
 use std::{
+    ops::RangeInclusive,
     pin::Pin,
     task::{ready, Context, Poll},
 };
@@ -25,6 +26,19 @@ pub trait BodiesClient: DownloadClient {
     fn get_block_bodies_with_priority(&self, hashes: Vec<B256>, priority: Priority)
         -> Self::Output;
 
+    /// Fetches the block bodies for the requested blocks with priority and a hint for the range
+    /// of block numbers the hashes belong to.
+    ///
+    /// The hint lets the client skip peers that announced they don't serve these blocks.
+    fn get_block_bodies_with_priority_and_range_hint(
+        &self,
+        hashes: Vec<B256>,
+        priority: Priority,
+        _range_hint: Option<RangeInclusive<u64>>,
+    ) -> Self::Output {
+        self.get_block_bodies_with_priority(hashes, priority)
+    }
+
     /// Fetches a single block body for the requested hash.
     fn get_block_body(&self, hash: B256) -> SingleBodyRequest<Self::Output> {
         self.get_block_body_with_priority(hash, Priority::Normal)
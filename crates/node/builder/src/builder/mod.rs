@@ -31,6 +31,7 @@ use reth_node_core::{
 };
 use reth_primitives::revm_primitives::EnvKzgSettings;
 use reth_provider::{providers::BlockchainProvider, ChainSpecProvider, FullProvider};
+use reth_prune::PruneModes;
 use reth_tasks::TaskExecutor;
 use reth_transaction_pool::{PoolConfig, TransactionPool};
 use secp256k1::SecretKey;
@@ -640,11 +641,22 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
                 default_peers_path,
             )
             .with_task_executor(Box::new(self.executor.clone()))
-            .set_head(self.head);
+            .set_head(self.head)
+            .prune_modes(self.prune_modes());
 
         Ok(builder)
     }
 
+    /// Returns the configured [`PruneModes`], returning the default if no config was available.
+    pub fn prune_modes(&self) -> PruneModes {
+        self.reth_config()
+            .prune
+            .clone()
+            .or_else(|| self.config().prune_config())
+            .map(|config| config.segments)
+            .unwrap_or_default()
+    }
+
     /// Get the network secret from the given data dir
     fn network_secret(&self, data_dir: &ChainPath<DataDirPath>) -> eyre::Result<SecretKey> {
         let network_secret_path =
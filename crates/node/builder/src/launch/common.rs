@@ -15,6 +15,7 @@ use reth_consensus::Consensus;
 use reth_db_api::{database::Database, database_metrics::DatabaseMetrics};
 use reth_db_common::init::{init_genesis, InitDatabaseError};
 use reth_downloaders::{bodies::noop::NoopBodiesDownloader, headers::noop::NoopHeaderDownloader};
+use reth_engine_util::engine_store::EngineMessageStore;
 use reth_evm::noop::NoopBlockExecutorProvider;
 use reth_network_p2p::headers::client::HeadersClient;
 use reth_node_api::FullNodeTypes;
@@ -34,8 +35,8 @@ use reth_node_metrics::{
 use reth_primitives::{BlockNumber, Head, B256};
 use reth_provider::{
     providers::{BlockchainProvider, BlockchainProvider2, StaticFileProvider},
-    BlockHashReader, CanonStateNotificationSender, FullProvider, ProviderFactory, ProviderResult,
-    StageCheckpointReader, StaticFileProviderFactory, TreeViewer,
+    BlockHashReader, CanonStateNotificationSender, FullProvider, HistoricalStateCache,
+    ProviderFactory, ProviderResult, StageCheckpointReader, StaticFileProviderFactory, TreeViewer,
 };
 use reth_prune::{PruneModes, PrunerBuilder};
 use reth_rpc_builder::config::RethRpcServerConfig;
@@ -359,6 +360,18 @@ impl<R> LaunchContextWith<Attached<WithConfigs, R>> {
             .timeout(PrunerBuilder::DEFAULT_TIMEOUT)
     }
 
+    /// Returns the [`EngineMessageStore`] configured with `--debug.engine-api-store`, if any.
+    pub fn engine_message_store(&self) -> Option<EngineMessageStore> {
+        let debug = &self.node_config().debug;
+        debug.engine_api_store.clone().map(|path| {
+            EngineMessageStore::with_limits(
+                path,
+                debug.engine_api_store_max_file_size,
+                debug.engine_api_store_max_files,
+            )
+        })
+    }
+
     /// Loads the JWT secret for the engine API
     pub fn auth_jwt_secret(&self) -> eyre::Result<JwtSecret> {
         let default_jwt_path = self.data_dir().jwt();
@@ -386,7 +399,7 @@ where
     /// between the database and static files. **It may execute a pipeline unwind if it fails this
     /// check.**
     pub async fn create_provider_factory(&self) -> eyre::Result<ProviderFactory<DB>> {
-        let factory = ProviderFactory::new(
+        let mut factory = ProviderFactory::new(
             self.right().clone(),
             self.chain_spec(),
             StaticFileProvider::read_write(self.data_dir().static_files())?,
@@ -394,6 +407,14 @@ where
         .with_prune_modes(self.prune_modes())
         .with_static_files_metrics();
 
+        let max_historical_state_entries =
+            self.node_config().rpc.rpc_state_cache.max_historical_state_entries;
+        if max_historical_state_entries > 0 {
+            factory = factory.with_historical_state_cache(HistoricalStateCache::new(
+                max_historical_state_entries,
+            ));
+        }
+
         let has_receipt_pruning =
             self.toml_config().prune.as_ref().map_or(false, |a| a.has_receipts_pruning());
 
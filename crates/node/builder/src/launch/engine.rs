@@ -16,16 +16,21 @@ use reth_engine_util::EngineMessageStreamExt;
 use reth_exex::ExExManagerHandle;
 use reth_network::{NetworkSyncUpdater, SyncState};
 use reth_network_api::{BlockDownloaderProvider, NetworkEventListenerProvider};
-use reth_node_api::{BuiltPayload, FullNodeTypes, NodeAddOns};
+use reth_node_api::{BuiltPayload, FullNodeTypes, NodeAddOns, PayloadTimingsTracker};
 use reth_node_core::{
     dirs::{ChainPath, DataDirPath},
     exit::NodeExitFuture,
     primitives::Head,
-    rpc::eth::{helpers::AddDevSigners, FullEthApiServer},
+    rpc::{
+        api::{AdminReorgApiServer, DebugForkTreeApiServer, RethPayloadTimingsApiServer},
+        eth::{helpers::AddDevSigners, FullEthApiServer},
+    },
     version::{CARGO_PKG_VERSION, CLIENT_CODE, NAME_CLIENT, VERGEN_GIT_SHA},
 };
 use reth_node_events::{cl::ConsensusLayerHealthEvents, node};
 use reth_provider::providers::BlockchainProvider2;
+use reth_rpc::{AdminReorgApi, DebugForkTreeApi, RethPayloadTimingsApi};
+use reth_rpc_builder::RethRpcModule;
 use reth_rpc_engine_api::{capabilities::EngineCapabilities, EngineApi};
 use reth_rpc_types::{engine::ClientVersionV1, WithOtherFields};
 use reth_tasks::TaskExecutor;
@@ -36,7 +41,7 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 
 use crate::{
     hooks::NodeHooks,
-    rpc::{launch_rpc_servers, EthApiBuilderProvider},
+    rpc::{launch_rpc_servers, EthApiBuilderProvider, RpcContext},
     setup::build_networked_pipeline,
     AddOns, ExExLauncher, FullNode, LaunchContext, LaunchNode, NodeAdapter,
     NodeBuilderWithComponents, NodeComponents, NodeComponentsBuilder, NodeHandle, NodeTypesAdapter,
@@ -83,7 +88,7 @@ where
         let NodeBuilderWithComponents {
             adapter: NodeTypesAdapter { database },
             components_builder,
-            add_ons: AddOns { hooks, rpc, exexs: installed_exex },
+            add_ons: AddOns { hooks, mut rpc, exexs: installed_exex },
             config,
         } = target;
         let NodeHooks { on_component_initialized, on_node_started, .. } = hooks;
@@ -157,7 +162,10 @@ where
             // Store messages _after_ skipping so that `replay-engine` command
             // would replay only the messages that were observed by the engine
             // during this run.
-            .maybe_store_messages(node_config.debug.engine_api_store.clone());
+            .maybe_store_messages(
+                ctx.engine_message_store(),
+                Box::new(ctx.task_executor().clone()),
+            );
 
         let max_block = ctx.max_block(network_client.clone()).await?;
         let mut hooks = EngineHooks::new();
@@ -202,6 +210,21 @@ where
         let pruner_events = pruner.events();
         info!(target: "reth::cli", prune_config=?ctx.prune_config().unwrap_or_default(), "Pruner initialized");
 
+        let mut tree_config = TreeConfig::default();
+        if let Some(dir) = node_config.debug.invalid_block_witness_dir.clone() {
+            tree_config = tree_config.with_invalid_block_witness_dir(dir);
+        }
+        if let Some(depth) = node_config.engine.reorg_alert_depth {
+            tree_config = tree_config.with_reorg_alert_depth(depth);
+        }
+        if let Some(max_fork_branches) = node_config.engine.max_fork_branches {
+            tree_config = tree_config.with_max_fork_branches(max_fork_branches);
+        }
+        tree_config = tree_config.with_protect_safe_block(node_config.engine.protect_safe_block);
+
+        // shared between the engine API and the engine tree
+        let payload_timings = PayloadTimingsTracker::default();
+
         // Configure the consensus engine
         let mut eth_service = EngineService::new(
             ctx.consensus(),
@@ -215,7 +238,8 @@ where
             ctx.blockchain_db().clone(),
             pruner,
             ctx.components().payload_builder().clone(),
-            TreeConfig::default(),
+            tree_config,
+            payload_timings.clone(),
         );
 
         let event_sender = EventSender::default();
@@ -263,12 +287,34 @@ where
             Box::new(ctx.task_executor().clone()),
             client,
             EngineCapabilities::default(),
+            Some(payload_timings.clone()),
         );
         info!(target: "reth::cli", "Engine API handler initialized");
 
         // extract the jwt secret from the args if possible
         let jwt_secret = ctx.auth_jwt_secret()?;
 
+        // install `debug_forkTree`, `admin_allowReorg` and `reth_getPayloadTimings` before running
+        // the user provided rpc hook
+        let canonical_in_memory_state = ctx.blockchain_db().canonical_in_memory_state();
+        let (fork_tree_tx, mut fork_tree_requests) = unbounded_channel();
+        let extend_rpc_modules = std::mem::replace(&mut rpc.hooks.extend_rpc_modules, Box::new(()));
+        rpc.hooks.set_extend_rpc_modules(move |rpc_ctx: RpcContext<'_, _, _>| {
+            rpc_ctx.modules.merge_if_module_configured(
+                RethRpcModule::Debug,
+                DebugForkTreeApi::new(fork_tree_tx).into_rpc(),
+            )?;
+            rpc_ctx.modules.merge_if_module_configured(
+                RethRpcModule::Admin,
+                AdminReorgApi::new(canonical_in_memory_state).into_rpc(),
+            )?;
+            rpc_ctx.modules.merge_if_module_configured(
+                RethRpcModule::Reth,
+                RethPayloadTimingsApi::new(payload_timings).into_rpc(),
+            )?;
+            extend_rpc_modules.extend_rpc_modules(rpc_ctx)
+        });
+
         // Start RPC servers
         let (rpc_server_handles, rpc_registry) = launch_rpc_servers(
             ctx.node_adapter().clone(),
@@ -310,6 +356,9 @@ where
                             eth_service.orchestrator_mut().handler_mut().handler_mut().on_event(EngineApiRequest::InsertExecutedBlock(executed_block).into());
                         }
                     }
+                    Some(tx) = fork_tree_requests.recv() => {
+                        eth_service.orchestrator_mut().handler_mut().handler_mut().on_event(EngineApiRequest::ForkTree(tx).into());
+                    }
                     event =  eth_service.next() => {
                         let Some(event) = event else { break };
                         debug!(target: "reth::cli", "Event: {event:?}");
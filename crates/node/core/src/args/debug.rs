@@ -63,6 +63,32 @@ pub struct DebugArgs {
     /// will be written to specified location.
     #[arg(long = "debug.engine-api-store", help_heading = "Debug", value_name = "PATH")]
     pub engine_api_store: Option<PathBuf>,
+
+    /// The size in bytes after which the engine API message store rotates to a new file.
+    #[arg(
+        long = "debug.engine-api-store-max-file-size",
+        help_heading = "Debug",
+        requires = "engine_api_store",
+        value_name = "BYTES"
+    )]
+    pub engine_api_store_max_file_size: Option<u64>,
+
+    /// The maximum number of files the engine API message store retains. The oldest files are
+    /// removed when the store rotates to a new file.
+    #[arg(
+        long = "debug.engine-api-store-max-files",
+        help_heading = "Debug",
+        requires = "engine_api_store",
+        value_name = "COUNT"
+    )]
+    pub engine_api_store_max_files: Option<usize>,
+
+    /// The directory to write execution witnesses of invalid blocks to.
+    ///
+    /// If specified, the engine generates a self-contained witness file for every block that
+    /// fails validation, which can be re-executed with `reth debug reexecute-witness`.
+    #[arg(long = "debug.invalid-block-witness-dir", help_heading = "Debug", value_name = "PATH")]
+    pub invalid_block_witness_dir: Option<PathBuf>,
 }
 
 #[cfg(test)]
@@ -0,0 +1,97 @@
+//! clap [Args](clap::Args) for engine configuration
+
+use clap::Args;
+
+/// Parameters for configuring the engine
+#[derive(Debug, Args, PartialEq, Eq, Default, Clone, Copy)]
+#[command(next_help_heading = "Engine")]
+pub struct EngineArgs {
+    /// Enable the engine2 experimental features
+    #[arg(long = "engine.experimental", default_value = "false")]
+    pub experimental: bool,
+
+    /// Raise an alert when the consensus layer requests a reorg that reverts more than the given
+    /// number of canonical blocks.
+    ///
+    /// Requires `--engine.experimental`.
+    #[arg(long = "engine.reorg-alert-depth", value_name = "BLOCKS", requires = "experimental")]
+    pub reorg_alert_depth: Option<u64>,
+
+    /// Refuse reorgs that revert the safe block.
+    ///
+    /// A refused reorg is answered with `SYNCING` and is only applied once it is explicitly
+    /// allowed with the `admin_allowReorg` RPC method.
+    ///
+    /// Requires `--engine.experimental`.
+    #[arg(long = "engine.protect-safe-block", requires = "experimental")]
+    pub protect_safe_block: bool,
+
+    /// Maximum number of non-canonical fork branches of executed blocks to keep in memory.
+    ///
+    /// The lowest branches are removed first once the limit is exceeded. Defaults to 8.
+    ///
+    /// Requires `--engine.experimental`.
+    #[arg(long = "engine.max-fork-branches", value_name = "BRANCHES", requires = "experimental")]
+    pub max_fork_branches: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_engine_args() {
+        let args = CommandParser::<EngineArgs>::parse_from(["reth"]).args;
+        assert_eq!(
+            args,
+            EngineArgs {
+                experimental: false,
+                reorg_alert_depth: None,
+                protect_safe_block: false,
+                max_fork_branches: None,
+            }
+        );
+
+        let args = CommandParser::<EngineArgs>::parse_from([
+            "reth",
+            "--engine.experimental",
+            "--engine.reorg-alert-depth",
+            "3",
+            "--engine.protect-safe-block",
+            "--engine.max-fork-branches",
+            "4",
+        ])
+        .args;
+        assert_eq!(
+            args,
+            EngineArgs {
+                experimental: true,
+                reorg_alert_depth: Some(3),
+                protect_safe_block: true,
+                max_fork_branches: Some(4),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_engine_args_require_experimental() {
+        let result =
+            CommandParser::<EngineArgs>::try_parse_from(["reth", "--engine.protect-safe-block"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn engine_args_default_sanity_check() {
+        let default_args = EngineArgs::default();
+        let args = CommandParser::<EngineArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, default_args);
+    }
+}
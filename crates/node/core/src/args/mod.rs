@@ -40,6 +40,10 @@ pub use gas_price_oracle::GasPriceOracleArgs;
 mod txpool;
 pub use txpool::TxPoolArgs;
 
+/// EngineArgs for configuring the engine
+mod engine;
+pub use engine::EngineArgs;
+
 /// DevArgs for configuring the dev testnet
 mod dev;
 pub use dev::DevArgs;
@@ -7,7 +7,12 @@ use reth_cli_util::{parse_duration_from_secs, parse_duration_from_secs_or_ms};
 use reth_primitives::constants::{
     ETHEREUM_BLOCK_GAS_LIMIT, MAXIMUM_EXTRA_DATA_SIZE, SLOT_DURATION,
 };
-use std::{borrow::Cow, ffi::OsStr, time::Duration};
+use std::{
+    borrow::Cow,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 /// Parameters for configuring the Payload Builder
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
@@ -36,8 +41,43 @@ pub struct PayloadBuilderArgs {
     /// Maximum number of tasks to spawn for building a payload.
     #[arg(long = "builder.max-tasks", default_value = "3", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
     pub max_payload_tasks: usize,
+
+    /// Rebuild payloads as soon as new pending transactions raise the fees enough, in addition to
+    /// the fixed interval.
+    ///
+    /// Build attempts that are not expected to finish before the payload is requested are skipped.
+    #[arg(long = "builder.adaptive", default_value_t = false)]
+    pub adaptive: bool,
+
+    /// Minimum estimated fee increase in wei from new pending transactions that triggers an
+    /// adaptive rebuild.
+    #[arg(long = "builder.adaptive-min-fee-delta", default_value_t = DEFAULT_ADAPTIVE_MIN_FEE_DELTA, value_name = "WEI")]
+    pub adaptive_min_fee_delta: u128,
+
+    /// Minimum time between the start of two adaptive rebuilds.
+    ///
+    /// Interval is specified in seconds or in milliseconds if the value ends with `ms`.
+    #[arg(long = "builder.adaptive-min-interval", value_parser = parse_duration_from_secs_or_ms, default_value = "100ms", value_name = "DURATION")]
+    pub adaptive_min_interval: Duration,
+
+    /// Secret key file of a builder account that pays the proposer.
+    ///
+    /// The file contains the hex encoded key, like the `--p2p-secret-key` file, and must exist.
+    /// If set, the builder account collects the fees of built payloads and a transfer of the
+    /// collected fees minus `--builder.payment-margin` to the suggested fee recipient is appended
+    /// at the end of each payload.
+    #[arg(long = "builder.payment-secret-key", value_name = "PATH")]
+    pub payment_secret_key: Option<PathBuf>,
+
+    /// Part of the collected fees in wei that is kept by the builder account when paying the
+    /// proposer.
+    #[arg(long = "builder.payment-margin", default_value_t = 0, value_name = "WEI")]
+    pub payment_margin: u128,
 }
 
+/// Default minimum fee increase that triggers an adaptive rebuild: 0.0001 ETH
+const DEFAULT_ADAPTIVE_MIN_FEE_DELTA: u128 = 100_000_000_000_000;
+
 impl Default for PayloadBuilderArgs {
     fn default() -> Self {
         Self {
@@ -46,6 +86,11 @@ impl Default for PayloadBuilderArgs {
             interval: Duration::from_secs(1),
             deadline: SLOT_DURATION,
             max_payload_tasks: 3,
+            adaptive: false,
+            adaptive_min_fee_delta: DEFAULT_ADAPTIVE_MIN_FEE_DELTA,
+            adaptive_min_interval: Duration::from_millis(100),
+            payment_secret_key: None,
+            payment_margin: 0,
         }
     }
 }
@@ -70,6 +115,26 @@ impl PayloadBuilderConfig for PayloadBuilderArgs {
     fn max_payload_tasks(&self) -> usize {
         self.max_payload_tasks
     }
+
+    fn adaptive(&self) -> bool {
+        self.adaptive
+    }
+
+    fn adaptive_min_fee_delta(&self) -> u128 {
+        self.adaptive_min_fee_delta
+    }
+
+    fn adaptive_min_interval(&self) -> Duration {
+        self.adaptive_min_interval
+    }
+
+    fn payment_secret_key(&self) -> Option<&Path> {
+        self.payment_secret_key.as_deref()
+    }
+
+    fn payment_margin(&self) -> u128 {
+        self.payment_margin
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -174,4 +239,34 @@ mod tests {
                 .args;
         assert_eq!(args.interval, Duration::from_millis(50));
     }
+
+    #[test]
+    fn test_args_with_adaptive() {
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.adaptive",
+            "--builder.adaptive-min-fee-delta",
+            "1000",
+            "--builder.adaptive-min-interval",
+            "20ms",
+        ])
+        .args;
+        assert!(args.adaptive);
+        assert_eq!(args.adaptive_min_fee_delta, 1000);
+        assert_eq!(args.adaptive_min_interval, Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_args_with_payment() {
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.payment-secret-key",
+            "/tmp/builder.key",
+            "--builder.payment-margin",
+            "1000",
+        ])
+        .args;
+        assert_eq!(args.payment_secret_key, Some(PathBuf::from("/tmp/builder.key")));
+        assert_eq!(args.payment_margin, 1000);
+    }
 }
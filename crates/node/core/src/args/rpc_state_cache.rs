@@ -1,7 +1,7 @@
 use clap::Args;
 use reth_rpc_server_types::constants::cache::{
     DEFAULT_BLOCK_CACHE_MAX_LEN, DEFAULT_CONCURRENT_DB_REQUESTS, DEFAULT_ENV_CACHE_MAX_LEN,
-    DEFAULT_RECEIPT_CACHE_MAX_LEN,
+    DEFAULT_HISTORICAL_STATE_CACHE_MAX_LEN, DEFAULT_RECEIPT_CACHE_MAX_LEN,
 };
 
 /// Parameters to configure RPC state cache.
@@ -35,6 +35,14 @@ pub struct RpcStateCacheArgs {
         default_value_t = DEFAULT_CONCURRENT_DB_REQUESTS,
     )]
     pub max_concurrent_db_requests: usize,
+
+    /// Max number of historical accounts and storage slots in cache, shared by all historical
+    /// state providers. 0 disables the cache.
+    #[arg(
+        long = "rpc-cache.max-historical-state-entries",
+        default_value_t = DEFAULT_HISTORICAL_STATE_CACHE_MAX_LEN,
+    )]
+    pub max_historical_state_entries: u32,
 }
 
 impl Default for RpcStateCacheArgs {
@@ -44,6 +52,7 @@ impl Default for RpcStateCacheArgs {
             max_receipts: DEFAULT_RECEIPT_CACHE_MAX_LEN,
             max_envs: DEFAULT_ENV_CACHE_MAX_LEN,
             max_concurrent_db_requests: DEFAULT_CONCURRENT_DB_REQUESTS,
+            max_historical_state_entries: DEFAULT_HISTORICAL_STATE_CACHE_MAX_LEN,
         }
     }
 }
@@ -3,7 +3,7 @@
 use reth_network::protocol::IntoRlpxSubProtocol;
 use reth_primitives::Bytes;
 use reth_transaction_pool::PoolConfig;
-use std::{borrow::Cow, time::Duration};
+use std::{borrow::Cow, path::Path, time::Duration};
 
 /// A trait that provides payload builder settings.
 ///
@@ -29,6 +29,22 @@ pub trait PayloadBuilderConfig {
 
     /// Maximum number of tasks to spawn for building a payload.
     fn max_payload_tasks(&self) -> usize;
+
+    /// Whether payloads are rebuilt as soon as new pending transactions raise the fees enough.
+    fn adaptive(&self) -> bool;
+
+    /// Minimum estimated fee increase in wei that triggers an adaptive rebuild.
+    fn adaptive_min_fee_delta(&self) -> u128;
+
+    /// Minimum time between the start of two adaptive rebuilds.
+    fn adaptive_min_interval(&self) -> Duration;
+
+    /// The secret key file of the builder account that pays the proposer at the end of each
+    /// block, if any.
+    fn payment_secret_key(&self) -> Option<&Path>;
+
+    /// The part of the collected fees in wei that is kept by the builder when paying the proposer.
+    fn payment_margin(&self) -> u128;
 }
 
 /// A trait that represents the configured network and can be used to apply additional configuration
@@ -2,7 +2,7 @@
 
 use crate::{
     args::{
-        DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs,
+        DatabaseArgs, DatadirArgs, DebugArgs, DevArgs, EngineArgs, NetworkArgs, PayloadBuilderArgs,
         PruningArgs, RpcServerArgs, TxPoolArgs,
     },
     dirs::{ChainPath, DataDirPath},
@@ -127,6 +127,9 @@ pub struct NodeConfig {
 
     /// All pruning related arguments
     pub pruning: PruningArgs,
+
+    /// All engine related arguments with --engine prefix
+    pub engine: EngineArgs,
 }
 
 impl NodeConfig {
@@ -234,6 +237,12 @@ impl NodeConfig {
         self
     }
 
+    /// Set the engine args for the node
+    pub const fn with_engine(mut self, engine: EngineArgs) -> Self {
+        self.engine = engine;
+        self
+    }
+
     /// Returns pruning configuration.
     pub fn prune_config(&self) -> Option<PruneConfig> {
         self.pruning.prune_config(&self.chain)
@@ -412,6 +421,7 @@ impl Default for NodeConfig {
             db: DatabaseArgs::default(),
             dev: DevArgs::default(),
             pruning: PruningArgs::default(),
+            engine: EngineArgs::default(),
             datadir: DatadirArgs::default(),
         }
     }
@@ -4,7 +4,7 @@ use crate::cl::ConsensusLayerHealthEvent;
 use alloy_rpc_types_engine::ForkchoiceState;
 use futures::Stream;
 use reth_beacon_consensus::{
-    BeaconConsensusEngineEvent, ConsensusEngineLiveSyncProgress, ForkchoiceStatus,
+    BeaconConsensusEngineEvent, ConsensusEngineLiveSyncProgress, ForkchoiceStatus, ReorgAlert,
 };
 use reth_network::NetworkEvent;
 use reth_network_api::PeersInfo;
@@ -278,6 +278,23 @@ impl NodeState {
             BeaconConsensusEngineEvent::ForkBlockAdded(block) => {
                 info!(number=block.number, hash=?block.hash(), "Block added to fork chain");
             }
+            BeaconConsensusEngineEvent::ReorgAlert(ReorgAlert {
+                depth,
+                old_head,
+                new_head,
+                rejected,
+            }) => {
+                if rejected {
+                    warn!(
+                        depth,
+                        old_head=?old_head.hash,
+                        new_head=?new_head.hash,
+                        "Refused reorg below the safe block, call admin_allowReorg with the new head to apply it"
+                    );
+                } else {
+                    warn!(depth, old_head=?old_head.hash, new_head=?new_head.hash, "Deep reorg requested");
+                }
+            }
         }
     }
 
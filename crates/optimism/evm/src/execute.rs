@@ -5,7 +5,8 @@ use reth_chainspec::{ChainSpec, EthereumHardforks, OptimismHardfork};
 use reth_evm::{
     execute::{
         BatchExecutor, BlockExecutionError, BlockExecutionInput, BlockExecutionOutput,
-        BlockExecutorProvider, BlockValidationError, Executor, ProviderError,
+        BlockExecutorProvider, BlockValidationError, Executor, NoopHook, OnStateHook,
+        ProviderError,
     },
     system_calls::apply_beacon_root_contract_call,
     ConfigureEvm,
@@ -107,13 +108,15 @@ where
     /// # Note
     ///
     /// It does __not__ apply post-execution changes.
-    fn execute_pre_and_transactions<Ext, DB>(
+    fn execute_pre_and_transactions<Ext, DB, F>(
         &self,
         block: &BlockWithSenders,
         mut evm: Evm<'_, Ext, &mut State<DB>>,
+        mut state_hook: F,
     ) -> Result<(Vec<Receipt>, u64), BlockExecutionError>
     where
         DB: Database<Error: Into<ProviderError> + std::fmt::Display>,
+        F: OnStateHook,
     {
         // apply pre execution changes
         apply_beacon_root_contract_call(
@@ -195,6 +198,7 @@ where
                 "Executed transaction"
             );
 
+            state_hook.on_state(&state);
             evm.db_mut().commit(state);
 
             // append gas used
@@ -287,6 +291,22 @@ where
         block: &BlockWithSenders,
         total_difficulty: U256,
     ) -> Result<(Vec<Receipt>, u64), BlockExecutionError> {
+        self.execute_without_verification_with_state_hook(block, total_difficulty, NoopHook)
+    }
+
+    /// Execute a single block and apply the state changes to the internal state, invoking the
+    /// given hook with the state changes of every executed transaction.
+    ///
+    /// Returns an error if execution fails.
+    fn execute_without_verification_with_state_hook<F>(
+        &mut self,
+        block: &BlockWithSenders,
+        total_difficulty: U256,
+        state_hook: F,
+    ) -> Result<(Vec<Receipt>, u64), BlockExecutionError>
+    where
+        F: OnStateHook,
+    {
         // 1. prepare state on new block
         self.on_new_block(&block.header);
 
@@ -295,7 +315,7 @@ where
 
         let (receipts, gas_used) = {
             let evm = self.executor.evm_config.evm_with_env(&mut self.state, env);
-            self.executor.execute_pre_and_transactions(block, evm)
+            self.executor.execute_pre_and_transactions(block, evm, state_hook)
         }?;
 
         // 3. apply post execution changes
@@ -345,9 +365,23 @@ where
     /// Returns an error if the block could not be executed or failed verification.
     ///
     /// State changes are committed to the database.
-    fn execute(mut self, input: Self::Input<'_>) -> Result<Self::Output, Self::Error> {
+    fn execute(self, input: Self::Input<'_>) -> Result<Self::Output, Self::Error> {
+        self.execute_with_state_hook(input, NoopHook)
+    }
+
+    /// Executes the block and commits the state changes, invoking the given hook with the state
+    /// changes of every executed transaction.
+    fn execute_with_state_hook<F>(
+        mut self,
+        input: Self::Input<'_>,
+        state_hook: F,
+    ) -> Result<Self::Output, Self::Error>
+    where
+        F: OnStateHook,
+    {
         let BlockExecutionInput { block, total_difficulty } = input;
-        let (receipts, gas_used) = self.execute_without_verification(block, total_difficulty)?;
+        let (receipts, gas_used) =
+            self.execute_without_verification_with_state_hook(block, total_difficulty, state_hook)?;
 
         // NOTE: we need to merge keep the reverts for the bundle retention
         self.state.merge_transitions(BundleRetention::Reverts);
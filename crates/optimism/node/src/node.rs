@@ -2,7 +2,9 @@
 
 use std::sync::Arc;
 
-use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
+use reth_basic_payload_builder::{
+    AdaptiveRebuildConfig, BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig,
+};
 use reth_chainspec::ChainSpec;
 use reth_evm::ConfigureEvm;
 use reth_evm_optimism::{OpExecutorProvider, OptimismEvmConfig};
@@ -255,13 +257,21 @@ where
                 .set_compute_pending_block(self.compute_pending_block);
         let conf = ctx.payload_builder_config();
 
-        let payload_job_config = BasicPayloadJobGeneratorConfig::default()
+        let mut payload_job_config = BasicPayloadJobGeneratorConfig::default()
             .interval(conf.interval())
             .deadline(conf.deadline())
             .max_payload_tasks(conf.max_payload_tasks())
             // no extradata for OP
             .extradata(Default::default());
 
+        if conf.adaptive() {
+            payload_job_config = payload_job_config.adaptive(
+                AdaptiveRebuildConfig::default()
+                    .with_min_fee_delta(conf.adaptive_min_fee_delta())
+                    .with_min_rebuild_interval(conf.adaptive_min_interval()),
+            );
+        }
+
         let payload_generator = BasicPayloadJobGenerator::with_builder(
             ctx.provider().clone(),
             pool,
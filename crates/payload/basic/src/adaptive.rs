@@ -0,0 +1,306 @@
+//! Support for adaptive payload rebuilds.
+
+use futures_util::StreamExt;
+use reth_primitives::U256;
+use reth_transaction_pool::{NewSubpoolTransactionStream, PoolTransaction};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{Instant, Sleep};
+
+/// The number of previous build attempts that are considered when estimating the build time.
+const BUILD_TIME_SAMPLES: usize = 8;
+
+/// Settings for adaptive payload rebuilds.
+///
+/// In adaptive mode a payload job does not only rebuild on the configured interval, but also as
+/// soon as the pool received new pending transactions that are estimated to raise the fees of the
+/// payload by at least [`AdaptiveRebuildConfig::min_fee_delta`]. Build attempts that are not
+/// expected to finish before the payload is requested are skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptiveRebuildConfig {
+    /// Minimum estimated increase in fees, in wei, from new pending transactions that triggers a
+    /// rebuild.
+    pub min_fee_delta: U256,
+    /// Minimum time between the start of two build attempts.
+    pub min_rebuild_interval: Duration,
+    /// Safety margin that is added to the estimated build time.
+    pub build_time_margin: Duration,
+}
+
+impl AdaptiveRebuildConfig {
+    /// Sets the minimum estimated fee increase that triggers a rebuild.
+    pub fn with_min_fee_delta(mut self, min_fee_delta: impl Into<U256>) -> Self {
+        self.min_fee_delta = min_fee_delta.into();
+        self
+    }
+
+    /// Sets the minimum time between the start of two build attempts.
+    pub const fn with_min_rebuild_interval(mut self, min_rebuild_interval: Duration) -> Self {
+        self.min_rebuild_interval = min_rebuild_interval;
+        self
+    }
+
+    /// Sets the safety margin that is added to the estimated build time.
+    pub const fn with_build_time_margin(mut self, build_time_margin: Duration) -> Self {
+        self.build_time_margin = build_time_margin;
+        self
+    }
+}
+
+impl Default for AdaptiveRebuildConfig {
+    fn default() -> Self {
+        Self {
+            // 0.0001 ETH
+            min_fee_delta: U256::from(100_000_000_000_000u64),
+            min_rebuild_interval: Duration::from_millis(100),
+            build_time_margin: Duration::from_millis(50),
+        }
+    }
+}
+
+/// Estimates the duration of the next build attempt from the most recent attempts.
+#[derive(Debug, Default)]
+pub(crate) struct BuildTimeEstimator {
+    /// Durations of the most recent build attempts.
+    samples: VecDeque<Duration>,
+}
+
+impl BuildTimeEstimator {
+    /// Records the duration of a finished build attempt.
+    pub(crate) fn record(&mut self, duration: Duration) {
+        if self.samples.len() == BUILD_TIME_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(duration);
+    }
+
+    /// Returns the estimated duration of the next build attempt.
+    ///
+    /// This is the slowest of the recent attempts, or `None` if nothing was recorded yet.
+    pub(crate) fn estimate(&self) -> Option<Duration> {
+        self.samples.iter().max().copied()
+    }
+}
+
+/// Tracks new pending transactions and the build time budget of a payload job.
+#[derive(Debug)]
+pub(crate) struct AdaptiveRebuild<T: PoolTransaction> {
+    /// The settings for adaptive rebuilds.
+    config: AdaptiveRebuildConfig,
+    /// New transactions of the pending sub-pool.
+    pending_transactions: NewSubpoolTransactionStream<T>,
+    /// The base fee of the payload that is built.
+    base_fee: u64,
+    /// Estimated fee increase of the transactions that arrived since the last attempt started.
+    fee_delta: U256,
+    /// When the last build attempt was started.
+    last_attempt: Option<Instant>,
+    /// Wakes up the job once the minimum rebuild interval elapsed.
+    rebuild_delay: Option<Pin<Box<Sleep>>>,
+    /// Estimates how long a build attempt takes.
+    build_times: BuildTimeEstimator,
+    /// The instant at which the payload is expected to be requested.
+    payload_deadline: Instant,
+}
+
+impl<T: PoolTransaction> AdaptiveRebuild<T> {
+    /// Creates a new instance for a payload with the given base fee that is expected to be
+    /// requested at `payload_deadline`.
+    pub(crate) fn new(
+        config: AdaptiveRebuildConfig,
+        pending_transactions: NewSubpoolTransactionStream<T>,
+        base_fee: u64,
+        payload_deadline: Instant,
+    ) -> Self {
+        Self {
+            config,
+            pending_transactions,
+            base_fee,
+            fee_delta: U256::ZERO,
+            last_attempt: None,
+            rebuild_delay: None,
+            build_times: BuildTimeEstimator::default(),
+            payload_deadline,
+        }
+    }
+
+    /// Drains all new pending transactions and accumulates their estimated fee increase.
+    ///
+    /// The increase of a transaction is estimated by its effective tip times its gas limit, which
+    /// is an upper bound of what it would contribute to the payload.
+    fn poll_pending_transactions(&mut self, cx: &mut Context<'_>) {
+        while let Poll::Ready(Some(event)) = self.pending_transactions.poll_next_unpin(cx) {
+            let transaction = &event.transaction;
+            if let Some(tip) = transaction.effective_tip_per_gas(self.base_fee) {
+                self.fee_delta += U256::from(tip) * U256::from(transaction.gas_limit());
+            }
+        }
+    }
+
+    /// Returns true if a new build attempt should be started because enough fees arrived since the
+    /// last attempt.
+    ///
+    /// If the fee threshold is reached but the minimum rebuild interval did not elapse yet, this
+    /// schedules a wakeup for when it does.
+    pub(crate) fn poll_rebuild(&mut self, cx: &mut Context<'_>) -> bool {
+        self.poll_pending_transactions(cx);
+
+        if self.fee_delta < self.config.min_fee_delta {
+            return false
+        }
+
+        let Some(last_attempt) = self.last_attempt else { return true };
+        let next_attempt = last_attempt + self.config.min_rebuild_interval;
+        if Instant::now() >= next_attempt {
+            self.rebuild_delay = None;
+            return true
+        }
+
+        let delay = self
+            .rebuild_delay
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(next_attempt)));
+        delay.as_mut().reset(next_attempt);
+        if delay.as_mut().poll(cx).is_ready() {
+            self.rebuild_delay = None;
+            return true
+        }
+        false
+    }
+
+    /// Returns true if a build attempt that starts now is expected to finish before the payload is
+    /// requested.
+    pub(crate) fn has_time_budget(&self) -> bool {
+        self.build_times.estimate().map_or(true, |estimate| {
+            Instant::now() + estimate + self.config.build_time_margin <= self.payload_deadline
+        })
+    }
+
+    /// Records that a build attempt was started.
+    pub(crate) fn on_attempt_started(&mut self) {
+        self.fee_delta = U256::ZERO;
+        self.last_attempt = Some(Instant::now());
+        self.rebuild_delay = None;
+    }
+
+    /// Records the duration of a finished build attempt.
+    pub(crate) fn on_attempt_finished(&mut self, duration: Duration) {
+        self.build_times.record(duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::task::noop_waker_ref;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        TransactionPool,
+    };
+
+    const BASE_FEE: u64 = 1_000_000_000;
+
+    /// Adds a pending transaction that is estimated to raise the fees by 21000 gwei.
+    async fn add_transaction<P: TransactionPool<Transaction = MockTransaction>>(pool: &P) {
+        pool.add_external_transaction(
+            MockTransaction::eip1559()
+                .with_gas_limit(21_000)
+                .with_max_fee(10 * BASE_FEE as u128)
+                .with_priority_fee(BASE_FEE as u128),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn build_time_estimate_uses_recent_attempts() {
+        let mut estimator = BuildTimeEstimator::default();
+        assert_eq!(estimator.estimate(), None);
+
+        estimator.record(Duration::from_millis(500));
+        for _ in 0..BUILD_TIME_SAMPLES - 1 {
+            estimator.record(Duration::from_millis(100));
+        }
+        assert_eq!(estimator.estimate(), Some(Duration::from_millis(500)));
+
+        // the slow attempt is evicted
+        estimator.record(Duration::from_millis(200));
+        assert_eq!(estimator.estimate(), Some(Duration::from_millis(200)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn poll_rebuild_respects_fee_threshold_and_min_interval() {
+        let pool = testing_pool();
+        let config = AdaptiveRebuildConfig::default()
+            .with_min_fee_delta(30_000 * BASE_FEE)
+            .with_min_rebuild_interval(Duration::from_millis(100));
+        let mut adaptive = AdaptiveRebuild::new(
+            config,
+            pool.new_pending_pool_transactions_listener(),
+            BASE_FEE,
+            Instant::now() + Duration::from_secs(12),
+        );
+        let mut cx = Context::from_waker(noop_waker_ref());
+        assert!(!adaptive.poll_rebuild(&mut cx));
+
+        // below the fee threshold
+        add_transaction(&pool).await;
+        assert!(!adaptive.poll_rebuild(&mut cx));
+
+        // the threshold is reached and there was no attempt yet
+        add_transaction(&pool).await;
+        assert!(adaptive.poll_rebuild(&mut cx));
+
+        // starting an attempt resets the fees
+        adaptive.on_attempt_started();
+        assert!(!adaptive.poll_rebuild(&mut cx));
+
+        // the threshold is reached again, but the minimum interval did not elapse yet
+        add_transaction(&pool).await;
+        add_transaction(&pool).await;
+        assert!(!adaptive.poll_rebuild(&mut cx));
+        tokio::time::advance(Duration::from_millis(50)).await;
+        assert!(!adaptive.poll_rebuild(&mut cx));
+
+        tokio::time::advance(Duration::from_millis(50)).await;
+        assert!(adaptive.poll_rebuild(&mut cx));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn skips_attempts_without_time_budget() {
+        let pool = testing_pool();
+        let config =
+            AdaptiveRebuildConfig::default().with_build_time_margin(Duration::from_millis(50));
+        let mut adaptive = AdaptiveRebuild::new(
+            config,
+            pool.new_pending_pool_transactions_listener(),
+            BASE_FEE,
+            Instant::now() + Duration::from_secs(1),
+        );
+
+        // without a previous attempt the build time is unknown
+        assert!(adaptive.has_time_budget());
+
+        adaptive.on_attempt_finished(Duration::from_millis(900));
+        assert!(adaptive.has_time_budget());
+
+        // an attempt started now would not finish before the payload is requested
+        tokio::time::advance(Duration::from_millis(100)).await;
+        assert!(!adaptive.has_time_budget());
+
+        // a slower attempt is not offset by faster ones
+        let mut adaptive = AdaptiveRebuild::new(
+            config,
+            pool.new_pending_pool_transactions_listener(),
+            BASE_FEE,
+            Instant::now() + Duration::from_secs(1),
+        );
+        adaptive.on_attempt_finished(Duration::from_secs(1));
+        adaptive.on_attempt_finished(Duration::from_millis(100));
+        assert!(!adaptive.has_time_budget());
+    }
+}
@@ -8,7 +8,7 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
-use crate::metrics::PayloadBuilderMetrics;
+use crate::{adaptive::AdaptiveRebuild, metrics::PayloadBuilderMetrics};
 use futures_core::ready;
 use futures_util::FutureExt;
 use reth_chainspec::{ChainSpec, EthereumHardforks};
@@ -19,7 +19,8 @@ use reth_payload_builder::{
 use reth_payload_primitives::{BuiltPayload, PayloadBuilderAttributes};
 use reth_primitives::{
     constants::{EMPTY_WITHDRAWALS, RETH_CLIENT_VERSION, SLOT_DURATION},
-    proofs, BlockNumberOrTag, Bytes, SealedBlock, Withdrawals, B256, U256,
+    proofs, BlockNumberOrTag, Bytes, Receipt, SealedBlock, TransactionSigned, Withdrawals, B256,
+    U256,
 };
 use reth_provider::{
     BlockReaderIdExt, BlockSource, CanonStateNotification, ProviderError, StateProviderFactory,
@@ -28,6 +29,7 @@ use reth_revm::state_change::post_block_withdrawals_balance_increments;
 use reth_tasks::TaskSpawner;
 use reth_transaction_pool::TransactionPool;
 use revm::{
+    db::BundleState,
     primitives::{BlockEnv, CfgEnvWithHandlerCfg},
     Database, State,
 };
@@ -42,10 +44,13 @@ use std::{
 };
 use tokio::{
     sync::{oneshot, Semaphore},
-    time::{Interval, Sleep},
+    time::{Instant, Interval, Sleep},
 };
 use tracing::{debug, trace, warn};
 
+mod adaptive;
+pub use adaptive::AdaptiveRebuildConfig;
+
 mod metrics;
 
 /// The [`PayloadJobGenerator`] that creates [`BasicPayloadJob`]s.
@@ -178,6 +183,16 @@ where
 
         let cached_reads = self.maybe_pre_cached(config.parent_block.hash());
 
+        let adaptive = self.config.adaptive.map(|adaptive| {
+            AdaptiveRebuild::new(
+                adaptive,
+                self.pool.new_pending_pool_transactions_listener(),
+                config.initialized_block_env.basefee.to::<u64>(),
+                // the payload is expected to be requested at the start of the slot
+                Instant::now() + duration_until(config.attributes.timestamp()),
+            )
+        });
+
         let mut job = BasicPayloadJob {
             config,
             client: self.client.clone(),
@@ -188,14 +203,17 @@ where
             interval: tokio::time::interval(self.config.interval),
             best_payload: None,
             pending_block: None,
+            pending_block_started: None,
             cached_reads,
+            executed_prefix: None,
+            adaptive,
             payload_task_guard: self.payload_task_guard.clone(),
             metrics: Default::default(),
             builder: self.builder.clone(),
         };
 
         // start the first job right away
-        job.spawn_build_job();
+        job.spawn_build_job(false);
 
         Ok(job)
     }
@@ -265,6 +283,8 @@ pub struct BasicPayloadJobGeneratorConfig {
     deadline: Duration,
     /// Maximum number of tasks to spawn for building a payload.
     max_payload_tasks: usize,
+    /// Settings for adaptive rebuilds, disabled by default.
+    adaptive: Option<AdaptiveRebuildConfig>,
 }
 
 // === impl BasicPayloadJobGeneratorConfig ===
@@ -300,6 +320,16 @@ impl BasicPayloadJobGeneratorConfig {
         self.extradata = extradata;
         self
     }
+
+    /// Enables adaptive rebuilds with the given settings.
+    ///
+    /// In addition to the fixed interval, jobs then rebuild as soon as new pending transactions
+    /// raise the fees enough, skip attempts that won't finish before the payload is requested, and
+    /// resume from the transactions of the best payload instead of executing them again.
+    pub const fn adaptive(mut self, adaptive: AdaptiveRebuildConfig) -> Self {
+        self.adaptive = Some(adaptive);
+        self
+    }
 }
 
 impl Default for BasicPayloadJobGeneratorConfig {
@@ -310,6 +340,7 @@ impl Default for BasicPayloadJobGeneratorConfig {
             // 12s slot time
             deadline: SLOT_DURATION,
             max_payload_tasks: 3,
+            adaptive: None,
         }
     }
 }
@@ -318,6 +349,7 @@ impl Default for BasicPayloadJobGeneratorConfig {
 #[derive(Debug)]
 pub struct BasicPayloadJob<Client, Pool, Tasks, Builder>
 where
+    Pool: TransactionPool,
     Builder: PayloadBuilder<Pool, Client>,
 {
     /// The configuration for how the payload will be created.
@@ -336,6 +368,8 @@ where
     best_payload: Option<Builder::BuiltPayload>,
     /// Receiver for the block that is currently being built.
     pending_block: Option<PendingPayload<Builder::BuiltPayload>>,
+    /// When the block that is currently being built was started.
+    pending_block_started: Option<Instant>,
     /// Restricts how many generator tasks can be executed at once.
     payload_task_guard: PayloadTaskGuard,
     /// Caches all disk reads for the state the new payloads builds on
@@ -343,6 +377,12 @@ where
     /// This is used to avoid reading the same state over and over again when new attempts are
     /// triggered, because during the building process we'll repeatedly execute the transactions.
     cached_reads: Option<CachedReads>,
+    /// The transactions of the best payload so far and the state after executing them.
+    ///
+    /// Adaptive rebuilds resume from this instead of executing the same transactions again.
+    executed_prefix: Option<ExecutedPrefix>,
+    /// Tracks when to rebuild if adaptive rebuilds are enabled.
+    adaptive: Option<AdaptiveRebuild<Pool::Transaction>>,
     /// metrics for this type
     metrics: PayloadBuilderMetrics,
     /// The type responsible for building payloads.
@@ -361,8 +401,10 @@ where
     <Builder as PayloadBuilder<Pool, Client>>::BuiltPayload: Unpin + Clone,
 {
     /// Spawns a new payload build task.
-    fn spawn_build_job(&mut self) {
-        trace!(target: "payload_builder", "spawn new payload build task");
+    ///
+    /// If `resume` is true, the build resumes from the transactions of the best payload so far.
+    fn spawn_build_job(&mut self, resume: bool) {
+        trace!(target: "payload_builder", resume, "spawn new payload build task");
         let (tx, rx) = oneshot::channel();
         let client = self.client.clone();
         let pool = self.pool.clone();
@@ -373,7 +415,12 @@ where
         let best_payload = self.best_payload.clone();
         self.metrics.inc_initiated_payload_builds();
         let cached_reads = self.cached_reads.take().unwrap_or_default();
+        let executed_prefix = if resume { self.executed_prefix.clone() } else { None };
         let builder = self.builder.clone();
+        let retain_executed_prefix = self.adaptive.is_some();
+        if let Some(adaptive) = &mut self.adaptive {
+            adaptive.on_attempt_started();
+        }
         self.executor.spawn_blocking(Box::pin(async move {
             // acquire the permit for executing the task
             let _permit = guard.acquire().await;
@@ -384,12 +431,22 @@ where
                 config: payload_config,
                 cancel,
                 best_payload,
+                executed_prefix,
+                retain_executed_prefix,
             };
             let result = builder.try_build(args);
             let _ = tx.send(result);
         }));
 
         self.pending_block = Some(PendingPayload { _cancel, payload: rx });
+        self.pending_block_started = Some(Instant::now());
+    }
+
+    /// Returns true if a build attempt that starts now is expected to finish in time.
+    ///
+    /// This is always true if adaptive rebuilds are disabled.
+    fn has_time_budget(&self) -> bool {
+        self.adaptive.as_ref().map_or(true, |adaptive| adaptive.has_time_budget())
     }
 }
 
@@ -417,31 +474,47 @@ where
         while this.interval.poll_tick(cx).is_ready() {
             // start a new job if there is no pending block and we haven't reached the deadline
             if this.pending_block.is_none() {
-                this.spawn_build_job();
+                if this.has_time_budget() {
+                    this.spawn_build_job(false);
+                } else {
+                    trace!(target: "payload_builder", "skipping payload build without time budget");
+                    this.metrics.inc_skipped_payload_builds();
+                }
             }
         }
 
         // poll the pending block
         if let Some(mut fut) = this.pending_block.take() {
             match fut.poll_unpin(cx) {
-                Poll::Ready(Ok(outcome)) => match outcome {
-                    BuildOutcome::Better { payload, cached_reads } => {
-                        this.cached_reads = Some(cached_reads);
-                        debug!(target: "payload_builder", value = %payload.fees(), "built better payload");
-                        this.best_payload = Some(payload);
-                    }
-                    BuildOutcome::Aborted { fees, cached_reads } => {
-                        this.cached_reads = Some(cached_reads);
-                        trace!(target: "payload_builder", worse_fees = %fees, "skipped payload build of worse block");
+                Poll::Ready(res) => {
+                    if let Some(started) = this.pending_block_started.take() {
+                        let elapsed = started.elapsed();
+                        this.metrics.payload_build_duration.record(elapsed);
+                        if let Some(adaptive) = &mut this.adaptive {
+                            adaptive.on_attempt_finished(elapsed);
+                        }
                     }
-                    BuildOutcome::Cancelled => {
-                        unreachable!("the cancel signal never fired")
+
+                    match res {
+                        Ok(BuildOutcome::Better { payload, cached_reads, executed_prefix }) => {
+                            this.cached_reads = Some(cached_reads);
+                            this.executed_prefix = executed_prefix;
+                            debug!(target: "payload_builder", value = %payload.fees(), "built better payload");
+                            this.best_payload = Some(payload);
+                        }
+                        Ok(BuildOutcome::Aborted { fees, cached_reads }) => {
+                            this.cached_reads = Some(cached_reads);
+                            trace!(target: "payload_builder", worse_fees = %fees, "skipped payload build of worse block");
+                        }
+                        Ok(BuildOutcome::Cancelled) => {
+                            unreachable!("the cancel signal never fired")
+                        }
+                        Err(error) => {
+                            // job failed, but we simply try again next interval
+                            debug!(target: "payload_builder", %error, "payload build attempt failed");
+                            this.metrics.inc_failed_payload_builds();
+                        }
                     }
-                },
-                Poll::Ready(Err(error)) => {
-                    // job failed, but we simply try again next interval
-                    debug!(target: "payload_builder", %error, "payload build attempt failed");
-                    this.metrics.inc_failed_payload_builds();
                 }
                 Poll::Pending => {
                     this.pending_block = Some(fut);
@@ -449,6 +522,24 @@ where
             }
         }
 
+        // rebuild right away if new pending transactions raised the fees enough
+        if this.pending_block.is_none() {
+            let rebuild = this.adaptive.as_mut().is_some_and(|adaptive| adaptive.poll_rebuild(cx));
+            if rebuild {
+                if this.has_time_budget() {
+                    this.metrics.inc_adaptive_payload_builds();
+                    // resume from the best payload so only the new transactions are executed,
+                    // reordering all transactions is left to the rebuilds on the interval
+                    this.spawn_build_job(true);
+                    // poll again to register interest in the result of the new attempt
+                    cx.waker().wake_by_ref();
+                } else {
+                    trace!(target: "payload_builder", "skipping adaptive payload build without time budget");
+                    this.metrics.inc_skipped_payload_builds();
+                }
+            }
+        }
+
         Poll::Pending
     }
 }
@@ -489,7 +580,7 @@ where
 
         if best_payload.is_none() && self.pending_block.is_none() {
             // ensure we have a job scheduled if we don't have a best payload yet and none is active
-            self.spawn_build_job();
+            self.spawn_build_job(false);
         }
 
         let maybe_better = self.pending_block.take();
@@ -505,6 +596,8 @@ where
                 config: self.config.clone(),
                 cancel: Cancelled::default(),
                 best_payload: None,
+                executed_prefix: None,
+                retain_executed_prefix: false,
             };
 
             match self.builder.on_missing_payload(args) {
@@ -734,6 +827,9 @@ pub enum BuildOutcome<Payload> {
         payload: Payload,
         /// The cached reads that were used to build the payload.
         cached_reads: CachedReads,
+        /// The executed transactions of the payload, if they were requested with
+        /// [`BuildArguments::retain_executed_prefix`] and the builder supports resuming from them.
+        executed_prefix: Option<ExecutedPrefix>,
     },
     /// Aborted payload building because resulted in worse block wrt. fees.
     Aborted {
@@ -746,6 +842,29 @@ pub enum BuildOutcome<Payload> {
     Cancelled,
 }
 
+/// The transactions of a previous payload and the state after executing them on top of the parent
+/// block.
+///
+/// A builder can resume from this on a subsequent attempt instead of executing the same
+/// transactions again, and only append new transactions.
+#[derive(Debug, Clone)]
+pub struct ExecutedPrefix {
+    /// The parent block the transactions were executed on.
+    pub parent_hash: B256,
+    /// The executed transactions, in order.
+    pub transactions: Vec<TransactionSigned>,
+    /// The receipts of the executed transactions.
+    pub receipts: Vec<Option<Receipt>>,
+    /// The state changes of the pre-block system calls and the executed transactions.
+    pub state: BundleState,
+    /// Gas used by the executed transactions.
+    pub cumulative_gas_used: u64,
+    /// Blob gas used by the executed transactions.
+    pub blob_gas_used: u64,
+    /// Fees paid by the executed transactions.
+    pub fees: U256,
+}
+
 /// A collection of arguments used for building payloads.
 ///
 /// This struct encapsulates the essential components and configuration required for the payload
@@ -765,6 +884,14 @@ pub struct BuildArguments<Pool, Client, Attributes, Payload> {
     pub cancel: Cancelled,
     /// The best payload achieved so far.
     pub best_payload: Option<Payload>,
+    /// The executed transactions of the best payload to resume from, if any.
+    pub executed_prefix: Option<ExecutedPrefix>,
+    /// Whether the builder should return the executed transactions of a better payload, so that
+    /// subsequent attempts can resume from them.
+    ///
+    /// This is only enabled for adaptive rebuilds, because keeping the executed transactions
+    /// clones the state changes of every payload.
+    pub retain_executed_prefix: bool,
 }
 
 impl<Pool, Client, Attributes, Payload> BuildArguments<Pool, Client, Attributes, Payload> {
@@ -777,7 +904,29 @@ impl<Pool, Client, Attributes, Payload> BuildArguments<Pool, Client, Attributes,
         cancel: Cancelled,
         best_payload: Option<Payload>,
     ) -> Self {
-        Self { client, pool, cached_reads, config, cancel, best_payload }
+        Self {
+            client,
+            pool,
+            cached_reads,
+            config,
+            cancel,
+            best_payload,
+            executed_prefix: None,
+            retain_executed_prefix: false,
+        }
+    }
+
+    /// Sets the executed transactions to resume from.
+    pub fn with_executed_prefix(mut self, executed_prefix: ExecutedPrefix) -> Self {
+        self.executed_prefix = Some(executed_prefix);
+        self
+    }
+
+    /// Sets whether the executed transactions of a better payload are returned, see
+    /// [`BuildArguments::retain_executed_prefix`].
+    pub const fn with_retain_executed_prefix(mut self, retain_executed_prefix: bool) -> Self {
+        self.retain_executed_prefix = retain_executed_prefix;
+        self
     }
 }
 
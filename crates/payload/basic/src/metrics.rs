@@ -1,6 +1,9 @@
 //! Metrics for the payload builder impl
 
-use reth_metrics::{metrics::Counter, Metrics};
+use reth_metrics::{
+    metrics::{Counter, Histogram},
+    Metrics,
+};
 
 /// Transaction pool metrics
 #[derive(Metrics)]
@@ -12,6 +15,12 @@ pub(crate) struct PayloadBuilderMetrics {
     pub(crate) initiated_payload_builds: Counter,
     /// Total number of failed payload build attempts
     pub(crate) failed_payload_builds: Counter,
+    /// Total number of payload build attempts triggered by new pending transactions
+    pub(crate) adaptive_payload_builds: Counter,
+    /// Total number of payload build attempts skipped because they would not finish in time
+    pub(crate) skipped_payload_builds: Counter,
+    /// Duration of payload build attempts
+    pub(crate) payload_build_duration: Histogram,
 }
 
 impl PayloadBuilderMetrics {
@@ -26,4 +35,12 @@ impl PayloadBuilderMetrics {
     pub(crate) fn inc_failed_payload_builds(&self) {
         self.failed_payload_builds.increment(1);
     }
+
+    pub(crate) fn inc_adaptive_payload_builds(&self) {
+        self.adaptive_payload_builds.increment(1);
+    }
+
+    pub(crate) fn inc_skipped_payload_builds(&self) {
+        self.skipped_payload_builds.increment(1);
+    }
 }
@@ -1,4 +1,5 @@
 mod receipts;
+mod sender_recovery;
 mod set;
 mod static_file;
 mod user;
@@ -14,7 +15,7 @@ use reth_prune_types::{
 };
 pub use set::SegmentSet;
 pub use static_file::{
-    Headers as StaticFileHeaders, Receipts as StaticFileReceipts,
+    Headers as StaticFileHeaders, Receipts as StaticFileReceipts, Senders as StaticFileSenders,
     Transactions as StaticFileTransactions,
 };
 use std::{fmt::Debug, ops::RangeInclusive};
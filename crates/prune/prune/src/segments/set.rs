@@ -6,7 +6,7 @@ use reth_db_api::database::Database;
 use reth_provider::providers::StaticFileProvider;
 use reth_prune_types::PruneModes;
 
-use super::{StaticFileHeaders, StaticFileReceipts, StaticFileTransactions};
+use super::{StaticFileHeaders, StaticFileReceipts, StaticFileSenders, StaticFileTransactions};
 
 /// Collection of [Segment]. Thread-safe, allocated on the heap.
 #[derive(Debug)]
@@ -60,7 +60,9 @@ impl<DB: Database> SegmentSet<DB> {
             // Static file transactions
             .segment(StaticFileTransactions::new(static_file_provider.clone()))
             // Static file receipts
-            .segment(StaticFileReceipts::new(static_file_provider))
+            .segment(StaticFileReceipts::new(static_file_provider.clone()))
+            // Static file senders
+            .segment(StaticFileSenders::new(static_file_provider))
             // Account history
             .segment_opt(account_history.map(AccountHistory::new))
             // Storage history
@@ -1,7 +1,9 @@
 mod headers;
 mod receipts;
+mod senders;
 mod transactions;
 
 pub use headers::Headers;
 pub use receipts::Receipts;
+pub use senders::Senders;
 pub use transactions::Transactions;
@@ -0,0 +1,43 @@
+use crate::{
+    segments::{PruneInput, Segment},
+    PrunerError,
+};
+use reth_db_api::database::Database;
+use reth_provider::{providers::StaticFileProvider, DatabaseProviderRW};
+use reth_prune_types::{PruneMode, PrunePurpose, PruneSegment, SegmentOutput};
+use reth_static_file_types::StaticFileSegment;
+
+#[derive(Debug)]
+pub struct Senders {
+    static_file_provider: StaticFileProvider,
+}
+
+impl Senders {
+    pub const fn new(static_file_provider: StaticFileProvider) -> Self {
+        Self { static_file_provider }
+    }
+}
+
+impl<DB: Database> Segment<DB> for Senders {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::SenderRecovery
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        self.static_file_provider
+            .get_highest_static_file_block(StaticFileSegment::Senders)
+            .map(PruneMode::before_inclusive)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::StaticFile
+    }
+
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<SegmentOutput, PrunerError> {
+        crate::segments::sender_recovery::prune(provider, input)
+    }
+}
@@ -1,5 +1,7 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_network_peers::{AnyNode, NodeRecord};
+use reth_network_types::{Bans, IpNet, ManagedPeerKind, ManagedPeers};
+use reth_primitives::B256;
 use reth_rpc_types::admin::{NodeInfo, PeerInfo};
 
 /// Admin namespace rpc interface that gives access to several non-standard RPC methods.
@@ -27,6 +29,60 @@ pub trait AdminApi {
     #[method(name = "removeTrustedPeer")]
     fn remove_trusted_peer(&self, record: AnyNode) -> RpcResult<bool>;
 
+    /// Bans a remote node and disconnects it if connected.
+    ///
+    /// The ban lasts for the given number of seconds or indefinitely if not set.
+    #[method(name = "banPeer")]
+    fn ban_peer(&self, record: AnyNode, duration: Option<u64>) -> RpcResult<bool>;
+
+    /// Lifts the ban of a remote node, including a ban due to its reputation.
+    #[method(name = "unbanPeer")]
+    fn unban_peer(&self, record: AnyNode) -> RpcResult<bool>;
+
+    /// Bans all IPs in the given CIDR range, e.g. `192.0.2.0/24`, and disconnects all peers
+    /// connected from it.
+    ///
+    /// The ban lasts for the given number of seconds or indefinitely if not set. Non-global IPs
+    /// within the range are not banned.
+    #[method(name = "banIpRange")]
+    fn ban_ip_range(&self, range: IpNet, duration: Option<u64>) -> RpcResult<bool>;
+
+    /// Lifts the ban of the given CIDR range.
+    ///
+    /// This only lifts a ban of exactly this range, IPs and ranges within it remain banned.
+    #[method(name = "unbanIpRange")]
+    fn unban_ip_range(&self, range: IpNet) -> RpcResult<bool>;
+
+    /// Returns all banned peers, IPs and IP ranges, with the unix timestamp at which each ban
+    /// ends.
+    #[method(name = "bans")]
+    async fn bans(&self) -> RpcResult<Bans>;
+
+    /// Returns the trusted, static and banned peers of the peers config file, or `null` if the
+    /// node runs without one.
+    #[method(name = "peersConfig")]
+    async fn peers_config(&self) -> RpcResult<Option<ManagedPeers>>;
+
+    /// Adds a remote node to the `trusted`, `static` or `banned` peers of the peers config file,
+    /// applies the change and writes the file.
+    ///
+    /// Trusted and static peers must be given as a record with an address. Returns true if the
+    /// configured peers changed.
+    #[method(name = "addConfiguredPeer")]
+    async fn add_configured_peer(&self, record: AnyNode, kind: ManagedPeerKind) -> RpcResult<bool>;
+
+    /// Removes a remote node from the `trusted`, `static` or `banned` peers of the peers config
+    /// file, applies the change and writes the file.
+    ///
+    /// A removed trusted or static peer is disconnected. Returns true if the configured peers
+    /// changed.
+    #[method(name = "removeConfiguredPeer")]
+    async fn remove_configured_peer(
+        &self,
+        record: AnyNode,
+        kind: ManagedPeerKind,
+    ) -> RpcResult<bool>;
+
     /// The peers administrative property can be queried for all the information known about the
     /// connected remote nodes at the networking granularity. These include general information
     /// about the nodes themselves as participants of the devp2p P2P overlay protocol, as well as
@@ -46,3 +102,16 @@ pub trait AdminApi {
     #[method(name = "nodeInfo")]
     async fn node_info(&self) -> RpcResult<NodeInfo>;
 }
+
+/// Admin rpc interface for overriding the reorg guardrails of the engine.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait AdminReorgApi {
+    /// Allows the next forkchoice update to the given head to reorg below the safe block.
+    ///
+    /// This only has an effect if the node refuses reorgs below the safe block. The permission is
+    /// consumed by the next reorg to the given head, whether or not it reverts the safe block,
+    /// and expires after 10 minutes.
+    #[method(name = "allowReorg")]
+    async fn allow_reorg(&self, head: B256) -> RpcResult<()>;
+}
@@ -1,6 +1,7 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256};
 use reth_rpc_types::{
+    reth::{ForkTree, StatelessWitness},
     trace::geth::{
         BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
         TraceResult,
@@ -145,6 +146,15 @@ pub trait DebugApi {
         block: BlockNumberOrTag,
     ) -> RpcResult<HashMap<B256, Bytes>>;
 
+    /// The `debug_statelessWitness` method re-executes a block like `debug_executionWitness`, and
+    /// returns the trie nodes together with the accessed bytecodes and the ancestor headers that
+    /// are required to validate the block statelessly.
+    ///
+    /// The first and only argument is the block number or block hash.
+    #[method(name = "statelessWitness")]
+    async fn debug_stateless_witness(&self, block: BlockNumberOrTag)
+        -> RpcResult<StatelessWitness>;
+
     /// Sets the logging backtrace location. When a backtrace location is set and a log message is
     /// emitted at that location, the stack of the goroutine executing the log statement will
     /// be printed to stderr.
@@ -389,3 +399,13 @@ pub trait DebugApi {
     #[method(name = "writeMutexProfile")]
     async fn debug_write_mutex_profile(&self, file: String) -> RpcResult<()>;
 }
+
+/// Debug rpc interface for inspecting the in-memory tree of executed blocks.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "debug"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "debug"))]
+pub trait DebugForkTreeApi {
+    /// Returns all executed blocks that are kept in memory, including blocks of non-canonical
+    /// forks, together with the current canonical head.
+    #[method(name = "forkTree")]
+    async fn fork_tree(&self) -> RpcResult<ForkTree>;
+}
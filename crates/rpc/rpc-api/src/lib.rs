@@ -36,13 +36,13 @@ pub use servers::*;
 /// Aggregates all server traits.
 pub mod servers {
     pub use crate::{
-        admin::AdminApiServer,
-        debug::DebugApiServer,
+        admin::{AdminApiServer, AdminReorgApiServer},
+        debug::{DebugApiServer, DebugForkTreeApiServer},
         engine::{EngineApiServer, EngineEthApiServer},
         mev::MevApiServer,
         net::NetApiServer,
         otterscan::OtterscanServer,
-        reth::RethApiServer,
+        reth::{RethApiServer, RethPayloadTimingsApiServer},
         rpc::RpcApiServer,
         trace::TraceApiServer,
         txpool::TxPoolApiServer,
@@ -63,16 +63,16 @@ pub use clients::*;
 #[cfg(feature = "client")]
 pub mod clients {
     pub use crate::{
-        admin::AdminApiClient,
+        admin::{AdminApiClient, AdminReorgApiClient},
         anvil::AnvilApiClient,
-        debug::DebugApiClient,
+        debug::{DebugApiClient, DebugForkTreeApiClient},
         engine::{EngineApiClient, EngineEthApiClient},
         ganache::GanacheApiClient,
         hardhat::HardhatApiClient,
         mev::MevApiClient,
         net::NetApiClient,
         otterscan::OtterscanClient,
-        reth::RethApiClient,
+        reth::{RethApiClient, RethPayloadTimingsApiClient},
         rpc::RpcApiServer,
         trace::TraceApiClient,
         txpool::TxPoolApiClient,
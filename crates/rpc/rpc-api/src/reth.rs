@@ -1,5 +1,6 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::{Address, BlockId, U256};
+use reth_primitives::{Address, BlockId, B256, U256};
+use reth_rpc_types::reth::PayloadTimings;
 use std::collections::HashMap;
 
 /// Reth API namespace for reth-specific methods
@@ -13,3 +14,13 @@ pub trait RethApi {
         block_id: BlockId,
     ) -> RpcResult<HashMap<Address, U256>>;
 }
+
+/// Reth API namespace for the timings of the payloads processed by the engine
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "reth"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "reth"))]
+pub trait RethPayloadTimingsApi {
+    /// Returns the time the payload with the given block hash spent in each phase of
+    /// `engine_newPayload`, if it is one of the recently processed payloads.
+    #[method(name = "getPayloadTimings")]
+    async fn payload_timings(&self, block_hash: B256) -> RpcResult<Option<PayloadTimings>>;
+}
@@ -1700,6 +1700,31 @@ impl TransportRpcModules {
         Ok(())
     }
 
+    /// Merge the given [Methods] in all transports that have the given [`RethRpcModule`]
+    /// configured.
+    ///
+    /// Fails if any of the methods in other is present already.
+    ///
+    /// Returns [Ok(false)] if the module is not configured on any transport.
+    pub fn merge_if_module_configured(
+        &mut self,
+        module: RethRpcModule,
+        other: impl Into<Methods>,
+    ) -> Result<bool, RegisterMethodError> {
+        let other = other.into();
+        let mut merged = false;
+        if self.config.http().is_some_and(|sel| sel.contains(&module)) {
+            merged |= self.merge_http(other.clone())?;
+        }
+        if self.config.ws().is_some_and(|sel| sel.contains(&module)) {
+            merged |= self.merge_ws(other.clone())?;
+        }
+        if self.config.ipc().is_some_and(|sel| sel.contains(&module)) {
+            merged |= self.merge_ipc(other)?;
+        }
+        Ok(merged)
+    }
+
     /// Removes the method with the given name from the configured http methods.
     ///
     /// Returns `true` if the method was found and removed, `false` otherwise.
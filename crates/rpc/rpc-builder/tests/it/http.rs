@@ -11,6 +11,7 @@ use jsonrpsee::{
     rpc_params,
     types::error::ErrorCode,
 };
+use reth_network_api::ManagedPeerKind;
 use reth_network_peers::NodeRecord;
 use reth_primitives::{
     hex_literal::hex, Address, BlockId, BlockNumberOrTag, Bytes, TxHash, B256, B64, U256, U64,
@@ -144,6 +145,18 @@ where
     AdminApiClient::remove_peer(client, node.into()).await.unwrap();
     AdminApiClient::add_trusted_peer(client, node.into()).await.unwrap();
     AdminApiClient::remove_trusted_peer(client, node.into()).await.unwrap();
+    AdminApiClient::ban_peer(client, node.into(), Some(60)).await.unwrap();
+    AdminApiClient::unban_peer(client, node.into()).await.unwrap();
+    AdminApiClient::ban_ip_range(client, "192.0.2.0/24".parse().unwrap(), None).await.unwrap();
+    AdminApiClient::unban_ip_range(client, "192.0.2.0/24".parse().unwrap()).await.unwrap();
+    AdminApiClient::bans(client).await.unwrap();
+    AdminApiClient::peers_config(client).await.unwrap();
+    AdminApiClient::add_configured_peer(client, node.into(), ManagedPeerKind::Static)
+        .await
+        .unwrap();
+    AdminApiClient::remove_configured_peer(client, node.into(), ManagedPeerKind::Static)
+        .await
+        .unwrap();
     AdminApiClient::node_info(client).await.unwrap();
 }
 
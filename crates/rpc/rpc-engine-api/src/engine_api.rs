@@ -5,7 +5,7 @@ use async_trait::async_trait;
 use jsonrpsee_core::RpcResult;
 use reth_beacon_consensus::BeaconConsensusEngineHandle;
 use reth_chainspec::ChainSpec;
-use reth_engine_primitives::EngineTypes;
+use reth_engine_primitives::{EngineTypes, PayloadTimingsTracker};
 use reth_evm::provider::EvmEnvProvider;
 use reth_payload_builder::PayloadStore;
 use reth_payload_primitives::{
@@ -27,9 +27,12 @@ use reth_rpc_types_compat::engine::payload::{
 };
 use reth_storage_api::{BlockReader, HeaderProvider, StateProviderFactory};
 use reth_tasks::TaskSpawner;
-use std::{sync::Arc, time::Instant};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::oneshot;
-use tracing::{trace, warn};
+use tracing::{debug_span, trace, warn, Instrument};
 
 /// The Engine API response sender.
 pub type EngineApiSender<Ok> = oneshot::Sender<EngineApiResult<Ok>>;
@@ -60,6 +63,8 @@ struct EngineApiInner<Provider, EngineT: EngineTypes> {
     client: ClientVersionV1,
     /// The list of all supported Engine capabilities available over the engine endpoint.
     capabilities: EngineCapabilities,
+    /// Per-phase timings of the most recent payloads, if they are tracked.
+    payload_timings: Option<PayloadTimingsTracker>,
 }
 
 impl<Provider, EngineT> EngineApi<Provider, EngineT>
@@ -76,6 +81,7 @@ where
         task_spawner: Box<dyn TaskSpawner>,
         client: ClientVersionV1,
         capabilities: EngineCapabilities,
+        payload_timings: Option<PayloadTimingsTracker>,
     ) -> Self {
         let inner = Arc::new(EngineApiInner {
             provider,
@@ -86,10 +92,26 @@ where
             metrics: EngineApiMetrics::default(),
             client,
             capabilities,
+            payload_timings,
         });
         Self { inner }
     }
 
+    /// Records that the payload with the given block hash was received, if timings are tracked.
+    fn on_payload_received(&self, block_hash: B256) {
+        if let Some(payload_timings) = &self.inner.payload_timings {
+            payload_timings.on_received(block_hash);
+        }
+    }
+
+    /// Records the total time it took to respond to the payload with the given block hash, if
+    /// timings are tracked.
+    fn on_payload_served(&self, block_hash: B256, elapsed: Duration) {
+        if let Some(payload_timings) = &self.inner.payload_timings {
+            payload_timings.update(block_hash, |timings| timings.total = Some(elapsed));
+        }
+    }
+
     /// Fetches the client version.
     fn get_client_version_v1(
         &self,
@@ -621,9 +643,14 @@ where
         trace!(target: "rpc::engine", "Serving engine_newPayloadV1");
         let start = Instant::now();
         let gas_used = payload.gas_used;
-        let res = Self::new_payload_v1(self, payload).await;
+        let block_hash = payload.block_hash;
+        self.on_payload_received(block_hash);
+        let res = Self::new_payload_v1(self, payload)
+            .instrument(debug_span!(target: "rpc::engine", "new_payload", %block_hash))
+            .await;
         let elapsed = start.elapsed();
         self.inner.metrics.latency.new_payload_v1.record(elapsed);
+        self.on_payload_served(block_hash, elapsed);
         self.inner.metrics.new_payload_response.update_response_metrics(&res, gas_used, elapsed);
         Ok(res?)
     }
@@ -634,9 +661,14 @@ where
         trace!(target: "rpc::engine", "Serving engine_newPayloadV2");
         let start = Instant::now();
         let gas_used = payload.execution_payload.gas_used;
-        let res = Self::new_payload_v2(self, payload).await;
+        let block_hash = payload.execution_payload.block_hash;
+        self.on_payload_received(block_hash);
+        let res = Self::new_payload_v2(self, payload)
+            .instrument(debug_span!(target: "rpc::engine", "new_payload", %block_hash))
+            .await;
         let elapsed = start.elapsed();
         self.inner.metrics.latency.new_payload_v2.record(elapsed);
+        self.on_payload_served(block_hash, elapsed);
         self.inner.metrics.new_payload_response.update_response_metrics(&res, gas_used, elapsed);
         Ok(res?)
     }
@@ -652,10 +684,14 @@ where
         trace!(target: "rpc::engine", "Serving engine_newPayloadV3");
         let start = Instant::now();
         let gas_used = payload.payload_inner.payload_inner.gas_used;
-        let res =
-            Self::new_payload_v3(self, payload, versioned_hashes, parent_beacon_block_root).await;
+        let block_hash = payload.payload_inner.payload_inner.block_hash;
+        self.on_payload_received(block_hash);
+        let res = Self::new_payload_v3(self, payload, versioned_hashes, parent_beacon_block_root)
+            .instrument(debug_span!(target: "rpc::engine", "new_payload", %block_hash))
+            .await;
         let elapsed = start.elapsed();
         self.inner.metrics.latency.new_payload_v3.record(elapsed);
+        self.on_payload_served(block_hash, elapsed);
         self.inner.metrics.new_payload_response.update_response_metrics(&res, gas_used, elapsed);
         Ok(res?)
     }
@@ -671,10 +707,14 @@ where
         trace!(target: "rpc::engine", "Serving engine_newPayloadV4");
         let start = Instant::now();
         let gas_used = payload.payload_inner.payload_inner.payload_inner.gas_used;
-        let res =
-            Self::new_payload_v4(self, payload, versioned_hashes, parent_beacon_block_root).await;
+        let block_hash = payload.payload_inner.payload_inner.payload_inner.block_hash;
+        self.on_payload_received(block_hash);
+        let res = Self::new_payload_v4(self, payload, versioned_hashes, parent_beacon_block_root)
+            .instrument(debug_span!(target: "rpc::engine", "new_payload", %block_hash))
+            .await;
         let elapsed = start.elapsed();
         self.inner.metrics.latency.new_payload_v4.record(elapsed);
+        self.on_payload_served(block_hash, elapsed);
         self.inner.metrics.new_payload_response.update_response_metrics(&res, gas_used, elapsed);
         Ok(res?)
     }
@@ -956,6 +996,7 @@ mod tests {
             task_executor,
             client,
             EngineCapabilities::default(),
+            Some(PayloadTimingsTracker::default()),
         );
         let handle = EngineApiTestHandle { chain_spec, provider, from_api: engine_rx };
         (handle, api)
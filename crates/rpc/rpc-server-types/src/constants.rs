@@ -115,6 +115,9 @@ pub mod cache {
     /// Default cache size for the env cache: 1000 envs.
     pub const DEFAULT_ENV_CACHE_MAX_LEN: u32 = 1000;
 
+    /// Default cache size for the historical state cache: disabled.
+    pub const DEFAULT_HISTORICAL_STATE_CACHE_MAX_LEN: u32 = 0;
+
     /// Default number of concurrent database requests.
     pub const DEFAULT_CONCURRENT_DB_REQUESTS: usize = 512;
 }
@@ -46,6 +46,9 @@ pub use alloy_rpc_types_beacon as beacon;
 // re-export txpool
 pub use alloy_rpc_types_txpool as txpool;
 
+// Reth specific rpc types.
+pub mod reth;
+
 // Ethereum specific rpc types related to typed transaction requests and the engine API.
 #[cfg(feature = "jsonrpsee-types")]
 pub use eth::error::ToRpcError;
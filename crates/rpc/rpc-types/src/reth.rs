@@ -0,0 +1,124 @@
+//! Reth specific RPC types.
+
+use alloy_primitives::{BlockNumber, Bytes, B256};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::Duration};
+
+/// The time a payload spent in each phase on the `engine_newPayload` path.
+///
+/// Phases that the payload did not reach, for example execution of a payload with a missing
+/// parent, are `None`. Durations are serialized as integer microseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadTimings {
+    /// Number of the payload block.
+    pub block_number: BlockNumber,
+    /// Time between the engine API receiving the payload and the engine tree starting to process
+    /// it, spent queued in the channels of the engine handler and the engine tree.
+    #[serde(with = "duration_micros")]
+    pub queue: Option<Duration>,
+    /// Time the persistence service spent saving blocks between the engine API receiving the
+    /// payload and the engine tree finishing processing it, contending with it for the disk.
+    #[serde(with = "duration_micros")]
+    pub persistence_overlap: Option<Duration>,
+    /// Time spent validating the payload and the consensus rules of the block before execution.
+    #[serde(with = "duration_micros")]
+    pub validation: Option<Duration>,
+    /// Time spent executing the block.
+    #[serde(with = "duration_micros")]
+    pub execution: Option<Duration>,
+    /// Time spent computing the state root after execution.
+    #[serde(with = "duration_micros")]
+    pub state_root: Option<Duration>,
+    /// Total time the engine tree spent processing the payload.
+    #[serde(with = "duration_micros")]
+    pub processing: Option<Duration>,
+    /// Total time until the engine API responded to the payload.
+    #[serde(with = "duration_micros")]
+    pub total: Option<Duration>,
+    /// Time the persistence service spent writing the batch of blocks that included the block.
+    #[serde(with = "duration_micros")]
+    pub persistence: Option<Duration>,
+}
+
+/// A snapshot of all executed blocks that are kept in memory by the engine tree, including the
+/// blocks of non-canonical forks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkTree {
+    /// Number of the canonical head.
+    pub canonical_head_number: BlockNumber,
+    /// Hash of the canonical head.
+    pub canonical_head_hash: B256,
+    /// All executed blocks in memory, ordered by block number.
+    pub blocks: Vec<ForkTreeBlock>,
+}
+
+/// An executed block in the [`ForkTree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkTreeBlock {
+    /// Block number.
+    pub number: BlockNumber,
+    /// Block hash.
+    pub hash: B256,
+    /// Hash of the parent block.
+    pub parent_hash: B256,
+    /// Whether the block is part of the canonical chain.
+    pub canonical: bool,
+}
+
+/// Everything that is needed to validate a block statelessly, as returned by
+/// `debug_statelessWitness`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatelessWitness {
+    /// The RLP encoded trie nodes that are accessed when executing the block and computing its
+    /// state root, by hash.
+    pub state: HashMap<B256, Bytes>,
+    /// The bytecodes that are accessed when executing the block, by code hash.
+    pub codes: HashMap<B256, Bytes>,
+    /// The RLP encoded headers of the ancestors of the block, from the parent down to the oldest
+    /// block whose hash is read with `BLOCKHASH`.
+    pub headers: Vec<Bytes>,
+}
+
+/// (De)serializes optional durations as integer microseconds.
+mod duration_micros {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub(super) fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.serialize_some(&(duration.as_micros() as u64)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_micros))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_timings_serde_micros() {
+        let timings = PayloadTimings {
+            block_number: 1,
+            execution: Some(Duration::from_micros(1500)),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(timings).unwrap();
+        assert_eq!(json["execution"], 1500);
+        assert!(json["queue"].is_null());
+        assert_eq!(serde_json::from_value::<PayloadTimings>(json).unwrap(), timings);
+    }
+}
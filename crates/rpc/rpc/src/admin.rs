@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use alloy_genesis::ChainConfig;
 use async_trait::async_trait;
@@ -6,10 +6,12 @@ use jsonrpsee::core::RpcResult;
 use reth_chainspec::ChainSpec;
 use reth_network_api::{NetworkInfo, Peers};
 use reth_network_peers::{id2pk, AnyNode, NodeRecord};
-use reth_network_types::PeerKind;
+use reth_network_types::{
+    Bans, IpNet, ManagedPeerKind, ManagedPeers, ManagedPeersUpdate, PeerKind,
+};
 use reth_primitives::EthereumHardfork;
 use reth_rpc_api::AdminApiServer;
-use reth_rpc_server_types::ToRpcResult;
+use reth_rpc_server_types::{result::invalid_params_rpc_err, ToRpcResult};
 use reth_rpc_types::admin::{
     EthInfo, EthPeerInfo, EthProtocolInfo, NodeInfo, PeerInfo, PeerNetworkInfo, PeerProtocolInfo,
     Ports, ProtocolInfo,
@@ -64,6 +66,65 @@ where
         Ok(true)
     }
 
+    /// Handler for `admin_banPeer`
+    fn ban_peer(&self, record: AnyNode, duration: Option<u64>) -> RpcResult<bool> {
+        self.network.ban_peer(record.peer_id(), duration.map(Duration::from_secs));
+        Ok(true)
+    }
+
+    /// Handler for `admin_unbanPeer`
+    fn unban_peer(&self, record: AnyNode) -> RpcResult<bool> {
+        self.network.unban_peer(record.peer_id());
+        Ok(true)
+    }
+
+    /// Handler for `admin_banIpRange`
+    fn ban_ip_range(&self, range: IpNet, duration: Option<u64>) -> RpcResult<bool> {
+        self.network.ban_ip_range(range, duration.map(Duration::from_secs));
+        Ok(true)
+    }
+
+    /// Handler for `admin_unbanIpRange`
+    fn unban_ip_range(&self, range: IpNet) -> RpcResult<bool> {
+        self.network.unban_ip_range(range);
+        Ok(true)
+    }
+
+    /// Handler for `admin_bans`
+    async fn bans(&self) -> RpcResult<Bans> {
+        self.network.bans().await.to_rpc_result()
+    }
+
+    /// Handler for `admin_peersConfig`
+    async fn peers_config(&self) -> RpcResult<Option<ManagedPeers>> {
+        self.network.managed_peers().await.to_rpc_result()
+    }
+
+    /// Handler for `admin_addConfiguredPeer`
+    async fn add_configured_peer(&self, record: AnyNode, kind: ManagedPeerKind) -> RpcResult<bool> {
+        let update = match (kind, record.node_record()) {
+            (ManagedPeerKind::Banned, _) => ManagedPeersUpdate::AddBanned(record.peer_id()),
+            (ManagedPeerKind::Trusted, Some(record)) => ManagedPeersUpdate::AddTrusted(record),
+            (ManagedPeerKind::Static, Some(record)) => ManagedPeersUpdate::AddStatic(record),
+            (_, None) => {
+                return Err(invalid_params_rpc_err(
+                    "trusted and static peers require a record with an address",
+                ))
+            }
+        };
+        self.network.update_managed_peers(update).await.to_rpc_result()
+    }
+
+    /// Handler for `admin_removeConfiguredPeer`
+    async fn remove_configured_peer(
+        &self,
+        record: AnyNode,
+        kind: ManagedPeerKind,
+    ) -> RpcResult<bool> {
+        let update = ManagedPeersUpdate::Remove(kind, record.peer_id());
+        self.network.update_managed_peers(update).await.to_rpc_result()
+    }
+
     /// Handler for `admin_peers`
     async fn peers(&self) -> RpcResult<Vec<PeerInfo>> {
         let peers = self.network.get_all_peers().await.to_rpc_result()?;
@@ -91,7 +152,7 @@ where
                     },
                     protocols: PeerProtocolInfo {
                         eth: Some(EthPeerInfo::Info(EthInfo {
-                            version: peer.status.version as u64,
+                            version: peer.status.version() as u64,
                         })),
                         snap: None,
                         other: Default::default(),
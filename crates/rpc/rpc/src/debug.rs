@@ -22,6 +22,7 @@ use reth_rpc_eth_api::{
 use reth_rpc_eth_types::{EthApiError, StateCacheDb};
 use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
 use reth_rpc_types::{
+    reth::StatelessWitness,
     state::EvmOverrides,
     trace::geth::{
         BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
@@ -29,6 +30,7 @@ use reth_rpc_types::{
     },
     Block as RpcBlock, BlockError, Bundle, StateContext, TransactionRequest,
 };
+use reth_stateless::ExecutionWitness;
 use reth_tasks::pool::BlockingTaskGuard;
 use reth_trie::{HashedPostState, HashedStorage};
 use revm::{
@@ -568,6 +570,16 @@ where
         &self,
         block_id: BlockNumberOrTag,
     ) -> Result<HashMap<B256, Bytes>, Eth::Error> {
+        Ok(self.debug_stateless_witness(block_id).await?.state)
+    }
+
+    /// The `debug_statelessWitness` method re-executes a block like `debug_executionWitness`, and
+    /// additionally collects the accessed bytecodes and the headers of the ancestors whose hashes
+    /// were accessed, so that the block can be validated statelessly.
+    pub async fn debug_stateless_witness(
+        &self,
+        block_id: BlockNumberOrTag,
+    ) -> Result<ExecutionWitness, Eth::Error> {
         let ((cfg, block_env, _), maybe_block) = futures::try_join!(
             self.inner.eth_api.evm_env_at(block_id.into()),
             self.inner.eth_api.block_with_senders(block_id.into()),
@@ -606,6 +618,8 @@ where
                 )
                 .map_err(|err| EthApiError::Internal(err.into()))?;
 
+                let parent_number = block.number.saturating_sub(1);
+
                 // Re-execute all of the transactions in the block to load all touched accounts into
                 // the cache DB.
                 for tx in block.into_transactions_ecrecovered() {
@@ -628,6 +642,23 @@ where
                 // Take the bundle state
                 let bundle_state = db.take_bundle();
 
+                // Collect the accessed bytecodes and the headers from the parent down to the
+                // oldest block whose hash was accessed.
+                let codes = db
+                    .cache
+                    .contracts
+                    .values()
+                    .filter(|code| !code.is_empty())
+                    .map(|code| (code.hash_slow(), code.original_bytes()))
+                    .collect();
+                let oldest = db.block_hashes.keys().next().copied().unwrap_or(parent_number);
+                let mut headers = this
+                    .inner
+                    .provider
+                    .headers_range(oldest..=parent_number)
+                    .map_err(Into::into)?;
+                headers.reverse();
+
                 // Grab all account proofs for the data accessed during block execution.
                 //
                 // Note: We grab *all* accounts in the cache here, as the `BundleState` prunes
@@ -656,10 +687,10 @@ where
                 // Generate an execution witness for the aggregated state of accessed accounts.
                 // Destruct the cache database to retrieve the state provider.
                 let state_provider = db.database.into_inner();
-                let witness = state_provider
+                let state = state_provider
                     .witness(HashedPostState::default(), hashed_state)
                     .map_err(Into::into)?;
-                Ok(witness)
+                Ok(ExecutionWitness { state, codes, headers })
             })
             .await
     }
@@ -936,6 +967,15 @@ where
         Self::debug_execution_witness(self, block).await.map_err(Into::into)
     }
 
+    /// Handler for `debug_statelessWitness`
+    async fn debug_stateless_witness(
+        &self,
+        block: BlockNumberOrTag,
+    ) -> RpcResult<StatelessWitness> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_stateless_witness(self, block).await.map(Into::into).map_err(Into::into)
+    }
+
     /// Handler for `debug_traceCall`
     async fn debug_trace_call(
         &self,
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_rpc_api::DebugForkTreeApiServer;
+use reth_rpc_server_types::result::internal_rpc_err;
+use reth_rpc_types::reth::ForkTree;
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+
+/// `debug_forkTree` API implementation.
+///
+/// This type exposes the shape of the engine's in-memory tree of executed blocks, including
+/// blocks of non-canonical forks that are retained for fast reorgs.
+#[derive(Debug, Clone)]
+pub struct DebugForkTreeApi {
+    /// The channel to request a snapshot of the fork tree from the engine.
+    to_engine: UnboundedSender<oneshot::Sender<ForkTree>>,
+}
+
+impl DebugForkTreeApi {
+    /// Creates a new instance of `DebugForkTreeApi`.
+    pub const fn new(to_engine: UnboundedSender<oneshot::Sender<ForkTree>>) -> Self {
+        Self { to_engine }
+    }
+}
+
+#[async_trait]
+impl DebugForkTreeApiServer for DebugForkTreeApi {
+    /// Handler for `debug_forkTree`
+    async fn fork_tree(&self) -> RpcResult<ForkTree> {
+        let (tx, rx) = oneshot::channel();
+        self.to_engine.send(tx).map_err(|_| internal_rpc_err("engine unavailable"))?;
+        rx.await.map_err(|_| internal_rpc_err("engine unavailable"))
+    }
+}
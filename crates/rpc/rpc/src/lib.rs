@@ -36,8 +36,11 @@ mod admin;
 mod debug;
 mod engine;
 pub mod eth;
+mod fork_tree;
 mod net;
 mod otterscan;
+mod payload_timings;
+mod reorg;
 mod reth;
 mod rpc;
 mod trace;
@@ -47,8 +50,11 @@ pub use admin::AdminApi;
 pub use debug::DebugApi;
 pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthBundle, EthFilter, EthPubSub};
+pub use fork_tree::DebugForkTreeApi;
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
+pub use payload_timings::RethPayloadTimingsApi;
+pub use reorg::AdminReorgApi;
 pub use reth::RethApi;
 pub use rpc::RPCApi;
 pub use trace::TraceApi;
@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_engine_primitives::PayloadTimingsTracker;
+use reth_primitives::B256;
+use reth_rpc_api::RethPayloadTimingsApiServer;
+use reth_rpc_types::reth::PayloadTimings;
+
+/// `reth_getPayloadTimings` API implementation.
+///
+/// This type provides the per-phase timings the engine recorded for recently processed payloads.
+#[derive(Debug, Clone)]
+pub struct RethPayloadTimingsApi {
+    /// The tracker that the engine API and the engine tree record payload timings to.
+    payload_timings: PayloadTimingsTracker,
+}
+
+impl RethPayloadTimingsApi {
+    /// Creates a new instance of `RethPayloadTimingsApi`.
+    pub const fn new(payload_timings: PayloadTimingsTracker) -> Self {
+        Self { payload_timings }
+    }
+}
+
+#[async_trait]
+impl RethPayloadTimingsApiServer for RethPayloadTimingsApi {
+    /// Handler for `reth_getPayloadTimings`
+    async fn payload_timings(&self, block_hash: B256) -> RpcResult<Option<PayloadTimings>> {
+        Ok(self.payload_timings.get(&block_hash))
+    }
+}
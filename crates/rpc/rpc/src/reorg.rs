@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_chain_state::CanonicalInMemoryState;
+use reth_primitives::B256;
+use reth_rpc_api::AdminReorgApiServer;
+
+/// `admin_allowReorg` API implementation.
+///
+/// This type allows the engine to apply a reorg that it refused because it would revert the safe
+/// block.
+#[derive(Debug, Clone)]
+pub struct AdminReorgApi {
+    /// The in-memory state that the engine reads allowed reorgs from.
+    canonical_in_memory_state: CanonicalInMemoryState,
+}
+
+impl AdminReorgApi {
+    /// Creates a new instance of `AdminReorgApi`.
+    pub const fn new(canonical_in_memory_state: CanonicalInMemoryState) -> Self {
+        Self { canonical_in_memory_state }
+    }
+}
+
+#[async_trait]
+impl AdminReorgApiServer for AdminReorgApi {
+    /// Handler for `admin_allowReorg`
+    async fn allow_reorg(&self, head: B256) -> RpcResult<()> {
+        self.canonical_in_memory_state.allow_reorg(head);
+        Ok(())
+    }
+}
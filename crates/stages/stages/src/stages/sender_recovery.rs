@@ -8,8 +8,8 @@ use reth_db_api::{
 };
 use reth_primitives::{Address, StaticFileSegment, TransactionSignedNoHash, TxNumber};
 use reth_provider::{
-    BlockReader, DatabaseProviderRW, HeaderProvider, ProviderError, PruneCheckpointReader,
-    StatsReader,
+    providers::StaticFileWriter, BlockReader, DatabaseProviderRW, HeaderProvider, ProviderError,
+    PruneCheckpointReader, StatsReader,
 };
 use reth_prune_types::PruneSegment;
 use reth_stages_api::{
@@ -122,6 +122,18 @@ impl<DB: Database> Stage<DB> for SenderRecoveryStage {
             .last_tx_num();
         provider.unwind_table_by_num::<tables::TransactionSenders>(latest_tx_id)?;
 
+        // Unwind from static files, if any of the unwound senders were already moved there.
+        let static_file_provider = provider.static_file_provider();
+        if let Some(static_file_tx_num) =
+            static_file_provider.get_highest_static_file_tx(StaticFileSegment::Senders)
+        {
+            if static_file_tx_num > latest_tx_id {
+                static_file_provider
+                    .latest_writer(StaticFileSegment::Senders)?
+                    .prune_senders(static_file_tx_num - latest_tx_id, unwind_to)?;
+            }
+        }
+
         Ok(UnwindOutput {
             checkpoint: StageCheckpoint::new(unwind_to)
                 .with_entities_stage_checkpoint(stage_checkpoint(provider)?),
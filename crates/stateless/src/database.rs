@@ -0,0 +1,160 @@
+//! An in-memory state database that is built from an execution witness.
+
+use crate::{
+    trie::{SparseTrie, TrieNodes},
+    ExecutionWitness,
+};
+use alloy_rlp::Decodable;
+use reth_primitives::{keccak256, Address, Bytes, B256, KECCAK_EMPTY, U256};
+use reth_revm::{
+    primitives::{AccountInfo, Bytecode},
+    Database,
+};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use reth_trie::HashedPostState;
+use reth_trie_common::{TrieAccount, EMPTY_ROOT_HASH};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+/// A [`Database`] that serves the state of an [`ExecutionWitness`].
+///
+/// Accounts and storage slots are read from the account and storage tries that are revealed from
+/// the trie nodes of the witness, starting at the state root of the parent block. Reading state
+/// that is not covered by the witness fails with [`ProviderError::TrieWitnessError`].
+///
+/// After execution, [`StatelessDatabase::state_root`] applies the state changes to the same tries
+/// to compute the state root of the block.
+#[derive(Debug)]
+pub struct StatelessDatabase {
+    /// The trie nodes of the witness.
+    nodes: TrieNodes,
+    /// The bytecodes of the witness.
+    codes: HashMap<B256, Bytes>,
+    /// The hashes of the ancestor blocks by number.
+    block_hashes: HashMap<u64, B256>,
+    /// The account trie.
+    accounts: SparseTrie,
+    /// The storage tries that were accessed, by hashed address.
+    storages: HashMap<B256, SparseTrie>,
+}
+
+impl StatelessDatabase {
+    /// Creates a new database for the state with the given root.
+    pub fn new(
+        state_root: B256,
+        witness: &ExecutionWitness,
+        block_hashes: HashMap<u64, B256>,
+    ) -> Self {
+        Self {
+            nodes: witness.trie_nodes(),
+            codes: witness.bytecodes(),
+            block_hashes,
+            accounts: SparseTrie::new(state_root),
+            storages: HashMap::new(),
+        }
+    }
+
+    /// Computes the state root after applying the given state changes.
+    pub fn state_root(&mut self, state: &HashedPostState) -> ProviderResult<B256> {
+        let hashed_addresses =
+            state.accounts.keys().chain(state.storages.keys()).copied().collect::<HashSet<_>>();
+        for hashed_address in hashed_addresses {
+            let existing = trie_account(&mut self.accounts, &self.nodes, hashed_address)?;
+
+            let storage_root = match state.storages.get(&hashed_address) {
+                Some(storage) => {
+                    if storage.wiped {
+                        self.storages.insert(hashed_address, SparseTrie::new(EMPTY_ROOT_HASH));
+                    }
+                    let trie = storage_trie(
+                        &mut self.storages,
+                        &mut self.accounts,
+                        &self.nodes,
+                        hashed_address,
+                    )?;
+                    for (hashed_slot, value) in &storage.storage {
+                        let value = (!value.is_zero())
+                            .then(|| alloy_rlp::encode_fixed_size(value).to_vec());
+                        trie.update(*hashed_slot, value, &self.nodes)?;
+                    }
+                    trie.root()
+                }
+                None => existing.map_or(EMPTY_ROOT_HASH, |account| account.storage_root),
+            };
+
+            let account = match state.accounts.get(&hashed_address) {
+                Some(account) => account.map(|account| TrieAccount::from((account, storage_root))),
+                None => existing.map(|account| TrieAccount { storage_root, ..account }),
+            };
+            self.accounts.update(hashed_address, account.map(alloy_rlp::encode), &self.nodes)?;
+        }
+        Ok(self.accounts.root())
+    }
+}
+
+impl Database for StatelessDatabase {
+    type Error = ProviderError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let account = trie_account(&mut self.accounts, &self.nodes, keccak256(address))?;
+        Ok(account.map(|account| AccountInfo {
+            balance: account.balance,
+            nonce: account.nonce,
+            code_hash: account.code_hash,
+            code: None,
+        }))
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if code_hash == KECCAK_EMPTY {
+            return Ok(Bytecode::default())
+        }
+        let code = self.codes.get(&code_hash).ok_or_else(|| {
+            ProviderError::TrieWitnessError(format!("missing bytecode {code_hash}"))
+        })?;
+        Ok(Bytecode::new_raw(code.clone()))
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let trie =
+            storage_trie(&mut self.storages, &mut self.accounts, &self.nodes, keccak256(address))?;
+        let Some(value) = trie.get(keccak256(B256::from(index)), &self.nodes)? else {
+            return Ok(U256::ZERO)
+        };
+        Ok(U256::decode(&mut &value[..])?)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hashes
+            .get(&number)
+            .copied()
+            .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))
+    }
+}
+
+/// Reads an account from the account trie.
+fn trie_account(
+    accounts: &mut SparseTrie,
+    nodes: &TrieNodes,
+    hashed_address: B256,
+) -> ProviderResult<Option<TrieAccount>> {
+    let Some(encoded) = accounts.get(hashed_address, nodes)? else { return Ok(None) };
+    Ok(Some(TrieAccount::decode(&mut &encoded[..])?))
+}
+
+/// Returns the storage trie of an account, which is opened at the storage root of the account in
+/// the account trie when it is accessed for the first time.
+fn storage_trie<'a>(
+    storages: &'a mut HashMap<B256, SparseTrie>,
+    accounts: &mut SparseTrie,
+    nodes: &TrieNodes,
+    hashed_address: B256,
+) -> ProviderResult<&'a mut SparseTrie> {
+    Ok(match storages.entry(hashed_address) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => {
+            let storage_root = trie_account(accounts, nodes, hashed_address)?
+                .map_or(EMPTY_ROOT_HASH, |account| account.storage_root);
+            entry.insert(SparseTrie::new(storage_root))
+        }
+    })
+}
@@ -0,0 +1,24 @@
+use reth_consensus::ConsensusError;
+use reth_evm::execute::BlockExecutionError;
+use reth_primitives::B256;
+use reth_storage_errors::provider::ProviderError;
+
+/// Errors of stateless block validation.
+#[derive(Debug, thiserror::Error)]
+pub enum StatelessValidationError {
+    /// The header of the parent block is not part of the witness.
+    #[error("missing header of parent block {0}")]
+    MissingParentHeader(B256),
+    /// The senders of the block transactions could not be recovered.
+    #[error("failed to recover transaction senders")]
+    SenderRecovery,
+    /// The block violates a consensus rule.
+    #[error(transparent)]
+    Consensus(#[from] ConsensusError),
+    /// The block could not be executed.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// The witness does not contain all state that is required to validate the block.
+    #[error(transparent)]
+    Witness(#[from] ProviderError),
+}
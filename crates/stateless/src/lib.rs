@@ -0,0 +1,265 @@
+//! Stateless block validation from execution witnesses.
+//!
+//! A block is validated with only an [`ExecutionWitness`] instead of a database: the trie nodes
+//! of the witness reveal the parts of the parent state that are accessed when executing the block,
+//! which are served to the executor by a [`StatelessDatabase`]. After execution the state changes
+//! are applied to the same partial tries to verify the state root of the block.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
+use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
+use reth_primitives::{BlockWithSenders, GotExpected, Receipt, SealedBlock, B256, U256};
+use reth_trie::HashedPostState;
+
+mod database;
+pub use database::StatelessDatabase;
+
+mod error;
+pub use error::StatelessValidationError;
+
+mod recording;
+
+mod trie;
+
+mod witness;
+pub use witness::ExecutionWitness;
+
+/// Validates a block using only the given witness.
+///
+/// This runs the same checks as the engine does for a new payload: the consensus checks of the
+/// header and body, execution of the block on top of the parent state, the consensus checks of
+/// the execution output, and the verification of the state root.
+///
+/// Like the engine, this only supports blocks after the merge.
+pub fn validate_block<E>(
+    executor_provider: &E,
+    consensus: &dyn Consensus,
+    block: SealedBlock,
+    witness: &ExecutionWitness,
+) -> Result<BlockExecutionOutput<Receipt>, StatelessValidationError>
+where
+    E: BlockExecutorProvider,
+{
+    let ancestors = witness.ancestors(block.parent_hash);
+    let parent = ancestors
+        .first()
+        .ok_or(StatelessValidationError::MissingParentHeader(block.parent_hash))?;
+
+    consensus.validate_header_with_total_difficulty(&block.header, U256::MAX)?;
+    consensus.validate_header(&block.header)?;
+    consensus.validate_header_against_parent(&block.header, parent)?;
+    consensus.validate_block_pre_execution(&block)?;
+
+    let block = block
+        .try_seal_with_senders()
+        .map_err(|_| StatelessValidationError::SenderRecovery)?
+        .unseal();
+    let (output, state_root) = execute_block(executor_provider, &block, witness)?;
+
+    consensus.validate_block_post_execution(
+        &block,
+        PostExecutionInput::new(&output.receipts, &output.requests),
+    )?;
+
+    if state_root != block.state_root {
+        return Err(ConsensusError::BodyStateRootDiff(
+            GotExpected { got: state_root, expected: block.state_root }.into(),
+        )
+        .into())
+    }
+
+    Ok(output)
+}
+
+/// Executes a block on top of the parent state of the given witness, and computes the state root
+/// after execution.
+///
+/// Unlike [`validate_block`] this runs no consensus checks, so it can be used to inspect the
+/// outcome of blocks that are invalid.
+pub fn execute_block<E>(
+    executor_provider: &E,
+    block: &BlockWithSenders,
+    witness: &ExecutionWitness,
+) -> Result<(BlockExecutionOutput<Receipt>, B256), StatelessValidationError>
+where
+    E: BlockExecutorProvider,
+{
+    let ancestors = witness.ancestors(block.parent_hash);
+    let parent = ancestors
+        .first()
+        .ok_or(StatelessValidationError::MissingParentHeader(block.parent_hash))?;
+
+    let block_hashes = ancestors.iter().map(|header| (header.number, header.hash())).collect();
+    let mut db = StatelessDatabase::new(parent.state_root, witness, block_hashes);
+    let output = executor_provider.executor(&mut db).execute((block, U256::MAX).into())?;
+    let state_root = db.state_root(&HashedPostState::from_bundle_state(&output.state.state))?;
+
+    Ok((output, state_root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::{ChainSpec, ChainSpecBuilder, MAINNET};
+    use reth_db_common::init::init_genesis;
+    use reth_ethereum_consensus::EthBeaconConsensus;
+    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_primitives::{
+        bytes, constants::ETH_TO_WEI, logs_bloom, proofs, public_key_to_address, Address, Block,
+        BlockWithSenders, Bytes, Genesis, GenesisAccount, Header, Transaction, TxEip1559, TxKind,
+        Withdrawals, B256, EMPTY_OMMER_ROOT_HASH,
+    };
+    use reth_provider::{
+        test_utils::create_test_provider_factory_with_chain_spec, HeaderProvider,
+        StateProviderFactory,
+    };
+    use reth_revm::database::StateProviderDatabase;
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use secp256k1::Keypair;
+    use std::{collections::BTreeMap, sync::Arc};
+
+    /// Stores the hash of the genesis block in slot 0 and copies slot 1 into slot 2.
+    const CONTRACT_CODE: Bytes = bytes!("60004060005560015460025500");
+
+    fn chain_spec(sender: Address, contract: Address) -> Arc<ChainSpec> {
+        Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(Genesis {
+                    gas_limit: 30_000_000,
+                    alloc: BTreeMap::from([
+                        (
+                            sender,
+                            GenesisAccount {
+                                balance: U256::from(ETH_TO_WEI),
+                                ..Default::default()
+                            },
+                        ),
+                        (
+                            contract,
+                            GenesisAccount {
+                                code: Some(CONTRACT_CODE),
+                                storage: Some(BTreeMap::from([(
+                                    B256::with_last_byte(1),
+                                    B256::with_last_byte(42),
+                                )])),
+                                ..Default::default()
+                            },
+                        ),
+                    ]),
+                    ..MAINNET.genesis.clone()
+                })
+                .shanghai_activated()
+                .build(),
+        )
+    }
+
+    #[test]
+    fn validates_block_with_generated_witness() {
+        let key_pair = Keypair::new_global(&mut generators::rng());
+        let sender = public_key_to_address(key_pair.public_key());
+        let contract = Address::with_last_byte(0xc0);
+        let chain_spec = chain_spec(sender, contract);
+
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(factory.clone()).unwrap();
+        let executor_provider = EthExecutorProvider::ethereum(chain_spec.clone());
+        let consensus = EthBeaconConsensus::new(chain_spec.clone());
+
+        let parent = chain_spec.sealed_genesis_header();
+        let timestamp = parent.timestamp + 12;
+        let transaction = sign_tx_with_key_pair(
+            key_pair,
+            Transaction::Eip1559(TxEip1559 {
+                chain_id: chain_spec.chain.id(),
+                nonce: 0,
+                gas_limit: 100_000,
+                max_fee_per_gas: 2_000_000_000,
+                max_priority_fee_per_gas: 1_000_000,
+                to: TxKind::Call(contract),
+                value: U256::from(1),
+                ..Default::default()
+            }),
+        );
+        let withdrawals = Withdrawals::default();
+        let mut block = Block {
+            header: Header {
+                parent_hash: parent.hash(),
+                ommers_hash: EMPTY_OMMER_ROOT_HASH,
+                transactions_root: proofs::calculate_transaction_root(&[transaction.clone()]),
+                withdrawals_root: Some(proofs::calculate_withdrawals_root(&withdrawals)),
+                number: parent.number + 1,
+                gas_limit: parent.gas_limit,
+                timestamp,
+                base_fee_per_gas: parent
+                    .next_block_base_fee(chain_spec.base_fee_params_at_timestamp(timestamp)),
+                ..Default::default()
+            },
+            body: vec![transaction],
+            ommers: Vec::new(),
+            withdrawals: Some(withdrawals),
+            requests: None,
+        };
+
+        // execute the block on the database to fill in the post-execution fields of the header
+        let state_provider = factory.latest().unwrap();
+        let executable = BlockWithSenders { block: block.clone(), senders: vec![sender] };
+        let output = executor_provider
+            .executor(StateProviderDatabase::new(&*state_provider))
+            .execute((&executable, U256::ZERO).into())
+            .unwrap();
+        block.header.gas_used = output.gas_used;
+        block.header.receipts_root =
+            proofs::calculate_receipt_root_no_memo(&output.receipts.iter().collect::<Vec<_>>());
+        block.header.logs_bloom = logs_bloom(output.receipts.iter().flat_map(|r| &r.logs));
+        block.header.state_root = state_provider
+            .state_root(HashedPostState::from_bundle_state(&output.state.state))
+            .unwrap();
+
+        let block = BlockWithSenders { block, senders: vec![sender] };
+        let (witness, witness_output) = ExecutionWitness::generate(
+            &executor_provider,
+            &*state_provider,
+            &parent,
+            |hash| factory.header(&hash),
+            &block,
+        )
+        .unwrap();
+        assert_eq!(witness_output.map(|output| output.gas_used), Some(output.gas_used));
+        assert_eq!(witness.codes.values().collect::<Vec<_>>(), vec![&CONTRACT_CODE]);
+        assert_eq!(witness.ancestors(parent.hash()), vec![parent]);
+
+        let sealed = block.block.clone().seal_slow();
+        let validated =
+            validate_block(&executor_provider, &consensus, sealed.clone(), &witness).unwrap();
+        assert_eq!(validated.gas_used, output.gas_used);
+        assert_eq!(validated.receipts, output.receipts);
+
+        // the witness survives the format of `debug_statelessWitness`
+        let json = serde_json::to_value(&witness).unwrap();
+        assert!(json["headers"][0].is_string());
+        assert_eq!(serde_json::from_value::<ExecutionWitness>(json).unwrap(), witness);
+
+        // the block can't be executed without the trie nodes of the parent state
+        let incomplete = ExecutionWitness { state: Default::default(), ..witness.clone() };
+        assert!(matches!(
+            validate_block(&executor_provider, &consensus, sealed, &incomplete),
+            Err(StatelessValidationError::Execution(_))
+        ));
+
+        // a wrong state root is detected
+        let mut tampered = block.block;
+        tampered.header.state_root = B256::random();
+        assert!(matches!(
+            validate_block(&executor_provider, &consensus, tampered.seal_slow(), &witness),
+            Err(StatelessValidationError::Consensus(ConsensusError::BodyStateRootDiff(_)))
+        ));
+    }
+}
@@ -0,0 +1,69 @@
+//! A database wrapper that records the state that is accessed during execution.
+
+use reth_primitives::{Account, Address, Bytes, B256, U256};
+use reth_revm::{
+    primitives::{AccountInfo, Bytecode},
+    Database,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// A [`Database`] wrapper that records the state that is read for the first time.
+#[derive(Debug)]
+pub(crate) struct RecordingDatabase<DB> {
+    /// The inner database.
+    db: DB,
+    /// Recorded accounts.
+    pub(crate) accounts: BTreeMap<Address, Option<Account>>,
+    /// Recorded storage slots.
+    pub(crate) storage: BTreeMap<Address, BTreeMap<B256, U256>>,
+    /// Recorded bytecodes, by code hash.
+    pub(crate) bytecodes: HashMap<B256, Bytes>,
+    /// Recorded block hashes.
+    pub(crate) block_hashes: BTreeMap<u64, B256>,
+}
+
+impl<DB> RecordingDatabase<DB> {
+    /// Creates a new recording database on top of the given database.
+    pub(crate) fn new(db: DB) -> Self {
+        Self {
+            db,
+            accounts: BTreeMap::new(),
+            storage: BTreeMap::new(),
+            bytecodes: HashMap::new(),
+            block_hashes: BTreeMap::new(),
+        }
+    }
+}
+
+impl<DB: Database> Database for RecordingDatabase<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.db.basic(address)?;
+        if let Some(code) = info.as_ref().and_then(|info| info.code.as_ref()) {
+            if !code.is_empty() {
+                self.bytecodes.entry(code.hash_slow()).or_insert_with(|| code.original_bytes());
+            }
+        }
+        self.accounts.entry(address).or_insert_with(|| info.clone().map(Account::from));
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.db.code_by_hash(code_hash)?;
+        self.bytecodes.entry(code_hash).or_insert_with(|| code.original_bytes());
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self.db.storage(address, index)?;
+        self.storage.entry(address).or_default().entry(B256::from(index)).or_insert(value);
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.db.block_hash(number)?;
+        self.block_hashes.entry(number).or_insert(hash);
+        Ok(hash)
+    }
+}
@@ -0,0 +1,387 @@
+//! A sparse Merkle Patricia Trie that is revealed from the trie nodes of a witness.
+
+use alloy_rlp::{Decodable, Encodable, Header, EMPTY_STRING_CODE};
+use reth_primitives::{keccak256, Bytes, B256};
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use reth_trie_common::{Nibbles, TrieNode, CHILD_INDEX_RANGE, EMPTY_ROOT_HASH};
+use std::collections::HashMap;
+
+/// Trie nodes by the hash of their RLP encoding.
+pub(crate) type TrieNodes = HashMap<B256, Bytes>;
+
+/// A Merkle Patricia Trie of which only the nodes on the paths of accessed keys are known.
+///
+/// All other subtries are represented by their hash. Nodes are revealed from [`TrieNodes`] when
+/// a key in their subtrie is accessed, which fails if the node is not part of the witness.
+///
+/// All keys of the trie are expected to have the same length, which holds for the hashed keys of
+/// the account and storage tries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SparseTrie {
+    /// The root node.
+    root: SparseNode,
+}
+
+impl SparseTrie {
+    /// Creates a new trie with the given root hash, of which no node is revealed yet.
+    pub(crate) fn new(root: B256) -> Self {
+        let root = if root == EMPTY_ROOT_HASH { SparseNode::Empty } else { SparseNode::Hash(root) };
+        Self { root }
+    }
+
+    /// Returns the value of the given key.
+    pub(crate) fn get(&mut self, key: B256, nodes: &TrieNodes) -> ProviderResult<Option<Vec<u8>>> {
+        self.root.get(Nibbles::unpack(key).as_slice(), nodes)
+    }
+
+    /// Sets the value of the given key, or removes the key if the value is `None`.
+    pub(crate) fn update(
+        &mut self,
+        key: B256,
+        value: Option<Vec<u8>>,
+        nodes: &TrieNodes,
+    ) -> ProviderResult<()> {
+        let path = Nibbles::unpack(key);
+        match value {
+            Some(value) => self.root.insert(path.as_slice(), value, nodes),
+            None => self.root.remove(path.as_slice(), nodes),
+        }
+    }
+
+    /// Computes the root hash of the trie.
+    pub(crate) fn root(&self) -> B256 {
+        self.root.hash()
+    }
+}
+
+/// A node of a [`SparseTrie`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum SparseNode {
+    /// The empty trie.
+    #[default]
+    Empty,
+    /// A node that is not revealed yet.
+    Hash(B256),
+    /// A leaf node with the remaining key and the value.
+    Leaf { key: Nibbles, value: Vec<u8> },
+    /// An extension node with the shared key and the child branch.
+    Extension { key: Nibbles, child: Box<SparseNode> },
+    /// A branch node with a child per nibble.
+    Branch { children: Box<[SparseNode; 16]> },
+}
+
+impl SparseNode {
+    /// Returns a branch node without children.
+    fn empty_children() -> Box<[Self; 16]> {
+        Box::new(std::array::from_fn(|_| Self::Empty))
+    }
+
+    /// Decodes a node from its RLP encoding.
+    fn decode(encoded: &[u8]) -> ProviderResult<Self> {
+        Ok(match TrieNode::decode(&mut &encoded[..])? {
+            TrieNode::Branch(branch) => {
+                let mut children = Self::empty_children();
+                let mut stack_ptr = branch.as_ref().first_child_index();
+                for index in CHILD_INDEX_RANGE {
+                    if branch.state_mask.is_bit_set(index) {
+                        children[index as usize] =
+                            Self::decode_reference(&branch.stack[stack_ptr])?;
+                        stack_ptr += 1;
+                    }
+                }
+                Self::Branch { children }
+            }
+            TrieNode::Extension(extension) => Self::Extension {
+                key: extension.key,
+                child: Box::new(Self::decode_reference(&extension.child)?),
+            },
+            TrieNode::Leaf(leaf) => Self::Leaf { key: leaf.key, value: leaf.value },
+        })
+    }
+
+    /// Decodes a reference to a child node, which is either the hash of the child or the child
+    /// itself if its encoding is shorter than a hash.
+    fn decode_reference(reference: &[u8]) -> ProviderResult<Self> {
+        if reference.len() == B256::len_bytes() + 1 {
+            Ok(Self::Hash(B256::from_slice(&reference[1..])))
+        } else {
+            Self::decode(reference)
+        }
+    }
+
+    /// Replaces a [`SparseNode::Hash`] with the node it refers to.
+    fn reveal(&mut self, nodes: &TrieNodes) -> ProviderResult<()> {
+        if let Self::Hash(hash) = self {
+            let encoded = nodes.get(hash).ok_or_else(|| {
+                ProviderError::TrieWitnessError(format!("missing trie node {hash}"))
+            })?;
+            *self = Self::decode(encoded)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the node with the given key prepended to its path.
+    ///
+    /// Leaves and extensions absorb the key, other nodes are wrapped into an extension. The node
+    /// must be revealed, because a hash may refer to a leaf or extension.
+    fn prefixed(prefix: &[u8], node: Self) -> Self {
+        let join =
+            |key: &Nibbles| Nibbles::from_nibbles_unchecked([prefix, key.as_slice()].concat());
+        match node {
+            Self::Empty => Self::Empty,
+            Self::Leaf { key, value } => Self::Leaf { key: join(&key), value },
+            Self::Extension { key, child } => Self::Extension { key: join(&key), child },
+            node if prefix.is_empty() => node,
+            node => Self::Extension {
+                key: Nibbles::from_nibbles_unchecked(prefix),
+                child: Box::new(node),
+            },
+        }
+    }
+
+    /// Returns the node that results from inserting a leaf into the subtrie of `existing` at a
+    /// path that diverges from `existing_path`.
+    ///
+    /// This creates a branch with both nodes as children, behind an extension of their shared
+    /// path. The existing node is either a leaf or the child of an extension, which is always a
+    /// branch.
+    fn split(existing_path: &[u8], existing: Self, path: &[u8], value: Vec<u8>) -> Self {
+        let common = common_prefix_length(existing_path, path);
+        let mut children = Self::empty_children();
+        children[existing_path[common] as usize] =
+            Self::prefixed(&existing_path[common + 1..], existing);
+        children[path[common] as usize] =
+            Self::Leaf { key: Nibbles::from_nibbles_unchecked(&path[common + 1..]), value };
+        Self::prefixed(&path[..common], Self::Branch { children })
+    }
+
+    fn get(&mut self, path: &[u8], nodes: &TrieNodes) -> ProviderResult<Option<Vec<u8>>> {
+        self.reveal(nodes)?;
+        match self {
+            Self::Empty => Ok(None),
+            Self::Hash(_) => unreachable!("node is revealed"),
+            Self::Leaf { key, value } => Ok((key.as_slice() == path).then(|| value.clone())),
+            Self::Extension { key, child } => match path.strip_prefix(key.as_slice()) {
+                Some(rest) => child.get(rest, nodes),
+                None => Ok(None),
+            },
+            Self::Branch { children } => match path.split_first() {
+                Some((index, rest)) => children[*index as usize].get(rest, nodes),
+                None => Ok(None),
+            },
+        }
+    }
+
+    fn insert(&mut self, path: &[u8], value: Vec<u8>, nodes: &TrieNodes) -> ProviderResult<()> {
+        self.reveal(nodes)?;
+        match self {
+            Self::Empty => {
+                *self = Self::Leaf { key: Nibbles::from_nibbles_unchecked(path), value };
+            }
+            Self::Hash(_) => unreachable!("node is revealed"),
+            Self::Leaf { key, value: existing } => {
+                if key.as_slice() == path {
+                    *existing = value;
+                    return Ok(())
+                }
+                let existing_path = key.as_slice().to_vec();
+                let existing =
+                    Self::Leaf { key: Nibbles::default(), value: std::mem::take(existing) };
+                *self = Self::split(&existing_path, existing, path, value);
+            }
+            Self::Extension { key, child } => {
+                if let Some(rest) = path.strip_prefix(key.as_slice()) {
+                    return child.insert(rest, value, nodes)
+                }
+                let existing_path = key.as_slice().to_vec();
+                let existing = std::mem::take(child.as_mut());
+                *self = Self::split(&existing_path, existing, path, value);
+            }
+            Self::Branch { children } => {
+                let (index, rest) = path.split_first().ok_or_else(|| {
+                    ProviderError::TrieWitnessError("trie key ends at a branch node".to_string())
+                })?;
+                children[*index as usize].insert(rest, value, nodes)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, path: &[u8], nodes: &TrieNodes) -> ProviderResult<()> {
+        self.reveal(nodes)?;
+        match self {
+            Self::Empty => {}
+            Self::Hash(_) => unreachable!("node is revealed"),
+            Self::Leaf { key, .. } => {
+                if key.as_slice() == path {
+                    *self = Self::Empty;
+                }
+            }
+            Self::Extension { key, child } => {
+                let Some(rest) = path.strip_prefix(key.as_slice()) else { return Ok(()) };
+                child.remove(rest, nodes)?;
+                // the child may have collapsed into a leaf or extension
+                let key = key.as_slice().to_vec();
+                let child = std::mem::take(child.as_mut());
+                *self = Self::prefixed(&key, child);
+            }
+            Self::Branch { children } => {
+                let Some((index, rest)) = path.split_first() else { return Ok(()) };
+                children[*index as usize].remove(rest, nodes)?;
+
+                let remaining = children
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, child)| !matches!(child, Self::Empty))
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>();
+                match remaining.as_slice() {
+                    [] => *self = Self::Empty,
+                    // a branch with a single child collapses into its child, which has to be
+                    // revealed to know whether it absorbs the nibble of the branch
+                    [index] => {
+                        let mut child = std::mem::take(&mut children[*index]);
+                        child.reveal(nodes)?;
+                        *self = Self::prefixed(&[*index as u8], child);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the RLP encoding of the node.
+    fn rlp(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        match self {
+            Self::Empty => return vec![EMPTY_STRING_CODE],
+            Self::Hash(hash) => return rlp_hash(*hash),
+            Self::Leaf { key, value } => {
+                key.encode_path_leaf(true).as_slice().encode(&mut payload);
+                value.as_slice().encode(&mut payload);
+            }
+            Self::Extension { key, child } => {
+                key.encode_path_leaf(false).as_slice().encode(&mut payload);
+                payload.extend(child.reference());
+            }
+            Self::Branch { children } => {
+                for child in children.iter() {
+                    payload.extend(child.reference());
+                }
+                // branch nodes of hashed tries never hold a value
+                payload.push(EMPTY_STRING_CODE);
+            }
+        }
+        let mut out = Vec::with_capacity(payload.len() + 3);
+        Header { list: true, payload_length: payload.len() }.encode(&mut out);
+        out.extend(payload);
+        out
+    }
+
+    /// Returns the reference to the node from its parent, which is the node itself if its encoding
+    /// is shorter than a hash, and the hash of the encoding otherwise.
+    fn reference(&self) -> Vec<u8> {
+        let rlp = self.rlp();
+        if matches!(self, Self::Hash(_)) || rlp.len() < B256::len_bytes() {
+            rlp
+        } else {
+            rlp_hash(keccak256(&rlp))
+        }
+    }
+
+    /// Returns the hash of the node.
+    fn hash(&self) -> B256 {
+        match self {
+            Self::Hash(hash) => *hash,
+            node => keccak256(node.rlp()),
+        }
+    }
+}
+
+/// Returns the RLP encoding of a hash.
+fn rlp_hash(hash: B256) -> Vec<u8> {
+    let mut out = Vec::with_capacity(B256::len_bytes() + 1);
+    hash.encode(&mut out);
+    out
+}
+
+/// Returns the length of the shared prefix of two paths.
+fn common_prefix_length(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_trie_common::HashBuilder;
+    use std::collections::BTreeMap;
+
+    fn test_entries(range: std::ops::Range<u64>) -> BTreeMap<B256, Vec<u8>> {
+        range.map(|i| (keccak256(i.to_be_bytes()), alloy_rlp::encode(i + 1))).collect()
+    }
+
+    fn expected_root(entries: &BTreeMap<B256, Vec<u8>>) -> B256 {
+        let mut hash_builder = HashBuilder::default();
+        for (key, value) in entries {
+            hash_builder.add_leaf(Nibbles::unpack(key), value);
+        }
+        hash_builder.root()
+    }
+
+    /// Collects the encodings of all hashed nodes of a fully revealed trie.
+    fn collect_nodes(node: &SparseNode, nodes: &mut TrieNodes) {
+        match node {
+            SparseNode::Extension { child, .. } => collect_nodes(child, nodes),
+            SparseNode::Branch { children } => {
+                children.iter().for_each(|child| collect_nodes(child, nodes));
+            }
+            _ => {}
+        }
+        let rlp = node.rlp();
+        nodes.insert(keccak256(&rlp), rlp.into());
+    }
+
+    #[test]
+    fn root_matches_hash_builder() {
+        let entries = test_entries(0..100);
+        let mut trie = SparseTrie::new(EMPTY_ROOT_HASH);
+        for (key, value) in &entries {
+            trie.update(*key, Some(value.clone()), &TrieNodes::default()).unwrap();
+        }
+        assert_eq!(trie.root(), expected_root(&entries));
+    }
+
+    #[test]
+    fn revealed_updates_match_hash_builder() {
+        let mut entries = test_entries(0..100);
+        let mut full = SparseTrie::new(EMPTY_ROOT_HASH);
+        for (key, value) in &entries {
+            full.update(*key, Some(value.clone()), &TrieNodes::default()).unwrap();
+        }
+        let mut nodes = TrieNodes::default();
+        collect_nodes(&full.root, &mut nodes);
+
+        let mut trie = SparseTrie::new(full.root());
+        let (key, value) = entries.iter().next().unwrap();
+        assert_eq!(trie.get(*key, &nodes).unwrap().as_ref(), Some(value));
+        assert_eq!(trie.get(keccak256([0xff]), &nodes).unwrap(), None);
+
+        let removed = entries.keys().copied().step_by(3).collect::<Vec<_>>();
+        for key in removed {
+            trie.update(key, None, &nodes).unwrap();
+            entries.remove(&key);
+        }
+        for (key, value) in test_entries(100..110) {
+            trie.update(key, Some(value.clone()), &nodes).unwrap();
+            entries.insert(key, value);
+        }
+        assert_eq!(trie.root(), expected_root(&entries));
+    }
+
+    #[test]
+    fn missing_node() {
+        let mut trie = SparseTrie::new(B256::with_last_byte(1));
+        assert!(trie.get(B256::ZERO, &TrieNodes::default()).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! The execution witness of a block.
+
+use crate::recording::RecordingDatabase;
+use alloy_rlp::Decodable;
+use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
+use reth_primitives::{
+    keccak256, Account, Address, BlockWithSenders, Bytes, Header, Receipt, SealedHeader, B256, U256,
+};
+use reth_revm::database::StateProviderDatabase;
+use reth_rpc_types::reth::StatelessWitness;
+use reth_storage_api::StateProvider;
+use reth_storage_errors::provider::ProviderResult;
+use reth_trie::{HashedPostState, HashedStorage};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Everything that is needed to execute a block and compute its state root without a database.
+///
+/// The witness of a block is returned by `debug_statelessWitness`, or can be generated from the
+/// state of its parent with [`ExecutionWitness::generate`]. It is (de)serialized in the format of
+/// [`StatelessWitness`].
+///
+/// The keys of [`ExecutionWitness::state`] and [`ExecutionWitness::codes`] are not trusted, trie
+/// nodes and bytecodes are looked up by the hash of their content.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "StatelessWitness", try_from = "StatelessWitness")]
+pub struct ExecutionWitness {
+    /// The RLP encoded nodes of the account and storage tries that are accessed when executing
+    /// the block and computing its state root, by hash.
+    pub state: HashMap<B256, Bytes>,
+    /// The bytecodes of the accounts that are accessed when executing the block, by code hash.
+    pub codes: HashMap<B256, Bytes>,
+    /// Headers of ancestors of the block.
+    ///
+    /// This must contain the parent of the block, and all ancestors down to the oldest block whose
+    /// hash is read with `BLOCKHASH`.
+    pub headers: Vec<Header>,
+}
+
+impl ExecutionWitness {
+    /// Generates the witness of a block by executing it on top of the state of its parent, while
+    /// recording all state that is accessed.
+    ///
+    /// The ancestors of the parent whose hashes are read with `BLOCKHASH` are looked up with
+    /// `header_by_hash`.
+    ///
+    /// Returns the witness together with the execution output, which is `None` if the block fails
+    /// execution. In that case the witness covers the state that was accessed until the failure.
+    pub fn generate<E, F>(
+        executor_provider: &E,
+        state_provider: &dyn StateProvider,
+        parent: &SealedHeader,
+        mut header_by_hash: F,
+        block: &BlockWithSenders,
+    ) -> ProviderResult<(Self, Option<BlockExecutionOutput<Receipt>>)>
+    where
+        E: BlockExecutorProvider,
+        F: FnMut(B256) -> ProviderResult<Option<Header>>,
+    {
+        let mut db = RecordingDatabase::new(StateProviderDatabase::new(state_provider));
+        let output = executor_provider.executor(&mut db).execute((block, U256::MAX).into()).ok();
+        let RecordingDatabase { accounts, storage, bytecodes, block_hashes, .. } = db;
+
+        let target = witness_target(state_provider, &accounts, &storage, output.as_ref())?;
+        let state = state_provider.witness(HashedPostState::default(), target)?;
+
+        let mut headers = vec![parent.header().clone()];
+        if let Some(oldest) = block_hashes.keys().next() {
+            while let Some(header) = headers.last().filter(|header| header.number > *oldest) {
+                let Some(ancestor) = header_by_hash(header.parent_hash)? else { break };
+                headers.push(ancestor);
+            }
+        }
+
+        Ok((Self { state, codes: bytecodes, headers }, output))
+    }
+
+    /// Returns the headers of the witness that form the chain of ancestors ending at the block
+    /// with the given hash, newest first.
+    ///
+    /// Headers that are not linked to this chain by their hash are ignored.
+    pub fn ancestors(&self, hash: B256) -> Vec<SealedHeader> {
+        let mut headers = self
+            .headers
+            .iter()
+            .map(|header| {
+                let header = header.clone().seal_slow();
+                (header.hash(), header)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut ancestors = Vec::new();
+        let mut next = hash;
+        while let Some(header) = headers.remove(&next) {
+            next = header.parent_hash;
+            ancestors.push(header);
+        }
+        ancestors
+    }
+
+    /// Returns the trie nodes of the witness by the hash of their encoding.
+    pub(crate) fn trie_nodes(&self) -> HashMap<B256, Bytes> {
+        self.state.values().map(|node| (keccak256(node), node.clone())).collect()
+    }
+
+    /// Returns the bytecodes of the witness by the hash of the code.
+    pub(crate) fn bytecodes(&self) -> HashMap<B256, Bytes> {
+        self.codes.values().map(|code| (keccak256(code), code.clone())).collect()
+    }
+}
+
+impl From<ExecutionWitness> for StatelessWitness {
+    fn from(witness: ExecutionWitness) -> Self {
+        let ExecutionWitness { state, codes, headers } = witness;
+        let headers = headers.iter().map(|header| alloy_rlp::encode(header).into()).collect();
+        Self { state, codes, headers }
+    }
+}
+
+impl TryFrom<StatelessWitness> for ExecutionWitness {
+    type Error = alloy_rlp::Error;
+
+    fn try_from(witness: StatelessWitness) -> Result<Self, Self::Error> {
+        let StatelessWitness { state, codes, headers } = witness;
+        let headers = headers
+            .iter()
+            .map(|header| Header::decode(&mut header.as_ref()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { state, codes, headers })
+    }
+}
+
+/// Returns the state the trie witness is generated for: all accessed accounts and storage slots,
+/// with the values after execution if the block could be executed.
+fn witness_target(
+    state_provider: &dyn StateProvider,
+    accounts: &BTreeMap<Address, Option<Account>>,
+    storage: &BTreeMap<Address, BTreeMap<B256, U256>>,
+    output: Option<&BlockExecutionOutput<Receipt>>,
+) -> ProviderResult<HashedPostState> {
+    let mut target = HashedPostState::default();
+    for (address, account) in accounts {
+        target.accounts.insert(keccak256(address), *account);
+    }
+    for (address, slots) in storage {
+        let hashed_address = keccak256(address);
+        // every storage trie is reached through its account leaf
+        if !target.accounts.contains_key(&hashed_address) {
+            target.accounts.insert(hashed_address, state_provider.basic_account(*address)?);
+        }
+        let hashed_storage =
+            target.storages.entry(hashed_address).or_insert_with(|| HashedStorage::new(false));
+        for (slot, value) in slots {
+            hashed_storage.storage.insert(keccak256(slot), *value);
+        }
+    }
+    if let Some(output) = output {
+        target.extend(HashedPostState::from_bundle_state(&output.state.state));
+    }
+    Ok(target)
+}
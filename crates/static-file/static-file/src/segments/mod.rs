@@ -9,6 +9,9 @@ pub use headers::Headers;
 mod receipts;
 pub use receipts::Receipts;
 
+mod senders;
+pub use senders::Senders;
+
 use alloy_primitives::BlockNumber;
 use reth_db_api::database::Database;
 use reth_provider::{providers::StaticFileProvider, DatabaseProviderRO};
@@ -0,0 +1,49 @@
+use crate::segments::Segment;
+use alloy_primitives::BlockNumber;
+use reth_db::tables;
+use reth_db_api::{cursor::DbCursorRO, database::Database, transaction::DbTx};
+use reth_provider::{
+    providers::{StaticFileProvider, StaticFileWriter},
+    BlockReader, DatabaseProviderRO,
+};
+use reth_static_file_types::StaticFileSegment;
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use std::ops::RangeInclusive;
+
+/// Static File segment responsible for [`StaticFileSegment::Senders`] part of data.
+#[derive(Debug, Default)]
+pub struct Senders;
+
+impl<DB: Database> Segment<DB> for Senders {
+    fn segment(&self) -> StaticFileSegment {
+        StaticFileSegment::Senders
+    }
+
+    fn copy_to_static_files(
+        &self,
+        provider: DatabaseProviderRO<DB>,
+        static_file_provider: StaticFileProvider,
+        block_range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let mut static_file_writer =
+            static_file_provider.get_writer(*block_range.start(), StaticFileSegment::Senders)?;
+
+        for block in block_range {
+            let _static_file_block = static_file_writer.increment_block(block)?;
+            debug_assert_eq!(_static_file_block, block);
+
+            let block_body_indices = provider
+                .block_body_indices(block)?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(block))?;
+
+            let mut senders_cursor =
+                provider.tx_ref().cursor_read::<tables::TransactionSenders>()?;
+            let senders_walker = senders_cursor.walk_range(block_body_indices.tx_num_range())?;
+
+            static_file_writer
+                .append_senders(senders_walker.map(|result| result.map_err(ProviderError::from)))?;
+        }
+
+        Ok(())
+    }
+}
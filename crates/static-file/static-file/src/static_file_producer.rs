@@ -66,12 +66,16 @@ pub struct StaticFileTargets {
     headers: Option<RangeInclusive<BlockNumber>>,
     receipts: Option<RangeInclusive<BlockNumber>>,
     transactions: Option<RangeInclusive<BlockNumber>>,
+    senders: Option<RangeInclusive<BlockNumber>>,
 }
 
 impl StaticFileTargets {
     /// Returns `true` if any of the targets are [Some].
     pub const fn any(&self) -> bool {
-        self.headers.is_some() || self.receipts.is_some() || self.transactions.is_some()
+        self.headers.is_some() ||
+            self.receipts.is_some() ||
+            self.transactions.is_some() ||
+            self.senders.is_some()
     }
 
     // Returns `true` if all targets are either [`None`] or has beginning of the range equal to the
@@ -81,6 +85,7 @@ impl StaticFileTargets {
             (self.headers.as_ref(), static_files.headers),
             (self.receipts.as_ref(), static_files.receipts),
             (self.transactions.as_ref(), static_files.transactions),
+            (self.senders.as_ref(), static_files.senders),
         ]
         .iter()
         .all(|(target_block_range, highest_static_fileted_block)| {
@@ -139,6 +144,9 @@ impl<DB: Database> StaticFileProducerInner<DB> {
         if let Some(block_range) = targets.receipts.clone() {
             segments.push((Box::new(segments::Receipts), block_range));
         }
+        if let Some(block_range) = targets.senders.clone() {
+            segments.push((Box::new(segments::Senders), block_range));
+        }
 
         segments.par_iter().try_for_each(|(segment, block_range)| -> ProviderResult<()> {
             debug!(target: "static_file", segment = %segment.segment(), ?block_range, "StaticFileProducer segment");
@@ -177,15 +185,19 @@ impl<DB: Database> StaticFileProducerInner<DB> {
     /// Returns highest block numbers for all static file segments.
     pub fn copy_to_static_files(&self) -> ProviderResult<HighestStaticFiles> {
         let provider = self.provider_factory.provider()?;
-        let stages_checkpoints = [StageId::Headers, StageId::Execution, StageId::Bodies]
-            .into_iter()
-            .map(|stage| provider.get_stage_checkpoint(stage).map(|c| c.map(|c| c.block_number)))
-            .collect::<Result<Vec<_>, _>>()?;
+        let stages_checkpoints =
+            [StageId::Headers, StageId::Execution, StageId::Bodies, StageId::SenderRecovery]
+                .into_iter()
+                .map(|stage| {
+                    provider.get_stage_checkpoint(stage).map(|c| c.map(|c| c.block_number))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
 
         let highest_static_files = HighestStaticFiles {
             headers: stages_checkpoints[0],
             receipts: stages_checkpoints[1],
             transactions: stages_checkpoints[2],
+            senders: stages_checkpoints[3],
         };
         let targets = self.get_static_file_targets(highest_static_files)?;
         self.run(targets)?;
@@ -226,6 +238,17 @@ impl<DB: Database> StaticFileProducerInner<DB> {
                     finalized_block_number,
                 )
             }),
+            // StaticFile senders only if they're not pruned according to the user configuration
+            senders: if self.prune_modes.sender_recovery.is_none() {
+                finalized_block_numbers.senders.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.senders,
+                        finalized_block_number,
+                    )
+                })
+            } else {
+                None
+            },
         };
 
         trace!(
@@ -257,10 +280,11 @@ mod tests {
     };
     use alloy_primitives::{B256, U256};
     use assert_matches::assert_matches;
-    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
-    use reth_db_api::{database::Database, transaction::DbTx};
+    use reth_db::{tables, test_utils::TempDatabase, DatabaseEnv};
+    use reth_db_api::{cursor::DbCursorRO, database::Database, transaction::DbTx};
     use reth_provider::{
         providers::StaticFileWriter, ProviderError, ProviderFactory, StaticFileProviderFactory,
+        TransactionsProvider,
     };
     use reth_prune_types::PruneModes;
     use reth_stages::test_utils::{StorageKind, TestStageDB};
@@ -309,6 +333,17 @@ mod tests {
         }
         db.insert_receipts(receipts).expect("insert receipts");
 
+        let mut senders = Vec::new();
+        for block in &blocks {
+            for transaction in &block.body {
+                senders.push((
+                    senders.len() as u64,
+                    transaction.recover_signer().expect("recover signer"),
+                ));
+            }
+        }
+        db.insert_transaction_senders(senders).expect("insert transaction senders");
+
         let provider_factory = db.factory;
         (provider_factory, db.temp_static_files_dir)
     }
@@ -325,6 +360,7 @@ mod tests {
                 headers: Some(1),
                 receipts: Some(1),
                 transactions: Some(1),
+                senders: Some(1),
             })
             .expect("get static file targets");
         assert_eq!(
@@ -332,13 +368,39 @@ mod tests {
             StaticFileTargets {
                 headers: Some(0..=1),
                 receipts: Some(0..=1),
-                transactions: Some(0..=1)
+                transactions: Some(0..=1),
+                senders: Some(0..=1)
             }
         );
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(1), receipts: Some(1), transactions: Some(1) }
+            HighestStaticFiles {
+                headers: Some(1),
+                receipts: Some(1),
+                transactions: Some(1),
+                senders: Some(1)
+            }
+        );
+
+        // Senders are served from static files once they're moved there
+        let tx = provider_factory.db_ref().tx().expect("init tx");
+        let senders = tx
+            .cursor_read::<tables::TransactionSenders>()
+            .and_then(|mut cursor| cursor.walk(None)?.collect::<Result<Vec<_>, _>>())
+            .expect("read transaction senders");
+        let highest_tx = provider_factory
+            .static_file_provider()
+            .get_highest_static_file_tx(StaticFileSegment::Senders)
+            .expect("senders static file");
+        let (static_file_senders, _) = senders.split_at(highest_tx as usize + 1);
+        assert_eq!(
+            provider_factory.static_file_provider().senders_by_tx_range(0..=highest_tx),
+            Ok(static_file_senders.iter().map(|(_, sender)| *sender).collect())
+        );
+        assert_eq!(
+            provider_factory.static_file_provider().transaction_sender(highest_tx),
+            Ok(Some(static_file_senders.last().unwrap().1))
         );
 
         let targets = static_file_producer
@@ -346,6 +408,7 @@ mod tests {
                 headers: Some(3),
                 receipts: Some(3),
                 transactions: Some(3),
+                senders: Some(3),
             })
             .expect("get static file targets");
         assert_eq!(
@@ -353,13 +416,19 @@ mod tests {
             StaticFileTargets {
                 headers: Some(2..=3),
                 receipts: Some(2..=3),
-                transactions: Some(2..=3)
+                transactions: Some(2..=3),
+                senders: Some(2..=3)
             }
         );
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                senders: Some(3)
+            }
         );
 
         let targets = static_file_producer
@@ -367,6 +436,7 @@ mod tests {
                 headers: Some(4),
                 receipts: Some(4),
                 transactions: Some(4),
+                senders: Some(4),
             })
             .expect("get static file targets");
         assert_eq!(
@@ -374,7 +444,8 @@ mod tests {
             StaticFileTargets {
                 headers: Some(4..=4),
                 receipts: Some(4..=4),
-                transactions: Some(4..=4)
+                transactions: Some(4..=4),
+                senders: Some(4..=4)
             }
         );
         assert_matches!(
@@ -383,7 +454,12 @@ mod tests {
         );
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                senders: Some(3)
+            }
         );
     }
 
@@ -411,6 +487,7 @@ mod tests {
                         headers: Some(1),
                         receipts: Some(1),
                         transactions: Some(1),
+                        senders: Some(1),
                     })
                     .expect("get static file targets");
                 assert_matches!(locked_producer.run(targets.clone()), Ok(_));
@@ -32,6 +32,9 @@ pub struct HighestStaticFiles {
     /// Highest static file block of transactions, inclusive.
     /// If [`None`], no static file is available.
     pub transactions: Option<BlockNumber>,
+    /// Highest static file block of transaction senders, inclusive.
+    /// If [`None`], no static file is available.
+    pub senders: Option<BlockNumber>,
 }
 
 impl HighestStaticFiles {
@@ -41,6 +44,7 @@ impl HighestStaticFiles {
             StaticFileSegment::Headers => self.headers,
             StaticFileSegment::Transactions => self.transactions,
             StaticFileSegment::Receipts => self.receipts,
+            StaticFileSegment::Senders => self.senders,
         }
     }
 
@@ -50,17 +54,24 @@ impl HighestStaticFiles {
             StaticFileSegment::Headers => &mut self.headers,
             StaticFileSegment::Transactions => &mut self.transactions,
             StaticFileSegment::Receipts => &mut self.receipts,
+            StaticFileSegment::Senders => &mut self.senders,
         }
     }
 
     /// Returns the minimum block of all segments.
     pub fn min(&self) -> Option<u64> {
-        [self.headers, self.transactions, self.receipts].iter().filter_map(|&option| option).min()
+        [self.headers, self.transactions, self.receipts, self.senders]
+            .iter()
+            .filter_map(|&option| option)
+            .min()
     }
 
     /// Returns the maximum block of all segments.
     pub fn max(&self) -> Option<u64> {
-        [self.headers, self.transactions, self.receipts].iter().filter_map(|&option| option).max()
+        [self.headers, self.transactions, self.receipts, self.senders]
+            .iter()
+            .filter_map(|&option| option)
+            .max()
     }
 }
 
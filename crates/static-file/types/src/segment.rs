@@ -34,6 +34,9 @@ pub enum StaticFileSegment {
     #[strum(serialize = "receipts")]
     /// Static File segment responsible for the `Receipts` table.
     Receipts,
+    #[strum(serialize = "senders")]
+    /// Static File segment responsible for the `TransactionSenders` table.
+    Senders,
 }
 
 impl StaticFileSegment {
@@ -43,6 +46,7 @@ impl StaticFileSegment {
             Self::Headers => "headers",
             Self::Transactions => "transactions",
             Self::Receipts => "receipts",
+            Self::Senders => "senders",
         }
     }
 
@@ -57,7 +61,7 @@ impl StaticFileSegment {
         };
 
         match self {
-            Self::Headers | Self::Transactions | Self::Receipts => default_config,
+            Self::Headers | Self::Transactions | Self::Receipts | Self::Senders => default_config,
         }
     }
 
@@ -65,7 +69,7 @@ impl StaticFileSegment {
     pub const fn columns(&self) -> usize {
         match self {
             Self::Headers => 3,
-            Self::Transactions | Self::Receipts => 1,
+            Self::Transactions | Self::Receipts | Self::Senders => 1,
         }
     }
 
@@ -138,6 +142,11 @@ impl StaticFileSegment {
     pub const fn is_receipts(&self) -> bool {
         matches!(self, Self::Receipts)
     }
+
+    /// Returns `true` if the segment is `StaticFileSegment::Senders`.
+    pub const fn is_senders(&self) -> bool {
+        matches!(self, Self::Senders)
+    }
 }
 
 /// A segment header that contains information common to all segments. Used for storage.
@@ -240,7 +249,9 @@ impl SegmentHeader {
     pub fn increment_tx(&mut self) {
         match self.segment {
             StaticFileSegment::Headers => (),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::Senders => {
                 if let Some(tx_range) = &mut self.tx_range {
                     tx_range.end += 1;
                 } else {
@@ -262,7 +273,9 @@ impl SegmentHeader {
                     }
                 };
             }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::Senders => {
                 if let Some(range) = &mut self.tx_range {
                     if num > range.end {
                         self.tx_range = None;
@@ -298,7 +311,9 @@ impl SegmentHeader {
     pub fn start(&self) -> Option<u64> {
         match self.segment {
             StaticFileSegment::Headers => self.block_start(),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => self.tx_start(),
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::Senders => self.tx_start(),
         }
     }
 }
@@ -371,6 +386,7 @@ mod tests {
         let test_vectors = [
             (StaticFileSegment::Headers, 2..=30, "static_file_headers_2_30", None),
             (StaticFileSegment::Receipts, 30..=300, "static_file_receipts_30_300", None),
+            (StaticFileSegment::Senders, 0..=499_999, "static_file_senders_0_499999", None),
             (
                 StaticFileSegment::Transactions,
                 1_123_233..=11_223_233,
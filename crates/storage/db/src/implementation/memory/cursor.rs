@@ -0,0 +1,474 @@
+//! Cursors of the in-memory database.
+
+use super::{
+    is_dupsort,
+    tx::{TransactionKind, RW},
+    MemoryDatabaseError, Snapshot, TableData,
+};
+use crate::DatabaseError;
+use parking_lot::RwLock;
+use reth_db_api::{
+    common::{PairResult, ValueOnlyResult},
+    cursor::{
+        DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker, RangeWalker,
+        ReverseWalker, Walker,
+    },
+    table::{Compress, Decode, Decompress, DupSort, Encode, Table, TableRow},
+};
+use reth_storage_errors::db::{DatabaseWriteError, DatabaseWriteOperation};
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{
+        Bound,
+        Bound::{Excluded, Included, Unbounded},
+        RangeBounds,
+    },
+    sync::Arc,
+};
+
+/// Encoded `(key, value)` pair.
+type Entry = (Vec<u8>, Vec<u8>);
+
+/// Position of a cursor.
+///
+/// Positions are tracked by entry rather than by index, so the cursor stays valid when the table
+/// is modified through other cursors of the same transaction.
+#[derive(Debug, Clone, Default)]
+enum Position {
+    /// The cursor has not been positioned yet.
+    #[default]
+    Unset,
+    /// The cursor points to the entry.
+    At(Entry),
+    /// The entry the cursor pointed to was deleted. Moving forward continues with the entry that
+    /// followed it.
+    Deleted(Entry),
+    /// The cursor was positioned past the last entry of the table.
+    Eof,
+}
+
+/// Cursor over a table of the in-memory database.
+///
+/// The cursor mimics the positioning rules of MDBX cursors: failed forward seeks leave the cursor
+/// past the end of the table, moving beyond the first or last entry keeps the current position,
+/// and deleting the current entry makes the next forward step return its successor.
+pub struct Cursor<K: TransactionKind, T: Table> {
+    /// State of the transaction the cursor belongs to.
+    state: Arc<RwLock<Snapshot>>,
+    /// Current position of the cursor.
+    position: Position,
+    _kind: PhantomData<(K, T)>,
+}
+
+impl<K: TransactionKind, T: Table> fmt::Debug for Cursor<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("table", &T::NAME)
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K: TransactionKind, T: Table> Cursor<K, T> {
+    pub(crate) const fn new(state: Arc<RwLock<Snapshot>>) -> Self {
+        Self { state, position: Position::Unset, _kind: PhantomData }
+    }
+
+    /// Executes the closure with the data of the table. Missing tables are treated as empty.
+    fn with_table<R>(&self, f: impl FnOnce(&TableData) -> R) -> R {
+        let state = self.state.read();
+        match state.get(T::NAME) {
+            Some(table) => f(table),
+            None => f(&TableData::new()),
+        }
+    }
+
+    /// Positions the cursor at the entry and decodes it. If there is no entry, the position is set
+    /// to `otherwise`.
+    fn position_at(&mut self, entry: Option<Entry>, otherwise: Option<Position>) -> PairResult<T> {
+        match entry {
+            Some(entry) => {
+                let row = decode::<T>(&entry);
+                self.position = Position::At(entry);
+                row.map(Some)
+            }
+            None => {
+                if let Some(position) = otherwise {
+                    self.position = position;
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Returns the entry the cursor points to, if it still exists.
+    fn current_entry(&self) -> Option<Entry> {
+        match &self.position {
+            Position::At((key, value)) => self.with_table(|table| {
+                table
+                    .get(key)
+                    .filter(|values| values.contains(value))
+                    .map(|_| (key.clone(), value.clone()))
+            }),
+            Position::Deleted((key, value)) => self.with_table(|table| after(table, key, value)),
+            Position::Unset | Position::Eof => None,
+        }
+    }
+
+    /// Positions the cursor at the first entry whose key is greater than or equal to `key`.
+    fn set_range(&mut self, key: &[u8]) -> PairResult<T> {
+        let entry = self.with_table(|table| first_of(table, (Included(key), Unbounded)));
+        self.position_at(entry, Some(Position::Eof))
+    }
+
+    /// Positions the cursor at the first value of `key` that is greater than or equal to
+    /// `subkey`.
+    fn get_both_range(&mut self, key: &[u8], subkey: &[u8]) -> ValueOnlyResult<T> {
+        let entry = self.with_table(|table| {
+            table.get(key).and_then(|values| {
+                values
+                    .range::<[u8], _>((Included(subkey), Unbounded))
+                    .next()
+                    .map(|value| (key.to_vec(), value.clone()))
+            })
+        });
+        Ok(self.position_at(entry, Some(Position::Unset))?.map(|(_, value)| value))
+    }
+
+    /// Modifies the table data under the write lock of the transaction.
+    fn with_table_mut<R>(&self, f: impl FnOnce(&mut TableData) -> R) -> R {
+        let mut state = self.state.write();
+        f(Arc::make_mut(state.entry(T::NAME).or_default()))
+    }
+
+    /// Writes the entry into the table, replacing the existing value of non-`DUPSORT` tables.
+    fn put_entry(&mut self, key: Vec<u8>, value: Vec<u8>) {
+        self.with_table_mut(|table| {
+            let values = table.entry(key.clone()).or_default();
+            if !is_dupsort::<T>() {
+                values.clear();
+            }
+            values.insert(value.clone());
+        });
+        self.position = Position::At((key, value));
+    }
+
+    /// Positions the cursor at the last entry of the table and returns a write error.
+    fn write_error(
+        &mut self,
+        error: MemoryDatabaseError,
+        operation: DatabaseWriteOperation,
+        key: Vec<u8>,
+    ) -> Result<(), DatabaseError> {
+        let _ = self.last();
+        Err(DatabaseWriteError { info: error.into(), operation, table_name: T::NAME, key }.into())
+    }
+}
+
+/// Decodes an encoded `(key, value)` pair.
+fn decode<T: Table>((key, value): &Entry) -> Result<TableRow<T>, DatabaseError> {
+    Ok((T::Key::decode(key)?, T::Value::decompress(value)?))
+}
+
+/// Returns the first entry of the first key within the bounds.
+fn first_of(table: &TableData, bounds: (Bound<&[u8]>, Bound<&[u8]>)) -> Option<Entry> {
+    table
+        .range::<[u8], _>(bounds)
+        .find_map(|(key, values)| values.first().map(|value| (key.clone(), value.clone())))
+}
+
+/// Returns the last entry of the last key within the bounds.
+fn last_of(table: &TableData, bounds: (Bound<&[u8]>, Bound<&[u8]>)) -> Option<Entry> {
+    table
+        .range::<[u8], _>(bounds)
+        .rev()
+        .find_map(|(key, values)| values.last().map(|value| (key.clone(), value.clone())))
+}
+
+/// Returns the next value of the same key.
+fn next_dup(table: &TableData, key: &[u8], value: &[u8]) -> Option<Entry> {
+    table.get(key).and_then(|values| {
+        values
+            .range::<[u8], _>((Excluded(value), Unbounded))
+            .next()
+            .map(|value| (key.to_vec(), value.clone()))
+    })
+}
+
+/// Returns the entry that follows the `(key, value)` pair.
+fn after(table: &TableData, key: &[u8], value: &[u8]) -> Option<Entry> {
+    next_dup(table, key, value).or_else(|| first_of(table, (Excluded(key), Unbounded)))
+}
+
+/// Returns the entry that precedes the `(key, value)` pair.
+fn before(table: &TableData, key: &[u8], value: &[u8]) -> Option<Entry> {
+    table
+        .get(key)
+        .and_then(|values| {
+            values
+                .range::<[u8], _>((Unbounded, Excluded(value)))
+                .next_back()
+                .map(|value| (key.to_vec(), value.clone()))
+        })
+        .or_else(|| last_of(table, (Unbounded, Excluded(key))))
+}
+
+impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
+    fn first(&mut self) -> PairResult<T> {
+        let entry = self.with_table(|table| first_of(table, (Unbounded, Unbounded)));
+        self.position_at(entry, None)
+    }
+
+    fn seek_exact(&mut self, key: T::Key) -> PairResult<T> {
+        let key = key.encode();
+        let row = self.set_range(key.as_ref())?;
+        Ok(row.filter(|_| {
+            matches!(&self.position, Position::At((found, _)) if found.as_slice() == key.as_ref())
+        }))
+    }
+
+    fn seek(&mut self, key: T::Key) -> PairResult<T> {
+        self.set_range(key.encode().as_ref())
+    }
+
+    fn next(&mut self) -> PairResult<T> {
+        let entry = match &self.position {
+            Position::Unset => self.with_table(|table| first_of(table, (Unbounded, Unbounded))),
+            Position::At((key, value)) | Position::Deleted((key, value)) => {
+                self.with_table(|table| after(table, key, value))
+            }
+            Position::Eof => None,
+        };
+        let otherwise = matches!(self.position, Position::Deleted(_)).then_some(Position::Eof);
+        self.position_at(entry, otherwise)
+    }
+
+    fn prev(&mut self) -> PairResult<T> {
+        let entry = match &self.position {
+            Position::Unset | Position::Eof => {
+                self.with_table(|table| last_of(table, (Unbounded, Unbounded)))
+            }
+            Position::At((key, value)) | Position::Deleted((key, value)) => {
+                self.with_table(|table| before(table, key, value))
+            }
+        };
+        self.position_at(entry, None)
+    }
+
+    fn last(&mut self) -> PairResult<T> {
+        let entry = self.with_table(|table| last_of(table, (Unbounded, Unbounded)));
+        self.position_at(entry, None)
+    }
+
+    fn current(&mut self) -> PairResult<T> {
+        self.current_entry().as_ref().map(decode::<T>).transpose()
+    }
+
+    fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
+        let start = match start_key {
+            Some(start_key) => self.seek(start_key),
+            None => self.first(),
+        }
+        .transpose();
+
+        Ok(Walker::new(self, start))
+    }
+
+    fn walk_range(
+        &mut self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<RangeWalker<'_, T, Self>, DatabaseError> {
+        let start = match range.start_bound().cloned() {
+            Bound::Included(key) => self.seek(key),
+            Bound::Excluded(_key) => {
+                unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
+            }
+            Bound::Unbounded => self.first(),
+        }
+        .transpose();
+
+        Ok(RangeWalker::new(self, start, range.end_bound().cloned()))
+    }
+
+    fn walk_back(
+        &mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let start = match start_key {
+            Some(start_key) => self.seek(start_key),
+            None => self.last(),
+        }
+        .transpose();
+
+        Ok(ReverseWalker::new(self, start))
+    }
+}
+
+impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
+    fn next_dup(&mut self) -> PairResult<T> {
+        let entry = match &self.position {
+            Position::Unset => self.with_table(|table| first_of(table, (Unbounded, Unbounded))),
+            Position::At((key, value)) | Position::Deleted((key, value)) => {
+                self.with_table(|table| next_dup(table, key, value))
+            }
+            Position::Eof => None,
+        };
+        self.position_at(entry, None)
+    }
+
+    fn next_no_dup(&mut self) -> PairResult<T> {
+        let entry = match &self.position {
+            Position::Unset => self.with_table(|table| first_of(table, (Unbounded, Unbounded))),
+            Position::At((key, _)) => {
+                self.with_table(|table| first_of(table, (Excluded(key), Unbounded)))
+            }
+            Position::Deleted((key, value)) => self.with_table(|table| after(table, key, value)),
+            Position::Eof => None,
+        };
+        self.position_at(entry, None)
+    }
+
+    fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
+        Ok(self.next_dup()?.map(|(_, value)| value))
+    }
+
+    fn seek_by_key_subkey(&mut self, key: T::Key, subkey: T::SubKey) -> ValueOnlyResult<T> {
+        self.get_both_range(key.encode().as_ref(), subkey.encode().as_ref())
+    }
+
+    /// Depending on its arguments, returns an iterator starting at:
+    /// - Some(key), Some(subkey): a `key` item whose data is >= than `subkey`
+    /// - Some(key), None: first item of a specified `key`
+    /// - None, Some(subkey): like first case, but in the first key
+    /// - None, None: first item in the table of a DUPSORT table.
+    fn walk_dup(
+        &mut self,
+        key: Option<T::Key>,
+        subkey: Option<T::SubKey>,
+    ) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
+        let start = match (key, subkey) {
+            (Some(key), Some(subkey)) => self
+                .get_both_range(key.clone().encode().as_ref(), subkey.encode().as_ref())?
+                .map(|value| Ok((key, value))),
+            (Some(key), None) => self.seek_exact(key).transpose(),
+            (None, Some(subkey)) => {
+                if let Some((key, _)) = self.first()? {
+                    self.get_both_range(key.clone().encode().as_ref(), subkey.encode().as_ref())?
+                        .map(|value| Ok((key, value)))
+                } else {
+                    Some(Err(DatabaseError::Read(MemoryDatabaseError::NotFound.into())))
+                }
+            }
+            (None, None) => self.first().transpose(),
+        };
+
+        Ok(DupWalker::<'_, T, Self> { cursor: self, start })
+    }
+}
+
+impl<T: Table> DbCursorRW<T> for Cursor<RW, T> {
+    /// For a `DUPSORT` table, `upsert` adds the value to the duplicates of the key, even if a
+    /// value with the same subkey already exists. This matches the MDBX implementation.
+    fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.put_entry(key.encode().into(), value.compress().into());
+        Ok(())
+    }
+
+    fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key: Vec<u8> = key.encode().into();
+        let existing = self.with_table(|table| {
+            table
+                .get(&key)
+                .and_then(|values| values.first())
+                .map(|value| (key.clone(), value.clone()))
+        });
+        if let Some(existing) = existing {
+            self.position = Position::At(existing);
+            return Err(DatabaseWriteError {
+                info: MemoryDatabaseError::KeyExist.into(),
+                operation: DatabaseWriteOperation::CursorInsert,
+                table_name: T::NAME,
+                key,
+            }
+            .into())
+        }
+
+        self.put_entry(key, value.compress().into());
+        Ok(())
+    }
+
+    /// Appends the data to the end of the table. Consequently, the append operation
+    /// will fail if the inserted key is less than the last table key
+    fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key: Vec<u8> = key.encode().into();
+        let out_of_order = self.with_table(|table| {
+            table.last_key_value().is_some_and(|(last, _)| {
+                if is_dupsort::<T>() {
+                    key < *last
+                } else {
+                    key <= *last
+                }
+            })
+        });
+        if out_of_order {
+            return self.write_error(
+                MemoryDatabaseError::KeyMismatch,
+                DatabaseWriteOperation::CursorAppend,
+                key,
+            )
+        }
+
+        self.put_entry(key, value.compress().into());
+        Ok(())
+    }
+
+    fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        if let Position::At((key, value)) = &self.position {
+            let (key, value) = (key.clone(), value.clone());
+            self.with_table_mut(|table| {
+                if let Some(values) = table.get_mut(&key) {
+                    values.remove(&value);
+                    if values.is_empty() {
+                        table.remove(&key);
+                    }
+                }
+            });
+            self.position = Position::Deleted((key, value));
+        }
+        Ok(())
+    }
+}
+
+impl<T: DupSort> DbDupCursorRW<T> for Cursor<RW, T> {
+    fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        if let Position::At((key, _)) = &self.position {
+            let key = key.clone();
+            let last = self
+                .with_table_mut(|table| table.remove(&key))
+                .and_then(|values| values.last().cloned());
+            if let Some(last) = last {
+                self.position = Position::Deleted((key, last));
+            }
+        }
+        Ok(())
+    }
+
+    fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key: Vec<u8> = key.encode().into();
+        let value: Vec<u8> = value.compress().into();
+        let out_of_order = self.with_table(|table| {
+            table.get(&key).and_then(|values| values.last()).is_some_and(|last| value <= *last)
+        });
+        if out_of_order {
+            return self.write_error(
+                MemoryDatabaseError::KeyMismatch,
+                DatabaseWriteOperation::CursorAppendDup,
+                key,
+            )
+        }
+
+        self.put_entry(key, value);
+        Ok(())
+    }
+}
@@ -0,0 +1,833 @@
+//! In-memory implementation of the database abstraction.
+//!
+//! Mirrors the observable behavior of the MDBX backend (including `DUPSORT` semantics) while
+//! keeping all data in process memory, which makes it well suited for tests that would otherwise
+//! need to create a temporary MDBX environment on disk.
+
+use crate::{DatabaseError, Tables};
+use parking_lot::{Condvar, Mutex, RwLock};
+use reth_db_api::{
+    database::Database,
+    database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics},
+    table::Table,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    sync::Arc,
+};
+use tx::{Tx, RO, RW};
+
+pub mod cursor;
+pub mod tx;
+
+/// Contents of a single table.
+///
+/// Every key maps to the sorted set of its encoded values. Tables that are not `DUPSORT` always
+/// hold exactly one value per key.
+pub(crate) type TableData = BTreeMap<Vec<u8>, BTreeSet<Vec<u8>>>;
+
+/// Contents of all tables, keyed by table name.
+///
+/// Tables are reference counted, so taking a snapshot only clones the table handles and a table is
+/// only copied once a write transaction modifies it.
+pub(crate) type Snapshot = HashMap<&'static str, Arc<TableData>>;
+
+/// Returns `true` if the table is known to be a `DUPSORT` table.
+pub(crate) fn is_dupsort<T: Table>() -> bool {
+    T::NAME.parse::<Tables>().is_ok_and(|table| table.is_dupsort())
+}
+
+/// Errors specific to the in-memory database.
+///
+/// The error codes match the ones returned by MDBX for the same conditions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum MemoryDatabaseError {
+    /// Key/data pair already exists.
+    #[error("key/data pair already exists")]
+    KeyExist,
+    /// No matching key/data pair found.
+    #[error("no matching key/data pair found")]
+    NotFound,
+    /// The given key value is mismatched to the current cursor position.
+    #[error("the given key value is mismatched to the current cursor position")]
+    KeyMismatch,
+}
+
+impl From<MemoryDatabaseError> for i32 {
+    fn from(value: MemoryDatabaseError) -> Self {
+        match value {
+            MemoryDatabaseError::KeyExist => -30799,
+            MemoryDatabaseError::NotFound => -30798,
+            MemoryDatabaseError::KeyMismatch => -30418,
+        }
+    }
+}
+
+/// Database that keeps all tables in memory.
+///
+/// Read-only transactions operate on a snapshot of the data taken when they were opened. Only one
+/// read-write transaction can be open at a time, opening another one blocks until the previous one
+/// is committed or dropped. Changes of a read-write transaction become visible to new transactions
+/// once it is committed.
+///
+/// Cloning the database returns a new handle to the same underlying data.
+#[derive(Clone, Default)]
+pub struct MemoryDatabase {
+    inner: Arc<MemoryDatabaseInner>,
+}
+
+impl MemoryDatabase {
+    /// Creates a new empty in-memory database.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for MemoryDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryDatabase")
+            .field("tables", &self.inner.committed.read().len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MemoryDatabaseInner {
+    /// Last committed state of the database.
+    committed: RwLock<Snapshot>,
+    /// Whether a read-write transaction is currently open.
+    writer_active: Mutex<bool>,
+    /// Notified when the read-write transaction is closed.
+    writer_released: Condvar,
+}
+
+impl MemoryDatabaseInner {
+    /// Returns a snapshot of the committed state.
+    pub(crate) fn snapshot(&self) -> Snapshot {
+        self.committed.read().clone()
+    }
+
+    /// Replaces the committed state.
+    pub(crate) fn commit(&self, snapshot: Snapshot) {
+        *self.committed.write() = snapshot;
+    }
+
+    /// Waits until no other read-write transaction is open and marks one as active.
+    fn acquire_writer(&self) {
+        let mut active = self.writer_active.lock();
+        while *active {
+            self.writer_released.wait(&mut active);
+        }
+        *active = true;
+    }
+
+    /// Marks the read-write transaction as closed.
+    pub(crate) fn release_writer(&self) {
+        *self.writer_active.lock() = false;
+        self.writer_released.notify_one();
+    }
+}
+
+impl Database for MemoryDatabase {
+    type TX = Tx<RO>;
+    type TXMut = Tx<RW>;
+
+    fn tx(&self) -> Result<Self::TX, DatabaseError> {
+        Ok(Tx::new_ro(self.inner.snapshot()))
+    }
+
+    fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
+        self.inner.acquire_writer();
+        Ok(Tx::new_rw(self.inner.clone()))
+    }
+}
+
+impl DatabaseMetrics for MemoryDatabase {}
+
+impl DatabaseMetadata for MemoryDatabase {
+    fn metadata(&self) -> DatabaseMetadataValue {
+        DatabaseMetadataValue::new(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tables::{
+            AccountChangeSets, AccountsHistory, CanonicalHeaders, Headers, PlainAccountState,
+            PlainStorageState,
+        },
+        test_utils::create_test_rw_db,
+    };
+    use assert_matches::assert_matches;
+    use reth_db_api::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, ReverseWalker, Walker},
+        models::{AccountBeforeTx, ShardedKey},
+        table::{Encode, Table},
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{Account, Address, Header, StorageEntry, B256, U256};
+    use reth_primitives_traits::IntegerList;
+    use reth_storage_errors::db::{DatabaseWriteError, DatabaseWriteOperation};
+    use std::{thread, time::Duration};
+
+    const ERROR_PUT: &str = "Not able to insert value into table.";
+    const ERROR_APPEND: &str = "Not able to append the value to the table.";
+    const ERROR_UPSERT: &str = "Not able to upsert the value to the table.";
+    const ERROR_GET: &str = "Not able to get value from table.";
+    const ERROR_DEL: &str = "Not able to delete from table.";
+    const ERROR_COMMIT: &str = "Not able to commit transaction.";
+    const ERROR_INIT_TX: &str = "Failed to create a transaction.";
+
+    fn write_error(
+        error: MemoryDatabaseError,
+        operation: DatabaseWriteOperation,
+        table_name: &'static str,
+        key: Vec<u8>,
+    ) -> DatabaseError {
+        DatabaseWriteError { info: error.into(), operation, table_name, key }.into()
+    }
+
+    /// Collects all entries of the table using a fresh read-only transaction.
+    fn collect<DB: Database, T: Table>(db: &DB) -> Vec<(T::Key, T::Value)> {
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_read::<T>().unwrap();
+        cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>().unwrap()
+    }
+
+    #[test]
+    fn db_manual_put_get() {
+        let db = MemoryDatabase::new();
+
+        let value = Header::default();
+        let key = 1u64;
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(key, value.clone()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.get::<Headers>(key).expect(ERROR_GET), Some(value));
+        assert_eq!(tx.entries::<Headers>(), Ok(1));
+    }
+
+    #[test]
+    fn db_transaction_isolation() {
+        let db = MemoryDatabase::new();
+
+        let ro = db.tx().expect(ERROR_INIT_TX);
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<CanonicalHeaders>(1, B256::ZERO).expect(ERROR_PUT);
+
+        // Uncommitted changes are visible within the transaction only.
+        assert_eq!(tx.get::<CanonicalHeaders>(1), Ok(Some(B256::ZERO)));
+        assert_eq!(db.tx().unwrap().get::<CanonicalHeaders>(1), Ok(None));
+        tx.commit().expect(ERROR_COMMIT);
+
+        // Existing read transaction keeps its snapshot.
+        assert_eq!(ro.get::<CanonicalHeaders>(1), Ok(None));
+        assert_eq!(db.tx().unwrap().get::<CanonicalHeaders>(1), Ok(Some(B256::ZERO)));
+
+        // Aborted changes are discarded.
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<CanonicalHeaders>(2, B256::ZERO).expect(ERROR_PUT);
+        tx.abort();
+        assert_eq!(db.tx().unwrap().get::<CanonicalHeaders>(2), Ok(None));
+    }
+
+    #[test]
+    fn db_single_writer() {
+        let db = MemoryDatabase::new();
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+
+        let handle = thread::spawn(move || {
+            let tx = db.tx_mut().expect(ERROR_INIT_TX);
+            tx.get::<CanonicalHeaders>(1).unwrap()
+        });
+
+        thread::sleep(Duration::from_millis(50));
+        tx.put::<CanonicalHeaders>(1, B256::ZERO).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        // The second writer only starts once the first one has committed.
+        assert_eq!(handle.join().unwrap(), Some(B256::ZERO));
+    }
+
+    #[test]
+    fn db_dup_cursor_delete_first() {
+        let db = MemoryDatabase::new();
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+
+        let mut dup_cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+
+        let entry_0 = StorageEntry { key: B256::with_last_byte(1), value: U256::from(0) };
+        let entry_1 = StorageEntry { key: B256::with_last_byte(1), value: U256::from(1) };
+
+        dup_cursor.upsert(Address::with_last_byte(1), entry_0).expect(ERROR_UPSERT);
+        dup_cursor.upsert(Address::with_last_byte(1), entry_1).expect(ERROR_UPSERT);
+
+        assert_eq!(
+            dup_cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>(),
+            Ok(vec![(Address::with_last_byte(1), entry_0), (Address::with_last_byte(1), entry_1)])
+        );
+
+        let mut walker = dup_cursor.walk(None).unwrap();
+        walker.delete_current().expect(ERROR_DEL);
+
+        assert_eq!(walker.next(), Some(Ok((Address::with_last_byte(1), entry_1))));
+
+        // Other cursors of the transaction observe the deletion.
+        assert_eq!(
+            tx.cursor_dup_read::<PlainStorageState>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>(),
+            Ok(vec![(Address::with_last_byte(1), entry_1)])
+        );
+
+        assert_eq!(walker.next(), None);
+    }
+
+    #[test]
+    fn db_cursor_walk_range() {
+        let db = MemoryDatabase::new();
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        (0..4).try_for_each(|key| tx.put::<CanonicalHeaders>(key, B256::ZERO)).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+
+        let keys = |walker: reth_db_api::cursor::RangeWalker<'_, CanonicalHeaders, _>| {
+            walker.map(|res| res.unwrap().0).collect::<Vec<_>>()
+        };
+        assert_eq!(keys(cursor.walk_range(1..3).unwrap()), vec![1, 2]);
+        assert_eq!(keys(cursor.walk_range(1..=2).unwrap()), vec![1, 2]);
+        assert_eq!(keys(cursor.walk_range(1..).unwrap()), vec![1, 2, 3]);
+        assert_eq!(keys(cursor.walk_range(2..4).unwrap()), vec![2, 3]);
+        assert_eq!(keys(cursor.walk_range(..3).unwrap()), vec![0, 1, 2]);
+        assert_eq!(keys(cursor.walk_range(..).unwrap()), vec![0, 1, 2, 3]);
+        #[allow(clippy::reversed_empty_ranges)]
+        {
+            assert_eq!(keys(cursor.walk_range(3..1).unwrap()), Vec::<u64>::new());
+            assert_eq!(keys(cursor.walk_range(15..=2).unwrap()), Vec::<u64>::new());
+        }
+        assert_eq!(keys(cursor.walk_range(1..1).unwrap()), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn db_cursor_walk_range_on_dup_table() {
+        let db = MemoryDatabase::new();
+        let addresses = [Address::ZERO, Address::with_last_byte(1), Address::with_last_byte(2)];
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        for block in 0..2 {
+            for address in addresses {
+                tx.put::<AccountChangeSets>(block, AccountBeforeTx { address, info: None })
+                    .expect(ERROR_PUT);
+            }
+        }
+        tx.put::<AccountChangeSets>(2, AccountBeforeTx { address: addresses[0], info: None })
+            .expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_read::<AccountChangeSets>().unwrap();
+
+        assert_eq!(cursor.walk_range(..).unwrap().count(), 7);
+        assert_eq!(
+            cursor.walk_range(0..=1).unwrap().collect::<Result<Vec<_>, _>>().unwrap(),
+            (0..2)
+                .flat_map(|block| addresses
+                    .map(|address| (block, AccountBeforeTx { address, info: None })))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn db_walkers() {
+        let db = MemoryDatabase::new();
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        [0, 1, 3]
+            .into_iter()
+            .try_for_each(|key| tx.put::<CanonicalHeaders>(key, B256::ZERO))
+            .expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+
+        let mut walker = Walker::new(&mut cursor, None);
+        assert_eq!(walker.next(), Some(Ok((0, B256::ZERO))));
+        assert_eq!(walker.next(), Some(Ok((1, B256::ZERO))));
+        assert_eq!(walker.next(), Some(Ok((3, B256::ZERO))));
+        assert_eq!(walker.next(), None);
+
+        let reverse_walker = walker.rev();
+        assert_eq!(reverse_walker.map(|res| res.unwrap().0).collect::<Vec<_>>(), vec![3, 1, 0]);
+
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+        let mut reverse_walker = ReverseWalker::new(&mut cursor, None);
+        assert_eq!(reverse_walker.next(), Some(Ok((3, B256::ZERO))));
+        assert_eq!(reverse_walker.next(), Some(Ok((1, B256::ZERO))));
+        assert_eq!(reverse_walker.next(), Some(Ok((0, B256::ZERO))));
+        assert_eq!(reverse_walker.next(), None);
+
+        let walker = reverse_walker.forward();
+        assert_eq!(walker.map(|res| res.unwrap().0).collect::<Vec<_>>(), vec![0, 1, 3]);
+
+        let keys =
+            |walker: ReverseWalker<'_, _, _>| walker.map(|res| res.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(keys(cursor.walk_back(Some(1)).unwrap()), vec![1, 0]);
+        assert_eq!(keys(cursor.walk_back(Some(2)).unwrap()), vec![3, 1, 0]);
+        assert_eq!(keys(cursor.walk_back(Some(4)).unwrap()), vec![3, 1, 0]);
+        assert_eq!(keys(cursor.walk_back(None).unwrap()), vec![3, 1, 0]);
+    }
+
+    #[test]
+    fn db_cursor_seek_exact_or_previous_key() {
+        let db = MemoryDatabase::new();
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        [0, 1, 3]
+            .into_iter()
+            .try_for_each(|key| tx.put::<CanonicalHeaders>(key, B256::ZERO))
+            .expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let missing_key = 2;
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+        assert_eq!(cursor.current(), Ok(None));
+
+        assert_eq!(cursor.seek_exact(missing_key), Ok(None));
+        assert_eq!(cursor.current(), Ok(Some((missing_key + 1, B256::ZERO))));
+        assert_eq!(cursor.prev(), Ok(Some((missing_key - 1, B256::ZERO))));
+        assert_eq!(cursor.prev(), Ok(Some((missing_key - 2, B256::ZERO))));
+    }
+
+    #[test]
+    fn db_cursor_insert() {
+        let db = MemoryDatabase::new();
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        [0, 1, 3, 4, 5]
+            .into_iter()
+            .try_for_each(|key| tx.put::<CanonicalHeaders>(key, B256::ZERO))
+            .expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let key_to_insert = 2;
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+
+        assert_eq!(cursor.insert(key_to_insert, B256::ZERO), Ok(()));
+        assert_eq!(cursor.current(), Ok(Some((key_to_insert, B256::ZERO))));
+
+        assert_eq!(
+            cursor.insert(key_to_insert, B256::ZERO),
+            Err(write_error(
+                MemoryDatabaseError::KeyExist,
+                DatabaseWriteOperation::CursorInsert,
+                CanonicalHeaders::NAME,
+                key_to_insert.encode().into(),
+            ))
+        );
+        assert_eq!(cursor.current(), Ok(Some((key_to_insert, B256::ZERO))));
+        tx.commit().expect(ERROR_COMMIT);
+
+        let keys = collect::<_, CanonicalHeaders>(&db).into_iter().map(|(k, _)| k);
+        assert_eq!(keys.collect::<Vec<_>>(), vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn db_cursor_insert_dup() {
+        let db = MemoryDatabase::new();
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+
+        let mut dup_cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        let key = Address::random();
+
+        let entry1 = StorageEntry { key: B256::random(), value: U256::ZERO };
+        assert!(dup_cursor.insert(key, entry1).is_ok());
+
+        let entry2 = StorageEntry { key: B256::random(), value: U256::ZERO };
+        assert!(dup_cursor.insert(key, entry2).is_err());
+    }
+
+    #[test]
+    fn db_cursor_delete_current_non_existent() {
+        let db = MemoryDatabase::new();
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+
+        let key1 = Address::with_last_byte(1);
+        let key2 = Address::with_last_byte(2);
+        let key3 = Address::with_last_byte(3);
+        let mut cursor = tx.cursor_write::<PlainAccountState>().unwrap();
+
+        assert!(cursor.insert(key1, Account::default()).is_ok());
+        assert!(cursor.insert(key2, Account::default()).is_ok());
+        assert!(cursor.insert(key3, Account::default()).is_ok());
+
+        cursor.seek_exact(key2).unwrap();
+        assert_eq!(cursor.delete_current(), Ok(()));
+        assert_eq!(cursor.seek_exact(key2), Ok(None));
+
+        // The failed seek positions the cursor at the next key, which is deleted instead.
+        assert_eq!(cursor.seek_exact(key2), Ok(None));
+        assert_eq!(cursor.delete_current(), Ok(()));
+        assert_eq!(cursor.seek_exact(key1), Ok(Some((key1, Account::default()))));
+        assert_eq!(cursor.seek_exact(key3), Ok(None));
+    }
+
+    #[test]
+    fn db_cursor_insert_wherever_cursor_is() {
+        let db = MemoryDatabase::new();
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        [0, 1, 3, 5, 7, 9]
+            .into_iter()
+            .try_for_each(|key| tx.put::<CanonicalHeaders>(key, B256::ZERO))
+            .expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+
+        cursor.last().unwrap();
+        assert_eq!(cursor.current(), Ok(Some((9, B256::ZERO))));
+
+        for pos in (2..=8).step_by(2) {
+            assert_eq!(cursor.insert(pos, B256::ZERO), Ok(()));
+            assert_eq!(cursor.current(), Ok(Some((pos, B256::ZERO))));
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let keys = collect::<_, CanonicalHeaders>(&db).into_iter().map(|(k, _)| k);
+        assert_eq!(keys.collect::<Vec<_>>(), (0..=9).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn db_cursor_append() {
+        let db = MemoryDatabase::new();
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        [0, 1, 3, 4, 5]
+            .into_iter()
+            .try_for_each(|key| tx.put::<CanonicalHeaders>(key, B256::ZERO))
+            .expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        assert_eq!(
+            cursor.append(2, B256::ZERO),
+            Err(write_error(
+                MemoryDatabaseError::KeyMismatch,
+                DatabaseWriteOperation::CursorAppend,
+                CanonicalHeaders::NAME,
+                2u64.encode().into(),
+            ))
+        );
+        assert_eq!(cursor.current(), Ok(Some((5, B256::ZERO))));
+        assert_eq!(cursor.append(6, B256::ZERO), Ok(()));
+        tx.commit().expect(ERROR_COMMIT);
+
+        let keys = collect::<_, CanonicalHeaders>(&db).into_iter().map(|(k, _)| k);
+        assert_eq!(keys.collect::<Vec<_>>(), vec![0, 1, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn db_cursor_upsert() {
+        let db = MemoryDatabase::new();
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+
+        let mut cursor = tx.cursor_write::<PlainAccountState>().unwrap();
+        let key = Address::random();
+
+        for nonce in 0..3 {
+            let account = Account { nonce, ..Default::default() };
+            cursor.upsert(key, account).expect(ERROR_UPSERT);
+            assert_eq!(cursor.seek_exact(key), Ok(Some((key, account))));
+        }
+
+        let mut dup_cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        let subkey = B256::random();
+
+        let entry1 = StorageEntry { key: subkey, value: U256::from(1) };
+        dup_cursor.upsert(key, entry1).expect(ERROR_UPSERT);
+        assert_eq!(dup_cursor.seek_by_key_subkey(key, subkey), Ok(Some(entry1)));
+
+        // Upserting into a dupsort table adds another duplicate for the same subkey.
+        let entry2 = StorageEntry { key: subkey, value: U256::from(2) };
+        dup_cursor.upsert(key, entry2).expect(ERROR_UPSERT);
+        assert_eq!(dup_cursor.seek_by_key_subkey(key, subkey), Ok(Some(entry1)));
+        assert_eq!(dup_cursor.next_dup_val(), Ok(Some(entry2)));
+    }
+
+    #[test]
+    fn db_cursor_dupsort_append() {
+        let db = MemoryDatabase::new();
+        let transition_id = 2;
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_write::<AccountChangeSets>().unwrap();
+        [0, 1, 3, 4, 5]
+            .into_iter()
+            .try_for_each(|val| {
+                cursor.append(
+                    transition_id,
+                    AccountBeforeTx { address: Address::with_last_byte(val), info: None },
+                )
+            })
+            .expect(ERROR_APPEND);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let entry = AccountBeforeTx { address: Address::with_last_byte(2), info: None };
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_write::<AccountChangeSets>().unwrap();
+        assert_eq!(
+            cursor.append_dup(transition_id, entry.clone()),
+            Err(write_error(
+                MemoryDatabaseError::KeyMismatch,
+                DatabaseWriteOperation::CursorAppendDup,
+                AccountChangeSets::NAME,
+                transition_id.encode().into(),
+            ))
+        );
+        assert_eq!(
+            cursor.append(transition_id - 1, entry.clone()),
+            Err(write_error(
+                MemoryDatabaseError::KeyMismatch,
+                DatabaseWriteOperation::CursorAppend,
+                AccountChangeSets::NAME,
+                (transition_id - 1).encode().into(),
+            ))
+        );
+        assert_eq!(cursor.append(transition_id, entry), Ok(()));
+    }
+
+    #[test]
+    fn db_closure_put_get() {
+        let db = MemoryDatabase::new();
+
+        let value =
+            Account { nonce: u64::MAX, bytecode_hash: Some(B256::random()), balance: U256::MAX };
+        let key = Address::random();
+
+        let result = db.update(|tx| {
+            tx.put::<PlainAccountState>(key, value).expect(ERROR_PUT);
+            200
+        });
+        assert_eq!(result, Ok(200));
+
+        let result = db.view(|tx| tx.get::<PlainAccountState>(key).expect(ERROR_GET));
+        assert_eq!(result, Ok(Some(value)));
+    }
+
+    #[test]
+    fn db_dup_sort() {
+        let db = MemoryDatabase::new();
+        let key = Address::random();
+
+        let value00 = StorageEntry::default();
+        let value22 = StorageEntry { key: B256::with_last_byte(2), value: U256::from(2) };
+        let value11 = StorageEntry { key: B256::with_last_byte(1), value: U256::from(1) };
+        for value in [value00, value22, value11] {
+            db.update(|tx| tx.put::<PlainStorageState>(key, value).expect(ERROR_PUT)).unwrap();
+        }
+
+        {
+            let tx = db.tx().expect(ERROR_INIT_TX);
+            let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+
+            // Duplicates are ordered by their subkey.
+            assert_eq!(Some(value00), cursor.next_dup_val().unwrap());
+            assert_eq!(Some(value11), cursor.next_dup_val().unwrap());
+            assert_eq!(Some(value22), cursor.next_dup_val().unwrap());
+            assert_eq!(None, cursor.next_dup_val().unwrap());
+
+            // `get` returns the first duplicate.
+            assert_eq!(tx.get::<PlainStorageState>(key), Ok(Some(value00)));
+            assert_eq!(tx.entries::<PlainStorageState>(), Ok(3));
+        }
+
+        {
+            let tx = db.tx().expect(ERROR_INIT_TX);
+            let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+            let mut walker = cursor.walk_dup(Some(key), Some(B256::with_last_byte(1))).unwrap();
+            assert_eq!(walker.next(), Some(Ok((key, value11))));
+            assert_eq!(walker.next(), Some(Ok((key, value22))));
+            assert_eq!(walker.next(), None);
+        }
+
+        // Deleting a single duplicate leaves the others intact.
+        db.update(|tx| assert_eq!(tx.delete::<PlainStorageState>(key, Some(value11)), Ok(true)))
+            .unwrap();
+        assert_eq!(collect::<_, PlainStorageState>(&db), vec![(key, value00), (key, value22)]);
+
+        // Deleting without a value removes all duplicates.
+        db.update(|tx| assert_eq!(tx.delete::<PlainStorageState>(key, None), Ok(true))).unwrap();
+        assert_eq!(collect::<_, PlainStorageState>(&db), vec![]);
+    }
+
+    #[test]
+    fn db_iterate_over_all_dup_values() {
+        let db = MemoryDatabase::new();
+        let key1 = Address::with_last_byte(1);
+        let key2 = Address::with_last_byte(2);
+
+        let value00 = StorageEntry::default();
+        let value11 = StorageEntry { key: B256::with_last_byte(1), value: U256::from(1) };
+        let value22 = StorageEntry { key: B256::with_last_byte(2), value: U256::from(2) };
+        db.update(|tx| {
+            tx.put::<PlainStorageState>(key1, value00).expect(ERROR_PUT);
+            tx.put::<PlainStorageState>(key1, value11).expect(ERROR_PUT);
+            tx.put::<PlainStorageState>(key2, value22).expect(ERROR_PUT);
+        })
+        .unwrap();
+
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+
+        // The dup walker only iterates over the duplicates of the first key.
+        let mut walker = cursor.walk_dup(None, None).unwrap();
+        assert_eq!(Some(Ok((key1, value00))), walker.next());
+        assert_eq!(Some(Ok((key1, value11))), walker.next());
+        assert_eq!(None, walker.next());
+
+        let mut walker = cursor.walk(None).unwrap();
+        assert_eq!(Some(Ok((key1, value00))), walker.next());
+        assert_eq!(Some(Ok((key1, value11))), walker.next());
+        assert_eq!(Some(Ok((key2, value22))), walker.next());
+        assert_eq!(None, walker.next());
+
+        assert_eq!(cursor.first(), Ok(Some((key1, value00))));
+        assert_eq!(cursor.next_no_dup(), Ok(Some((key2, value22))));
+        assert_eq!(cursor.next_no_dup(), Ok(None));
+
+        // Clearing the table removes all entries.
+        db.update(|tx| tx.clear::<PlainStorageState>().unwrap()).unwrap();
+        assert_eq!(db.tx().unwrap().entries::<PlainStorageState>(), Ok(0));
+    }
+
+    #[test]
+    fn dup_value_with_same_subkey() {
+        let db = MemoryDatabase::new();
+        let key1 = Address::new([0x11; 20]);
+        let key2 = Address::new([0x22; 20]);
+
+        let value01 = StorageEntry { key: B256::with_last_byte(0), value: U256::from(1) };
+        let value00 = StorageEntry::default();
+        let value22 = StorageEntry { key: B256::with_last_byte(2), value: U256::from(2) };
+        db.update(|tx| {
+            tx.put::<PlainStorageState>(key1, value01).expect(ERROR_PUT);
+            tx.put::<PlainStorageState>(key1, value00).expect(ERROR_PUT);
+            tx.put::<PlainStorageState>(key2, value22).expect(ERROR_PUT);
+        })
+        .unwrap();
+
+        assert_eq!(
+            collect::<_, PlainStorageState>(&db),
+            vec![(key1, value00), (key1, value01), (key2, value22)]
+        );
+
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+        assert_eq!(Ok(Some(value00)), cursor.seek_by_key_subkey(key1, value00.key));
+        assert_eq!(Ok(None), cursor.seek_by_key_subkey(key1, value22.key));
+    }
+
+    #[test]
+    fn db_delete_current_duplicates() {
+        let db = MemoryDatabase::new();
+        let key1 = Address::with_last_byte(1);
+        let key2 = Address::with_last_byte(2);
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        for (key, slot) in [(key1, 1), (key1, 2), (key2, 1)] {
+            let entry = StorageEntry { key: B256::with_last_byte(slot), value: U256::from(slot) };
+            cursor.append_dup(key, entry).expect(ERROR_APPEND);
+        }
+
+        cursor.seek_exact(key1).unwrap();
+        cursor.delete_current_duplicates().expect(ERROR_DEL);
+        assert_eq!(
+            cursor.next(),
+            Ok(Some((key2, StorageEntry { key: B256::with_last_byte(1), value: U256::from(1) })))
+        );
+        tx.commit().expect(ERROR_COMMIT);
+
+        assert_eq!(db.tx().unwrap().entries::<PlainStorageState>(), Ok(1));
+    }
+
+    #[test]
+    fn db_range_delete() {
+        let db = MemoryDatabase::new();
+
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        (0..10).try_for_each(|key| tx.put::<CanonicalHeaders>(key, B256::ZERO)).expect(ERROR_PUT);
+
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        let mut walker = cursor.walk_range(3..7).unwrap();
+        while walker.next().transpose().unwrap().is_some() {
+            walker.delete_current().expect(ERROR_DEL);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let keys = collect::<_, CanonicalHeaders>(&db).into_iter().map(|(k, _)| k);
+        assert_eq!(keys.collect::<Vec<_>>(), vec![0, 1, 2, 7, 8, 9]);
+    }
+
+    #[test]
+    fn db_sharded_key() {
+        let db = MemoryDatabase::new();
+        let real_key = Address::random();
+
+        for i in 1..5 {
+            let key = ShardedKey::new(real_key, i * 100);
+            let list: IntegerList = vec![i * 100u64].into();
+
+            db.update(|tx| tx.put::<AccountsHistory>(key.clone(), list.clone()).expect(ERROR_PUT))
+                .unwrap();
+        }
+
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_read::<AccountsHistory>().unwrap();
+
+        let mut walker = cursor.walk(Some(ShardedKey::new(real_key, 150))).unwrap();
+        let (key, list) = walker.next().unwrap().unwrap();
+        assert_eq!(ShardedKey::new(real_key, 200), key);
+        assert_eq!(list, vec![200u64].into());
+
+        let _unknown = cursor.seek_exact(ShardedKey::new(real_key, u64::MAX)).unwrap();
+        let (key, list) = cursor.prev().unwrap().unwrap();
+        assert_eq!(ShardedKey::new(real_key, 400), key);
+        assert_eq!(list, vec![400u64].into());
+    }
+
+    #[test]
+    fn import_table_from_mdbx() {
+        let mdbx = create_test_rw_db();
+        let tx = mdbx.tx_mut().expect(ERROR_INIT_TX);
+        (0..4).try_for_each(|key| tx.put::<CanonicalHeaders>(key, B256::ZERO)).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let db = MemoryDatabase::new();
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        let source = mdbx.tx().expect(ERROR_INIT_TX);
+        assert_matches!(
+            reth_db_api::table::TableImporter::import_table::<CanonicalHeaders, _>(&tx, &source),
+            Ok(())
+        );
+        tx.commit().expect(ERROR_COMMIT);
+
+        assert_eq!(collect::<_, CanonicalHeaders>(&db), collect::<_, CanonicalHeaders>(&mdbx));
+    }
+}
@@ -0,0 +1,200 @@
+//! Transactions of the in-memory database.
+
+use super::{cursor::Cursor, is_dupsort, MemoryDatabaseInner, Snapshot, TableData};
+use crate::DatabaseError;
+use parking_lot::RwLock;
+use reth_db_api::{
+    table::{Compress, Decompress, DupSort, Encode, Table, TableImporter},
+    transaction::{DbTx, DbTxMut},
+};
+use std::{collections::BTreeSet, fmt, marker::PhantomData, sync::Arc};
+
+/// Marker for the kind of a transaction: [`RO`] or [`RW`].
+pub trait TransactionKind: private::Sealed + Send + Sync + fmt::Debug + 'static {
+    /// Whether the transaction is read-only.
+    const IS_READ_ONLY: bool;
+}
+
+/// Read-only transaction kind.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct RO;
+
+/// Read-write transaction kind.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct RW;
+
+impl TransactionKind for RO {
+    const IS_READ_ONLY: bool = true;
+}
+
+impl TransactionKind for RW {
+    const IS_READ_ONLY: bool = false;
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::RO {}
+    impl Sealed for super::RW {}
+}
+
+/// Transaction of the in-memory database.
+///
+/// The transaction works on its own copy of the table handles. For [`RW`] transactions, modified
+/// tables are copied on first write and the copy replaces the committed state on
+/// [`DbTx::commit`].
+pub struct Tx<K: TransactionKind> {
+    /// State of the tables as seen by this transaction, shared with its cursors.
+    pub(crate) state: Arc<RwLock<Snapshot>>,
+    /// Handle to the database, only set for [`RW`] transactions.
+    db: Option<Arc<MemoryDatabaseInner>>,
+    _kind: PhantomData<K>,
+}
+
+impl<K: TransactionKind> fmt::Debug for Tx<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tx").field("read_only", &K::IS_READ_ONLY).finish_non_exhaustive()
+    }
+}
+
+impl Tx<RO> {
+    /// Creates a read-only transaction over the given snapshot.
+    pub(crate) fn new_ro(snapshot: Snapshot) -> Self {
+        Self { state: Arc::new(RwLock::new(snapshot)), db: None, _kind: PhantomData }
+    }
+}
+
+impl Tx<RW> {
+    /// Creates a read-write transaction over the committed state of the database.
+    ///
+    /// The caller must have acquired the writer lock of the database, it is released once the
+    /// transaction is committed or dropped.
+    pub(crate) fn new_rw(db: Arc<MemoryDatabaseInner>) -> Self {
+        Self { state: Arc::new(RwLock::new(db.snapshot())), db: Some(db), _kind: PhantomData }
+    }
+}
+
+impl<K: TransactionKind> Tx<K> {
+    /// Creates a cursor over the table.
+    pub fn new_cursor<T: Table>(&self) -> Result<Cursor<K, T>, DatabaseError> {
+        Ok(Cursor::new(self.state.clone()))
+    }
+
+    /// Executes the closure with the data of the table, if the table has any.
+    fn with_table<T: Table, R>(&self, f: impl FnOnce(Option<&TableData>) -> R) -> R {
+        f(self.state.read().get(T::NAME).map(|table| table.as_ref()))
+    }
+}
+
+impl<K: TransactionKind> Drop for Tx<K> {
+    fn drop(&mut self) {
+        if let Some(db) = self.db.take() {
+            db.release_writer();
+        }
+    }
+}
+
+impl<K: TransactionKind> DbTx for Tx<K> {
+    type Cursor<T: Table> = Cursor<K, T>;
+    type DupCursor<T: DupSort> = Cursor<K, T>;
+
+    fn get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
+        let key = key.encode();
+        self.with_table::<T, _>(|table| {
+            table
+                .and_then(|table| table.get(key.as_ref()))
+                .and_then(|values| values.first())
+                .map(T::Value::decompress)
+                .transpose()
+        })
+    }
+
+    fn commit(mut self) -> Result<bool, DatabaseError> {
+        if let Some(db) = self.db.take() {
+            db.commit(self.state.read().clone());
+            db.release_writer();
+        }
+        Ok(false)
+    }
+
+    fn abort(self) {}
+
+    fn cursor_read<T: Table>(&self) -> Result<Self::Cursor<T>, DatabaseError> {
+        self.new_cursor()
+    }
+
+    fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError> {
+        self.new_cursor()
+    }
+
+    fn entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        Ok(self.with_table::<T, _>(|table| {
+            table.map_or(0, |table| table.values().map(BTreeSet::len).sum())
+        }))
+    }
+
+    fn disable_long_read_transaction_safety(&mut self) {}
+}
+
+impl DbTxMut for Tx<RW> {
+    type CursorMut<T: Table> = Cursor<RW, T>;
+    type DupCursorMut<T: DupSort> = Cursor<RW, T>;
+
+    fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key: Vec<u8> = key.encode().into();
+        let value: Vec<u8> = value.compress().into();
+
+        let mut state = self.state.write();
+        let values = Arc::make_mut(state.entry(T::NAME).or_default()).entry(key).or_default();
+        if !is_dupsort::<T>() {
+            values.clear();
+        }
+        values.insert(value);
+
+        Ok(())
+    }
+
+    fn delete<T: Table>(
+        &self,
+        key: T::Key,
+        value: Option<T::Value>,
+    ) -> Result<bool, DatabaseError> {
+        let key = key.encode();
+        let value: Option<Vec<u8>> = value.map(|value| value.compress().into());
+
+        let mut state = self.state.write();
+        let Some(table) = state.get_mut(T::NAME) else { return Ok(false) };
+        let table = Arc::make_mut(table);
+        let Some(values) = table.get_mut(key.as_ref()) else { return Ok(false) };
+
+        let deleted = match value {
+            Some(value) => values.remove(&value),
+            None => {
+                values.clear();
+                true
+            }
+        };
+        if values.is_empty() {
+            table.remove(key.as_ref());
+        }
+
+        Ok(deleted)
+    }
+
+    fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        self.state.write().remove(T::NAME);
+        Ok(())
+    }
+
+    fn cursor_write<T: Table>(&self) -> Result<Self::CursorMut<T>, DatabaseError> {
+        self.new_cursor()
+    }
+
+    fn cursor_dup_write<T: DupSort>(&self) -> Result<Self::DupCursorMut<T>, DatabaseError> {
+        self.new_cursor()
+    }
+}
+
+impl TableImporter for Tx<RW> {}
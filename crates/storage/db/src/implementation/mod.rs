@@ -1,2 +1,3 @@
 #[cfg(feature = "mdbx")]
 pub(crate) mod mdbx;
+pub(crate) mod memory;
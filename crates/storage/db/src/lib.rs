@@ -17,6 +17,7 @@
 
 mod implementation;
 pub mod lockfile;
+pub mod memory;
 #[cfg(feature = "mdbx")]
 mod metrics;
 pub mod static_file;
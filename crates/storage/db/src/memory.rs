@@ -0,0 +1,6 @@
+//! In-memory implementation of the database abstraction layer.
+//!
+//! [`MemoryDatabase`] mirrors the semantics of the MDBX backend, including `DupSort` tables, and
+//! is intended for tests that don't need a database on disk.
+
+pub use crate::implementation::memory::*;
@@ -1450,7 +1450,7 @@ mod tests {
     use rand::Rng;
     use reth_chain_state::{
         test_utils::TestBlockBuilder, CanonStateNotification, CanonStateSubscriptions,
-        ExecutedBlock, NewCanonicalChain,
+        ExecutedBlock, NewCanonicalChain, ReorgInfo,
     };
     use reth_chainspec::{
         ChainSpec, ChainSpecBuilder, ChainSpecProvider, EthereumHardfork, MAINNET,
@@ -2767,8 +2767,11 @@ mod tests {
         let block_3 = test_block_builder.generate_random_block(1, block_hash_1);
         let block_4 = test_block_builder.generate_random_block(2, block_3.hash());
         let new_chain = Chain::new(vec![block_3, block_4], ExecutionOutcome::default(), None);
-        let re_org =
-            CanonStateNotification::Reorg { old: Arc::new(chain), new: Arc::new(new_chain) };
+        let re_org = CanonStateNotification::Reorg {
+            info: ReorgInfo::new(chain.len() as u64),
+            old: Arc::new(chain),
+            new: Arc::new(new_chain),
+        };
         in_memory_state.notify_canon_state(re_org.clone());
         let (notification_1, notification_2) = tokio::join!(rx_1.recv(), rx_2.recv());
         assert_eq!(notification_1, Ok(re_org.clone()));
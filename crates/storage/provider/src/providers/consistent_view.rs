@@ -46,6 +46,11 @@ where
         Ok(Self::new(provider, tip))
     }
 
+    /// Returns the tip the view was initialized with.
+    pub const fn tip(&self) -> Option<B256> {
+        self.tip
+    }
+
     /// Creates new read-only provider and performs consistency checks on the current tip.
     pub fn provider_ro(&self) -> ProviderResult<DatabaseProviderRO<DB>> {
         // Create a new provider.
@@ -1,5 +1,5 @@
 use crate::{
-    providers::{state::latest::LatestStateProvider, StaticFileProvider},
+    providers::{state::latest::LatestStateProvider, HistoricalStateCache, StaticFileProvider},
     to_range,
     traits::{BlockSource, ReceiptProvider},
     BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
@@ -48,6 +48,8 @@ pub struct ProviderFactory<DB, Spec = ChainSpec> {
     static_file_provider: StaticFileProvider,
     /// Optional pruning configuration
     prune_modes: PruneModes,
+    /// Optional cache shared by historical state providers
+    historical_state_cache: Option<HistoricalStateCache>,
 }
 
 impl<DB> ProviderFactory<DB> {
@@ -57,7 +59,13 @@ impl<DB> ProviderFactory<DB> {
         chain_spec: Arc<ChainSpec>,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { db: Arc::new(db), chain_spec, static_file_provider, prune_modes: PruneModes::none() }
+        Self {
+            db: Arc::new(db),
+            chain_spec,
+            static_file_provider,
+            prune_modes: PruneModes::none(),
+            historical_state_cache: None,
+        }
     }
 
     /// Enables metrics on the static file provider.
@@ -72,6 +80,13 @@ impl<DB> ProviderFactory<DB> {
         self
     }
 
+    /// Sets the [`HistoricalStateCache`] shared by all historical state providers created by this
+    /// factory.
+    pub fn with_historical_state_cache(mut self, cache: HistoricalStateCache) -> Self {
+        self.historical_state_cache = Some(cache);
+        self
+    }
+
     /// Returns reference to the underlying database.
     pub fn db_ref(&self) -> &DB {
         &self.db
@@ -98,6 +113,7 @@ impl ProviderFactory<DatabaseEnv> {
             chain_spec,
             static_file_provider,
             prune_modes: PruneModes::none(),
+            historical_state_cache: None,
         })
     }
 }
@@ -116,7 +132,8 @@ impl<DB: Database> ProviderFactory<DB> {
             self.chain_spec.clone(),
             self.static_file_provider.clone(),
             self.prune_modes.clone(),
-        ))
+        )
+        .with_historical_state_cache(self.historical_state_cache.clone()))
     }
 
     /// Returns a provider with a created `DbTxMut` inside, which allows fetching and updating
@@ -437,11 +454,22 @@ impl<DB: Database> TransactionsProvider for ProviderFactory<DB> {
         &self,
         range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<Address>> {
-        self.provider()?.senders_by_tx_range(range)
+        self.static_file_provider.get_range_with_static_file_or_database(
+            StaticFileSegment::Senders,
+            to_range(range),
+            |static_file, range, _| static_file.senders_by_tx_range(range),
+            |range, _| self.provider()?.senders_by_tx_range(range),
+            |_| true,
+        )
     }
 
     fn transaction_sender(&self, id: TxNumber) -> ProviderResult<Option<Address>> {
-        self.provider()?.transaction_sender(id)
+        self.static_file_provider.get_with_static_file_or_database(
+            StaticFileSegment::Senders,
+            id,
+            |static_file| static_file.transaction_sender(id),
+            || self.provider()?.transaction_sender(id),
+        )
     }
 }
 
@@ -600,6 +628,7 @@ impl<DB, Spec> Clone for ProviderFactory<DB, Spec> {
             chain_spec: self.chain_spec.clone(),
             static_file_provider: self.static_file_provider.clone(),
             prune_modes: self.prune_modes.clone(),
+            historical_state_cache: self.historical_state_cache.clone(),
         }
     }
 }
@@ -608,7 +637,10 @@ mod tests {
     use super::*;
     use crate::{
         providers::{StaticFileProvider, StaticFileWriter},
-        test_utils::{blocks::TEST_BLOCK, create_test_provider_factory},
+        test_utils::{
+            blocks::TEST_BLOCK, create_test_provider_factory,
+            create_test_provider_factory_in_memory,
+        },
         BlockHashReader, BlockNumReader, BlockWriter, HeaderSyncGapProvider, TransactionsProvider,
     };
     use assert_matches::assert_matches;
@@ -705,6 +737,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn insert_block_in_memory_database() {
+        let factory = create_test_provider_factory_in_memory();
+
+        let block = TEST_BLOCK.clone();
+        let provider = factory.provider_rw().unwrap();
+        assert_matches!(
+            provider.insert_block(block.clone().try_seal_with_senders().unwrap()),
+            Ok(_)
+        );
+        provider.commit().unwrap();
+
+        let provider = factory.provider().unwrap();
+        assert_matches!(provider.block_hash(block.number), Ok(Some(hash)) if hash == block.hash());
+        assert_matches!(
+            provider.transaction_sender(0), Ok(Some(sender))
+            if sender == block.body[0].recover_signer().unwrap()
+        );
+        assert_matches!(provider.transaction_id(block.body[0].hash), Ok(Some(0)));
+    }
+
     #[test]
     fn take_block_transaction_range_recover_senders() {
         let factory = create_test_provider_factory();
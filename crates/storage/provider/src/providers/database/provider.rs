@@ -1,6 +1,8 @@
 use crate::{
     bundle_state::StorageRevertsIter,
-    providers::{database::metrics, static_file::StaticFileWriter, StaticFileProvider},
+    providers::{
+        database::metrics, static_file::StaticFileWriter, HistoricalStateCache, StaticFileProvider,
+    },
     to_range,
     traits::{
         AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
@@ -115,6 +117,8 @@ pub struct DatabaseProvider<TX> {
     static_file_provider: StaticFileProvider,
     /// Pruning configuration
     prune_modes: PruneModes,
+    /// Optional shared cache for historical state providers
+    historical_state_cache: Option<HistoricalStateCache>,
 }
 
 impl<TX> DatabaseProvider<TX> {
@@ -127,6 +131,13 @@ impl<TX> DatabaseProvider<TX> {
     pub const fn prune_modes_ref(&self) -> &PruneModes {
         &self.prune_modes
     }
+
+    /// Sets the [`HistoricalStateCache`] shared by historical state providers created from this
+    /// provider.
+    pub fn with_historical_state_cache(mut self, cache: Option<HistoricalStateCache>) -> Self {
+        self.historical_state_cache = cache;
+        self
+    }
 }
 
 impl<TX: DbTxMut> DatabaseProvider<TX> {
@@ -137,7 +148,7 @@ impl<TX: DbTxMut> DatabaseProvider<TX> {
         static_file_provider: StaticFileProvider,
         prune_modes: PruneModes,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider, prune_modes }
+        Self { tx, chain_spec, static_file_provider, prune_modes, historical_state_cache: None }
     }
 }
 
@@ -153,6 +164,13 @@ impl<TX: DbTx + 'static> DatabaseProvider<TX> {
             return Ok(Box::new(LatestStateProvider::new(self.tx, self.static_file_provider)))
         }
 
+        // Hash of the block whose post-state is exposed, used as the key of the historical state
+        // cache.
+        let block_hash = match self.historical_state_cache {
+            Some(_) => self.block_hash(block_number)?,
+            None => None,
+        };
+
         // +1 as the changeset that we want is the one that was applied after this block.
         block_number += 1;
 
@@ -181,6 +199,10 @@ impl<TX: DbTx + 'static> DatabaseProvider<TX> {
             );
         }
 
+        if let Some((cache, block_hash)) = self.historical_state_cache.zip(block_hash) {
+            state_provider = state_provider.with_cache(cache, block_hash)?;
+        }
+
         Ok(Box::new(state_provider))
     }
 }
@@ -274,7 +296,7 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         static_file_provider: StaticFileProvider,
         prune_modes: PruneModes,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider, prune_modes }
+        Self { tx, chain_spec, static_file_provider, prune_modes, historical_state_cache: None }
     }
 
     /// Consume `DbTx` or `DbTxMut`.
@@ -573,11 +595,25 @@ impl<TX: DbTx> DatabaseProvider<TX> {
                     .into_iter()
                     .map(Into::into)
                     .collect::<Vec<TransactionSigned>>();
-                // fetch senders from the senders table
-                let known_senders =
-                    senders_cursor
-                        .walk_range(tx_range.clone())?
-                        .collect::<Result<HashMap<_, _>, _>>()?;
+                // fetch senders from the senders static files and table
+                let known_senders = self
+                    .static_file_provider
+                    .get_range_with_static_file_or_database(
+                        StaticFileSegment::Senders,
+                        tx_range.clone(),
+                        |static_file, range, _| {
+                            Ok(range.clone().zip(static_file.senders_by_tx_range(range)?).collect())
+                        },
+                        |range, _| {
+                            senders_cursor
+                                .walk_range(range)?
+                                .collect::<Result<Vec<_>, _>>()
+                                .map_err(Into::into)
+                        },
+                        |_| true,
+                    )?
+                    .into_iter()
+                    .collect::<HashMap<_, _>>();
 
                 let mut senders = Vec::with_capacity(body.len());
                 for (tx_num, tx) in tx_range.zip(body.iter()) {
@@ -2343,11 +2379,24 @@ impl<TX: DbTx> TransactionsProvider for DatabaseProvider<TX> {
         &self,
         range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<Address>> {
-        self.cursor_read_collect::<tables::TransactionSenders>(range).map_err(Into::into)
+        self.static_file_provider.get_range_with_static_file_or_database(
+            StaticFileSegment::Senders,
+            to_range(range),
+            |static_file, range, _| static_file.senders_by_tx_range(range),
+            |range, _| {
+                self.cursor_read_collect::<tables::TransactionSenders>(range).map_err(Into::into)
+            },
+            |_| true,
+        )
     }
 
     fn transaction_sender(&self, id: TxNumber) -> ProviderResult<Option<Address>> {
-        Ok(self.tx.get::<tables::TransactionSenders>(id)?)
+        self.static_file_provider.get_with_static_file_or_database(
+            StaticFileSegment::Senders,
+            id,
+            |static_file| static_file.transaction_sender(id),
+            || Ok(self.tx.get::<tables::TransactionSenders>(id)?),
+        )
     }
 }
 
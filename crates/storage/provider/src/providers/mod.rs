@@ -48,6 +48,7 @@ pub use static_file::{
 
 mod state;
 pub use state::{
+    cache::HistoricalStateCache,
     historical::{HistoricalStateProvider, HistoricalStateProviderRef},
     latest::{LatestStateProvider, LatestStateProviderRef},
 };
@@ -130,6 +131,39 @@ where
         Ok(Self::with_blocks(database, tree, latest_header.seal(best.best_hash), finalized_header))
     }
 
+    /// Refreshes the static file index and the tracked canonical and finalized heads from storage
+    /// that is written to by another process.
+    ///
+    /// Returns `true` if the canonical head changed.
+    pub fn refresh_from_storage(&self) -> ProviderResult<bool> {
+        // The database is read before the static files are reloaded, since the writing process
+        // always commits static files first. This way every block visible in the database is also
+        // visible in the static file index.
+        let provider = self.database.provider()?;
+        let best_number = provider.best_block_number()?;
+        let finalized_number = provider.last_finalized_block_number()?;
+        self.database.static_file_provider().reload_index()?;
+
+        let mut head_changed = false;
+        if let Some(header) = provider.sealed_header(best_number)? {
+            if header.hash() != self.chain_info.get_canonical_head().hash() {
+                self.chain_info.set_canonical_head(header);
+                head_changed = true;
+            }
+        }
+
+        if let Some(finalized_number) = finalized_number {
+            let tracked = self.chain_info.get_finalized_num_hash().map(|num_hash| num_hash.number);
+            if tracked != Some(finalized_number) {
+                if let Some(header) = provider.sealed_header(finalized_number)? {
+                    self.chain_info.set_finalized(header);
+                }
+            }
+        }
+
+        Ok(head_changed)
+    }
+
     /// Ensures that the given block number is canonical (synced)
     ///
     /// This is a helper for guarding the [`HistoricalStateProvider`] against block numbers that are
@@ -0,0 +1,181 @@
+use parking_lot::Mutex;
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    models::BlockNumberAddress,
+    transaction::DbTx,
+};
+use reth_primitives::{Account, Address, BlockHash, BlockNumber, StorageKey, StorageValue};
+use reth_storage_errors::provider::ProviderResult;
+use schnellru::{ByLength, LruMap};
+use std::{fmt, sync::Arc};
+
+/// Maximum number of blocks for which the changeset prefetch is remembered.
+const PREFETCHED_BLOCKS_MAX_LEN: u32 = 1024;
+
+/// Key of a [`HistoricalStateCache`] entry.
+///
+/// Entries are keyed by the hash of the block whose post-state they describe, which uniquely
+/// identifies the state regardless of reorgs, so the cache never needs to be invalidated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum HistoricalStateKey {
+    /// Account at the given block.
+    Account(BlockHash, Address),
+    /// Storage slot of an account at the given block.
+    Storage(BlockHash, Address, StorageKey),
+}
+
+/// Value of a [`HistoricalStateCache`] entry.
+#[derive(Clone, Copy, Debug)]
+enum HistoricalStateValue {
+    Account(Option<Account>),
+    Storage(StorageValue),
+}
+
+struct HistoricalStateCacheInner {
+    /// Cached account and storage values.
+    entries: LruMap<HistoricalStateKey, HistoricalStateValue, ByLength>,
+    /// Blocks for which the changesets were already prefetched.
+    prefetched: LruMap<BlockHash, (), ByLength>,
+}
+
+/// A shared LRU cache of historical account and storage values, used by
+/// [`HistoricalStateProviderRef`](crate::HistoricalStateProviderRef) to avoid a history index seek
+/// and a changeset lookup for every read.
+///
+/// Once a historical state provider is opened at a block, all accounts and storage slots touched
+/// by that block are prefetched from the changesets of the block, since the changesets hold
+/// exactly the state the provider exposes. Any other value is cached after its first read.
+///
+/// The cache is cheap to clone and can be shared across all historical state providers.
+#[derive(Clone)]
+pub struct HistoricalStateCache {
+    inner: Arc<Mutex<HistoricalStateCacheInner>>,
+}
+
+impl HistoricalStateCache {
+    /// Creates a new cache that holds up to `max_entries` accounts and storage slots.
+    pub fn new(max_entries: u32) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(HistoricalStateCacheInner {
+                entries: LruMap::new(ByLength::new(max_entries)),
+                prefetched: LruMap::new(ByLength::new(PREFETCHED_BLOCKS_MAX_LEN)),
+            })),
+        }
+    }
+
+    /// Returns the number of cached accounts and storage slots.
+    pub fn len(&self) -> usize {
+        self.inner.lock().entries.len()
+    }
+
+    /// Returns `true` if there are no cached accounts and storage slots.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the cached account at the post-state of `block_hash`, if any.
+    ///
+    /// The inner [`None`] means the account didn't exist.
+    pub fn account(&self, block_hash: BlockHash, address: Address) -> Option<Option<Account>> {
+        let key = HistoricalStateKey::Account(block_hash, address);
+        match self.inner.lock().entries.get(&key)? {
+            HistoricalStateValue::Account(account) => Some(*account),
+            HistoricalStateValue::Storage(_) => None,
+        }
+    }
+
+    /// Returns the cached storage value at the post-state of `block_hash`, if any.
+    pub fn storage(
+        &self,
+        block_hash: BlockHash,
+        address: Address,
+        storage_key: StorageKey,
+    ) -> Option<StorageValue> {
+        let key = HistoricalStateKey::Storage(block_hash, address, storage_key);
+        match self.inner.lock().entries.get(&key)? {
+            HistoricalStateValue::Storage(value) => Some(*value),
+            HistoricalStateValue::Account(_) => None,
+        }
+    }
+
+    /// Caches the account at the post-state of `block_hash`.
+    pub fn insert_account(
+        &self,
+        block_hash: BlockHash,
+        address: Address,
+        account: Option<Account>,
+    ) {
+        self.inner.lock().entries.insert(
+            HistoricalStateKey::Account(block_hash, address),
+            HistoricalStateValue::Account(account),
+        );
+    }
+
+    /// Caches the storage value at the post-state of `block_hash`.
+    pub fn insert_storage(
+        &self,
+        block_hash: BlockHash,
+        address: Address,
+        storage_key: StorageKey,
+        value: StorageValue,
+    ) {
+        self.inner.lock().entries.insert(
+            HistoricalStateKey::Storage(block_hash, address, storage_key),
+            HistoricalStateValue::Storage(value),
+        );
+    }
+
+    /// Prefetches all accounts and storage slots touched by `block_number` from its changesets.
+    ///
+    /// The changesets of a block hold the values before the block was executed, which is the
+    /// post-state of its parent with the hash `parent_hash`. Does nothing if the block was already
+    /// prefetched.
+    pub fn prefetch_changesets<TX: DbTx>(
+        &self,
+        tx: &TX,
+        parent_hash: BlockHash,
+        block_number: BlockNumber,
+    ) -> ProviderResult<()> {
+        if self.inner.lock().prefetched.get(&parent_hash).is_some() {
+            return Ok(())
+        }
+
+        // Read the changesets without holding the lock, so concurrent readers are not blocked.
+        let accounts = tx
+            .cursor_dup_read::<tables::AccountChangeSets>()?
+            .walk_dup(Some(block_number), None)?
+            .collect::<Result<Vec<_>, _>>()?;
+        let storages = tx
+            .cursor_dup_read::<tables::StorageChangeSets>()?
+            .walk_range(BlockNumberAddress::range(block_number..=block_number))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut inner = self.inner.lock();
+        for (_, account) in accounts {
+            inner.entries.insert(
+                HistoricalStateKey::Account(parent_hash, account.address),
+                HistoricalStateValue::Account(account.info),
+            );
+        }
+        for (block_address, entry) in storages {
+            inner.entries.insert(
+                HistoricalStateKey::Storage(parent_hash, block_address.address(), entry.key),
+                HistoricalStateValue::Storage(entry.value),
+            );
+        }
+        inner.prefetched.insert(parent_hash, ());
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for HistoricalStateCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.lock();
+        f.debug_struct("HistoricalStateCache")
+            .field("entries", &inner.entries.len())
+            .field("prefetched", &inner.prefetched.len())
+            .finish()
+    }
+}
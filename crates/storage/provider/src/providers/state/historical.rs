@@ -1,5 +1,8 @@
 use crate::{
-    providers::{state::macros::delegate_provider_impls, StaticFileProvider},
+    providers::{
+        state::{cache::HistoricalStateCache, macros::delegate_provider_impls},
+        StaticFileProvider,
+    },
     AccountReader, BlockHashReader, ProviderError, StateProvider, StateRootProvider,
 };
 use reth_db::{tables, BlockNumberList};
@@ -10,8 +13,8 @@ use reth_db_api::{
     transaction::DbTx,
 };
 use reth_primitives::{
-    constants::EPOCH_SLOTS, Account, Address, BlockNumber, Bytecode, Bytes, StaticFileSegment,
-    StorageKey, StorageValue, B256,
+    constants::EPOCH_SLOTS, Account, Address, BlockHash, BlockNumber, Bytecode, Bytes,
+    StaticFileSegment, StorageKey, StorageValue, B256,
 };
 use reth_storage_api::{StateProofProvider, StorageRootProvider};
 use reth_storage_errors::provider::ProviderResult;
@@ -46,6 +49,9 @@ pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     lowest_available_blocks: LowestAvailableBlocks,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Optional shared cache of historical state, with the hash of the parent block of
+    /// `block_number`.
+    cache: Option<(HistoricalStateCache, BlockHash)>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -63,7 +69,13 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            static_file_provider,
+            cache: None,
+        }
     }
 
     /// Create new `StateProvider` for historical block number and lowest block numbers at which
@@ -74,7 +86,16 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         lowest_available_blocks: LowestAvailableBlocks,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks, static_file_provider }
+        Self { tx, block_number, lowest_available_blocks, static_file_provider, cache: None }
+    }
+
+    /// Set the shared [`HistoricalStateCache`] to read from and populate.
+    ///
+    /// `parent_hash` must be the hash of the canonical block preceding `block_number`, whose
+    /// post-state this provider exposes.
+    pub fn with_cache(mut self, cache: HistoricalStateCache, parent_hash: BlockHash) -> Self {
+        self.cache = Some((cache, parent_hash));
+        self
     }
 
     /// Lookup an account in the `AccountsHistory` table
@@ -229,22 +250,37 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
 impl<'b, TX: DbTx> AccountReader for HistoricalStateProviderRef<'b, TX> {
     /// Get basic account information.
     fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
-        match self.account_history_lookup(address)? {
-            HistoryInfo::NotYetWritten => Ok(None),
-            HistoryInfo::InChangeset(changeset_block_number) => Ok(self
-                .tx
-                .cursor_dup_read::<tables::AccountChangeSets>()?
-                .seek_by_key_subkey(changeset_block_number, address)?
-                .filter(|acc| acc.address == address)
-                .ok_or(ProviderError::AccountChangesetNotFound {
-                    block_number: changeset_block_number,
-                    address,
-                })?
-                .info),
+        if let Some((cache, parent_hash)) = &self.cache {
+            if self.lowest_available_blocks.is_account_history_available(self.block_number) {
+                if let Some(account) = cache.account(*parent_hash, address) {
+                    return Ok(account)
+                }
+            }
+        }
+
+        let account = match self.account_history_lookup(address)? {
+            HistoryInfo::NotYetWritten => None,
+            HistoryInfo::InChangeset(changeset_block_number) => {
+                self.tx
+                    .cursor_dup_read::<tables::AccountChangeSets>()?
+                    .seek_by_key_subkey(changeset_block_number, address)?
+                    .filter(|acc| acc.address == address)
+                    .ok_or(ProviderError::AccountChangesetNotFound {
+                        block_number: changeset_block_number,
+                        address,
+                    })?
+                    .info
+            }
             HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {
-                Ok(self.tx.get::<tables::PlainAccountState>(address)?)
+                self.tx.get::<tables::PlainAccountState>(address)?
             }
+        };
+
+        if let Some((cache, parent_hash)) = &self.cache {
+            cache.insert_account(*parent_hash, address, account);
         }
+
+        Ok(account)
     }
 }
 
@@ -382,9 +418,17 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
         address: Address,
         storage_key: StorageKey,
     ) -> ProviderResult<Option<StorageValue>> {
-        match self.storage_history_lookup(address, storage_key)? {
-            HistoryInfo::NotYetWritten => Ok(None),
-            HistoryInfo::InChangeset(changeset_block_number) => Ok(Some(
+        if let Some((cache, parent_hash)) = &self.cache {
+            if self.lowest_available_blocks.is_storage_history_available(self.block_number) {
+                if let Some(value) = cache.storage(*parent_hash, address, storage_key) {
+                    return Ok(Some(value))
+                }
+            }
+        }
+
+        let value = match self.storage_history_lookup(address, storage_key)? {
+            HistoryInfo::NotYetWritten => return Ok(None),
+            HistoryInfo::InChangeset(changeset_block_number) => {
                 self.tx
                     .cursor_dup_read::<tables::StorageChangeSets>()?
                     .seek_by_key_subkey((changeset_block_number, address).into(), storage_key)?
@@ -394,16 +438,22 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
                         address,
                         storage_key: Box::new(storage_key),
                     })?
-                    .value,
-            )),
-            HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => Ok(self
+                    .value
+            }
+            HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => self
                 .tx
                 .cursor_dup_read::<tables::PlainStorageState>()?
                 .seek_by_key_subkey(address, storage_key)?
                 .filter(|entry| entry.key == storage_key)
                 .map(|entry| entry.value)
-                .or(Some(StorageValue::ZERO))),
+                .unwrap_or(StorageValue::ZERO),
+        };
+
+        if let Some((cache, parent_hash)) = &self.cache {
+            cache.insert_storage(*parent_hash, address, storage_key, value);
         }
+
+        Ok(Some(value))
     }
 
     /// Get account code by its hash
@@ -424,6 +474,9 @@ pub struct HistoricalStateProvider<TX: DbTx> {
     lowest_available_blocks: LowestAvailableBlocks,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Optional shared cache of historical state, with the hash of the parent block of
+    /// `block_number`.
+    cache: Option<(HistoricalStateCache, BlockHash)>,
 }
 
 impl<TX: DbTx> HistoricalStateProvider<TX> {
@@ -433,7 +486,33 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            static_file_provider,
+            cache: None,
+        }
+    }
+
+    /// Set the shared [`HistoricalStateCache`] to read from and populate, and prefetch all
+    /// accounts and storage slots touched by the block into it.
+    ///
+    /// `parent_hash` must be the hash of the canonical block preceding `block_number`, whose
+    /// post-state this provider exposes.
+    pub fn with_cache(
+        mut self,
+        cache: HistoricalStateCache,
+        parent_hash: BlockHash,
+    ) -> ProviderResult<Self> {
+        // Changesets of pruned history are not available, so there's nothing to prefetch.
+        if self.lowest_available_blocks.is_account_history_available(self.block_number) &&
+            self.lowest_available_blocks.is_storage_history_available(self.block_number)
+        {
+            cache.prefetch_changesets(&self.tx, parent_hash, self.block_number)?;
+        }
+        self.cache = Some((cache, parent_hash));
+        Ok(self)
     }
 
     /// Set the lowest block number at which the account history is available.
@@ -457,12 +536,16 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
     /// Returns a new provider that takes the `TX` as reference
     #[inline(always)]
     fn as_ref(&self) -> HistoricalStateProviderRef<'_, TX> {
-        HistoricalStateProviderRef::new_with_lowest_available_blocks(
+        let provider = HistoricalStateProviderRef::new_with_lowest_available_blocks(
             &self.tx,
             self.block_number,
             self.lowest_available_blocks,
             self.static_file_provider.clone(),
-        )
+        );
+        match &self.cache {
+            Some((cache, parent_hash)) => provider.with_cache(cache.clone(), *parent_hash),
+            None => provider,
+        }
     }
 }
 
@@ -502,8 +585,8 @@ mod tests {
     use crate::{
         providers::state::historical::{HistoryInfo, LowestAvailableBlocks},
         test_utils::create_test_provider_factory,
-        AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, StateProvider,
-        StaticFileProviderFactory,
+        AccountReader, HistoricalStateCache, HistoricalStateProvider, HistoricalStateProviderRef,
+        StateProvider, StaticFileProviderFactory,
     };
     use reth_db::{tables, BlockNumberList};
     use reth_db_api::{
@@ -814,4 +897,36 @@ mod tests {
             Ok(HistoryInfo::MaybeInPlainState)
         );
     }
+
+    #[test]
+    fn history_provider_cache_prefetches_changesets() {
+        let cache = HistoricalStateCache::new(16);
+        let factory = create_test_provider_factory().with_historical_state_cache(cache.clone());
+        let tx = factory.provider_rw().unwrap().into_tx();
+
+        let acc_at7 = Account { nonce: 7, balance: U256::ZERO, bytecode_hash: None };
+        let entry_at7 = StorageEntry { key: STORAGE, value: U256::from(7) };
+
+        // No history indices are written, so the values can only be served from the changesets
+        // prefetched into the cache.
+        tx.put::<tables::CanonicalHeaders>(6, B256::with_last_byte(6)).unwrap();
+        tx.put::<tables::CanonicalHeaders>(10, B256::with_last_byte(10)).unwrap();
+        tx.put::<tables::AccountChangeSets>(
+            7,
+            AccountBeforeTx { address: ADDRESS, info: Some(acc_at7) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSets>((7, ADDRESS).into(), entry_at7).unwrap();
+        tx.commit().unwrap();
+
+        let provider = factory.history_by_block_number(6).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(provider.basic_account(ADDRESS), Ok(Some(acc_at7)));
+        assert_eq!(provider.storage(ADDRESS, STORAGE), Ok(Some(entry_at7.value)));
+
+        // Values read from the database are cached as well
+        assert_eq!(provider.basic_account(HIGHER_ADDRESS), Ok(None));
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.account(B256::with_last_byte(6), HIGHER_ADDRESS), Some(None));
+    }
 }
@@ -7,7 +7,9 @@ use crate::{
     TransactionsProvider,
 };
 use reth_chainspec::ChainInfo;
-use reth_db::static_file::{HeaderMask, ReceiptMask, StaticFileCursor, TransactionMask};
+use reth_db::static_file::{
+    HeaderMask, ReceiptMask, SenderMask, StaticFileCursor, TransactionMask,
+};
 use reth_db_api::models::CompactU256;
 use reth_primitives::{
     Address, BlockHash, BlockHashOrNumber, BlockNumber, Header, Receipt, SealedHeader,
@@ -276,12 +278,29 @@ impl<'a> TransactionsProvider for StaticFileJarProvider<'a> {
         &self,
         range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<Address>> {
+        if self.segment().is_senders() {
+            let range = to_range(range);
+            let mut cursor = self.cursor()?;
+            let mut senders = Vec::with_capacity((range.end - range.start) as usize);
+
+            for num in range {
+                if let Some(sender) = cursor.get_one::<SenderMask<Address>>(num.into())? {
+                    senders.push(sender)
+                }
+            }
+            return Ok(senders)
+        }
+
         let txs = self.transactions_by_tx_range(range)?;
         TransactionSignedNoHash::recover_signers(&txs, txs.len())
             .ok_or(ProviderError::SenderRecoveryError)
     }
 
     fn transaction_sender(&self, num: TxNumber) -> ProviderResult<Option<Address>> {
+        if self.segment().is_senders() {
+            return self.cursor()?.get_one::<SenderMask<Address>>(num.into())
+        }
+
         Ok(self
             .cursor()?
             .get_one::<TransactionMask<TransactionSignedNoHash>>(num.into())?
@@ -13,7 +13,9 @@ use parking_lot::RwLock;
 use reth_chainspec::ChainInfo;
 use reth_db::{
     lockfile::StorageLock,
-    static_file::{iter_static_files, HeaderMask, ReceiptMask, StaticFileCursor, TransactionMask},
+    static_file::{
+        iter_static_files, HeaderMask, ReceiptMask, SenderMask, StaticFileCursor, TransactionMask,
+    },
     tables,
 };
 use reth_db_api::{
@@ -450,7 +452,9 @@ impl StaticFileProvider {
                     if jar.user_header().expected_block_start() == 0 &&
                         matches!(
                             segment,
-                            StaticFileSegment::Receipts | StaticFileSegment::Transactions
+                            StaticFileSegment::Receipts |
+                                StaticFileSegment::Transactions |
+                                StaticFileSegment::Senders
                         )
                     {
                         tx_index.remove(&segment);
@@ -477,6 +481,7 @@ impl StaticFileProvider {
         let mut max_block = self.static_files_max_block.write();
         let mut tx_index = self.static_files_tx_index.write();
 
+        max_block.clear();
         tx_index.clear();
 
         for (segment, ranges) in
@@ -507,6 +512,33 @@ impl StaticFileProvider {
         Ok(())
     }
 
+    /// Reloads the inner transaction and block index from disk, and evicts the cached providers of
+    /// any static file that changed since the last time the index was built.
+    ///
+    /// Used by read-only providers to pick up static files that were written by another process.
+    pub fn reload_index(&self) -> ProviderResult<()> {
+        let previous = self.get_highest_static_files();
+        self.initialize_index()?;
+
+        for segment in StaticFileSegment::iter() {
+            let previous = previous.highest(segment);
+            let current = self.get_highest_static_file_block(segment);
+            if previous == current {
+                continue
+            }
+
+            // The static file holding the lowest of both highest blocks was either appended to or
+            // pruned, and any static file after it was created or deleted.
+            let lowest = previous.unwrap_or_default().min(current.unwrap_or_default());
+            let fixed_block_range_end = find_fixed_range(lowest).end();
+            self.map.retain(|(end, cached_segment), _| {
+                *cached_segment != segment || *end < fixed_block_range_end
+            });
+        }
+
+        Ok(())
+    }
+
     /// Ensures that any broken invariants which cannot be healed on the spot return a pipeline
     /// target to unwind to.
     ///
@@ -563,6 +595,12 @@ impl StaticFileProvider {
                 continue
             }
 
+            if segment.is_senders() && self.get_highest_static_file_block(segment).is_none() {
+                // Transaction senders are only moved to static files if they're not pruned, so
+                // they're kept in the database until the first static file is produced.
+                continue
+            }
+
             let initial_highest_block = self.get_highest_static_file_block(segment);
 
             //  File consistency is broken if:
@@ -591,7 +629,7 @@ impl StaticFileProvider {
                 update_unwind_target(highest_block.unwrap_or_default());
             }
 
-            // Only applies to transaction-based static files. (Receipts & Transactions)
+            // Only applies to transaction-based static files. (Receipts, Transactions & Senders)
             //
             // Make sure the last transaction matches the last block from its indices, since a heal
             // from a pruning interruption might have decreased the number of transactions without
@@ -647,6 +685,13 @@ impl StaticFileProvider {
                     highest_tx,
                     highest_block,
                 )?,
+                StaticFileSegment::Senders => self
+                    .ensure_invariants::<_, tables::TransactionSenders>(
+                        provider,
+                        segment,
+                        highest_tx,
+                        highest_block,
+                    )?,
             } {
                 update_unwind_target(unwind);
             }
@@ -712,6 +757,7 @@ impl StaticFileProvider {
                 StaticFileSegment::Headers => StageId::Headers,
                 StaticFileSegment::Transactions => StageId::Bodies,
                 StaticFileSegment::Receipts => StageId::Execution,
+                StaticFileSegment::Senders => StageId::SenderRecovery,
             })?
             .unwrap_or_default()
             .block_number;
@@ -744,10 +790,14 @@ impl StaticFileProvider {
                 writer.prune_headers(highest_static_file_block - checkpoint_block_number)?;
             } else if let Some(block) = provider.block_body_indices(checkpoint_block_number)? {
                 let number = highest_static_file_entry - block.last_tx_num();
-                if segment.is_receipts() {
-                    writer.prune_receipts(number, checkpoint_block_number)?;
-                } else {
-                    writer.prune_transactions(number, checkpoint_block_number)?;
+                match segment {
+                    StaticFileSegment::Receipts => {
+                        writer.prune_receipts(number, checkpoint_block_number)?
+                    }
+                    StaticFileSegment::Senders => {
+                        writer.prune_senders(number, checkpoint_block_number)?
+                    }
+                    _ => writer.prune_transactions(number, checkpoint_block_number)?,
                 }
             }
             writer.commit()?;
@@ -779,6 +829,7 @@ impl StaticFileProvider {
             headers: self.get_highest_static_file_block(StaticFileSegment::Headers),
             receipts: self.get_highest_static_file_block(StaticFileSegment::Receipts),
             transactions: self.get_highest_static_file_block(StaticFileSegment::Transactions),
+            senders: self.get_highest_static_file_block(StaticFileSegment::Senders),
         }
     }
 
@@ -825,7 +876,9 @@ impl StaticFileProvider {
             StaticFileSegment::Headers => {
                 self.get_segment_provider_from_block(segment, start, None)
             }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::Senders => {
                 self.get_segment_provider_from_transaction(segment, start, None)
             }
         };
@@ -901,7 +954,9 @@ impl StaticFileProvider {
             StaticFileSegment::Headers => {
                 self.get_segment_provider_from_block(segment, start, None)
             }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::Senders => {
                 self.get_segment_provider_from_transaction(segment, start, None)
             }
         };
@@ -951,9 +1006,9 @@ impl StaticFileProvider {
         // If there is, check the maximum block or transaction number of the segment.
         let static_file_upper_bound = match segment {
             StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                self.get_highest_static_file_tx(segment)
-            }
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::Senders => self.get_highest_static_file_tx(segment),
         };
 
         if static_file_upper_bound
@@ -993,9 +1048,9 @@ impl StaticFileProvider {
         // If there is, check the maximum block or transaction number of the segment.
         if let Some(static_file_upper_bound) = match segment {
             StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                self.get_highest_static_file_tx(segment)
-            }
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::Senders => self.get_highest_static_file_tx(segment),
         } {
             if block_or_tx_range.start <= static_file_upper_bound {
                 let end = block_or_tx_range.end.min(static_file_upper_bound + 1);
@@ -1399,12 +1454,44 @@ impl TransactionsProvider for StaticFileProvider {
         &self,
         range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<Address>> {
+        let range = to_range(range);
+
+        // Transaction senders are only moved to static files if they're not pruned, otherwise they
+        // need to be recovered from the transactions.
+        if self
+            .get_highest_static_file_tx(StaticFileSegment::Senders)
+            .map_or(false, |highest_tx| range.end <= highest_tx + 1)
+        {
+            return self.fetch_range_with_predicate(
+                StaticFileSegment::Senders,
+                range,
+                |cursor, number| cursor.get_one::<SenderMask<Address>>(number.into()),
+                |_| true,
+            )
+        }
+
         let txes = self.transactions_by_tx_range(range)?;
         TransactionSignedNoHash::recover_signers(&txes, txes.len())
             .ok_or(ProviderError::SenderRecoveryError)
     }
 
     fn transaction_sender(&self, id: TxNumber) -> ProviderResult<Option<Address>> {
+        if self
+            .get_highest_static_file_tx(StaticFileSegment::Senders)
+            .map_or(false, |highest_tx| id <= highest_tx)
+        {
+            return self
+                .get_segment_provider_from_transaction(StaticFileSegment::Senders, id, None)
+                .and_then(|provider| provider.transaction_sender(id))
+                .or_else(|err| {
+                    if let ProviderError::MissingStaticFileTx(_, _) = err {
+                        Ok(None)
+                    } else {
+                        Err(err)
+                    }
+                })
+        }
+
         Ok(self.transaction_by_id_no_hash(id)?.and_then(|tx| tx.recover_signer()))
     }
 }
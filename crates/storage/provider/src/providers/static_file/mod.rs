@@ -61,7 +61,7 @@ mod tests {
     use rand::seq::SliceRandom;
     use reth_db::{CanonicalHeaders, HeaderNumbers, HeaderTerminalDifficulties, Headers};
     use reth_db_api::transaction::DbTxMut;
-    use reth_primitives::{static_file::find_fixed_range, B256, U256};
+    use reth_primitives::{static_file::find_fixed_range, SealedHeader, B256, U256};
     use reth_testing_utils::generators::{self, random_header_range};
 
     #[test]
@@ -141,4 +141,49 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_reload_index() {
+        let static_files_path = tempfile::tempdir().unwrap();
+        let headers = random_header_range(&mut generators::rng(), 0..100, B256::random());
+        let (first, second) = headers.split_at(50);
+
+        let writer_provider = StaticFileProvider::read_write(static_files_path.path()).unwrap();
+        let append_headers = |headers: &[SealedHeader]| {
+            let mut writer = writer_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+            for header in headers {
+                writer.append_header(header.header(), U256::ZERO, &header.hash()).unwrap();
+            }
+            writer.commit().unwrap();
+        };
+        append_headers(first);
+
+        // Load the static file from a separate read-only provider, caching its jar
+        let reader_provider = StaticFileProvider::read_only(static_files_path.path()).unwrap();
+        assert_eq!(
+            reader_provider.get_highest_static_file_block(StaticFileSegment::Headers),
+            Some(49)
+        );
+        assert!(reader_provider.header_by_number(49).unwrap().is_some());
+
+        // New headers are not visible until the index is reloaded
+        append_headers(second);
+        assert_eq!(
+            reader_provider.get_highest_static_file_block(StaticFileSegment::Headers),
+            Some(49)
+        );
+        assert!(reader_provider.header_by_number(99).unwrap().is_none());
+
+        reader_provider.reload_index().unwrap();
+        assert_eq!(
+            reader_provider.get_highest_static_file_block(StaticFileSegment::Headers),
+            Some(99)
+        );
+        for header in headers {
+            assert_eq!(
+                reader_provider.header_by_number(header.number).unwrap().as_ref(),
+                Some(header.header())
+            );
+        }
+    }
 }
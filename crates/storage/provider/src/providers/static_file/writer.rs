@@ -8,8 +8,8 @@ use reth_db_api::models::CompactU256;
 use reth_nippy_jar::{ConsistencyFailStrategy, NippyJar, NippyJarError, NippyJarWriter};
 use reth_primitives::{
     static_file::{find_fixed_range, SegmentHeader, SegmentRangeInclusive},
-    BlockHash, BlockNumber, Header, Receipt, StaticFileSegment, TransactionSignedNoHash, TxNumber,
-    U256,
+    Address, BlockHash, BlockNumber, Header, Receipt, StaticFileSegment, TransactionSignedNoHash,
+    TxNumber, U256,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use std::{
@@ -29,6 +29,7 @@ pub(crate) struct StaticFileWriters {
     headers: RwLock<Option<StaticFileProviderRW>>,
     transactions: RwLock<Option<StaticFileProviderRW>>,
     receipts: RwLock<Option<StaticFileProviderRW>>,
+    senders: RwLock<Option<StaticFileProviderRW>>,
 }
 
 impl StaticFileWriters {
@@ -41,6 +42,7 @@ impl StaticFileWriters {
             StaticFileSegment::Headers => self.headers.write(),
             StaticFileSegment::Transactions => self.transactions.write(),
             StaticFileSegment::Receipts => self.receipts.write(),
+            StaticFileSegment::Senders => self.senders.write(),
         };
 
         if write_guard.is_none() {
@@ -51,7 +53,7 @@ impl StaticFileWriters {
     }
 
     pub(crate) fn commit(&self) -> ProviderResult<()> {
-        for writer_lock in [&self.headers, &self.transactions, &self.receipts] {
+        for writer_lock in [&self.headers, &self.transactions, &self.receipts, &self.senders] {
             let mut writer = writer_lock.write();
             if let Some(writer) = writer.as_mut() {
                 writer.commit()?;
@@ -238,6 +240,9 @@ impl StaticFileProviderRW {
                 StaticFileSegment::Receipts => {
                     self.prune_receipt_data(to_delete, last_block_number.expect("should exist"))?
                 }
+                StaticFileSegment::Senders => {
+                    self.prune_sender_data(to_delete, last_block_number.expect("should exist"))?
+                }
             }
         }
 
@@ -412,7 +417,9 @@ impl StaticFileProviderRW {
                 StaticFileSegment::Headers => {
                     self.writer.user_header().block_len().unwrap_or_default()
                 }
-                StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+                StaticFileSegment::Transactions |
+                StaticFileSegment::Receipts |
+                StaticFileSegment::Senders => {
                     self.writer.user_header().tx_len().unwrap_or_default()
                 }
             };
@@ -446,7 +453,7 @@ impl StaticFileProviderRW {
             }
         }
 
-        // Only Transactions and Receipts
+        // Only Transactions, Receipts and Senders
         if let Some(last_block) = last_block {
             let mut expected_block_start = self.writer.user_header().expected_block_start();
 
@@ -648,6 +655,47 @@ impl StaticFileProviderRW {
         Ok(Some(tx_number))
     }
 
+    /// Appends multiple transaction senders to the static file.
+    ///
+    /// It **DOES NOT** call `increment_block()`, it should be handled elsewhere. There might be
+    /// empty blocks and this function wouldn't be called.
+    ///
+    /// Returns the current [`TxNumber`] as seen in the static file, if any.
+    pub fn append_senders<I>(&mut self, senders: I) -> ProviderResult<Option<TxNumber>>
+    where
+        I: Iterator<Item = Result<(TxNumber, Address), ProviderError>>,
+    {
+        let mut senders_iter = senders.into_iter().peekable();
+        // If senders are empty, we can simply return None
+        if senders_iter.peek().is_none() {
+            return Ok(None);
+        }
+
+        let start = Instant::now();
+        self.ensure_no_queued_prune()?;
+
+        // At this point senders contains at least one sender, so this would be overwritten.
+        let mut tx_number = 0;
+        let mut count: u64 = 0;
+
+        for sender_result in senders_iter {
+            let (tx_num, sender) = sender_result?;
+            tx_number = self.append_with_tx_number(StaticFileSegment::Senders, tx_num, sender)?;
+            count += 1;
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operations(
+                StaticFileSegment::Senders,
+                StaticFileProviderOperation::Append,
+                count,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(Some(tx_number))
+    }
+
     /// Adds an instruction to prune `to_delete`transactions during commit.
     ///
     /// Note: `last_block` refers to the block the unwinds ends at.
@@ -672,6 +720,14 @@ impl StaticFileProviderRW {
         self.queue_prune(to_delete, Some(last_block))
     }
 
+    /// Adds an instruction to prune `to_delete` transaction senders during commit.
+    ///
+    /// Note: `last_block` refers to the block the unwinds ends at.
+    pub fn prune_senders(&mut self, to_delete: u64, last_block: BlockNumber) -> ProviderResult<()> {
+        debug_assert_eq!(self.writer.user_header().segment(), StaticFileSegment::Senders);
+        self.queue_prune(to_delete, Some(last_block))
+    }
+
     /// Adds an instruction to prune `to_delete` headers during commit.
     pub fn prune_headers(&mut self, to_delete: u64) -> ProviderResult<()> {
         debug_assert_eq!(self.writer.user_header().segment(), StaticFileSegment::Headers);
@@ -750,6 +806,26 @@ impl StaticFileProviderRW {
         Ok(())
     }
 
+    /// Prunes the last `to_delete` transaction senders from the data file.
+    fn prune_sender_data(&mut self, to_delete: u64, last_block: BlockNumber) -> ProviderResult<()> {
+        let start = Instant::now();
+
+        let segment = StaticFileSegment::Senders;
+        debug_assert!(self.writer.user_header().segment() == segment);
+
+        self.truncate(segment, to_delete, Some(last_block))?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operation(
+                StaticFileSegment::Senders,
+                StaticFileProviderOperation::Prune,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(())
+    }
+
     /// Prunes the last `to_delete` headers from the data file.
     fn prune_header_data(&mut self, to_delete: u64) -> ProviderResult<()> {
         let start = Instant::now();
@@ -823,7 +899,7 @@ fn create_jar(
     );
 
     // Transaction and Receipt already have the compression scheme used natively in its encoding.
-    // (zstd-dictionary)
+    // (zstd-dictionary). Senders are fixed-size addresses, which don't benefit from compression.
     if segment.is_headers() {
         jar = jar.with_lz4();
     }
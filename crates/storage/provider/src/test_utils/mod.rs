@@ -1,6 +1,7 @@
 use crate::{providers::StaticFileProvider, HashingWriter, ProviderFactory, TrieWriter};
 use reth_chainspec::{ChainSpec, MAINNET};
 use reth_db::{
+    memory::MemoryDatabase,
     test_utils::{create_test_rw_db, create_test_static_files_dir, TempDatabase},
     Database, DatabaseEnv,
 };
@@ -36,6 +37,16 @@ pub fn create_test_provider_factory_with_chain_spec(
     )
 }
 
+/// Creates test provider factory with mainnet chain spec, backed by an in-memory database.
+pub fn create_test_provider_factory_in_memory() -> ProviderFactory<MemoryDatabase> {
+    let (static_dir, _) = create_test_static_files_dir();
+    ProviderFactory::new(
+        MemoryDatabase::new(),
+        MAINNET.clone(),
+        StaticFileProvider::read_write(static_dir.into_path()).expect("static file provider"),
+    )
+}
+
 /// Inserts the genesis alloc from the provided chain spec into the trie.
 pub fn insert_genesis<DB: Database>(
     provider_factory: &ProviderFactory<DB>,
@@ -255,7 +255,7 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
         // handle the new block or reorg
         let Some(event) = event else { continue };
         match event {
-            CanonStateNotification::Reorg { old, new } => {
+            CanonStateNotification::Reorg { old, new, .. } => {
                 let (old_blocks, old_state) = old.inner();
                 let (new_blocks, new_state) = new.inner();
                 let new_tip = new_blocks.tip();
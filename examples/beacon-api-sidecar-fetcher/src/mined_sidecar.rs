@@ -181,7 +181,7 @@ where
                                 this.process_block(block);
                             }
                         }
-                        CanonStateNotification::Reorg { old, new } => {
+                        CanonStateNotification::Reorg { old, new, .. } => {
                             // handle reorged blocks
                             for (_, block) in old.blocks().iter() {
                                 let txs: Vec<BlobTransactionEvent> = block
@@ -78,7 +78,7 @@ async fn main() {
         // with the chain specific details
         match evt {
             NetworkEvent::SessionEstablished { status, client_version, peer_id, .. } => {
-                info!(peers=%net_handle.num_connected_peers() , %peer_id, chain = %status.chain, ?client_version, "Session established with a new peer.");
+                info!(peers=%net_handle.num_connected_peers() , %peer_id, chain = %status.chain(), ?client_version, "Session established with a new peer.");
             }
             NetworkEvent::SessionClosed { peer_id, reason } => {
                 info!(peers=%net_handle.num_connected_peers() , %peer_id, ?reason, "Session closed.");
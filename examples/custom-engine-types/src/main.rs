@@ -285,7 +285,16 @@ where
         &self,
         args: BuildArguments<Pool, Client, Self::Attributes, Self::BuiltPayload>,
     ) -> Result<BuildOutcome<Self::BuiltPayload>, PayloadBuilderError> {
-        let BuildArguments { client, pool, cached_reads, config, cancel, best_payload } = args;
+        let BuildArguments {
+            client,
+            pool,
+            cached_reads,
+            config,
+            cancel,
+            best_payload,
+            executed_prefix,
+            retain_executed_prefix,
+        } = args;
         let PayloadConfig {
             initialized_block_env,
             initialized_cfg,
@@ -311,6 +320,8 @@ where
             },
             cancel,
             best_payload,
+            executed_prefix,
+            retain_executed_prefix,
         })
     }
 
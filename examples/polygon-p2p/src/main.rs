@@ -73,7 +73,7 @@ async fn main() {
         // For the sake of the example we only print the session established event
         // with the chain specific details
         if let NetworkEvent::SessionEstablished { status, client_version, .. } = evt {
-            let chain = status.chain;
+            let chain = status.chain();
             info!(?chain, ?client_version, "Session established with a new peer.");
         }
         // More events here